@@ -1,51 +1,92 @@
 use std::cell::RefCell;
 use std::collections::HashMap;
-use std::io::Write;
+use std::io::{BufRead, Write};
 use std::path::{Path, PathBuf};
 
 use clap::Parser;
 
 use crate::config::VariableValue;
-use crate::editor::Editor;
+use crate::editor::{Cleanup, Editor};
 use crate::errors::Result;
 use crate::pager::Pager;
+use crate::repository::status::UntrackedFiles;
 use crate::repository::Repository;
 
+const DEFAULT_PAGER: &str = "less";
+
 mod add;
+mod am;
 mod branch;
 mod checkout;
 mod cherry_pick;
+mod clean;
 mod commit;
+mod commit_tree;
 mod config;
 mod diff;
+mod for_each_ref;
+mod format_patch;
+mod gc;
+mod hash_object;
 mod init;
 mod log;
 mod merge;
+mod notes;
+mod read_tree;
 mod remote;
 mod reset;
+mod rev_list;
+mod rev_parse;
 mod revert;
 mod rm;
 mod shared;
+mod sparse_checkout;
 mod status;
+mod submodule;
+mod symbolic_ref;
+mod update_ref;
+mod write_tree;
 
 use add::Add;
+use am::Am;
 use branch::Branch;
 use checkout::Checkout;
 use cherry_pick::CherryPick;
+use clean::Clean;
 use commit::Commit;
+use commit_tree::CommitTree;
 use config::ConfigCommand as Config;
 use diff::Diff;
+use for_each_ref::ForEachRef;
+use format_patch::FormatPatch;
+use gc::Gc;
+use hash_object::{HashObject, ObjectType};
 use init::Init;
-use log::{Log, LogDecoration, LogFormat};
-use merge::Merge;
+use log::{Log, LogDecoration, LogFormat, NoWalk};
+use merge::{Merge, MergeStrategy};
+use notes::Notes;
+use read_tree::ReadTree;
 use remote::Remote;
 use reset::Reset;
+use rev_list::RevListCommand as RevList;
+use rev_parse::RevParse;
 use revert::Revert;
 use rm::Rm;
-use status::Status;
+use sparse_checkout::SparseCheckout;
+use status::{PorcelainVersion, Status};
+use submodule::Submodule;
+use symbolic_ref::SymbolicRef;
+use update_ref::UpdateRef;
+use write_tree::WriteTree;
 
 #[derive(Parser, Debug)]
 pub struct Jit {
+    /// Do not pipe output into a pager
+    #[clap(long)]
+    pub no_pager: bool,
+    /// Only print errors and the output that was asked for
+    #[clap(short, long, global = true)]
+    pub quiet: bool,
     #[clap(subcommand)]
     pub cmd: Command,
 }
@@ -55,6 +96,24 @@ pub enum Command {
     Add {
         #[clap(value_parser)]
         files: Vec<PathBuf>,
+        /// Record that the files will be added later, so they show up in `jit diff`
+        #[clap(short = 'N', long)]
+        intent_to_add: bool,
+        /// Add files even if `.gitignore` ignores them
+        #[clap(short, long)]
+        force: bool,
+    },
+    Am {
+        /// The mbox files to read the patches from, instead of stdin
+        #[clap(value_parser)]
+        mailboxes: Vec<PathBuf>,
+        #[clap(long)]
+        r#continue: bool,
+        /// Skip the patch that failed to apply
+        #[clap(long)]
+        skip: bool,
+        #[clap(long)]
+        abort: bool,
     },
     Branch {
         args: Vec<String>,
@@ -68,7 +127,13 @@ pub enum Command {
         force_delete: bool,
     },
     Checkout {
-        tree_ish: String,
+        /// The branch or commit to switch to, or to restore <PATHS> from. A name that's both a
+        /// revision and a path is ambiguous, and one that's only a path is restored from the index.
+        #[clap(required_unless_present = "paths")]
+        tree_ish: Option<String>,
+        /// Restore these paths from <TREE_ISH>, or from the index without it, instead of switching
+        #[clap(last = true, value_parser)]
+        paths: Vec<PathBuf>,
     },
     CherryPick {
         args: Vec<String>,
@@ -80,6 +145,22 @@ pub enum Command {
         quit: bool,
         #[clap(short, long)]
         mainline: Option<u32>,
+        /// Append a line saying which commit each commit was picked from
+        #[clap(short = 'x')]
+        record_origin: bool,
+    },
+    Clean {
+        /// Only show what would be removed
+        #[clap(short = 'n', long)]
+        dry_run: bool,
+        #[clap(short, long)]
+        force: bool,
+        /// Choose the files to remove from a menu
+        #[clap(short, long)]
+        interactive: bool,
+        /// Remove untracked directories as well as files
+        #[clap(short = 'd')]
+        directories: bool,
     },
     Commit {
         #[clap(short, long)]
@@ -96,6 +177,38 @@ pub enum Command {
         reedit_message: Option<String>,
         #[clap(long)]
         amend: bool,
+        #[clap(long)]
+        date: Option<String>,
+        #[clap(short, long)]
+        verbose: bool,
+        /// Add a trailer like `Key: value` to the end of the message
+        #[clap(long = "trailer", value_name = "token: value")]
+        trailers: Vec<String>,
+        /// Add a `Signed-off-by` trailer for the committer
+        #[clap(short, long)]
+        signoff: bool,
+        /// How to clean up the message before committing it
+        #[clap(arg_enum, long, value_name = "mode", default_value = "strip")]
+        cleanup: Cleanup,
+        /// Sign the commit, with `<keyid>` or the committer's default key
+        #[clap(short = 'S', long, value_name = "keyid")]
+        #[allow(clippy::option_option)]
+        gpg_sign: Option<Option<String>>,
+        /// Don't sign the commit, even if `commit.gpgSign` is set
+        #[clap(long, overrides_with = "gpg-sign")]
+        no_gpg_sign: bool,
+        /// Show the status of what would be committed, without committing
+        #[clap(long)]
+        dry_run: bool,
+    },
+    CommitTree {
+        tree: String,
+        #[clap(short = 'p', value_name = "parent")]
+        parents: Vec<String>,
+        #[clap(short = 'm', value_name = "message")]
+        messages: Vec<String>,
+        #[clap(short = 'F', value_name = "file", conflicts_with = "messages")]
+        file: Option<PathBuf>,
     },
     Config {
         args: Vec<String>,
@@ -113,6 +226,9 @@ pub enum Command {
         replace_all: Option<String>,
         #[clap(long)]
         get_all: Option<String>,
+        /// List the variables whose names match a regular expression, with their values
+        #[clap(long, value_name = "pattern")]
+        get_regexp: Option<String>,
         #[clap(long)]
         unset: Option<String>,
         #[clap(long)]
@@ -122,16 +238,65 @@ pub enum Command {
     },
     Diff {
         args: Vec<String>,
-        #[clap(long)]
+        /// Compare the index with `HEAD`. Without this, the workspace is compared with the index.
+        #[clap(long, visible_alias = "staged", conflicts_with = "stage")]
         cached: bool,
-        #[clap(long)]
-        staged: bool,
         #[clap(short, long)]
         patch: bool,
         #[clap(short = 's', long)]
         no_patch: bool,
         #[clap(flatten)]
         stage: StageOptions,
+        /// Detect files that were renamed or copied from another file
+        #[clap(short = 'C', long)]
+        find_copies: bool,
+        /// Ignore changes whose lines are all blank
+        #[clap(long)]
+        ignore_blank_lines: bool,
+        /// Exit with 1 if there are differences and 0 if there are none
+        #[clap(long)]
+        exit_code: bool,
+        /// Only show changes inside a directory, with paths relative to it. Without a directory,
+        /// the current directory is used.
+        #[clap(long, value_name = "path")]
+        #[allow(clippy::option_option)]
+        relative: Option<Option<PathBuf>>,
+        /// Diff files as they are, without converting them with their `diff.<driver>.textconv`
+        /// command
+        #[clap(long)]
+        no_textconv: bool,
+    },
+    ForEachRef {
+        patterns: Vec<String>,
+        #[clap(long)]
+        format: Option<String>,
+        #[clap(long)]
+        sort: Option<String>,
+    },
+    FormatPatch {
+        args: Vec<String>,
+        /// Print the patches instead of writing one file per commit
+        #[clap(long)]
+        stdout: bool,
+        /// Write the patch files to `<dir>` instead of the current directory
+        #[clap(short, long, value_name = "dir")]
+        output_directory: Option<PathBuf>,
+    },
+    Gc {
+        /// Only report whether there are more loose objects than `gc.auto`, or more packs
+        /// than `gc.autoPackLimit`
+        #[clap(long)]
+        auto: bool,
+    },
+    HashObject {
+        #[clap(value_parser)]
+        files: Vec<PathBuf>,
+        #[clap(arg_enum, short, default_value = "blob")]
+        r#type: ObjectType,
+        #[clap(short)]
+        write: bool,
+        #[clap(long)]
+        stdin: bool,
     },
     Init {
         #[clap(value_parser)]
@@ -140,8 +305,18 @@ pub enum Command {
     Log {
         args: Vec<String>,
         #[clap(long = "abbrev-commit")]
-        abbrev: bool,
-        #[clap(long = "no-abbrev-commit", overrides_with = "abbrev", hide = true)]
+        abbrev_commit: bool,
+        #[clap(
+            long = "no-abbrev-commit",
+            overrides_with = "abbrev-commit",
+            hide = true
+        )]
+        no_abbrev_commit: bool,
+        /// Abbreviate object IDs to `<n>` characters. Defaults to `core.abbrev`, or 7.
+        #[clap(long, value_name = "n", require_equals = true)]
+        abbrev: Option<usize>,
+        /// Show whole object IDs
+        #[clap(long, overrides_with = "abbrev")]
         no_abbrev: bool,
         #[clap(arg_enum, long, visible_alias = "pretty", default_value = "medium")]
         format: LogFormat,
@@ -160,6 +335,34 @@ pub enum Command {
         _no_patch: bool,
         #[clap(long = "cc")]
         combined: bool,
+        #[clap(long)]
+        follow: bool,
+        #[clap(long)]
+        stat: bool,
+        /// Draw the history as a graph to the left of the log
+        #[clap(long)]
+        graph: bool,
+        /// Show only commits that change the number of occurrences of `<string>` in a file
+        #[clap(short = 'S', value_name = "string", conflicts_with = "pickaxe-regex")]
+        pickaxe_string: Option<String>,
+        /// Show only commits with an added or removed line matching `<regex>`
+        #[clap(short = 'G', value_name = "regex")]
+        pickaxe_regex: Option<String>,
+        /// Show whether each commit is signed. Signatures aren't verified.
+        #[clap(long)]
+        show_signature: bool,
+        /// Show only commits that a ref points at, merges, and commits where history branches
+        #[clap(long)]
+        simplify_by_decoration: bool,
+        /// With a range `A..B`, show only commits that descend from `A`, leaving out those
+        /// merged in from elsewhere
+        #[clap(long)]
+        ancestry_path: bool,
+        /// Show only the given commits, without their history. They're listed newest first
+        /// unless `--no-walk=unsorted` is used, which keeps the order they were given in.
+        #[clap(arg_enum, long, value_name = "order", require_equals = true)]
+        #[allow(clippy::option_option)]
+        no_walk: Option<Option<NoWalk>>,
     },
     Merge {
         args: Vec<String>,
@@ -176,6 +379,26 @@ pub enum Command {
         edit: bool,
         #[clap(long, overrides_with = "edit")]
         no_edit: bool,
+        /// Add the subjects of at most `<n>` merged commits (20 by default) to the message
+        #[clap(long, value_name = "n")]
+        #[allow(clippy::option_option)]
+        log: Option<Option<usize>>,
+        /// The merge strategy to use
+        #[clap(arg_enum, short, long, default_value = "resolve")]
+        strategy: MergeStrategy,
+        /// Name the current branch `<branch>` in conflict markers and the default message
+        #[clap(long, value_name = "branch")]
+        into_name: Option<String>,
+    },
+    Notes {
+        args: Vec<String>,
+        #[clap(short, long)]
+        message: Option<String>,
+        #[clap(short, long)]
+        force: bool,
+    },
+    ReadTree {
+        tree: String,
     },
     Remote {
         args: Vec<String>,
@@ -193,6 +416,37 @@ pub enum Command {
         _mixed: bool,
         #[clap(long)]
         hard: bool,
+        /// Reset the index, and update the files that differ between it and the commit, keeping
+        /// unstaged changes to other files. Abort if that would lose unstaged changes.
+        #[clap(long, conflicts_with_all = &["soft", "hard", "keep"])]
+        merge: bool,
+        /// Reset the index, and update the files that differ between `HEAD` and the commit,
+        /// keeping local changes to other files. Abort if that would lose local changes.
+        #[clap(long, conflicts_with_all = &["soft", "hard"])]
+        keep: bool,
+    },
+    RevList {
+        #[clap(required = true)]
+        args: Vec<String>,
+        #[clap(long)]
+        count: bool,
+        #[clap(long)]
+        left_right: bool,
+    },
+    RevParse {
+        /// Print the path of the `.git` directory, relative to the current directory at the root
+        /// of the workspace
+        #[clap(long)]
+        git_dir: bool,
+        /// Print the absolute path of the `.git` directory
+        #[clap(long)]
+        absolute_git_dir: bool,
+        /// Print the absolute path of the root of the workspace
+        #[clap(long)]
+        show_toplevel: bool,
+        /// Print whether the current directory is inside the workspace, rather than `.git`
+        #[clap(long)]
+        is_inside_work_tree: bool,
     },
     Revert {
         args: Vec<String>,
@@ -204,6 +458,14 @@ pub enum Command {
         quit: bool,
         #[clap(short, long)]
         mainline: Option<u32>,
+        /// Apply the reverts to the index and workspace without committing them
+        #[clap(short, long)]
+        no_commit: bool,
+        /// Edit the message of each revert commit, which is the default on a terminal
+        #[clap(short, long)]
+        edit: bool,
+        #[clap(long, overrides_with = "edit")]
+        no_edit: bool,
     },
     Rm {
         #[clap(value_parser)]
@@ -215,10 +477,58 @@ pub enum Command {
         #[clap(short)]
         recursive: bool,
     },
+    Show {
+        args: Vec<String>,
+        #[clap(short, long)]
+        patch: bool,
+        #[clap(long)]
+        stat: bool,
+        /// Show whether the commit is signed. Signatures aren't verified.
+        #[clap(long)]
+        show_signature: bool,
+    },
+    SparseCheckout {
+        args: Vec<String>,
+    },
     Status {
+        /// Print the status in a format for scripts. `--porcelain` alone uses `v1`.
+        #[clap(arg_enum, long, value_name = "version")]
+        #[allow(clippy::option_option)]
+        porcelain: Option<Option<PorcelainVersion>>,
+        /// Show the branch and any operation in progress as `# ` headers in the porcelain format
+        #[clap(short, long)]
+        branch: bool,
+        /// Which untracked files to show. Defaults to `status.showUntrackedFiles`, or `normal`.
+        /// `-u` alone shows `all` of them.
+        #[clap(
+            arg_enum,
+            short = 'u',
+            long,
+            value_name = "mode",
+            min_values = 0,
+            default_missing_value = "all"
+        )]
+        untracked_files: Option<UntrackedFiles>,
+        /// End each porcelain record with a NUL instead of a newline. Implies `--porcelain`.
+        #[clap(short = 'z')]
+        null_terminated: bool,
+    },
+    Submodule {
+        args: Vec<String>,
+    },
+    SymbolicRef {
+        name: String,
+        target: Option<String>,
         #[clap(long)]
-        porcelain: bool,
+        short: bool,
+    },
+    UpdateRef {
+        #[clap(required = true)]
+        args: Vec<String>,
+        #[clap(short)]
+        delete: bool,
     },
+    WriteTree,
 }
 
 #[derive(Parser, Debug)]
@@ -231,21 +541,34 @@ pub struct StageOptions {
     pub theirs: bool,
 }
 
-pub fn execute<O: Write + 'static, E: Write + 'static>(
+pub fn execute<I: BufRead + 'static, O: Write + 'static, E: Write + 'static>(
     dir: PathBuf,
     env: HashMap<String, String>,
     opt: Jit,
+    stdin: I,
     stdout: O,
     stderr: E,
     isatty: bool,
 ) -> Result<()> {
-    let ctx = CommandContext::new(dir, env, &opt, Box::new(stdout), Box::new(stderr), isatty);
+    let ctx = CommandContext::new(
+        dir,
+        env,
+        &opt,
+        Box::new(stdin),
+        Box::new(stdout),
+        Box::new(stderr),
+        isatty,
+    );
 
     match &opt.cmd {
         Command::Add { .. } => {
             let mut cmd = Add::new(ctx);
             cmd.run()
         }
+        Command::Am { .. } => {
+            let mut cmd = Am::new(ctx);
+            cmd.run()
+        }
         Command::Branch { .. } => {
             let mut cmd = Branch::new(ctx);
             cmd.run()
@@ -258,10 +581,22 @@ pub fn execute<O: Write + 'static, E: Write + 'static>(
             let mut cmd = CherryPick::new(ctx);
             cmd.run()
         }
+        Command::Clean { .. } => {
+            let mut cmd = Clean::new(ctx);
+            cmd.run()
+        }
+        Command::Commit { dry_run: true, .. } => {
+            let mut cmd = Status::new(ctx);
+            cmd.run()
+        }
         Command::Commit { .. } => {
             let mut cmd = Commit::new(ctx);
             cmd.run()
         }
+        Command::CommitTree { .. } => {
+            let cmd = CommitTree::new(ctx);
+            cmd.run()
+        }
         Command::Config { .. } => {
             let mut cmd = Config::new(ctx);
             cmd.run()
@@ -270,6 +605,22 @@ pub fn execute<O: Write + 'static, E: Write + 'static>(
             let mut cmd = Diff::new(ctx);
             cmd.run()
         }
+        Command::ForEachRef { .. } => {
+            let cmd = ForEachRef::new(ctx);
+            cmd.run()
+        }
+        Command::FormatPatch { .. } => {
+            let cmd = FormatPatch::new(ctx);
+            cmd.run()
+        }
+        Command::Gc { .. } => {
+            let mut cmd = Gc::new(ctx);
+            cmd.run()
+        }
+        Command::HashObject { .. } => {
+            let cmd = HashObject::new(ctx);
+            cmd.run()
+        }
         Command::Init { .. } => {
             let cmd = Init::new(ctx);
             cmd.run()
@@ -282,6 +633,14 @@ pub fn execute<O: Write + 'static, E: Write + 'static>(
             let mut cmd = Merge::new(ctx)?;
             cmd.run()
         }
+        Command::Notes { .. } => {
+            let mut cmd = Notes::new(ctx);
+            cmd.run()
+        }
+        Command::ReadTree { .. } => {
+            let mut cmd = ReadTree::new(ctx);
+            cmd.run()
+        }
         Command::Remote { .. } => {
             let mut cmd = Remote::new(ctx);
             cmd.run()
@@ -290,6 +649,14 @@ pub fn execute<O: Write + 'static, E: Write + 'static>(
             let mut cmd = Reset::new(ctx)?;
             cmd.run()
         }
+        Command::RevList { .. } => {
+            let cmd = RevList::new(ctx);
+            cmd.run()
+        }
+        Command::RevParse { .. } => {
+            let cmd = RevParse::new(ctx);
+            cmd.run()
+        }
         Command::Revert { .. } => {
             let mut cmd = Revert::new(ctx);
             cmd.run()
@@ -298,10 +665,34 @@ pub fn execute<O: Write + 'static, E: Write + 'static>(
             let mut cmd = Rm::new(ctx)?;
             cmd.run()
         }
+        Command::Show { .. } => {
+            let mut cmd = Log::new(ctx);
+            cmd.run()
+        }
+        Command::SparseCheckout { .. } => {
+            let mut cmd = SparseCheckout::new(ctx);
+            cmd.run()
+        }
         Command::Status { .. } => {
             let mut cmd = Status::new(ctx);
             cmd.run()
         }
+        Command::Submodule { .. } => {
+            let mut cmd = Submodule::new(ctx);
+            cmd.run()
+        }
+        Command::SymbolicRef { .. } => {
+            let cmd = SymbolicRef::new(ctx);
+            cmd.run()
+        }
+        Command::UpdateRef { .. } => {
+            let cmd = UpdateRef::new(ctx);
+            cmd.run()
+        }
+        Command::WriteTree => {
+            let mut cmd = WriteTree::new(ctx);
+            cmd.run()
+        }
     }
 }
 
@@ -310,6 +701,7 @@ pub struct CommandContext<'a> {
     env: HashMap<String, String>,
     opt: &'a Jit,
     repo: Box<Repository>,
+    stdin: RefCell<Box<dyn BufRead>>,
     stdout: RefCell<Box<dyn Write>>,
     stderr: RefCell<Box<dyn Write>>,
     using_pager: bool,
@@ -321,6 +713,7 @@ impl<'a> CommandContext<'a> {
         dir: PathBuf,
         env: HashMap<String, String>,
         opt: &'a Jit,
+        stdin: Box<dyn BufRead>,
         stdout: Box<dyn Write>,
         stderr: Box<dyn Write>,
         isatty: bool,
@@ -332,6 +725,7 @@ impl<'a> CommandContext<'a> {
             env,
             opt,
             repo,
+            stdin: RefCell::new(stdin),
             stdout: RefCell::new(stdout),
             stderr: RefCell::new(stderr),
             using_pager: false,
@@ -346,14 +740,41 @@ impl<'a> CommandContext<'a> {
         }
 
         // Only setup the pager if stdout is a tty
-        if !self.isatty {
+        if !self.isatty || self.opt.no_pager {
             return;
         }
 
-        self.stdout = RefCell::new(Box::new(Pager::new(&self.env)));
+        let command = self.pager_command();
+        let command = command.trim();
+        if command.is_empty() || command == "cat" {
+            return;
+        }
+
+        self.stdout = RefCell::new(Box::new(Pager::new(command, &self.env)));
         self.using_pager = true;
     }
 
+    /// Whether informational messages like progress and summaries should be suppressed.
+    pub fn quiet(&self) -> bool {
+        self.opt.quiet
+    }
+
+    fn pager_command(&self) -> String {
+        if let Some(pager) = self.env.get("GIT_PAGER") {
+            pager.to_owned()
+        } else if let Some(pager) = self
+            .repo
+            .config
+            .get(&[String::from("core"), String::from("pager")])
+        {
+            pager.to_string()
+        } else if let Some(pager) = self.env.get("PAGER") {
+            pager.to_owned()
+        } else {
+            DEFAULT_PAGER.to_owned()
+        }
+    }
+
     pub fn edit_file<F>(&self, path: &Path, f: F) -> Result<Option<String>>
     where
         F: Fn(&mut Editor) -> Result<()>,
@@ -402,4 +823,73 @@ mod tests {
 
         Jit::command().debug_assert()
     }
+
+    mod pager {
+        use std::fs;
+        use std::io;
+
+        use tempfile::TempDir;
+
+        use super::*;
+
+        fn context<'a>(dir: &TempDir, env: &[(&str, &str)], opt: &'a Jit) -> CommandContext<'a> {
+            let env = env
+                .iter()
+                .map(|(key, val)| (key.to_string(), val.to_string()))
+                .collect();
+
+            CommandContext::new(
+                dir.path().to_path_buf(),
+                env,
+                opt,
+                Box::new(io::empty()),
+                Box::new(io::sink()),
+                Box::new(io::sink()),
+                true,
+            )
+        }
+
+        #[test]
+        fn do_not_page_with_no_pager() {
+            let dir = TempDir::new().unwrap();
+            let opt = Jit::parse_from(["jit", "--no-pager", "log"]);
+            let mut ctx = context(&dir, &[("GIT_PAGER", "less")], &opt);
+
+            ctx.setup_pager();
+
+            assert!(!ctx.using_pager);
+        }
+
+        #[test]
+        fn do_not_page_with_cat() {
+            let dir = TempDir::new().unwrap();
+            let opt = Jit::parse_from(["jit", "log"]);
+            let mut ctx = context(&dir, &[("GIT_PAGER", "cat")], &opt);
+
+            ctx.setup_pager();
+
+            assert!(!ctx.using_pager);
+        }
+
+        #[test]
+        fn prefer_git_pager_then_core_pager_then_pager() {
+            let dir = TempDir::new().unwrap();
+            fs::create_dir(dir.path().join(".git")).unwrap();
+            fs::write(dir.path().join(".git/config"), "[core]\n\tpager = more\n").unwrap();
+            let opt = Jit::parse_from(["jit", "log"]);
+
+            let ctx = context(&dir, &[("GIT_PAGER", "most"), ("PAGER", "lv")], &opt);
+            assert_eq!(ctx.pager_command(), "most");
+
+            let ctx = context(&dir, &[("PAGER", "lv")], &opt);
+            assert_eq!(ctx.pager_command(), "more");
+
+            fs::remove_file(dir.path().join(".git/config")).unwrap();
+            let ctx = context(&dir, &[("PAGER", "lv")], &opt);
+            assert_eq!(ctx.pager_command(), "lv");
+
+            let ctx = context(&dir, &[], &opt);
+            assert_eq!(ctx.pager_command(), "less");
+        }
+    }
 }
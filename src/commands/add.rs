@@ -1,3 +1,4 @@
+use std::collections::BTreeSet;
 use std::io;
 use std::io::Write;
 use std::path::PathBuf;
@@ -7,21 +8,35 @@ use crate::database::blob::Blob;
 use crate::database::object::Object;
 use crate::errors::{Error, Result};
 use crate::util::path_to_string;
+use crate::workspace::SYMLINK_MODE;
 
 pub struct Add<'a> {
     ctx: CommandContext<'a>,
     /// `jit add <paths>...`
     paths: Vec<PathBuf>,
+    /// `jit add --intent-to-add`
+    intent_to_add: bool,
+    /// `jit add --force`
+    force: bool,
 }
 
 impl<'a> Add<'a> {
     pub fn new(ctx: CommandContext<'a>) -> Self {
-        let paths = match &ctx.opt.cmd {
-            Command::Add { files } => files.to_owned(),
+        let (paths, intent_to_add, force) = match &ctx.opt.cmd {
+            Command::Add {
+                files,
+                intent_to_add,
+                force,
+            } => (files.to_owned(), *intent_to_add, *force),
             _ => unreachable!(),
         };
 
-        Self { ctx, paths }
+        Self {
+            ctx,
+            paths,
+            intent_to_add,
+            force,
+        }
     }
 
     pub fn run(&mut self) -> Result<()> {
@@ -36,6 +51,11 @@ impl<'a> Add<'a> {
             Err(err) => return self.handle_locked_index(err),
         }
 
+        let conflicts = self.ctx.repo.index.conflict_paths();
+        let mut resolved = BTreeSet::new();
+        let ignore = self.ctx.repo.ignore()?;
+        let mut ignored = vec![];
+
         let paths = self.paths.clone();
         for path in &paths {
             let path = match path.canonicalize() {
@@ -43,17 +63,93 @@ impl<'a> Add<'a> {
                 Err(err) => return self.handle_missing_file(&path_to_string(path), err),
             };
 
+            // Ignored files found in a directory are skipped, but naming one is an error
+            let named_file = path.is_file();
             for path in self.ctx.repo.workspace.list_files(&path)? {
+                if !self.force
+                    && ignore.ignores(&path, false)
+                    && !self.ctx.repo.index.tracked_file(&path)
+                {
+                    if named_file {
+                        ignored.push(path_to_string(&path));
+                    }
+                    continue;
+                }
+
+                let name = path_to_string(&path);
                 self.add_to_index(path)?;
+                if conflicts.contains(&name) {
+                    resolved.insert(name);
+                }
             }
         }
 
         self.ctx.repo.index.write_updates()?;
 
+        self.print_resolved(&resolved)?;
+        self.print_ignored(&ignored)
+    }
+
+    /// Refuse to add the ignored files that were named on the command line.
+    fn print_ignored(&self, ignored: &[String]) -> Result<()> {
+        if ignored.is_empty() {
+            return Ok(());
+        }
+
+        let mut stderr = self.ctx.stderr.borrow_mut();
+        writeln!(
+            stderr,
+            "The following paths are ignored by one of your .gitignore files:"
+        )?;
+        for path in ignored {
+            writeln!(stderr, "{}", path)?;
+        }
+        writeln!(stderr, "hint: Use -f if you really want to add them.")?;
+
+        Err(Error::Exit(1))
+    }
+
+    /// Confirm that the conflicts in `resolved` are marked as fixed, and point out the paths that
+    /// still need fixing before the merge can be committed.
+    fn print_resolved(&self, resolved: &BTreeSet<String>) -> Result<()> {
+        if resolved.is_empty() || self.ctx.quiet() {
+            return Ok(());
+        }
+
+        let mut stderr = self.ctx.stderr.borrow_mut();
+        for path in resolved {
+            writeln!(stderr, "Resolved conflict in '{}'", path)?;
+        }
+
+        let remaining = self.ctx.repo.index.conflict_paths();
+        if remaining.is_empty() {
+            writeln!(stderr, "All conflicts fixed; run 'jit commit' to conclude")?;
+        } else {
+            writeln!(stderr, "Still unmerged:")?;
+            for path in remaining {
+                writeln!(stderr, "\t{}", path)?;
+            }
+        }
+
         Ok(())
     }
 
     fn add_to_index(&mut self, path: PathBuf) -> Result<()> {
+        // Files left out by sparse checkout stay as they are in the index
+        let entry = self
+            .ctx
+            .repo
+            .index
+            .entry_for_path(&path_to_string(&path), 0);
+        if entry.is_some_and(|entry| entry.skip_worktree()) {
+            return Ok(());
+        }
+        let tracked_mode = entry.map(|entry| entry.mode);
+        // Like Git, `--intent-to-add` leaves files that are already tracked alone
+        if self.intent_to_add && self.ctx.repo.index.tracked_file(&path) {
+            return Ok(());
+        }
+
         let data = match self.ctx.repo.workspace.read_file(&path) {
             Ok(data) => data,
             Err(err) => return self.handle_unreadable_file(err),
@@ -63,9 +159,30 @@ impl<'a> Add<'a> {
             Err(err) => return self.handle_unreadable_file(err),
         };
 
+        if self.intent_to_add {
+            let blob = Blob::new(vec![]);
+            self.ctx.repo.database.store(&blob)?;
+            self.ctx
+                .repo
+                .index
+                .add_intent_to_add(path, blob.oid(), stat);
+            return Ok(());
+        }
+
         let blob = Blob::new(data);
         self.ctx.repo.database.store(&blob)?;
-        self.ctx.repo.index.add(path, blob.oid(), stat);
+        // Without `core.symlinks`, a tracked symbolic link is a plain file holding its target
+        if !self.ctx.repo.workspace.symlinks()
+            && tracked_mode == Some(SYMLINK_MODE)
+            && stat.is_file()
+        {
+            self.ctx
+                .repo
+                .index
+                .add_with_mode(path, blob.oid(), stat, SYMLINK_MODE);
+        } else {
+            self.ctx.repo.index.add(path, blob.oid(), stat);
+        }
 
         Ok(())
     }
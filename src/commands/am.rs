@@ -0,0 +1,283 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+
+use crate::commands::shared::commit_writer::CommitWriter;
+use crate::commands::{Command, CommandContext};
+use crate::database::object::Object;
+use crate::database::EMPTY_TREE_OID;
+use crate::errors::{Error, Result};
+use crate::patch::mailbox::{self, Message};
+use crate::repository::sequencer::Sequencer;
+
+const RESOLVE_NOTES: &str = "\
+When you have resolved this problem, run \"jit am --continue\".
+If you prefer to skip this patch, run \"jit am --skip\" instead.
+To restore the original branch and stop patching, run \"jit am --abort\".";
+
+enum Mode {
+    Run,
+    Continue,
+    Skip,
+    Abort,
+}
+
+pub struct Am<'a> {
+    ctx: CommandContext<'a>,
+    /// `jit am <mbox>...`
+    mailboxes: Vec<PathBuf>,
+    mode: Mode,
+}
+
+impl<'a> Am<'a> {
+    pub fn new(ctx: CommandContext<'a>) -> Self {
+        let (mailboxes, mode) = match &ctx.opt.cmd {
+            Command::Am {
+                mailboxes,
+                r#continue,
+                skip,
+                abort,
+            } => (
+                mailboxes.to_owned(),
+                if *r#continue {
+                    Mode::Continue
+                } else if *skip {
+                    Mode::Skip
+                } else if *abort {
+                    Mode::Abort
+                } else {
+                    Mode::Run
+                },
+            ),
+            _ => unreachable!(),
+        };
+
+        Self {
+            ctx,
+            mailboxes,
+            mode,
+        }
+    }
+
+    pub fn run(&mut self) -> Result<()> {
+        let mut sequencer = Sequencer::for_am(&self.ctx.repo);
+
+        match self.mode {
+            Mode::Run => {
+                if sequencer.load_mailbox()?.is_some() {
+                    let mut stderr = self.ctx.stderr.borrow_mut();
+                    writeln!(
+                        stderr,
+                        "fatal: previous jit am is still in progress, use --continue, --skip or --abort"
+                    )?;
+                    return Err(Error::Exit(128));
+                }
+                if sequencer.in_progress() {
+                    let mut stderr = self.ctx.stderr.borrow_mut();
+                    writeln!(
+                        stderr,
+                        "fatal: previous rebase directory .git/rebase-apply still exists but mbox given."
+                    )?;
+                    return Err(Error::Exit(128));
+                }
+
+                let messages = mailbox::split(&self.read_mailboxes()?);
+                if messages.is_empty() {
+                    let mut stderr = self.ctx.stderr.borrow_mut();
+                    writeln!(stderr, "fatal: Patch format detection failed.")?;
+                    return Err(Error::Exit(128));
+                }
+
+                sequencer.start(&HashMap::new())?;
+                self.apply_messages(&mut sequencer, messages)
+            }
+            Mode::Continue => {
+                let mut messages = self.load_messages(&sequencer)?;
+                let message = self.parse(&sequencer, &messages)?;
+                self.commit_resolved(&message)?;
+
+                messages.remove(0);
+                self.apply_messages(&mut sequencer, messages)
+            }
+            Mode::Skip => {
+                let mut messages = self.load_messages(&sequencer)?;
+
+                let head = self.ctx.repo.refs.read_head()?;
+                self.ctx.repo.index.load_for_update()?;
+                self.ctx
+                    .repo
+                    .hard_reset(head.as_deref().unwrap_or(EMPTY_TREE_OID))?;
+                self.ctx.repo.index.write_updates()?;
+
+                messages.remove(0);
+                self.apply_messages(&mut sequencer, messages)
+            }
+            Mode::Abort => {
+                self.load_messages(&sequencer)?;
+
+                // `sequencer.abort()` resets the index of `sequencer.repo`, not `self.ctx.repo`
+                sequencer.repo.index.load_for_update()?;
+                if let Err(err) = sequencer.abort() {
+                    let mut stderr = self.ctx.stderr.borrow_mut();
+                    writeln!(stderr, "warning: {}", err)?;
+                }
+                sequencer.repo.index.write_updates()?;
+
+                Ok(())
+            }
+        }
+    }
+
+    fn read_mailboxes(&self) -> Result<String> {
+        if self.mailboxes.is_empty() {
+            let mut mbox = String::new();
+            self.ctx.stdin.borrow_mut().read_to_string(&mut mbox)?;
+            return Ok(mbox);
+        }
+
+        let mut mbox = String::new();
+        for path in &self.mailboxes {
+            match fs::read_to_string(self.ctx.dir.join(path)) {
+                Ok(contents) => mbox.push_str(&contents),
+                Err(err) => {
+                    let mut stderr = self.ctx.stderr.borrow_mut();
+                    writeln!(
+                        stderr,
+                        "fatal: could not open '{}': {}",
+                        path.display(),
+                        err
+                    )?;
+                    return Err(Error::Exit(128));
+                }
+            }
+        }
+
+        Ok(mbox)
+    }
+
+    fn load_messages(&self, sequencer: &Sequencer) -> Result<Vec<String>> {
+        match sequencer.load_mailbox()? {
+            Some(mbox) => Ok(mailbox::split(&mbox)),
+            None => {
+                let mut stderr = self.ctx.stderr.borrow_mut();
+                writeln!(
+                    stderr,
+                    "fatal: Resolve operation not in progress, we are not resuming."
+                )?;
+                Err(Error::Exit(128))
+            }
+        }
+    }
+
+    /// Apply each message's patch and commit it as the message's author, stopping at the first
+    /// patch that doesn't apply so it can be resolved by hand.
+    fn apply_messages(&mut self, sequencer: &mut Sequencer, messages: Vec<String>) -> Result<()> {
+        let mut remaining = &messages[..];
+        while !remaining.is_empty() {
+            let message = self.parse(sequencer, remaining)?;
+            {
+                let mut stdout = self.ctx.stdout.borrow_mut();
+                writeln!(stdout, "Applying: {}", message.title_line())?;
+            }
+
+            self.ctx.repo.index.load_for_update()?;
+            match self.ctx.repo.apply_patch(&message.patch) {
+                Ok(()) => self.ctx.repo.index.write_updates()?,
+                Err(
+                    err @ (Error::PatchFailed(..)
+                    | Error::PatchMissingFile(..)
+                    | Error::PatchExistingFile(..)),
+                ) => {
+                    self.ctx.repo.index.release_lock()?;
+                    return self.fail_on_patch(sequencer, remaining, &message, err);
+                }
+                Err(err) => return Err(err),
+            }
+
+            self.commit_writer().write_commit_as(
+                self.ctx.repo.refs.read_head()?.into_iter().collect(),
+                Some(&message.message),
+                Some(message.author),
+            )?;
+            sequencer.update_abort_safety()?;
+
+            remaining = &remaining[1..];
+        }
+
+        sequencer.quit()?;
+
+        Ok(())
+    }
+
+    fn fail_on_patch(
+        &self,
+        sequencer: &mut Sequencer,
+        remaining: &[String],
+        message: &Message,
+        err: Error,
+    ) -> Result<()> {
+        sequencer.save_mailbox(remaining)?;
+        sequencer.dump()?;
+
+        let mut stderr = self.ctx.stderr.borrow_mut();
+        writeln!(stderr, "error: {}", err)?;
+        writeln!(stderr, "Patch failed at {}", message.title_line())?;
+
+        let mut stdout = self.ctx.stdout.borrow_mut();
+        writeln!(stdout, "{}", RESOLVE_NOTES)?;
+
+        Err(Error::Exit(1))
+    }
+
+    /// Commit the changes made by hand to resolve `message`, whose patch didn't apply.
+    fn commit_resolved(&mut self, message: &Message) -> Result<()> {
+        self.ctx.repo.index.load()?;
+
+        let commit_writer = self.commit_writer();
+        let head = self.ctx.repo.refs.read_head()?;
+        let head_tree = match &head {
+            Some(head) => Some(self.ctx.repo.database.load_commit(head)?.tree),
+            None => None,
+        };
+        if head_tree == Some(commit_writer.write_tree().oid()) {
+            let mut stderr = self.ctx.stderr.borrow_mut();
+            writeln!(stderr, "No changes - did you forget to use 'jit add'?")?;
+            writeln!(
+                stderr,
+                "If there is nothing left to stage, chances are that something else"
+            )?;
+            writeln!(
+                stderr,
+                "already introduced the same changes; you might want to skip this patch."
+            )?;
+            return Err(Error::Exit(1));
+        }
+
+        commit_writer.write_commit_as(
+            head.into_iter().collect(),
+            Some(&message.message),
+            Some(message.author.clone()),
+        )?;
+
+        Ok(())
+    }
+
+    fn parse(&self, sequencer: &Sequencer, messages: &[String]) -> Result<Message> {
+        match Message::parse(&messages[0]) {
+            Ok(message) => Ok(message),
+            Err(err @ (Error::EmptyPatch | Error::CorruptPatch(..))) => {
+                sequencer.save_mailbox(messages)?;
+
+                let mut stderr = self.ctx.stderr.borrow_mut();
+                writeln!(stderr, "fatal: {}", err)?;
+                Err(Error::Exit(128))
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn commit_writer(&self) -> CommitWriter<'_> {
+        CommitWriter::new(&self.ctx)
+    }
+}
@@ -6,8 +6,10 @@ use crate::commands::{Command, CommandContext};
 use crate::database::object::Object;
 use crate::database::Database;
 use crate::errors::{Error, Result};
-use crate::refs::{Ref, HEAD};
+use crate::merge::bases::Bases;
+use crate::refs::{Ref, HEAD, HEADS_DIR};
 use crate::revision::{Revision, COMMIT};
+use crate::util::path_to_string;
 
 pub struct Branch<'a> {
     ctx: CommandContext<'a>,
@@ -67,22 +69,20 @@ impl<'a> Branch<'a> {
                 let mut revision = Revision::new(&self.ctx.repo, start_point);
                 match revision.resolve(Some(COMMIT)) {
                     Ok(start_oid) => start_oid,
-                    Err(err) => match err {
-                        Error::InvalidObject(..) => {
-                            let mut stderr = self.ctx.stderr.borrow_mut();
-
-                            for error in revision.errors {
-                                writeln!(stderr, "error: {}", error.message)?;
-                                for line in error.hint {
-                                    writeln!(stderr, "hint: {}", line)?;
-                                }
-                            }
+                    Err(err) if err.is_revision_error() => {
+                        let mut stderr = self.ctx.stderr.borrow_mut();
 
-                            writeln!(stderr, "fatal: {}", err)?;
-                            return Err(Error::Exit(128));
+                        for error in revision.errors {
+                            writeln!(stderr, "error: {}", error.message)?;
+                            for line in error.hint {
+                                writeln!(stderr, "hint: {}", line)?;
+                            }
                         }
-                        _ => return Err(err),
-                    },
+
+                        writeln!(stderr, "fatal: Not a valid object name: '{}'.", start_point)?;
+                        return Err(Error::Exit(128));
+                    }
+                    Err(err) => return Err(err),
                 }
             }
             None => self.ctx.repo.refs.read_head()?.unwrap(),
@@ -103,11 +103,7 @@ impl<'a> Branch<'a> {
 
     fn list_branches(&mut self) -> Result<()> {
         let current = self.ctx.repo.refs.current_ref(HEAD)?;
-        let mut branches = self.ctx.repo.refs.list_branches()?;
-        branches.sort_by_key(|branch| match branch {
-            Ref::SymRef { path } => path.to_owned(),
-            Ref::Ref { .. } => unreachable!(),
-        });
+        let branches = self.ctx.repo.refs.list_branches()?;
 
         let max_width = branches
             .iter()
@@ -163,8 +159,19 @@ impl<'a> Branch<'a> {
     }
 
     fn delete_branch(&self, branch_name: &str) -> Result<()> {
-        if !self.force {
-            return Ok(());
+        if !self.force && !self.is_merged(branch_name)? {
+            let mut stderr = self.ctx.stderr.borrow_mut();
+            writeln!(
+                stderr,
+                "error: The branch '{}' is not fully merged.",
+                branch_name
+            )?;
+            writeln!(
+                stderr,
+                "If you are sure you want to delete it, run 'jit branch -D {}'.",
+                branch_name
+            )?;
+            return Err(Error::Exit(1));
         }
 
         match self.ctx.repo.refs.delete_branch(branch_name) {
@@ -186,4 +193,27 @@ impl<'a> Branch<'a> {
             },
         }
     }
+
+    /// Whether the branch `branch_name` is merged into its upstream, or into `HEAD` if it has
+    /// none, so deleting it loses no commits. A branch that doesn't exist counts as merged, so
+    /// deleting it reports that it wasn't found.
+    fn is_merged(&self, branch_name: &str) -> Result<bool> {
+        let refs = &self.ctx.repo.refs;
+        let branch_oid = match refs.read_ref(&path_to_string(&HEADS_DIR.join(branch_name)))? {
+            Some(oid) => oid,
+            None => return Ok(true),
+        };
+        let target_oid = match self.ctx.repo.remotes.get_upstream(branch_name)? {
+            Some(upstream) => refs.read_ref(&upstream)?,
+            None => refs.read_head()?,
+        };
+
+        match target_oid {
+            Some(target_oid) => {
+                let mut common = Bases::new(&self.ctx.repo.database, &branch_oid, &target_oid)?;
+                Ok(common.find()? == vec![branch_oid])
+            }
+            None => Ok(false),
+        }
+    }
 }
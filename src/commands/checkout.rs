@@ -1,11 +1,15 @@
 use std::io::Write;
+use std::path::{Component, Path, PathBuf};
 
 use crate::commands::{Command, CommandContext};
+use crate::database::tree::TreeEntry;
 use crate::database::tree_diff::Differ;
 use crate::database::Database;
 use crate::errors::{Error, Result};
 use crate::refs::{Ref, HEAD};
+use crate::rev_list::RevList;
 use crate::revision::{Revision, COMMIT};
+use crate::util::{path_to_string, plural};
 
 const DETACHED_HEAD_MESSAGE: &str = "\
 You are in 'detached HEAD' state. You can look around, make experimental
@@ -21,25 +25,60 @@ pub struct Checkout<'a> {
     ctx: CommandContext<'a>,
     /// `jit checkout <target>`
     target: String,
+    /// `jit checkout [<target>] -- <paths>...`
+    paths: Vec<PathBuf>,
+    /// `jit checkout -- <paths>...`, restoring the paths from the index
+    from_index: bool,
 }
 
 impl<'a> Checkout<'a> {
     pub fn new(ctx: CommandContext<'a>) -> Self {
-        let target = match &ctx.opt.cmd {
-            Command::Checkout { tree_ish } => tree_ish.to_owned(),
+        let (target, paths) = match &ctx.opt.cmd {
+            Command::Checkout { tree_ish, paths } => (tree_ish.to_owned(), paths.to_owned()),
             _ => unreachable!(),
         };
 
-        Self { ctx, target }
+        Self {
+            ctx,
+            from_index: target.is_none(),
+            target: target.unwrap_or_default(),
+            paths,
+        }
     }
 
     pub fn run(&mut self) -> Result<()> {
+        if self.from_index {
+            return self.checkout_paths(None);
+        }
+
         let current_ref = self.ctx.repo.refs.current_ref(HEAD)?;
         let current_oid = self.ctx.repo.refs.read_oid(&current_ref)?.unwrap();
 
+        // Without `--`, the target may name a revision or a path, but not both
+        let target_is_path = self.paths.is_empty() && self.target_is_path()?;
+
         let mut revision = Revision::new(&self.ctx.repo, &self.target);
         let target_oid = match revision.resolve(Some(COMMIT)) {
+            Ok(_) if target_is_path => {
+                let mut stderr = self.ctx.stderr.borrow_mut();
+                writeln!(
+                    stderr,
+                    "fatal: ambiguous argument '{}': both revision and filename",
+                    self.target
+                )?;
+                writeln!(
+                    stderr,
+                    "Use '--' to separate paths from revisions, like this:"
+                )?;
+                writeln!(stderr, "'jit <command> [<revision>...] -- [<file>...]'")?;
+
+                return Err(Error::Exit(128));
+            }
             Ok(oid) => oid,
+            Err(_) if target_is_path => {
+                self.paths = vec![PathBuf::from(&self.target)];
+                return self.checkout_paths(None);
+            }
             Err(error) => {
                 let mut stderr = self.ctx.stderr.borrow_mut();
 
@@ -55,13 +94,17 @@ impl<'a> Checkout<'a> {
             }
         };
 
+        if !self.paths.is_empty() {
+            return self.checkout_paths(Some(&target_oid));
+        }
+
         self.ctx.repo.index.load_for_update()?;
 
-        let tree_diff =
-            self.ctx
-                .repo
-                .database
-                .tree_diff(Some(&current_oid), Some(&target_oid), None)?;
+        let tree_diff = self
+            .ctx
+            .repo
+            .database
+            .tree_diff(&current_oid, &target_oid, None)?;
         let mut migration = self.ctx.repo.migration(tree_diff);
 
         match migration.apply_changes() {
@@ -78,17 +121,128 @@ impl<'a> Checkout<'a> {
 
                 return Err(Error::Exit(1));
             }
-            Err(err) => return Err(err),
+            Err(err) => {
+                self.ctx.repo.index.release_lock()?;
+                return Err(err);
+            }
         }
 
         self.ctx.repo.index.write_updates()?;
         self.ctx.repo.refs.set_head(&self.target, &target_oid)?;
         let new_ref = self.ctx.repo.refs.current_ref(HEAD)?;
 
+        if self.ctx.quiet() {
+            return Ok(());
+        }
+
         let target = self.target.clone();
         self.print_previous_head(&current_ref, &current_oid, &target_oid)?;
         self.print_detachment_notice(&current_ref, &new_ref, &target)?;
         self.print_new_head(&current_ref, &new_ref, &target, &target_oid)?;
+        self.print_tracking_info(&new_ref, &target_oid)?;
+
+        Ok(())
+    }
+
+    /// Whether the target names a file in the workspace or a path in the index.
+    fn target_is_path(&mut self) -> Result<bool> {
+        let path = Path::new(&self.target);
+        if self.ctx.repo.workspace.stat_file(path)?.is_some() {
+            return Ok(true);
+        }
+
+        self.ctx.repo.index.load()?;
+        Ok(self.ctx.repo.index.tracked(path))
+    }
+
+    /// The files at `path` in the index, with their object IDs and modes.
+    fn index_entries(&self, path: &Path) -> Vec<(String, String, u32)> {
+        let index = &self.ctx.repo.index;
+        let path = PathBuf::from(index.canonical_path(&path_to_string(path)));
+
+        index
+            .entries
+            .values()
+            .filter(|entry| entry.stage() == 0 && Path::new(&entry.path).starts_with(&path))
+            .map(|entry| (entry.path.clone(), entry.oid.clone(), entry.mode))
+            .collect()
+    }
+
+    /// Overwrite the given paths in the index and workspace with their versions in `target_oid`,
+    /// or the workspace with their versions in the index if there's no target, leaving HEAD and
+    /// every other path alone.
+    fn checkout_paths(&mut self, target_oid: Option<&str>) -> Result<()> {
+        self.ctx.repo.index.load_for_update()?;
+
+        let mut entries = vec![];
+        for path in &self.paths {
+            // `.` names every path, and `dir/` the same paths as `dir`
+            let pathspec: PathBuf = path
+                .components()
+                .filter(|component| component != &Component::CurDir)
+                .collect();
+            let listing: Vec<_> = match target_oid {
+                Some(target_oid) => self
+                    .ctx
+                    .repo
+                    .database
+                    .load_tree_list(Some(target_oid), Some(&pathspec))?
+                    .into_iter()
+                    .map(|(path, entry)| match entry {
+                        TreeEntry::Entry(entry) => (path, entry.oid.clone(), entry.mode()),
+                        TreeEntry::Tree(_tree) => unreachable!(),
+                    })
+                    .collect(),
+                None => self.index_entries(&pathspec),
+            };
+            if listing.is_empty() {
+                self.ctx.repo.index.release_lock()?;
+
+                let mut stderr = self.ctx.stderr.borrow_mut();
+                writeln!(
+                    stderr,
+                    "error: pathspec '{}' did not match any file(s) known to jit",
+                    path_to_string(path)
+                )?;
+                return Err(Error::Exit(1));
+            }
+            entries.extend(listing);
+        }
+
+        for (path, oid, mode) in &entries {
+            let path = PathBuf::from(path);
+            let blob = self.ctx.repo.database.load_blob(oid)?;
+            self.ctx
+                .repo
+                .workspace
+                .write_file(&path, &blob.data, Some(*mode), true)?;
+            let stat = self.ctx.repo.workspace.stat_file(&path)?.unwrap();
+            self.ctx
+                .repo
+                .index
+                .add_with_mode(path, oid.clone(), stat, *mode);
+        }
+
+        self.ctx.repo.index.write_updates()?;
+        if self.ctx.quiet() {
+            return Ok(());
+        }
+
+        let source = match target_oid {
+            Some(target_oid) => {
+                let tree_oid = self.ctx.repo.database.load_commit(target_oid)?.tree;
+                Database::short_oid(&tree_oid)
+            }
+            None => String::from("the index"),
+        };
+        let mut stderr = self.ctx.stderr.borrow_mut();
+        writeln!(
+            stderr,
+            "Updated {} path{} from {}",
+            entries.len(),
+            if entries.len() == 1 { "" } else { "s" },
+            source
+        )?;
 
         Ok(())
     }
@@ -106,13 +260,23 @@ impl<'a> Checkout<'a> {
         Ok(())
     }
 
+    /// Whether to explain the detached HEAD state, which `advice.detachedHead` can turn off.
+    fn advise_detached_head(&self) -> Result<bool> {
+        let config = &self.ctx.repo.config;
+        config.open()?;
+
+        Ok(config
+            .get_bool(&[String::from("advice"), String::from("detachedHead")])
+            .unwrap_or(true))
+    }
+
     fn print_detachment_notice(
         &self,
         current_ref: &Ref,
         new_ref: &Ref,
         target: &str,
     ) -> Result<()> {
-        if new_ref.is_head() && !current_ref.is_head() {
+        if new_ref.is_head() && !current_ref.is_head() && self.advise_detached_head()? {
             let mut stderr = self.ctx.stderr.borrow_mut();
 
             writeln!(stderr, "Note: checking out '{}'.", target)?;
@@ -143,6 +307,85 @@ impl<'a> Checkout<'a> {
         Ok(())
     }
 
+    /// Compare the branch checked out with its upstream, if it has one, like `git checkout`.
+    fn print_tracking_info(&self, new_ref: &Ref, target_oid: &str) -> Result<()> {
+        if new_ref.is_head() {
+            return Ok(());
+        }
+
+        let refs = &self.ctx.repo.refs;
+        let branch = refs.short_name(new_ref);
+        let upstream = match self.ctx.repo.remotes.get_upstream(&branch)? {
+            Some(upstream) => upstream,
+            None => return Ok(()),
+        };
+        let upstream_name = refs.short_name(&Ref::SymRef {
+            path: upstream.clone(),
+        });
+
+        let mut stdout = self.ctx.stdout.borrow_mut();
+
+        let upstream_oid = match refs.read_ref(&upstream)? {
+            Some(oid) => oid,
+            None => {
+                writeln!(
+                    stdout,
+                    "Your branch is based on '{}', but the upstream is gone.",
+                    upstream_name
+                )?;
+                return Ok(());
+            }
+        };
+
+        let count = |range: String| -> Result<usize> {
+            Ok(RevList::new(&self.ctx.repo, &[range], Default::default())?.count())
+        };
+        let ahead = count(format!("{}..{}", upstream_oid, target_oid))?;
+        let behind = count(format!("{}..{}", target_oid, upstream_oid))?;
+
+        match (ahead, behind) {
+            (0, 0) => writeln!(
+                stdout,
+                "Your branch is up to date with '{}'.",
+                upstream_name
+            )?,
+            (ahead, 0) => {
+                writeln!(
+                    stdout,
+                    "Your branch is ahead of '{}' by {} commit{}.",
+                    upstream_name,
+                    ahead,
+                    plural(ahead)
+                )?;
+                writeln!(stdout, "  (use \"jit push\" to publish your local commits)")?;
+            }
+            (0, behind) => {
+                writeln!(
+                    stdout,
+                    "Your branch is behind '{}' by {} commit{}, and can be fast-forwarded.",
+                    upstream_name,
+                    behind,
+                    plural(behind)
+                )?;
+                writeln!(stdout, "  (use \"jit pull\" to update your local branch)")?;
+            }
+            (ahead, behind) => {
+                writeln!(stdout, "Your branch and '{}' have diverged,", upstream_name)?;
+                writeln!(
+                    stdout,
+                    "and have {} and {} different commits each, respectively.",
+                    ahead, behind
+                )?;
+                writeln!(
+                    stdout,
+                    "  (use \"jit pull\" to merge the remote branch into yours)"
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+
     fn print_head_position(&self, message: &str, oid: &str) -> Result<()> {
         let commit = self.ctx.repo.database.load_commit(oid)?;
         let short = Database::short_oid(oid);
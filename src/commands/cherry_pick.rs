@@ -16,23 +16,26 @@ use crate::refs::HEAD;
 use crate::repository::pending_commit::PendingCommitType;
 use crate::repository::sequencer::Sequencer;
 use crate::rev_list::{RevList, RevListOptions};
+use crate::trailers::add_cherry_pick_origin;
 
 pub struct CherryPick<'a> {
     ctx: CommandContext<'a>,
     args: Vec<String>,
     mode: Mode,
     mainline: Option<u32>,
+    record_origin: bool,
 }
 
 impl<'a> CherryPick<'a> {
     pub fn new(ctx: CommandContext<'a>) -> Self {
-        let (args, mode, mainline) = match &ctx.opt.cmd {
+        let (args, mode, mainline, record_origin) = match &ctx.opt.cmd {
             Command::CherryPick {
                 args,
                 r#continue,
                 abort,
                 quit,
                 mainline,
+                record_origin,
             } => (
                 args.to_owned(),
                 if *r#continue {
@@ -45,6 +48,7 @@ impl<'a> CherryPick<'a> {
                     Mode::Run
                 },
                 mainline.to_owned(),
+                *record_origin,
             ),
             _ => unreachable!(),
         };
@@ -54,6 +58,7 @@ impl<'a> CherryPick<'a> {
             args,
             mode,
             mainline,
+            record_origin,
         }
     }
 
@@ -64,6 +69,9 @@ impl<'a> CherryPick<'a> {
         if let Some(mainline) = self.mainline {
             options.insert("mainline", VariableValue::Int(mainline as i32));
         }
+        if self.record_origin {
+            options.insert("record-origin", VariableValue::Bool(true));
+        }
 
         match self.mode {
             Mode::Continue => self.handle_continue(&mut sequencer)?,
@@ -94,8 +102,15 @@ impl<'a> CherryPick<'a> {
 
     fn store_commit_sequence(&self, sequencer: &mut Sequencer) -> Result<()> {
         let args: Vec<_> = self.args.iter().map(|s| s.to_owned()).rev().collect();
-        let commits: Vec<_> =
-            RevList::new(&self.ctx.repo, &args, RevListOptions { walk: false })?.collect();
+        let commits: Vec<_> = RevList::new(
+            &self.ctx.repo,
+            &args,
+            RevListOptions {
+                walk: false,
+                ..Default::default()
+            },
+        )?
+        .collect::<Result<_>>()?;
         for commit in commits.iter().rev() {
             sequencer.pick(commit);
         }
@@ -105,6 +120,11 @@ impl<'a> CherryPick<'a> {
 
     fn pick(&mut self, sequencer: &mut Sequencer, commit: &Commit) -> Result<()> {
         let inputs = self.pick_merge_inputs(sequencer, commit)?;
+        let message = if self.record_origin {
+            add_cherry_pick_origin(&commit.message, &commit.oid())
+        } else {
+            commit.message.clone()
+        };
 
         resolve_merge(&mut self.ctx.repo, &inputs)?;
 
@@ -117,7 +137,7 @@ impl<'a> CherryPick<'a> {
                 sequencer,
                 &inputs,
                 PendingCommitType::CherryPick,
-                &commit.message,
+                &message,
             )?;
         }
 
@@ -126,7 +146,7 @@ impl<'a> CherryPick<'a> {
             commit_writer.write_tree().oid(),
             commit.author.clone(),
             commit_writer.current_author(),
-            commit.message.clone(),
+            message,
         );
 
         finish_commit(&self.ctx.repo, &commit_writer, &picked)?;
@@ -145,7 +165,7 @@ impl<'a> CherryPick<'a> {
         let left_name = HEAD.to_owned();
         let left_oid = self.ctx.repo.refs.read_head()?.unwrap();
 
-        let right_name = format!("{}... {}", short, commit.title_line().trim());
+        let right_name = format!("{}... {}", short, commit.title_line());
         let right_oid = commit.oid();
 
         Ok(inputs::CherryPick::new(
@@ -159,9 +179,13 @@ impl<'a> CherryPick<'a> {
 
     fn handle_continue(&mut self, sequencer: &mut Sequencer) -> Result<()> {
         self.ctx.repo.index.load()?;
+        self.record_origin = matches!(
+            sequencer.get_option("record-origin")?,
+            Some(VariableValue::Bool(true))
+        );
 
         if self.commit_writer().pending_commit.in_progress() {
-            match self.commit_writer().write_cherry_pick_commit() {
+            match self.commit_writer().write_cherry_pick_commit(None) {
                 Ok(()) => (),
                 Err(err) => match err {
                     Error::NoMergeInProgress(..) => {
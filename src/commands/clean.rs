@@ -0,0 +1,229 @@
+use std::io::{BufRead, Write};
+use std::path::Path;
+
+use crate::commands::{Command, CommandContext};
+use crate::errors::{Error, Result};
+use crate::util::glob_match;
+
+const MENU: &str = "\
+*** Commands ***
+    1: clean                2: filter by pattern    3: select by numbers    4: quit";
+
+pub struct Clean<'a> {
+    ctx: CommandContext<'a>,
+    /// `jit clean -n`
+    dry_run: bool,
+    /// `jit clean -f`
+    force: bool,
+    /// `jit clean -i`
+    interactive: bool,
+    /// `jit clean -d`
+    directories: bool,
+}
+
+impl<'a> Clean<'a> {
+    pub fn new(ctx: CommandContext<'a>) -> Self {
+        let (dry_run, force, interactive, directories) = match &ctx.opt.cmd {
+            Command::Clean {
+                dry_run,
+                force,
+                interactive,
+                directories,
+            } => (*dry_run, *force, *interactive, *directories),
+            _ => unreachable!(),
+        };
+
+        Self {
+            ctx,
+            dry_run,
+            force,
+            interactive,
+            directories,
+        }
+    }
+
+    pub fn run(&mut self) -> Result<()> {
+        if !(self.force || self.dry_run || self.interactive) {
+            let mut stderr = self.ctx.stderr.borrow_mut();
+            writeln!(
+                stderr,
+                "fatal: clean.requireForce defaults to true and neither -i, -n, nor -f given; refusing to clean"
+            )?;
+            return Err(Error::Exit(128));
+        }
+
+        let paths = self.untracked_paths()?;
+
+        if self.dry_run {
+            let mut stdout = self.ctx.stdout.borrow_mut();
+            for path in &paths {
+                writeln!(stdout, "Would remove {}", path)?;
+            }
+            return Ok(());
+        }
+
+        let paths = if self.interactive {
+            match self.select_interactively(paths)? {
+                Some(paths) => paths,
+                None => return Ok(()),
+            }
+        } else {
+            paths
+        };
+
+        self.remove_paths(&paths)
+    }
+
+    /// The untracked files, and the untracked directories with `-d`, which are listed with a
+    /// trailing slash.
+    fn untracked_paths(&mut self) -> Result<Vec<String>> {
+        self.ctx.repo.index.load()?;
+        let mut status = self.ctx.repo.status(None);
+        status.initialize()?;
+
+        Ok(status
+            .untracked_files
+            .into_iter()
+            .filter(|path| self.directories || !path.ends_with('/'))
+            .collect())
+    }
+
+    fn remove_paths(&self, paths: &[String]) -> Result<()> {
+        let mut stdout = self.ctx.stdout.borrow_mut();
+        for path in paths {
+            writeln!(stdout, "Removing {}", path)?;
+            self.ctx
+                .repo
+                .workspace
+                .remove(Path::new(path.trim_end_matches('/')))?;
+        }
+
+        Ok(())
+    }
+
+    /// Show the menu until the user chooses to clean or quit, returning the paths to remove, or
+    /// `None` if nothing should be removed.
+    fn select_interactively(&self, mut paths: Vec<String>) -> Result<Option<Vec<String>>> {
+        loop {
+            if paths.is_empty() {
+                let mut stdout = self.ctx.stdout.borrow_mut();
+                writeln!(stdout, "No more files to clean, exiting.")?;
+                return Ok(None);
+            }
+
+            {
+                let mut stdout = self.ctx.stdout.borrow_mut();
+                writeln!(stdout, "Would remove the following items:")?;
+                for path in &paths {
+                    writeln!(stdout, "  {}", path)?;
+                }
+                writeln!(stdout, "{}", MENU)?;
+            }
+
+            let choice = match self.prompt("What now> ")? {
+                Some(choice) => choice,
+                None => return Ok(None),
+            };
+            match choice.as_str() {
+                "1" | "c" | "clean" => return Ok(Some(paths)),
+                "2" | "f" | "filter by pattern" => {
+                    if let Some(patterns) = self.prompt("Input ignore patterns>> ")? {
+                        paths = filter_by_patterns(paths, &patterns);
+                    }
+                }
+                "3" | "s" | "select by numbers" => {
+                    {
+                        let mut stdout = self.ctx.stdout.borrow_mut();
+                        for (i, path) in paths.iter().enumerate() {
+                            writeln!(stdout, "  {}: {}", i + 1, path)?;
+                        }
+                    }
+                    if let Some(numbers) = self.prompt("Select items to delete>> ")? {
+                        paths = select_by_numbers(paths, &numbers);
+                    }
+                }
+                "4" | "q" | "quit" => {
+                    let mut stdout = self.ctx.stdout.borrow_mut();
+                    writeln!(stdout, "Bye.")?;
+                    return Ok(None);
+                }
+                "" => (),
+                _ => {
+                    let mut stdout = self.ctx.stdout.borrow_mut();
+                    writeln!(stdout, "Huh ({})?", choice)?;
+                }
+            }
+        }
+    }
+
+    /// Print `prompt` and read a line of input, or `None` at the end of the input.
+    fn prompt(&self, prompt: &str) -> Result<Option<String>> {
+        {
+            let mut stdout = self.ctx.stdout.borrow_mut();
+            write!(stdout, "{}", prompt)?;
+            stdout.flush()?;
+        }
+
+        let mut line = String::new();
+        if self.ctx.stdin.borrow_mut().read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        Ok(Some(line.trim().to_owned()))
+    }
+}
+
+/// Keep the paths that don't match any of the space-separated glob `patterns`. Like
+/// `.gitignore`, a pattern without a slash is matched against the filename.
+fn filter_by_patterns(paths: Vec<String>, patterns: &str) -> Vec<String> {
+    let patterns: Vec<_> = patterns.split_whitespace().collect();
+
+    paths
+        .into_iter()
+        .filter(|path| {
+            let path = path.trim_end_matches('/');
+            let filename = path.rsplit('/').next().unwrap();
+            !patterns.iter().any(|pattern| {
+                if pattern.contains('/') {
+                    glob_match(pattern.trim_start_matches('/'), path)
+                } else {
+                    glob_match(pattern, filename)
+                }
+            })
+        })
+        .collect()
+}
+
+/// Keep the paths chosen by `numbers`, a list of 1-based indexes and ranges like `1 3-4`, or `*`
+/// for all of them. An empty selection keeps every path.
+fn select_by_numbers(paths: Vec<String>, numbers: &str) -> Vec<String> {
+    if numbers.is_empty() {
+        return paths;
+    }
+
+    let mut selected = vec![false; paths.len()];
+    for choice in numbers.split(|c: char| c == ',' || c.is_whitespace()) {
+        let (start, end) = match choice {
+            "" => continue,
+            "*" => (1, paths.len()),
+            _ => match choice.split_once('-') {
+                Some((start, "")) => (start.parse().unwrap_or(0), paths.len()),
+                Some((start, end)) => (start.parse().unwrap_or(0), end.parse().unwrap_or(0)),
+                None => {
+                    let number = choice.parse().unwrap_or(0);
+                    (number, number)
+                }
+            },
+        };
+
+        for number in start.max(1)..=end.min(paths.len()) {
+            selected[number - 1] = true;
+        }
+    }
+
+    paths
+        .into_iter()
+        .zip(selected)
+        .filter_map(|(path, selected)| selected.then_some(path))
+        .collect()
+}
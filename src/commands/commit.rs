@@ -1,12 +1,19 @@
+use std::io::Write;
 use std::path::PathBuf;
 
+use chrono::{DateTime, FixedOffset, Local};
+
 use crate::commands::shared::commit_writer::CommitWriter;
+use crate::commands::shared::diff_printer::DiffPrinter;
 use crate::commands::{Command, CommandContext};
+use crate::database::author::{parse_date, Author};
 use crate::database::commit::Commit as DatabaseCommit;
 use crate::database::object::Object;
-use crate::editor::Editor;
+use crate::database::EMPTY_TREE_OID;
+use crate::editor::{cleanup_message, Cleanup, Editor};
 use crate::errors::{Error, Result};
 use crate::revision::{Revision, COMMIT};
+use crate::trailers::{self, Trailer};
 
 pub const COMMIT_NOTES: &str = "\
 Please enter the commit message for your changes. Lines starting
@@ -19,11 +26,38 @@ pub struct Commit<'a> {
     edit: bool,
     reuse: Option<String>,
     amend: bool,
+    /// `jit commit --date <date>`
+    date: Option<String>,
+    /// `jit commit --verbose`
+    verbose: bool,
+    /// `jit commit --trailer <token: value>...`
+    trailers: Vec<String>,
+    /// `jit commit --signoff`
+    signoff: bool,
+    /// `jit commit --cleanup <mode>`
+    cleanup: Cleanup,
+    /// `jit commit --gpg-sign` or `--no-gpg-sign`, overriding `commit.gpgSign`
+    gpg_sign: Option<bool>,
+    /// `jit commit --gpg-sign=<keyid>`
+    key_id: Option<String>,
 }
 
 impl<'a> Commit<'a> {
     pub fn new(ctx: CommandContext<'a>) -> Self {
-        let (message, file, edit, reuse, amend) = match &ctx.opt.cmd {
+        let (
+            message,
+            file,
+            edit,
+            reuse,
+            amend,
+            date,
+            verbose,
+            trailers,
+            signoff,
+            cleanup,
+            gpg_sign,
+            key_id,
+        ) = match &ctx.opt.cmd {
             Command::Commit {
                 message,
                 file,
@@ -32,6 +66,14 @@ impl<'a> Commit<'a> {
                 reuse_message,
                 reedit_message,
                 amend,
+                date,
+                verbose,
+                trailers,
+                signoff,
+                cleanup,
+                gpg_sign,
+                no_gpg_sign,
+                ..
             } => (
                 message.as_ref().map(|m| m.to_owned()),
                 file.as_ref().map(|f| f.to_owned()),
@@ -42,6 +84,17 @@ impl<'a> Commit<'a> {
                     .to_owned()
                     .or_else(|| reuse_message.to_owned()),
                 *amend,
+                date.to_owned(),
+                *verbose,
+                trailers.to_owned(),
+                *signoff,
+                *cleanup,
+                if *no_gpg_sign {
+                    Some(false)
+                } else {
+                    gpg_sign.as_ref().map(|_| true)
+                },
+                gpg_sign.to_owned().flatten(),
             ),
             _ => unreachable!(),
         };
@@ -53,21 +106,42 @@ impl<'a> Commit<'a> {
             edit,
             reuse,
             amend,
+            date,
+            verbose,
+            trailers,
+            signoff,
+            cleanup,
+            gpg_sign,
+            key_id,
         }
     }
 
     pub fn run(&mut self) -> Result<()> {
         self.ctx.repo.index.load()?;
 
+        let author_date = match self.author_date() {
+            Ok(date) => date,
+            Err(err) => {
+                let mut stderr = self.ctx.stderr.borrow_mut();
+                writeln!(stderr, "fatal: {}", err)?;
+                return Err(Error::Exit(128));
+            }
+        };
+
+        let mut commit_writer = self.commit_writer();
+        commit_writer.signing_key = self.signing_key(&commit_writer);
+
         if self.amend {
-            self.handle_amend()?;
+            self.handle_amend(&commit_writer, author_date)?;
         }
 
-        let commit_writer = self.commit_writer();
+        let message = commit_writer.read_message(self.message.as_deref(), self.file.as_deref())?;
 
         let merge_type = commit_writer.pending_commit.merge_type();
         if let Some(merge_type) = merge_type {
-            commit_writer.resume_merge(merge_type)?;
+            // Without `-m` or `-F`, the message saved in MERGE_MSG is used
+            let message = Some(message.as_str()).filter(|message| !message.is_empty());
+            commit_writer.resume_merge(merge_type, message)?;
         }
 
         let parents = if let Some(parent) = self.ctx.repo.refs.read_head()? {
@@ -76,40 +150,145 @@ impl<'a> Commit<'a> {
             vec![]
         };
 
-        let message = commit_writer.read_message(self.message.as_deref(), self.file.as_deref())?;
         let message = if message.is_empty() {
             self.reused_message()?.unwrap_or_default()
         } else {
             message
         };
+        let message = self.add_trailers(&message)?;
         let message = self.compose_message(&message)?;
 
-        let commit = commit_writer.write_commit(parents, message.as_deref())?;
+        let author = author_date.map(|date| with_date(commit_writer.current_author(), Some(date)));
+        let commit = commit_writer.write_commit_as(parents, message.as_deref(), author)?;
 
         commit_writer.print_commit(&commit)?;
 
         Ok(())
     }
 
+    fn author_date(&self) -> Result<Option<DateTime<FixedOffset>>> {
+        match &self.date {
+            Some(date) => {
+                let now = Local::now();
+                parse_date(date, now.with_timezone(now.offset()))
+                    .map(Some)
+                    .ok_or_else(|| Error::InvalidDate(date.to_owned()))
+            }
+            None => Ok(None),
+        }
+    }
+
+    /// The key to sign the commit with if `--gpg-sign` or `commit.gpgSign` is given: the
+    /// `<keyid>` passed to `--gpg-sign`, or `user.signingKey`, or the committer's identity.
+    fn signing_key(&self, commit_writer: &CommitWriter) -> Option<String> {
+        let config = &self.ctx.repo.config;
+        let sign = self.gpg_sign.unwrap_or_else(|| {
+            config
+                .get_bool(&[String::from("commit"), String::from("gpgSign")])
+                .unwrap_or(false)
+        });
+        if !sign {
+            return None;
+        }
+
+        let key = self.key_id.clone().or_else(|| {
+            config
+                .get(&[String::from("user"), String::from("signingKey")])
+                .map(|key| key.to_string())
+        });
+        Some(key.unwrap_or_else(|| {
+            let committer = commit_writer.current_author();
+            format!("{} <{}>", committer.name, committer.email)
+        }))
+    }
+
+    /// Append the `--trailer` trailers and the `--signoff` line to `message`.
+    fn add_trailers(&self, message: &str) -> Result<String> {
+        let mut trailers = vec![];
+        for text in &self.trailers {
+            match Trailer::parse(text) {
+                Some(trailer) => trailers.push(trailer),
+                None => {
+                    let mut stderr = self.ctx.stderr.borrow_mut();
+                    writeln!(stderr, "fatal: invalid trailer '{}'", text)?;
+                    return Err(Error::Exit(128));
+                }
+            }
+        }
+        if self.signoff {
+            let committer = self.commit_writer().current_author();
+            trailers.push(Trailer::new(
+                "Signed-off-by",
+                &format!("{} <{}>", committer.name, committer.email),
+            ));
+        }
+
+        Ok(trailers::add_trailers(message, &trailers))
+    }
+
     fn commit_writer(&self) -> CommitWriter {
         CommitWriter::new(&self.ctx)
     }
 
     fn compose_message(&self, message: &str) -> Result<Option<String>> {
-        self.ctx.edit_file(
+        let diff = if self.verbose {
+            Some(self.staged_diff()?)
+        } else {
+            None
+        };
+
+        let edited = self.ctx.edit_file(
             &self.commit_writer().commit_message_path(),
             |editor: &mut Editor| {
                 editor.write(message)?;
                 editor.write("")?;
                 editor.note(COMMIT_NOTES)?;
 
+                if let Some(diff) = &diff {
+                    editor.scissors()?;
+                    editor.write(diff.trim_end())?;
+                }
+
                 if !self.edit {
                     editor.close();
                 }
+                editor.cleanup(self.cleanup);
 
                 Ok(())
             },
-        )
+        )?;
+
+        // Without an editor, the notes in the template never become part of the message
+        if self.edit {
+            Ok(edited)
+        } else {
+            Ok(cleanup_message(message, self.cleanup))
+        }
+    }
+
+    /// Render the changes between the new commit's parent and the index, as they will be
+    /// committed.
+    fn staged_diff(&self) -> Result<String> {
+        let head = self.ctx.repo.refs.read_head()?;
+        let head = match head {
+            Some(head) if self.amend => self.ctx.repo.database.load_commit(&head)?.first_parent(),
+            head => head,
+        };
+        let tree = self.commit_writer().write_tree();
+
+        let mut diff = Vec::new();
+        colored::control::set_override(false);
+        let result = DiffPrinter::new().print_commit_diff(
+            &mut diff,
+            &self.ctx.repo,
+            head.as_deref().unwrap_or(EMPTY_TREE_OID),
+            &tree.oid(),
+            None,
+        );
+        colored::control::unset_override();
+        result?;
+
+        Ok(String::from_utf8_lossy(&diff).into_owned())
     }
 
     fn reused_message(&self) -> Result<Option<String>> {
@@ -127,14 +306,17 @@ impl<'a> Commit<'a> {
         }
     }
 
-    fn handle_amend(&self) -> Result<()> {
+    fn handle_amend(
+        &self,
+        commit_writer: &CommitWriter,
+        author_date: Option<DateTime<FixedOffset>>,
+    ) -> Result<()> {
         let old = self
             .ctx
             .repo
             .database
             .load_commit(&self.ctx.repo.refs.read_head()?.expect("nothing to amend"))?;
 
-        let commit_writer = self.commit_writer();
         let tree = commit_writer.write_tree();
 
         let message = commit_writer.read_message(self.message.as_deref(), self.file.as_deref())?;
@@ -143,6 +325,7 @@ impl<'a> Commit<'a> {
         } else {
             message
         };
+        let message = self.add_trailers(&message)?;
         let message = self.compose_message(&message)?;
 
         let committer = commit_writer.current_author();
@@ -150,15 +333,22 @@ impl<'a> Commit<'a> {
         let new = DatabaseCommit::new(
             old.parents.clone(),
             tree.oid(),
-            old.author,
+            with_date(old.author, author_date),
             committer,
             message.unwrap_or_default(),
         );
-        self.ctx.repo.database.store(&new)?;
-        self.ctx.repo.refs.update_head(&new.oid())?;
+        let new = commit_writer.store_commit(new)?;
 
         commit_writer.print_commit(&new)?;
 
         Err(Error::Exit(0))
     }
 }
+
+fn with_date(mut author: Author, date: Option<DateTime<FixedOffset>>) -> Author {
+    if let Some(date) = date {
+        author.time = date;
+    }
+
+    author
+}
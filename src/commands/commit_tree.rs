@@ -0,0 +1,116 @@
+use std::fs::read_to_string;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+
+use crate::commands::shared::commit_writer::CommitWriter;
+use crate::commands::{Command, CommandContext};
+use crate::database::commit::Commit;
+use crate::database::object::Object;
+use crate::errors::{Error, Result};
+use crate::revision::{Revision, COMMIT, TREE};
+
+pub struct CommitTree<'a> {
+    ctx: CommandContext<'a>,
+    /// `jit commit-tree <tree>`
+    tree: String,
+    /// `jit commit-tree -p <parent>...`
+    parents: Vec<String>,
+    /// `jit commit-tree -m <message>...`
+    messages: Vec<String>,
+    /// `jit commit-tree -F <file>`
+    file: Option<PathBuf>,
+}
+
+impl<'a> CommitTree<'a> {
+    pub fn new(ctx: CommandContext<'a>) -> Self {
+        let (tree, parents, messages, file) = match &ctx.opt.cmd {
+            Command::CommitTree {
+                tree,
+                parents,
+                messages,
+                file,
+            } => (
+                tree.to_owned(),
+                parents.to_owned(),
+                messages.to_owned(),
+                file.to_owned(),
+            ),
+            _ => unreachable!(),
+        };
+
+        Self {
+            ctx,
+            tree,
+            parents,
+            messages,
+            file,
+        }
+    }
+
+    pub fn run(&self) -> Result<()> {
+        let tree = self.resolve(&self.tree, TREE)?;
+
+        let mut parents = Vec::new();
+        for parent in &self.parents {
+            let parent = self.resolve(parent, COMMIT)?;
+            if !parents.contains(&parent) {
+                parents.push(parent);
+            }
+        }
+
+        let message = self.read_message()?;
+
+        let commit_writer = CommitWriter::new(&self.ctx);
+        let author = commit_writer.current_author();
+        let commit = Commit::new(parents, tree, author.clone(), author, message);
+        self.ctx.repo.database.store(&commit)?;
+
+        let mut stdout = self.ctx.stdout.borrow_mut();
+        writeln!(stdout, "{}", commit.oid())?;
+
+        Ok(())
+    }
+
+    fn resolve(&self, name: &str, r#type: &str) -> Result<String> {
+        let mut revision = Revision::new(&self.ctx.repo, name);
+
+        match revision.resolve(Some(r#type)) {
+            Ok(oid) => Ok(oid),
+            Err(err) if err.is_revision_error() => {
+                let mut stderr = self.ctx.stderr.borrow_mut();
+
+                for error in revision.errors {
+                    writeln!(stderr, "error: {}", error.message)?;
+                    for line in error.hint {
+                        writeln!(stderr, "hint: {}", line)?;
+                    }
+                }
+
+                writeln!(stderr, "fatal: Not a valid object name: '{}'.", name)?;
+                Err(Error::Exit(128))
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    /// Each `-m` becomes its own paragraph, as with `jit commit`. Without `-m` or `-F`, the
+    /// message is read from standard input.
+    fn read_message(&self) -> Result<String> {
+        if !self.messages.is_empty() {
+            let paragraphs: Vec<_> = self
+                .messages
+                .iter()
+                .map(|message| message.trim_end())
+                .collect();
+
+            Ok(format!("{}\n", paragraphs.join("\n\n")))
+        } else if let Some(file) = &self.file {
+            Ok(read_to_string(file)?)
+        } else {
+            let mut message = String::new();
+            self.ctx.stdin.borrow_mut().read_to_string(&mut message)?;
+
+            Ok(message)
+        }
+    }
+}
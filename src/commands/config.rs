@@ -1,4 +1,7 @@
 use std::cell::RefMut;
+use std::fmt::Display;
+
+use regex::Regex;
 
 use crate::commands::{Command, CommandContext};
 use crate::config::stack::{ConfigFile, Stack};
@@ -18,6 +21,7 @@ enum Mode {
     Add,
     Replace,
     GetAll,
+    GetRegexp,
     Unset,
     UnsetAll,
     RemoveSection,
@@ -41,6 +45,7 @@ impl<'a> ConfigCommand<'a> {
                 add,
                 replace_all,
                 get_all,
+                get_regexp,
                 unset,
                 unset_all,
                 remove_section,
@@ -69,6 +74,8 @@ impl<'a> ConfigCommand<'a> {
                     )
                 } else if let Some(raw_key) = get_all {
                     (Some(Mode::GetAll), raw_key.to_owned(), None)
+                } else if let Some(pattern) = get_regexp {
+                    (Some(Mode::GetRegexp), pattern.to_owned(), None)
                 } else if let Some(raw_key) = unset {
                     (Some(Mode::Unset), raw_key.to_owned(), None)
                 } else if let Some(raw_key) = unset_all {
@@ -104,6 +111,7 @@ impl<'a> ConfigCommand<'a> {
             Some(Mode::Add) => self.add_variable(value.as_deref().unwrap())?,
             Some(Mode::Replace) => self.replace_variable(value.as_deref().unwrap())?,
             Some(Mode::GetAll) => self.get_all_values()?,
+            Some(Mode::GetRegexp) => self.get_regexp_values()?,
             Some(Mode::Unset) => self.unset_single()?,
             Some(Mode::UnsetAll) => self.unset_all()?,
             Some(Mode::RemoveSection) => self.remove_section()?,
@@ -177,9 +185,33 @@ impl<'a> ConfigCommand<'a> {
         })
     }
 
-    fn read_config<F>(&mut self, f: F) -> Result<()>
+    fn get_regexp_values(&mut self) -> Result<()> {
+        let pattern = match Regex::new(&self.raw_key) {
+            Ok(pattern) => pattern,
+            Err(_) => {
+                let mut stderr = self.ctx.stderr.borrow_mut();
+                writeln!(stderr, "error: invalid key pattern: {}", self.raw_key)?;
+                return Err(Error::Exit(6));
+            }
+        };
+
+        self.read_config(|config_or_stack| {
+            let variables = match config_or_stack {
+                ConfigOrStack::Config(config) => config.get_regexp(&pattern),
+                ConfigOrStack::Stack(stack) => stack.get_regexp(&pattern),
+            };
+
+            variables
+                .into_iter()
+                .map(|(name, value)| format!("{} {}", name, value))
+                .collect()
+        })
+    }
+
+    fn read_config<F, T>(&mut self, f: F) -> Result<()>
     where
-        F: Fn(ConfigOrStack) -> Vec<VariableValue>,
+        F: Fn(ConfigOrStack) -> Vec<T>,
+        T: Display,
     {
         let values = if let Some(file) = &self.file {
             let config = self.ctx.repo.config.file(file.clone());
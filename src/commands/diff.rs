@@ -1,11 +1,13 @@
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 use itertools::Itertools;
 
 use crate::commands::shared::diff_printer::{DiffPrinter, Target};
 use crate::commands::{Command, CommandContext};
 use crate::database::blob::Blob;
-use crate::errors::Result;
+use crate::database::entry::Entry as DatabaseEntry;
+use crate::database::tree_diff::{Differ, TreeDiffChanges};
+use crate::errors::{Error, Result};
 use crate::index::Entry;
 use crate::repository::status::Status;
 use crate::repository::ChangeType;
@@ -23,18 +25,36 @@ pub struct Diff<'a> {
     patch: bool,
     /// `jit diff --base` or `jit diff --ours` or `jit diff --theirs`
     stage: u16,
+    /// `jit diff --exit-code`, which `jit diff --quiet` implies
+    exit_code: bool,
+    /// Turned off by `jit diff --no-textconv`
+    textconv: bool,
 }
 
 impl<'a> Diff<'a> {
     pub fn new(mut ctx: CommandContext<'a>) -> Self {
-        let (args, cached, patch, stage) = match &ctx.opt.cmd {
+        let (
+            args,
+            cached,
+            patch,
+            stage,
+            find_copies,
+            ignore_blank_lines,
+            exit_code,
+            relative,
+            textconv,
+        ) = match &ctx.opt.cmd {
             Command::Diff {
                 args,
                 cached,
-                staged,
                 patch,
                 no_patch,
                 stage,
+                find_copies,
+                ignore_blank_lines,
+                exit_code,
+                relative,
+                no_textconv,
             } => {
                 let stage: u16 = if stage.base {
                     1
@@ -45,17 +65,28 @@ impl<'a> Diff<'a> {
                 } else {
                     0
                 };
+                // `--quiet` prints nothing, and only reports whether there are differences
                 (
                     args.to_owned(),
-                    *cached || *staged,
-                    *patch || !*no_patch,
+                    *cached,
+                    (*patch || !*no_patch) && !ctx.quiet(),
                     stage,
+                    *find_copies,
+                    *ignore_blank_lines,
+                    *exit_code || ctx.quiet(),
+                    // jit always runs from the root of the workspace, so `--relative` without
+                    // a directory shows every path as it is
+                    relative.to_owned().flatten(),
+                    !*no_textconv,
                 )
             }
             _ => unreachable!(),
         };
 
-        let diff_printer = DiffPrinter::new();
+        let mut diff_printer = DiffPrinter::new();
+        diff_printer.find_copies = find_copies;
+        diff_printer.ignore_blank_lines = ignore_blank_lines;
+        diff_printer.relative = relative;
 
         let status = ctx.repo.status(None);
 
@@ -67,93 +98,121 @@ impl<'a> Diff<'a> {
             cached,
             patch,
             stage,
+            exit_code,
+            textconv,
         }
     }
 
     pub fn run(&mut self) -> Result<()> {
+        self.diff_printer.quote_path = self.ctx.repo.quote_path()?;
+        self.diff_printer.abbrev = self.ctx.repo.abbrev()?;
+        if self.textconv {
+            self.diff_printer.load_textconv(&self.ctx.repo)?;
+        }
         self.ctx.repo.index.load()?;
         self.status.initialize()?;
 
         self.ctx.setup_pager();
 
-        if self.cached {
-            self.diff_head_index()?;
+        let changed = if self.cached {
+            self.diff_head_index()?
         } else if self.args.len() == 2 {
-            self.diff_commits()?;
+            self.diff_commits()?
         } else {
-            self.diff_index_workspace()?;
+            self.diff_index_workspace()?
+        };
+
+        if self.exit_code && changed {
+            return Err(Error::Exit(1));
         }
 
         Ok(())
     }
 
-    fn diff_commits(&self) -> Result<()> {
-        if !self.patch {
-            return Ok(());
-        }
-
+    /// Each `diff_*` method prints its diff, and returns whether there were any differences.
+    fn diff_commits(&self) -> Result<bool> {
         let mut args = vec![];
         for rev in &self.args {
             args.push(Revision::new(&self.ctx.repo, rev).resolve(Some("commit"))?);
         }
+        let changed = self
+            .ctx
+            .repo
+            .database
+            .tree_diff(&args[0], &args[1], None)?
+            .keys()
+            .any(|path| self.diff_printer.includes(path));
+        if !self.patch {
+            return Ok(changed);
+        }
+
         let mut stdout = self.ctx.stdout.borrow_mut();
         self.diff_printer.print_commit_diff(
-            &mut stdout,
+            &mut *stdout,
             &self.ctx.repo,
-            Some(&args[0]),
+            &args[0],
             &args[1],
             None,
         )?;
 
-        Ok(())
+        Ok(changed)
     }
 
-    fn diff_head_index(&self) -> Result<()> {
+    fn diff_head_index(&self) -> Result<bool> {
+        let paths: Vec<_> = self
+            .status
+            .index_changes
+            .keys()
+            .filter(|path| self.diff_printer.includes(Path::new(path)))
+            .collect();
+        let changed = !paths.is_empty();
         if !self.patch {
-            return Ok(());
+            return Ok(changed);
         }
 
-        for path in self.status.index_changes.keys() {
-            let mut stdout = self.ctx.stdout.borrow_mut();
-            let state = &self.status.index_changes[path];
-            match state {
-                ChangeType::Added => {
-                    let mut a = self.diff_printer.from_nothing(path);
-                    let mut b = self.from_index(path)?;
-
-                    self.diff_printer.print_diff(&mut stdout, &mut a, &mut b)?;
-                }
-                ChangeType::Modified => {
-                    let mut a = self.from_head(path)?;
-                    let mut b = self.from_index(path)?;
-
-                    self.diff_printer.print_diff(&mut stdout, &mut a, &mut b)?;
-                }
-                ChangeType::Deleted => {
-                    let mut a = self.from_head(path)?;
-                    let mut b = self.diff_printer.from_nothing(path);
-
-                    self.diff_printer.print_diff(&mut stdout, &mut a, &mut b)?;
-                }
-                ChangeType::Untracked => unreachable!(),
-            }
+        let mut changes = TreeDiffChanges::new();
+        for path in paths {
+            let old_entry = self
+                .status
+                .head_tree
+                .get(path)
+                .map(|entry| DatabaseEntry::new(entry.oid(), entry.mode()));
+            let new_entry = self
+                .ctx
+                .repo
+                .index
+                .entry_for_path(path, 0)
+                .map(DatabaseEntry::from);
+
+            changes.insert(PathBuf::from(path), (old_entry, new_entry));
         }
 
-        Ok(())
-    }
+        let file_diffs =
+            self.diff_printer
+                .file_diffs(&self.ctx.repo, &changes, &self.status.head_tree)?;
 
-    fn diff_index_workspace(&self) -> Result<()> {
-        if !self.patch {
-            return Ok(());
+        let mut stdout = self.ctx.stdout.borrow_mut();
+        for file_diff in file_diffs {
+            self.diff_printer.print_file_diff(&mut *stdout, file_diff)?;
         }
 
-        let paths = self
+        Ok(changed)
+    }
+
+    fn diff_index_workspace(&self) -> Result<bool> {
+        let paths: Vec<_> = self
             .status
             .workspace_changes
             .keys()
             .into_iter()
             // Merge the two iterators in sorted order
-            .merge(self.status.conflicts.keys().into_iter());
+            .merge(self.status.conflicts.keys().into_iter())
+            .filter(|path| self.diff_printer.includes(Path::new(path)))
+            .collect();
+        let changed = !paths.is_empty();
+        if !self.patch {
+            return Ok(changed);
+        }
 
         for path in paths {
             if self.status.conflicts.contains_key(path) {
@@ -163,7 +222,7 @@ impl<'a> Diff<'a> {
             }
         }
 
-        Ok(())
+        Ok(changed)
     }
 
     fn print_conflict_diff(&self, path: &str) -> Result<()> {
@@ -177,15 +236,20 @@ impl<'a> Diff<'a> {
         let mut stdout = self.ctx.stdout.borrow_mut();
 
         if self.stage != 0 {
-            writeln!(stdout, "* Unmerged path {}", path)?;
+            writeln!(
+                stdout,
+                "* Unmerged path {}",
+                self.diff_printer
+                    .quote(&self.diff_printer.display_path(path))
+            )?;
             self.diff_printer.print_diff(
-                &mut stdout,
+                &mut *stdout,
                 targets[self.stage as usize].as_mut().unwrap(),
                 &mut self.from_file(path)?,
             )?;
         } else if left.is_some() && right.is_some() {
             self.diff_printer.print_combined_diff(
-                &mut stdout,
+                &mut *stdout,
                 &[
                     left.as_ref().unwrap().clone(),
                     right.as_ref().unwrap().clone(),
@@ -193,7 +257,12 @@ impl<'a> Diff<'a> {
                 &self.from_file(path)?,
             )?;
         } else {
-            writeln!(stdout, "* Unmerged path {}", path)?;
+            writeln!(
+                stdout,
+                "* Unmerged path {}",
+                self.diff_printer
+                    .quote(&self.diff_printer.display_path(path))
+            )?;
         }
 
         Ok(())
@@ -207,13 +276,20 @@ impl<'a> Diff<'a> {
                 let mut a = self.from_index(path)?;
                 let mut b = self.from_file(path)?;
 
-                self.diff_printer.print_diff(&mut stdout, &mut a, &mut b)?;
+                self.diff_printer.print_diff(&mut *stdout, &mut a, &mut b)?;
             }
             ChangeType::Deleted => {
                 let mut a = self.from_index(path)?;
                 let mut b = self.diff_printer.from_nothing(path);
 
-                self.diff_printer.print_diff(&mut stdout, &mut a, &mut b)?;
+                self.diff_printer.print_diff(&mut *stdout, &mut a, &mut b)?;
+            }
+            // Files added with `--intent-to-add`
+            ChangeType::Added => {
+                let mut a = self.diff_printer.from_nothing(path);
+                let mut b = self.from_file(path)?;
+
+                self.diff_printer.print_diff(&mut *stdout, &mut a, &mut b)?;
             }
             _ => unreachable!(),
         }
@@ -221,19 +297,6 @@ impl<'a> Diff<'a> {
         Ok(())
     }
 
-    fn from_head(&self, path: &str) -> Result<Target> {
-        let entry = &self.status.head_tree[path];
-        let oid = entry.oid();
-        let blob = self.ctx.repo.database.load_blob(&oid)?;
-
-        Ok(Target::new(
-            path.to_string(),
-            oid,
-            Some(entry.mode()),
-            blob.data,
-        ))
-    }
-
     fn from_index(&self, path: &str) -> Result<Target> {
         let entry = self.ctx.repo.index.entry_for_path(path, 0).unwrap();
         let blob = self.ctx.repo.database.load_blob(&entry.oid)?;
@@ -242,7 +305,7 @@ impl<'a> Diff<'a> {
             path.to_string(),
             entry.oid.clone(),
             Some(entry.mode),
-            blob.data,
+            self.diff_printer.convert(path, blob.data.clone())?,
         ))
     }
 
@@ -254,7 +317,7 @@ impl<'a> Diff<'a> {
                 path.to_string(),
                 entry.oid.clone(),
                 Some(entry.mode),
-                blob.data,
+                self.diff_printer.convert(path, blob.data.clone())?,
             )))
         } else {
             Ok(None)
@@ -266,6 +329,11 @@ impl<'a> Diff<'a> {
         let oid = self.ctx.repo.database.hash_object(&blob);
         let mode = Entry::mode_for_stat(&self.status.stats[path]);
 
-        Ok(Target::new(path.to_string(), oid, Some(mode), blob.data))
+        Ok(Target::new(
+            path.to_string(),
+            oid,
+            Some(mode),
+            self.diff_printer.convert(path, blob.data.clone())?,
+        ))
     }
 }
@@ -0,0 +1,187 @@
+use std::cmp::Ordering;
+use std::io::Write;
+
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+
+use crate::commands::{Command, CommandContext};
+use crate::database::{Database, ParsedObject};
+use crate::errors::{Error, Result};
+use crate::refs::Ref;
+
+const DEFAULT_FORMAT: &str = "%(objectname) %(objecttype)\t%(refname)";
+const ATOMS: [&str; 8] = [
+    "refname",
+    "refname:short",
+    "objectname",
+    "objectname:short",
+    "objecttype",
+    "subject",
+    "authorname",
+    "committerdate",
+];
+const SORT_KEYS: [&str; 2] = ["refname", "committerdate"];
+static ATOM: Lazy<Regex> = Lazy::new(|| Regex::new(r"%\(([^)]*)\)|%%").unwrap());
+
+/// A ref along with the object it points at, from which `--format` atoms are filled in.
+struct RefItem {
+    name: String,
+    short_name: String,
+    oid: String,
+    object: ParsedObject,
+}
+
+impl RefItem {
+    fn atom(&self, atom: &str) -> String {
+        match atom {
+            "refname" => self.name.clone(),
+            "refname:short" => self.short_name.clone(),
+            "objectname" => self.oid.clone(),
+            "objectname:short" => Database::short_oid(&self.oid),
+            "objecttype" => self.object.r#type().to_owned(),
+            "subject" => match &self.object {
+                ParsedObject::Commit(commit) => commit.title_line(),
+                _ => String::new(),
+            },
+            "authorname" => match &self.object {
+                ParsedObject::Commit(commit) => commit.author.name.clone(),
+                _ => String::new(),
+            },
+            "committerdate" => match &self.object {
+                ParsedObject::Commit(commit) => commit.committer.readable_time(),
+                _ => String::new(),
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    fn compare(&self, other: &RefItem, key: &str) -> Ordering {
+        match key {
+            "committerdate" => self.commit_time().cmp(&other.commit_time()),
+            _ => self.name.cmp(&other.name),
+        }
+    }
+
+    fn commit_time(&self) -> Option<i64> {
+        match &self.object {
+            ParsedObject::Commit(commit) => Some(commit.committer.time.timestamp()),
+            _ => None,
+        }
+    }
+}
+
+pub struct ForEachRef<'a> {
+    ctx: CommandContext<'a>,
+    /// `jit for-each-ref [<pattern>...]`
+    patterns: Vec<String>,
+    /// `jit for-each-ref --format=<format>`
+    format: Option<String>,
+    /// `jit for-each-ref --sort=<key>`
+    sort: Option<String>,
+}
+
+impl<'a> ForEachRef<'a> {
+    pub fn new(ctx: CommandContext<'a>) -> Self {
+        let (patterns, format, sort) = match &ctx.opt.cmd {
+            Command::ForEachRef {
+                patterns,
+                format,
+                sort,
+            } => (patterns.to_owned(), format.to_owned(), sort.to_owned()),
+            _ => unreachable!(),
+        };
+
+        Self {
+            ctx,
+            patterns,
+            format,
+            sort,
+        }
+    }
+
+    pub fn run(&self) -> Result<()> {
+        let format = self.format.as_deref().unwrap_or(DEFAULT_FORMAT);
+        let sort = self.sort.as_deref().unwrap_or("refname");
+        let (key, reverse) = match sort.strip_prefix('-') {
+            Some(key) => (key, true),
+            None => (sort, false),
+        };
+
+        for r#match in ATOM.captures_iter(format) {
+            if let Some(atom) = r#match.get(1) {
+                if !ATOMS.contains(&atom.as_str()) {
+                    return self.fatal(&format!("unknown field name: {}", atom.as_str()));
+                }
+            }
+        }
+        if !SORT_KEYS.contains(&key) {
+            return self.fatal(&format!("unsupported sort key: {}", key));
+        }
+
+        let mut items = self.list_items()?;
+        items.sort_by(|a, b| a.compare(b, key).then_with(|| a.name.cmp(&b.name)));
+        if reverse {
+            items.reverse();
+        }
+
+        let mut stdout = self.ctx.stdout.borrow_mut();
+        for item in items {
+            let line = ATOM.replace_all(format, |captures: &Captures| match captures.get(1) {
+                Some(atom) => item.atom(atom.as_str()),
+                None => String::from("%"),
+            });
+            writeln!(stdout, "{}", line)?;
+        }
+
+        Ok(())
+    }
+
+    fn list_items(&self) -> Result<Vec<RefItem>> {
+        let refs = &self.ctx.repo.refs;
+        let mut items = Vec::new();
+
+        for r#ref in refs.list_all_refs()? {
+            if r#ref.is_head() {
+                continue;
+            }
+            let name = match &r#ref {
+                Ref::SymRef { path } => path.to_owned(),
+                Ref::Ref { .. } => unreachable!(),
+            };
+            if !self.matches(&name) {
+                continue;
+            }
+
+            if let Some(oid) = refs.read_oid(&r#ref)? {
+                items.push(RefItem {
+                    short_name: refs.short_name(&r#ref),
+                    object: self.ctx.repo.database.load(&oid)?,
+                    name,
+                    oid,
+                });
+            }
+        }
+
+        Ok(items)
+    }
+
+    /// Patterns match refs by whole path components, so `refs/heads` matches `refs/heads/main`
+    /// but not `refs/headsup`.
+    fn matches(&self, name: &str) -> bool {
+        if self.patterns.is_empty() {
+            return true;
+        }
+
+        self.patterns.iter().any(|pattern| {
+            let pattern = pattern.trim_end_matches('/');
+            name == pattern || name.starts_with(&format!("{}/", pattern))
+        })
+    }
+
+    fn fatal(&self, message: &str) -> Result<()> {
+        let mut stderr = self.ctx.stderr.borrow_mut();
+        writeln!(stderr, "fatal: {}", message)?;
+
+        Err(Error::Exit(128))
+    }
+}
@@ -0,0 +1,176 @@
+use std::fs;
+use std::io::Write;
+use std::path::PathBuf;
+
+use crate::commands::shared::diff_printer::DiffPrinter;
+use crate::commands::{Command, CommandContext};
+use crate::database::commit::Commit;
+use crate::database::object::Object;
+use crate::errors::{Error, Result};
+use crate::patch::mailbox;
+use crate::rev_list::RevList;
+use crate::revision::HEAD;
+
+/// The longest patch filename, including the `.patch` suffix.
+const PATCH_NAME_MAX: usize = 64;
+const PATCH_SUFFIX: &str = ".patch";
+
+pub struct FormatPatch<'a> {
+    ctx: CommandContext<'a>,
+    /// `jit format-patch <revision range>`
+    args: Vec<String>,
+    /// `jit format-patch --stdout`
+    stdout: bool,
+    /// `jit format-patch -o <dir>`
+    output_directory: Option<PathBuf>,
+}
+
+impl<'a> FormatPatch<'a> {
+    pub fn new(ctx: CommandContext<'a>) -> Self {
+        let (args, stdout, output_directory) = match &ctx.opt.cmd {
+            Command::FormatPatch {
+                args,
+                stdout,
+                output_directory,
+            } => (args.to_owned(), *stdout, output_directory.to_owned()),
+            _ => unreachable!(),
+        };
+
+        Self {
+            ctx,
+            args,
+            stdout,
+            output_directory,
+        }
+    }
+
+    pub fn run(&self) -> Result<()> {
+        // Like Git, a single revision means the commits since that revision
+        let args = match self.args.as_slice() {
+            [since] if !since.contains("..") => vec![format!("{}..{}", since, HEAD)],
+            args => args.to_vec(),
+        };
+
+        let rev_list = match RevList::new(&self.ctx.repo, &args, Default::default()) {
+            Ok(rev_list) => rev_list,
+            Err(err) if err.is_revision_error() || matches!(err, Error::InvalidArgument(..)) => {
+                let mut stderr = self.ctx.stderr.borrow_mut();
+                writeln!(stderr, "fatal: {}", err)?;
+                return Err(Error::Exit(128));
+            }
+            Err(err) => return Err(err),
+        };
+        let mut commits: Vec<_> = rev_list.collect::<Result<_>>()?;
+        commits.retain(|commit| !commit.is_merge());
+        commits.reverse();
+
+        colored::control::set_override(false);
+        let result = self.write_patches(&commits);
+        colored::control::unset_override();
+
+        result
+    }
+
+    fn write_patches(&self, commits: &[Commit]) -> Result<()> {
+        let total = commits.len();
+
+        for (index, commit) in commits.iter().enumerate() {
+            let mut patch = Vec::new();
+            self.write_patch(&mut patch, commit, index + 1, total)?;
+
+            let mut stdout = self.ctx.stdout.borrow_mut();
+            if self.stdout {
+                stdout.write_all(&patch)?;
+                continue;
+            }
+
+            let mut name = PathBuf::from(patch_name(commit, index + 1));
+            if let Some(dir) = &self.output_directory {
+                fs::create_dir_all(self.ctx.dir.join(dir))?;
+                name = dir.join(name);
+            }
+            fs::write(self.ctx.dir.join(&name), patch)?;
+            writeln!(stdout, "{}", name.display())?;
+        }
+
+        Ok(())
+    }
+
+    /// Write `commit` as the `number`th of `total` patches, in the mbox format used by
+    /// `git format-patch`.
+    fn write_patch(
+        &self,
+        out: &mut dyn Write,
+        commit: &Commit,
+        number: usize,
+        total: usize,
+    ) -> Result<()> {
+        let prefix = if total == 1 {
+            String::from("[PATCH]")
+        } else {
+            format!("[PATCH {}/{}]", number, total)
+        };
+
+        mailbox::write_headers(out, commit, &prefix)?;
+
+        let body = commit
+            .message
+            .split_once('\n')
+            .map_or("", |(_, body)| body)
+            .trim();
+        if !body.is_empty() {
+            writeln!(out, "{}", body)?;
+        }
+        writeln!(out, "---")?;
+
+        let mut diff_printer = DiffPrinter::new();
+        diff_printer.quote_path = self.ctx.repo.quote_path()?;
+        diff_printer.abbrev = self.ctx.repo.abbrev()?;
+        let file_diffs = diff_printer.commit_diffs(
+            &self.ctx.repo,
+            &commit.first_parent_or_empty_tree(),
+            &commit.oid(),
+            None,
+        )?;
+        diff_printer.print_stat(out, &file_diffs)?;
+        writeln!(out)?;
+        for file_diff in file_diffs {
+            diff_printer.print_file_diff(out, file_diff)?;
+        }
+
+        writeln!(out, "-- ")?;
+        writeln!(out, "{}", env!("CARGO_PKG_VERSION"))?;
+        writeln!(out)?;
+
+        Ok(())
+    }
+}
+
+/// The file the `number`th patch is written to, named after the commit's title like
+/// `0001-Fix-the-bug.patch`.
+fn patch_name(commit: &Commit, number: usize) -> String {
+    let mut name = format!("{:04}-{}", number, sanitized_subject(&commit.title_line()));
+    name.truncate(PATCH_NAME_MAX - PATCH_SUFFIX.len() - 1);
+
+    format!("{}{}", name, PATCH_SUFFIX)
+}
+
+/// Replace each run of characters that aren't safe in a filename with a single `-`.
+fn sanitized_subject(title: &str) -> String {
+    let mut subject = String::new();
+    let mut pending_dash = false;
+
+    for c in title.chars() {
+        if c.is_ascii_alphanumeric() || c == '.' || c == '_' {
+            if pending_dash && !subject.is_empty() {
+                subject.push('-');
+            }
+            pending_dash = false;
+            subject.push(c);
+        } else {
+            pending_dash = true;
+        }
+    }
+
+    subject.trim_matches(&['.', '-'][..]).replace("..", ".")
+}
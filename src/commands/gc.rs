@@ -0,0 +1,62 @@
+use std::io::Write;
+
+use crate::commands::{Command, CommandContext};
+use crate::errors::Result;
+use crate::util::plural;
+
+pub struct Gc<'a> {
+    ctx: CommandContext<'a>,
+    /// `jit gc --auto`
+    auto: bool,
+}
+
+impl<'a> Gc<'a> {
+    pub fn new(ctx: CommandContext<'a>) -> Self {
+        let auto = match ctx.opt.cmd {
+            Command::Gc { auto } => auto,
+            _ => unreachable!(),
+        };
+
+        Self { ctx, auto }
+    }
+
+    /// jit can't write packs yet, so `jit gc` only deletes the loose objects nothing reaches,
+    /// like `git gc --prune=now`.
+    pub fn run(&mut self) -> Result<()> {
+        if self.auto {
+            return self.report_housekeeping();
+        }
+
+        self.ctx.repo.index.load()?;
+        let reachable = self.ctx.repo.reachable_objects()?;
+        for oid in self.ctx.repo.database.loose_objects()? {
+            if !reachable.contains(&oid) {
+                self.ctx.repo.database.remove_loose_object(&oid)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Report whether the heuristics Git uses to decide on housekeeping are exceeded. Like Git,
+    /// nothing is printed when they aren't.
+    fn report_housekeeping(&self) -> Result<()> {
+        if !self.ctx.repo.needs_auto_gc()? {
+            return Ok(());
+        }
+
+        let loose_objects = self.ctx.repo.database.count_loose_objects()?;
+        let packs = self.ctx.repo.database.count_packs()?;
+        let mut stdout = self.ctx.stdout.borrow_mut();
+        writeln!(
+            stdout,
+            "Housekeeping is needed: {} loose object{} and {} pack{}",
+            loose_objects,
+            plural(loose_objects),
+            packs,
+            plural(packs),
+        )?;
+
+        Ok(())
+    }
+}
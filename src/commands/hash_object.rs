@@ -0,0 +1,116 @@
+use std::fs;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+
+use clap::ValueEnum;
+
+use crate::commands::{Command, CommandContext};
+use crate::database::object::Object;
+use crate::errors::{Error, Result};
+
+#[derive(ValueEnum, Debug, Clone, PartialEq, Eq)]
+pub enum ObjectType {
+    Blob,
+    Tree,
+    Commit,
+}
+
+/// Arbitrary data stored under a given object type, without being parsed.
+struct RawObject {
+    r#type: String,
+    data: Vec<u8>,
+}
+
+impl Object for RawObject {
+    fn r#type(&self) -> &str {
+        &self.r#type
+    }
+
+    fn bytes(&self) -> Vec<u8> {
+        self.data.clone()
+    }
+}
+
+pub struct HashObject<'a> {
+    ctx: CommandContext<'a>,
+    /// `jit hash-object <file>...`
+    files: Vec<PathBuf>,
+    /// `jit hash-object -t <type>`
+    r#type: ObjectType,
+    /// `jit hash-object -w`
+    write: bool,
+    /// `jit hash-object --stdin`
+    stdin: bool,
+}
+
+impl<'a> HashObject<'a> {
+    pub fn new(ctx: CommandContext<'a>) -> Self {
+        let (files, r#type, write, stdin) = match &ctx.opt.cmd {
+            Command::HashObject {
+                files,
+                r#type,
+                write,
+                stdin,
+            } => (files.to_owned(), r#type.to_owned(), *write, *stdin),
+            _ => unreachable!(),
+        };
+
+        Self {
+            ctx,
+            files,
+            r#type,
+            write,
+            stdin,
+        }
+    }
+
+    pub fn run(&self) -> Result<()> {
+        if self.stdin {
+            let mut data = Vec::new();
+            self.ctx.stdin.borrow_mut().read_to_end(&mut data)?;
+            self.hash(data)?;
+        }
+
+        for file in &self.files {
+            match fs::read(self.ctx.dir.join(file)) {
+                Ok(data) => self.hash(data)?,
+                Err(err) => {
+                    let mut stderr = self.ctx.stderr.borrow_mut();
+                    writeln!(
+                        stderr,
+                        "fatal: could not open '{}' for reading: {}",
+                        file.display(),
+                        err
+                    )?;
+                    return Err(Error::Exit(128));
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn hash(&self, data: Vec<u8>) -> Result<()> {
+        let object = RawObject {
+            r#type: self.type_name().to_owned(),
+            data,
+        };
+
+        if self.write {
+            self.ctx.repo.database.store(&object)?;
+        }
+
+        let mut stdout = self.ctx.stdout.borrow_mut();
+        writeln!(stdout, "{}", self.ctx.repo.database.hash_object(&object))?;
+
+        Ok(())
+    }
+
+    fn type_name(&self) -> &str {
+        match self.r#type {
+            ObjectType::Blob => "blob",
+            ObjectType::Tree => "tree",
+            ObjectType::Commit => "commit",
+        }
+    }
+}
@@ -1,8 +1,10 @@
 use std::fs;
 use std::io::Write;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use crate::commands::{Command, CommandContext};
+use crate::config::stack::Stack as ConfigStack;
+use crate::config::VariableValue;
 use crate::errors::Result;
 use crate::refs::Refs;
 
@@ -38,7 +40,7 @@ impl<'a> Init<'a> {
         }
 
         let refs = Refs::new(git_path.clone());
-        let path = format!("refs/heads/{}", DEFAULT_BRANCH);
+        let path = format!("refs/heads/{}", self.default_branch(&git_path)?);
         refs.update_head(&format!("ref: {}", path))?;
 
         let mut stdout = self.ctx.stdout.borrow_mut();
@@ -46,4 +48,17 @@ impl<'a> Init<'a> {
 
         Ok(())
     }
+
+    /// The branch `HEAD` starts out on, which `init.defaultBranch` can change.
+    fn default_branch(&self, git_path: &Path) -> Result<String> {
+        let config = ConfigStack::new(git_path);
+        config.open()?;
+
+        Ok(
+            match config.get(&[String::from("init"), String::from("defaultBranch")]) {
+                Some(VariableValue::String(name)) if !name.is_empty() => name,
+                _ => String::from(DEFAULT_BRANCH),
+            },
+        )
+    }
 }
@@ -1,25 +1,35 @@
+mod graph;
+
 use std::cell::RefCell;
-use std::collections::HashMap;
-use std::io::Write;
+use std::collections::{BTreeMap, HashMap};
+use std::io::{self, Write};
+use std::rc::Rc;
 
 use clap::ValueEnum;
 use colored::Colorize;
+use regex::Regex;
 
-use crate::commands::shared::diff_printer::DiffPrinter;
+use crate::commands::shared::diff_printer::{DiffPrinter, FileDiff};
 use crate::commands::{Command, CommandContext};
 use crate::database::commit::Commit;
 use crate::database::object::Object;
-use crate::database::tree_diff::Differ;
 use crate::database::Database;
-use crate::errors::Result;
+use crate::errors::{Error, Result};
+use crate::patch::mailbox;
 use crate::refs::Ref;
-use crate::rev_list::RevList;
+use crate::rev_list::{Pickaxe, RevList, RevListOptions};
 use crate::util::path_to_string;
+use graph::{Graph, GraphWriter};
 
 #[derive(ValueEnum, Debug, Clone, PartialEq, Eq)]
 pub enum LogFormat {
     Medium,
     Oneline,
+    Raw,
+    /// Each commit as an email in an mbox, like `jit format-patch --stdout`
+    Email,
+    /// Like `email`, but quoting body lines that look like `From ` separators
+    Mboxrd,
 }
 
 #[derive(ValueEnum, Debug, Clone, PartialEq, Eq)]
@@ -30,6 +40,13 @@ pub enum LogDecoration {
     No,
 }
 
+/// How `jit log --no-walk` orders the commits it's given.
+#[derive(ValueEnum, Debug, Clone, PartialEq, Eq)]
+pub enum NoWalk {
+    Sorted,
+    Unsorted,
+}
+
 pub struct Log<'a> {
     ctx: CommandContext<'a>,
     diff_printer: DiffPrinter,
@@ -39,6 +56,8 @@ pub struct Log<'a> {
     args: Vec<String>,
     /// `jit log --abbrev-commit`
     abbrev: bool,
+    /// `jit log --abbrev=<n>` or `jit log --no-abbrev`. Without them, `core.abbrev` is used.
+    abbrev_length: Option<usize>,
     /// `jit log --pretty=<format>` or `jit log --format=<format>`
     format: LogFormat,
     /// `jit log --patch`
@@ -47,15 +66,41 @@ pub struct Log<'a> {
     combined: bool,
     /// `jit log --decorate=<format>` or `jit log --no-decorate`
     decorate: LogDecoration,
+    /// `jit log --follow <path>`
+    follow: bool,
+    /// `jit log --stat`
+    stat: bool,
+    /// `jit log --graph`
+    graph: Option<Rc<RefCell<Graph>>>,
+    /// False for `jit show` and `jit log --no-walk`, which list only the given commits rather
+    /// than their history
+    walk: bool,
+    /// `jit log --no-walk=sorted`, which lists the given commits newest first
+    sort_unwalked: bool,
+    /// `jit log -S<string>`
+    pickaxe_string: Option<String>,
+    /// `jit log -G<regex>`
+    pickaxe_regex: Option<String>,
+    /// `jit log --show-signature`
+    show_signature: bool,
+    /// `jit log --simplify-by-decoration`
+    simplify_by_decoration: bool,
+    /// `jit log --ancestry-path`
+    ancestry_path: bool,
     reverse_refs: Option<HashMap<String, Vec<Ref>>>,
     current_ref: Option<Ref>,
+    notes: Option<BTreeMap<String, String>>,
 }
 
 impl<'a> Log<'a> {
     pub fn new(ctx: CommandContext<'a>) -> Self {
-        let (args, abbrev, format, patch, combined, decorate) = match &ctx.opt.cmd {
+        let opt = ctx.opt;
+
+        match &opt.cmd {
             Command::Log {
                 args,
+                abbrev_commit,
+                no_abbrev_commit,
                 abbrev,
                 no_abbrev,
                 format,
@@ -65,6 +110,15 @@ impl<'a> Log<'a> {
                 patch,
                 _no_patch,
                 combined,
+                follow,
+                stat,
+                graph,
+                pickaxe_string,
+                pickaxe_regex,
+                show_signature,
+                simplify_by_decoration,
+                ancestry_path,
+                no_walk,
             } => {
                 let format = if *one_line {
                     LogFormat::Oneline
@@ -72,9 +126,6 @@ impl<'a> Log<'a> {
                     format.to_owned()
                 };
 
-                // `--oneline --no-abbrev-commit` sets `abbrev = false`
-                let abbrev = (*abbrev || *one_line) && !*no_abbrev;
-
                 let decorate = if *no_decorate {
                     LogDecoration::No
                 } else {
@@ -85,50 +136,156 @@ impl<'a> Log<'a> {
                     }
                 };
 
-                let patch = if *combined { true } else { *patch };
-
-                (args.to_owned(), abbrev, format, patch, *combined, decorate)
+                Self {
+                    ctx,
+                    diff_printer: DiffPrinter::new(),
+                    blank_line: RefCell::new(false),
+                    args: args.to_owned(),
+                    // `--oneline --no-abbrev-commit` sets `abbrev = false`
+                    abbrev: (*abbrev_commit || *one_line) && !*no_abbrev_commit,
+                    abbrev_length: if *no_abbrev {
+                        Some(usize::MAX)
+                    } else {
+                        abbrev.to_owned()
+                    },
+                    format,
+                    patch: *patch || *combined,
+                    combined: *combined,
+                    decorate,
+                    follow: *follow,
+                    stat: *stat,
+                    graph: graph.then(|| Rc::new(RefCell::new(Graph::new()))),
+                    walk: no_walk.is_none(),
+                    // `--no-walk` alone sorts the commits
+                    sort_unwalked: matches!(no_walk, Some(None | Some(NoWalk::Sorted))),
+                    pickaxe_string: pickaxe_string.to_owned(),
+                    pickaxe_regex: pickaxe_regex.to_owned(),
+                    show_signature: *show_signature,
+                    simplify_by_decoration: *simplify_by_decoration,
+                    ancestry_path: *ancestry_path,
+                    reverse_refs: None,
+                    current_ref: None,
+                    notes: None,
+                }
+            }
+            Command::Show {
+                args,
+                patch,
+                stat,
+                show_signature,
+            } => {
+                // `jit show` prints the patch unless only `--stat` is asked for
+                let patch = *patch || !*stat;
+
+                Self {
+                    ctx,
+                    diff_printer: DiffPrinter::new(),
+                    blank_line: RefCell::new(false),
+                    args: args.to_owned(),
+                    abbrev: false,
+                    abbrev_length: None,
+                    format: LogFormat::Medium,
+                    patch,
+                    combined: patch,
+                    decorate: LogDecoration::Auto,
+                    follow: false,
+                    stat: *stat,
+                    graph: None,
+                    walk: false,
+                    sort_unwalked: false,
+                    pickaxe_string: None,
+                    pickaxe_regex: None,
+                    show_signature: *show_signature,
+                    simplify_by_decoration: false,
+                    ancestry_path: false,
+                    reverse_refs: None,
+                    current_ref: None,
+                    notes: None,
+                }
             }
             _ => unreachable!(),
-        };
-
-        Self {
-            ctx,
-            diff_printer: DiffPrinter::new(),
-            blank_line: RefCell::new(false),
-            args,
-            abbrev,
-            format,
-            patch,
-            combined,
-            decorate,
-            reverse_refs: None,
-            current_ref: None,
         }
     }
 
     pub fn run(&mut self) -> Result<()> {
+        self.diff_printer.quote_path = self.ctx.repo.quote_path()?;
+        self.diff_printer.abbrev = match self.abbrev_length {
+            Some(length) => length,
+            None => self.ctx.repo.abbrev()?,
+        };
+        self.diff_printer.load_textconv(&self.ctx.repo)?;
         self.ctx.setup_pager();
 
+        // Draw the graph to the left of everything written for each commit
+        if let Some(graph) = &self.graph {
+            let stdout = self.ctx.stdout.replace(Box::new(io::sink()));
+            self.ctx
+                .stdout
+                .replace(Box::new(GraphWriter::new(stdout, Rc::clone(graph))));
+        }
+
         self.reverse_refs = Some(self.ctx.repo.refs.reverse_refs()?);
         self.current_ref = Some(self.ctx.repo.refs.current_ref("HEAD")?);
+        self.notes = Some(self.ctx.repo.notes().list()?);
+
+        let pickaxe = match self.pickaxe() {
+            Ok(pickaxe) => pickaxe,
+            Err(err) => {
+                let mut stderr = self.ctx.stderr.borrow_mut();
+                writeln!(stderr, "fatal: invalid regex: {}", err)?;
+                return Err(Error::Exit(128));
+            }
+        };
+        let options = RevListOptions {
+            walk: self.walk,
+            sort_unwalked: self.sort_unwalked,
+            follow: self.follow,
+            pickaxe,
+            // Like Git, `--graph` lists commits in topological order so each one's lane leads
+            // down to its parents
+            topo_order: self.graph.is_some(),
+            simplify_by_decoration: self.simplify_by_decoration,
+            ancestry_path: self.ancestry_path,
+        };
+        let mut rev_list = match RevList::new(&self.ctx.repo, &self.args, options) {
+            Ok(rev_list) => rev_list,
+            Err(err @ Error::InvalidArgument(..)) => {
+                let mut stderr = self.ctx.stderr.borrow_mut();
+                writeln!(stderr, "fatal: {}", err)?;
+                return Err(Error::Exit(128));
+            }
+            Err(err) => return Err(err),
+        };
 
-        // We need to pass rev_list down to `show_patch()`, but we can't pass the `RevList` we're
-        // iterating over because iteration requires a mutable borrow. We work around this by
-        // creating two identical `RevList`s and iterating over one and passing the other.
-        // Inefficient? Yes, but I don't have any better ideas.
-        let rev_list = RevList::new(&self.ctx.repo, &self.args, Default::default())?;
-        for commit in RevList::new(&self.ctx.repo, &self.args, Default::default())? {
-            self.show_commit(&commit, &rev_list)?;
+        // Iterate with `while let` rather than `for` so `rev_list` isn't borrowed while each
+        // commit is shown; `show_patch()` needs it to reuse its cached tree diffs.
+        while let Some(commit) = rev_list.next() {
+            self.show_commit(&commit?, &rev_list)?;
         }
 
         Ok(())
     }
 
+    fn pickaxe(&self) -> std::result::Result<Option<Pickaxe>, regex::Error> {
+        if let Some(string) = &self.pickaxe_string {
+            Ok(Some(Pickaxe::String(string.to_owned())))
+        } else if let Some(regex) = &self.pickaxe_regex {
+            Ok(Some(Pickaxe::Regex(Regex::new(regex)?)))
+        } else {
+            Ok(None)
+        }
+    }
+
     fn show_commit(&self, commit: &Commit, rev_list: &RevList) -> Result<()> {
+        if let Some(graph) = &self.graph {
+            graph.borrow_mut().commit(commit);
+        }
+
         match self.format {
             LogFormat::Medium => self.show_commit_medium(commit)?,
             LogFormat::Oneline => self.show_commit_oneline(commit)?,
+            LogFormat::Raw => self.show_commit_raw(commit)?,
+            LogFormat::Email | LogFormat::Mboxrd => self.show_commit_email(commit)?,
         }
 
         self.show_patch(commit, rev_list)?;
@@ -147,12 +304,15 @@ impl<'a> Log<'a> {
             format!("commit {}", self.maybe_abbrev(commit)).yellow(),
             self.decorate(commit),
         )?;
+        drop(stdout);
+        self.show_signature(commit)?;
 
+        let mut stdout = self.ctx.stdout.borrow_mut();
         if commit.is_merge() {
             let oids: Vec<_> = commit
                 .parents
                 .iter()
-                .map(|oid| Database::short_oid(oid))
+                .map(|oid| Database::abbreviate(oid, self.diff_printer.abbrev))
                 .collect();
             writeln!(stdout, "Merge: {}", oids.join(" "))?;
         }
@@ -166,11 +326,44 @@ impl<'a> Log<'a> {
         for line in commit.message.lines() {
             writeln!(stdout, "    {}", line)?;
         }
+        drop(stdout);
+
+        self.show_notes(commit)?;
+
+        Ok(())
+    }
+
+    fn show_notes(&self, commit: &Commit) -> Result<()> {
+        let note_oid = match self.notes.as_ref().unwrap().get(&commit.oid()) {
+            Some(note_oid) => note_oid,
+            None => return Ok(()),
+        };
+        let note = self.ctx.repo.notes().read_blob(note_oid)?;
+
+        let mut stdout = self.ctx.stdout.borrow_mut();
+        writeln!(stdout)?;
+        writeln!(stdout, "Notes:")?;
+        for line in note.lines() {
+            writeln!(stdout, "    {}", line)?;
+        }
+
+        Ok(())
+    }
+
+    /// With `--show-signature`, note that a commit is signed. Like Git, unsigned commits show
+    /// nothing. jit can't check signatures, so they're never reported as good or bad.
+    fn show_signature(&self, commit: &Commit) -> Result<()> {
+        if self.show_signature && commit.gpgsig().is_some() {
+            let mut stdout = self.ctx.stdout.borrow_mut();
+            writeln!(stdout, "Signature: present (not verified)")?;
+        }
 
         Ok(())
     }
 
     fn show_commit_oneline(&self, commit: &Commit) -> Result<()> {
+        self.show_signature(commit)?;
+
         let mut stdout = self.ctx.stdout.borrow_mut();
         writeln!(
             stdout,
@@ -183,6 +376,64 @@ impl<'a> Log<'a> {
         Ok(())
     }
 
+    /// Show the commit as an email, with the same headers as `jit format-patch`.
+    fn show_commit_email(&self, commit: &Commit) -> Result<()> {
+        self.blank_line()?;
+        let mut stdout = self.ctx.stdout.borrow_mut();
+        mailbox::write_headers(&mut *stdout, commit, "[PATCH]")?;
+
+        let body = commit
+            .message
+            .split_once('\n')
+            .map_or("", |(_, body)| body)
+            .trim();
+        for line in body.lines() {
+            // mboxrd quotes `From ` lines, and lines already quoted, so they can be unquoted
+            if self.format == LogFormat::Mboxrd && line.trim_start_matches('>').starts_with("From ")
+            {
+                writeln!(stdout, ">{}", line)?;
+            } else {
+                writeln!(stdout, "{}", line)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Show the commit's headers as they're stored, followed by its indented message.
+    fn show_commit_raw(&self, commit: &Commit) -> Result<()> {
+        self.blank_line()?;
+        let mut stdout = self.ctx.stdout.borrow_mut();
+        writeln!(
+            stdout,
+            "{}{}",
+            format!("commit {}", self.maybe_abbrev(commit)).yellow(),
+            self.decorate(commit),
+        )?;
+
+        writeln!(stdout, "tree {}", commit.tree)?;
+        for parent in &commit.parents {
+            writeln!(stdout, "parent {}", parent)?;
+        }
+        writeln!(stdout, "author {}", commit.author)?;
+        writeln!(stdout, "committer {}", commit.committer)?;
+        for (key, value) in &commit.extra_headers {
+            writeln!(stdout, "{} {}", key, value.replace('\n', "\n "))?;
+        }
+        drop(stdout);
+        self.blank_line()?;
+
+        let mut stdout = self.ctx.stdout.borrow_mut();
+        for line in commit.message.lines() {
+            writeln!(stdout, "    {}", line)?;
+        }
+        drop(stdout);
+
+        self.show_notes(commit)?;
+
+        Ok(())
+    }
+
     fn decorate(&self, commit: &Commit) -> String {
         if (self.decorate == LogDecoration::Auto && !self.ctx.isatty)
             || self.decorate == LogDecoration::No
@@ -248,7 +499,7 @@ impl<'a> Log<'a> {
 
     fn maybe_abbrev(&self, commit: &Commit) -> String {
         if self.abbrev {
-            Database::short_oid(&commit.oid())
+            Database::abbreviate(&commit.oid(), self.diff_printer.abbrev)
         } else {
             commit.oid()
         }
@@ -263,63 +514,99 @@ impl<'a> Log<'a> {
     }
 
     fn show_patch(&self, commit: &Commit, rev_list: &RevList) -> Result<()> {
-        if !self.patch {
+        if !self.patch && !self.stat {
             return Ok(());
         }
         if commit.is_merge() {
+            if !self.patch {
+                return Ok(());
+            }
             return self.show_merge_patch(commit, rev_list);
         }
 
-        self.blank_line()?;
-
-        let mut stdout = self.ctx.stdout.borrow_mut();
-        self.diff_printer.print_commit_diff(
-            &mut stdout,
+        // Diff each file once, and share the edits between the stat and the patch
+        let mut file_diffs = self.diff_printer.commit_diffs(
             &self.ctx.repo,
-            commit.parent().as_deref(),
+            &commit.first_parent_or_empty_tree(),
             &commit.oid(),
             Some(rev_list),
         )?;
 
+        // With `--follow`, show the commit that renamed the file as a rename rather than as
+        // adding it
+        if let (Some(rename), Some(parent)) = (
+            rev_list.followed_rename(&commit.oid()),
+            commit.first_parent(),
+        ) {
+            let path = path_to_string(&rename.new_path);
+            if let Some(file_diff) = file_diffs.iter_mut().find(|diff| diff.path() == path) {
+                *file_diff = self.diff_printer.rename_diff(
+                    &self.ctx.repo,
+                    &parent,
+                    &commit.oid(),
+                    rename,
+                )?;
+            }
+        }
+
+        if self.stat {
+            self.show_stat(&file_diffs)?;
+        }
+
+        if self.patch {
+            // The stat already ends with a blank line separating it from the patch
+            if !self.stat {
+                self.blank_line()?;
+            }
+
+            let mut stdout = self.ctx.stdout.borrow_mut();
+            for file_diff in file_diffs {
+                self.diff_printer.print_file_diff(&mut *stdout, file_diff)?;
+            }
+        }
+
         Ok(())
     }
 
-    fn show_merge_patch(&self, commit: &Commit, rev_list: &RevList) -> Result<()> {
-        if !self.combined {
-            return Ok(());
+    fn show_stat(&self, file_diffs: &[FileDiff]) -> Result<()> {
+        // Like Git, separate the message from a stat that's followed by a patch with `---`
+        if self.patch
+            && matches!(
+                self.format,
+                LogFormat::Medium | LogFormat::Email | LogFormat::Mboxrd
+            )
+        {
+            let mut stdout = self.ctx.stdout.borrow_mut();
+            writeln!(stdout, "---")?;
+        } else {
+            self.blank_line()?;
         }
 
-        let mut diffs = Vec::new();
-        for oid in &commit.parents {
-            diffs.push(rev_list.tree_diff(Some(oid), Some(&commit.oid()), None)?);
+        let mut stdout = self.ctx.stdout.borrow_mut();
+        self.diff_printer.print_stat(&mut *stdout, file_diffs)?;
+
+        if self.patch {
+            writeln!(stdout)?;
         }
 
-        let paths = diffs[0]
-            .keys()
-            .into_iter()
-            .filter(|path| diffs.iter().all(|diff| diff.contains_key(path.to_owned())));
+        Ok(())
+    }
+
+    fn show_merge_patch(&self, commit: &Commit, rev_list: &RevList) -> Result<()> {
+        if !self.combined {
+            return Ok(());
+        }
 
         self.blank_line()?;
 
         let mut stdout = self.ctx.stdout.borrow_mut();
-        for path in paths {
-            let mut parents = Vec::new();
-            for diff in &diffs {
-                parents.push(self.diff_printer.from_entry(
-                    &self.ctx.repo,
-                    &path_to_string(path),
-                    diff[path].0.as_ref(),
-                )?);
-            }
-            let child = self.diff_printer.from_entry(
-                &self.ctx.repo,
-                &path_to_string(path),
-                diffs[0][path].1.as_ref(),
-            )?;
-
-            self.diff_printer
-                .print_combined_diff(&mut stdout, &parents, &child)?;
-        }
+        self.diff_printer.print_merge_diff(
+            &mut *stdout,
+            &self.ctx.repo,
+            &commit.parents,
+            &commit.oid(),
+            rev_list,
+        )?;
 
         Ok(())
     }
@@ -0,0 +1,224 @@
+use std::cell::RefCell;
+use std::io::{self, Write};
+use std::rc::Rc;
+
+use crate::database::commit::Commit;
+use crate::database::object::Object;
+
+/// The lanes `jit log --graph` draws in the margin, one for each line of history that's waiting
+/// for a commit to be shown.
+#[derive(Debug, Default)]
+pub struct Graph {
+    /// The commit each lane is waiting for, from left to right
+    lanes: Vec<String>,
+    /// The lane and parents of the commit being shown
+    current: Option<(usize, Vec<String>)>,
+    /// Whether the line with the current commit's `*` has been drawn
+    drawn_commit: bool,
+    /// Rows joining the lanes above the current commit to the lanes leading to it
+    rows: Vec<String>,
+}
+
+impl Graph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start showing `commit`, moving the lanes on from the previous commit to its parents.
+    pub fn commit(&mut self, commit: &Commit) {
+        if let Some((column, parents)) = self.current.take() {
+            self.rows = self.advance(column, &parents);
+        }
+
+        let oid = commit.oid();
+        let column = match self.lanes.iter().position(|lane| lane == &oid) {
+            Some(column) => column,
+            None => {
+                self.lanes.push(oid);
+                self.lanes.len() - 1
+            }
+        };
+
+        self.current = Some((column, commit.parents.clone()));
+        self.drawn_commit = false;
+    }
+
+    /// Replace the commit in `column` with its `parents`, returning the rows that draw each lane
+    /// moving to its new place. Lanes move at most one place per row.
+    fn advance(&mut self, column: usize, parents: &[String]) -> Vec<String> {
+        let mut new_parents: Vec<String> = vec![];
+        for parent in parents {
+            if !self.lanes.contains(parent) && !new_parents.contains(parent) {
+                new_parents.push(parent.to_owned());
+            }
+        }
+
+        let mut lanes = self.lanes[..column].to_vec();
+        lanes.extend(new_parents);
+        lanes.extend(self.lanes[column + 1..].iter().cloned());
+
+        let position = |oid: &String| lanes.iter().position(|lane| lane == oid).unwrap();
+        let mut edges = vec![];
+        for (i, lane) in self.lanes.iter().enumerate() {
+            if i == column {
+                edges.extend(parents.iter().map(|parent| (i, position(parent))));
+            } else {
+                edges.push((i, position(lane)));
+            }
+        }
+
+        let width = 2 * self.lanes.len().max(lanes.len());
+        let mut rows = vec![];
+        while edges.iter().any(|(from, to)| from != to) {
+            let mut row = vec![' '; width];
+            for (from, to) in edges.iter_mut() {
+                if *from < *to {
+                    row[2 * *from + 1] = '\\';
+                    *from += 1;
+                } else if *from > *to {
+                    row[2 * *from - 1] = '/';
+                    *from -= 1;
+                } else {
+                    row[2 * *from] = '|';
+                }
+            }
+            rows.push(row.into_iter().collect::<String>().trim_end().to_owned());
+        }
+
+        self.lanes = lanes;
+        rows
+    }
+
+    /// The prefix for the next line of output. The first line with any content is the commit's
+    /// own line, marked with a `*`; blank lines before it and every line after it continue the
+    /// lanes, except the lane of a root commit, which ends with it.
+    fn prefix(&mut self, blank: bool) -> String {
+        let (column, parents) = match &self.current {
+            Some(current) => current,
+            None => return String::new(),
+        };
+
+        let commit_line = !self.drawn_commit && !blank;
+        let marks: Vec<_> = (0..self.lanes.len())
+            .map(|i| {
+                if i != *column || (!self.drawn_commit && blank) {
+                    "|"
+                } else if commit_line {
+                    "*"
+                } else if parents.is_empty() {
+                    " "
+                } else {
+                    "|"
+                }
+            })
+            .collect();
+        if commit_line {
+            self.drawn_commit = true;
+        }
+
+        format!("{} ", marks.join(" "))
+    }
+}
+
+/// Writes the log with the graph drawn to the left of each line.
+pub struct GraphWriter {
+    inner: Box<dyn Write>,
+    graph: Rc<RefCell<Graph>>,
+    /// The start of a line that hasn't been finished yet
+    line: Vec<u8>,
+}
+
+impl GraphWriter {
+    pub fn new(inner: Box<dyn Write>, graph: Rc<RefCell<Graph>>) -> Self {
+        Self {
+            inner,
+            graph,
+            line: vec![],
+        }
+    }
+
+    fn write_line(&mut self) -> io::Result<()> {
+        let mut graph = self.graph.borrow_mut();
+        for row in graph.rows.drain(..) {
+            writeln!(self.inner, "{}", row)?;
+        }
+
+        let prefix = graph.prefix(self.line.is_empty());
+        if self.line.is_empty() {
+            writeln!(self.inner, "{}", prefix.trim_end())?;
+        } else {
+            self.inner.write_all(prefix.as_bytes())?;
+            self.inner.write_all(&self.line)?;
+            writeln!(self.inner)?;
+        }
+        self.line.clear();
+
+        Ok(())
+    }
+}
+
+impl Write for GraphWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        for byte in buf {
+            if *byte == b'\n' {
+                self.write_line()?;
+            } else {
+                self.line.push(*byte);
+            }
+        }
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn advance(lanes: &[&str], column: usize, parents: &[&str]) -> (Vec<String>, Vec<String>) {
+        let mut graph = Graph {
+            lanes: lanes.iter().map(|lane| lane.to_string()).collect(),
+            ..Graph::new()
+        };
+        let parents: Vec<_> = parents.iter().map(|parent| parent.to_string()).collect();
+        let rows = graph.advance(column, &parents);
+
+        (graph.lanes, rows)
+    }
+
+    #[test]
+    fn continue_a_lane_with_its_parent() {
+        assert_eq!(
+            advance(&["b"], 0, &["a"]),
+            (vec![String::from("a")], vec![])
+        );
+    }
+
+    #[test]
+    fn split_a_lane_for_a_merge() {
+        let (lanes, rows) = advance(&["m", "x"], 0, &["a", "b"]);
+
+        assert_eq!(lanes, vec!["a", "b", "x"]);
+        assert_eq!(rows, vec!["|\\ \\"]);
+    }
+
+    #[test]
+    fn join_lanes_waiting_for_the_same_commit() {
+        let (lanes, rows) = advance(&["b", "a"], 0, &["a"]);
+
+        assert_eq!(lanes, vec!["a"]);
+        assert_eq!(rows, vec!["|/"]);
+    }
+
+    #[test]
+    fn end_the_lane_of_a_root_commit() {
+        let (lanes, rows) = advance(&["a", "x", "y"], 0, &[]);
+
+        assert_eq!(lanes, vec!["x", "y"]);
+        assert_eq!(rows, vec![" / /"]);
+    }
+}
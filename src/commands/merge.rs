@@ -1,5 +1,7 @@
 use std::path::PathBuf;
 
+use clap::ValueEnum;
+
 use crate::commands::shared::commit_writer::{CommitWriter, CONFLICT_MESSAGE};
 use crate::commands::{Command, CommandContext};
 use crate::database::tree_diff::Differ;
@@ -10,6 +12,7 @@ use crate::merge::inputs::Inputs;
 use crate::merge::resolve::Resolve;
 use crate::refs::ORIG_HEAD;
 use crate::repository::pending_commit::{PendingCommit, PendingCommitType};
+use crate::rev_list::RevList;
 use crate::revision::HEAD;
 
 const COMMIT_NOTES: &str = "\
@@ -19,6 +22,17 @@ especially if it merges an updated upstream into a topic branch.
 Lines starting with '#' will be ignored, and an empty message aborts
 the commit.\n";
 
+/// The number of merged commits listed by `--log` when no limit is given.
+const DEFAULT_LOG_LENGTH: usize = 20;
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// Merge the content of both sides, stopping on conflicts
+    Resolve,
+    /// Record the merge but keep the current tree, discarding the other side's changes
+    Ours,
+}
+
 enum Mode {
     Run,
     Abort,
@@ -31,12 +45,18 @@ pub struct Merge<'a> {
     message: Option<String>,
     file: Option<PathBuf>,
     edit: bool,
+    /// `jit merge --log[=<n>]`
+    log: Option<usize>,
+    /// `jit merge --strategy=<strategy>`
+    strategy: MergeStrategy,
+    /// `jit merge --into-name=<branch>`
+    into_name: Option<String>,
     mode: Mode,
 }
 
 impl<'a> Merge<'a> {
     pub fn new(ctx: CommandContext<'a>) -> Result<Self> {
-        let (args, mode, message, file, edit) = match &ctx.opt.cmd {
+        let (args, mode, message, file, edit, log, strategy, into_name) = match &ctx.opt.cmd {
             Command::Merge {
                 args,
                 abort,
@@ -45,6 +65,9 @@ impl<'a> Merge<'a> {
                 file,
                 edit,
                 no_edit,
+                log,
+                strategy,
+                into_name,
             } => {
                 let mode = if *abort {
                     Mode::Abort
@@ -59,6 +82,9 @@ impl<'a> Merge<'a> {
                     message.as_ref().map(|m| m.to_owned()),
                     file.as_ref().map(|f| f.to_owned()),
                     *edit || !*no_edit && message.is_none() && file.is_none(),
+                    log.map(|n| n.unwrap_or(DEFAULT_LOG_LENGTH)),
+                    *strategy,
+                    into_name.to_owned(),
                 )
             }
             _ => unreachable!(),
@@ -70,6 +96,9 @@ impl<'a> Merge<'a> {
             message,
             file,
             edit,
+            log,
+            strategy,
+            into_name,
             mode,
         })
     }
@@ -86,7 +115,10 @@ impl<'a> Merge<'a> {
             self.handle_in_progress_merge()?;
         }
 
-        let inputs = Inputs::new(&self.ctx.repo, HEAD.to_string(), self.args[0].clone())?;
+        let mut inputs = Inputs::new(&self.ctx.repo, HEAD.to_string(), self.args[0].clone())?;
+        if let Some(into_name) = &self.into_name {
+            inputs.left_name = into_name.to_owned();
+        }
         self.ctx.repo.refs.update_ref(ORIG_HEAD, &inputs.left_oid)?;
 
         if inputs.already_merged() {
@@ -97,7 +129,11 @@ impl<'a> Merge<'a> {
         }
 
         pending_commit.start(&inputs.right_oid, PendingCommitType::Merge)?;
-        self.resolve_merge(&inputs, &pending_commit)?;
+        match self.strategy {
+            MergeStrategy::Resolve => self.resolve_merge(&inputs, &pending_commit)?,
+            // Commit the index, and so HEAD's tree, unchanged
+            MergeStrategy::Ours => self.ctx.repo.index.load()?,
+        }
         self.commit_merge(&inputs, &pending_commit)?;
 
         Ok(())
@@ -106,11 +142,14 @@ impl<'a> Merge<'a> {
     fn resolve_merge(&mut self, inputs: &Inputs, pending_commit: &PendingCommit) -> Result<()> {
         self.ctx.repo.index.load_for_update()?;
 
+        let quiet = self.ctx.quiet();
         let mut merge = Resolve::new(&mut self.ctx.repo, inputs);
         // While not ideal, it's safe to use `println!()` here because `jit merge` doesn't use a
         // pager. Ideally this would be a closure using `self.ctx.stdout` and `writeln!()`, but I
         // couldn't figure out how to get that to work.
-        merge.on_progress = |info| println!("{}", info);
+        if !quiet {
+            merge.on_progress = |info| println!("{}", info);
+        }
         merge.execute()?;
 
         self.ctx.repo.index.write_updates()?;
@@ -122,22 +161,17 @@ impl<'a> Merge<'a> {
     }
 
     fn fail_on_conflict(&self, inputs: &Inputs, pending_commit: &PendingCommit) -> Result<()> {
-        let commit_writer = self.commit_writer();
-
-        let message = commit_writer.read_message(self.message.as_deref(), self.file.as_deref())?;
-        let message = if message.is_empty() {
-            self.default_commit_message(inputs)
-        } else {
-            message
-        };
+        let message = self.merge_message(inputs)?;
 
+        // The list of conflicts is part of the message, so it's kept in the merge commit once the
+        // conflicts have been resolved
         self.ctx
             .edit_file(&pending_commit.message_path, |editor: &mut Editor| {
-                editor.write(&message)?;
+                editor.write(message.trim_end())?;
                 editor.write("")?;
-                editor.note("Conflicts:")?;
+                editor.write("Conflicts:")?;
                 for name in self.ctx.repo.index.conflict_paths() {
-                    editor.note(&format!("\t{}", name))?;
+                    editor.write(&format!("\t{}", name))?;
                 }
                 editor.close();
 
@@ -172,14 +206,7 @@ impl<'a> Merge<'a> {
         inputs: &Inputs,
         pending_commit: &PendingCommit,
     ) -> Result<Option<String>> {
-        let commit_writer = self.commit_writer();
-
-        let message = commit_writer.read_message(self.message.as_deref(), self.file.as_deref())?;
-        let message = if message.is_empty() {
-            self.default_commit_message(inputs)
-        } else {
-            message
-        };
+        let message = self.merge_message(inputs)?;
 
         self.ctx
             .edit_file(&pending_commit.message_path, |editor: &mut Editor| {
@@ -195,14 +222,57 @@ impl<'a> Merge<'a> {
             })
     }
 
+    /// The message given with `-m` or `-F`, or the default one, followed by the `--log` summary.
+    fn merge_message(&self, inputs: &Inputs) -> Result<String> {
+        let commit_writer = self.commit_writer();
+
+        let message = commit_writer.read_message(self.message.as_deref(), self.file.as_deref())?;
+        let mut message = if message.is_empty() {
+            self.default_commit_message(inputs)
+        } else {
+            message
+        };
+
+        if let Some(limit) = self.log {
+            message = format!(
+                "{}\n\n{}",
+                message.trim_end(),
+                self.merged_log(inputs, limit)?
+            );
+        }
+
+        Ok(message)
+    }
+
     fn default_commit_message(&self, inputs: &Inputs) -> String {
-        format!("Merge commit '{}'", inputs.right_name.clone())
+        match &self.into_name {
+            Some(into_name) => format!("Merge commit '{}' into {}", inputs.right_name, into_name),
+            None => format!("Merge commit '{}'", inputs.right_name),
+        }
     }
 
-    fn handle_merged_ancestor(&self) -> Result<()> {
-        let mut stdout = self.ctx.stdout.borrow_mut();
+    /// List the subjects of up to `limit` of the commits being merged in, like `git merge --log`.
+    fn merged_log(&self, inputs: &Inputs, limit: usize) -> Result<String> {
+        let range = format!("{}..{}", inputs.left_oid, inputs.right_oid);
+        let commits: Vec<_> =
+            RevList::new(&self.ctx.repo, &[range], Default::default())?.collect::<Result<_>>()?;
 
-        writeln!(stdout, "Already up to date.")?;
+        let mut log = format!("* {}:\n", inputs.right_name);
+        for commit in commits.iter().take(limit) {
+            log.push_str(&format!("  {}\n", commit.title_line()));
+        }
+        if commits.len() > limit {
+            log.push_str("  ...\n");
+        }
+
+        Ok(log)
+    }
+
+    fn handle_merged_ancestor(&self) -> Result<()> {
+        if !self.ctx.quiet() {
+            let mut stdout = self.ctx.stdout.borrow_mut();
+            writeln!(stdout, "Already up to date.")?;
+        }
 
         Err(Error::Exit(0))
     }
@@ -211,17 +281,19 @@ impl<'a> Merge<'a> {
         let a = Database::short_oid(&inputs.left_oid);
         let b = Database::short_oid(&inputs.right_oid);
 
-        let mut stdout = self.ctx.stdout.borrow_mut();
-        writeln!(stdout, "Updating {}..{}", a, b)?;
-        writeln!(stdout, "Fast-forward")?;
+        if !self.ctx.quiet() {
+            let mut stdout = self.ctx.stdout.borrow_mut();
+            writeln!(stdout, "Updating {}..{}", a, b)?;
+            writeln!(stdout, "Fast-forward")?;
+        }
 
         self.ctx.repo.index.load_for_update()?;
 
-        let tree_diff = self.ctx.repo.database.tree_diff(
-            Some(&inputs.left_oid),
-            Some(&inputs.right_oid),
-            None,
-        )?;
+        let tree_diff =
+            self.ctx
+                .repo
+                .database
+                .tree_diff(&inputs.left_oid, &inputs.right_oid, None)?;
         self.ctx.repo.migration(tree_diff).apply_changes()?;
 
         self.ctx.repo.index.write_updates()?;
@@ -258,7 +330,10 @@ impl<'a> Merge<'a> {
     fn handle_continue(&mut self) -> Result<()> {
         self.ctx.repo.index.load()?;
 
-        match self.commit_writer().resume_merge(PendingCommitType::Merge) {
+        match self
+            .commit_writer()
+            .resume_merge(PendingCommitType::Merge, None)
+        {
             Ok(()) => Ok(()),
             Err(err) => match err {
                 Error::NoMergeInProgress(..) => {
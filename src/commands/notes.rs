@@ -0,0 +1,146 @@
+use std::io::Write;
+
+use crate::commands::shared::commit_writer::CommitWriter;
+use crate::commands::{Command, CommandContext};
+use crate::errors::{Error, Result};
+use crate::refs::HEAD;
+use crate::revision::{Revision, COMMIT};
+
+pub struct Notes<'a> {
+    ctx: CommandContext<'a>,
+    /// `jit notes [list | add | show] [<object>]`
+    args: Vec<String>,
+    /// `jit notes add --message <msg>`
+    message: Option<String>,
+    /// `jit notes add --force`
+    force: bool,
+}
+
+impl<'a> Notes<'a> {
+    pub fn new(ctx: CommandContext<'a>) -> Self {
+        let (args, message, force) = match &ctx.opt.cmd {
+            Command::Notes {
+                args,
+                message,
+                force,
+            } => (args.to_owned(), message.to_owned(), *force),
+            _ => unreachable!(),
+        };
+
+        Self {
+            ctx,
+            args,
+            message,
+            force,
+        }
+    }
+
+    pub fn run(&mut self) -> Result<()> {
+        if self.args.is_empty() {
+            self.list_notes()
+        } else {
+            match self.args.remove(0).as_str() {
+                "list" => self.list_notes(),
+                "add" => self.add_note(),
+                "show" => self.show_note(),
+                subcommand => {
+                    let mut stderr = self.ctx.stderr.borrow_mut();
+                    writeln!(stderr, "error: unknown subcommand: {}", subcommand)?;
+                    Err(Error::Exit(129))
+                }
+            }
+        }
+    }
+
+    fn list_notes(&self) -> Result<()> {
+        let notes = self.ctx.repo.notes().list()?;
+
+        if self.args.is_empty() {
+            let mut stdout = self.ctx.stdout.borrow_mut();
+            for (oid, note_oid) in notes {
+                writeln!(stdout, "{} {}", note_oid, oid)?;
+            }
+        } else {
+            let oid = self.select_object()?;
+
+            match notes.get(&oid) {
+                Some(note_oid) => {
+                    let mut stdout = self.ctx.stdout.borrow_mut();
+                    writeln!(stdout, "{}", note_oid)?;
+                }
+                None => return self.no_note_found(&oid),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn add_note(&self) -> Result<()> {
+        let oid = self.select_object()?;
+        let notes = self.ctx.repo.notes();
+
+        if !self.force && notes.read(&oid)?.is_some() {
+            let mut stderr = self.ctx.stderr.borrow_mut();
+            writeln!(
+                stderr,
+                "error: Cannot add notes. Found existing notes for object {}. \
+                Use '-f' to overwrite existing notes",
+                oid
+            )?;
+            return Err(Error::Exit(1));
+        }
+
+        let message = match &self.message {
+            Some(message) => format!("{}\n", message.trim_end()),
+            None => {
+                let mut stderr = self.ctx.stderr.borrow_mut();
+                writeln!(stderr, "error: a note message is required (use -m <msg>)")?;
+                return Err(Error::Exit(129));
+            }
+        };
+
+        let author = CommitWriter::new(&self.ctx).current_author();
+        notes.add(&oid, &message, author)?;
+
+        Ok(())
+    }
+
+    fn show_note(&self) -> Result<()> {
+        let oid = self.select_object()?;
+
+        match self.ctx.repo.notes().read(&oid)? {
+            Some(note) => {
+                let mut stdout = self.ctx.stdout.borrow_mut();
+                write!(stdout, "{}", note)?;
+
+                Ok(())
+            }
+            None => self.no_note_found(&oid),
+        }
+    }
+
+    fn select_object(&self) -> Result<String> {
+        let name = self.args.first().map(String::as_str).unwrap_or(HEAD);
+
+        match Revision::new(&self.ctx.repo, name).resolve(Some(COMMIT)) {
+            Ok(oid) => Ok(oid),
+            Err(err) if err.is_revision_error() => {
+                let mut stderr = self.ctx.stderr.borrow_mut();
+                writeln!(
+                    stderr,
+                    "fatal: Failed to resolve '{}' as a valid ref.",
+                    name
+                )?;
+                Err(Error::Exit(128))
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn no_note_found(&self, oid: &str) -> Result<()> {
+        let mut stderr = self.ctx.stderr.borrow_mut();
+        writeln!(stderr, "error: no note found for object {}.", oid)?;
+
+        Err(Error::Exit(1))
+    }
+}
@@ -0,0 +1,66 @@
+use std::io::Write;
+
+use crate::commands::{Command, CommandContext};
+use crate::database::tree::TreeEntry;
+use crate::database::ParsedObject;
+use crate::errors::{Error, Result};
+use crate::revision::Revision;
+
+pub struct ReadTree<'a> {
+    ctx: CommandContext<'a>,
+    /// `jit read-tree <tree-ish>`
+    tree: String,
+}
+
+impl<'a> ReadTree<'a> {
+    pub fn new(ctx: CommandContext<'a>) -> Self {
+        let tree = match &ctx.opt.cmd {
+            Command::ReadTree { tree } => tree.to_owned(),
+            _ => unreachable!(),
+        };
+
+        Self { ctx, tree }
+    }
+
+    /// Replace the contents of the index with the entries of a tree, leaving the workspace as it
+    /// is.
+    pub fn run(&mut self) -> Result<()> {
+        let oid = self.resolve_tree()?;
+
+        self.ctx.repo.index.load_for_update()?;
+        self.ctx.repo.index.clear();
+
+        let listing = self.ctx.repo.database.load_tree_list(Some(&oid), None)?;
+        for (path, entry) in listing {
+            let entry = match entry {
+                TreeEntry::Entry(entry) => entry,
+                TreeEntry::Tree(_tree) => unreachable!(),
+            };
+            self.ctx.repo.index.add_from_db(&path, &entry);
+        }
+
+        self.ctx.repo.index.write_updates()?;
+
+        Ok(())
+    }
+
+    fn resolve_tree(&self) -> Result<String> {
+        let oid = Revision::new(&self.ctx.repo, &self.tree).resolve(None);
+
+        match oid {
+            Ok(oid) => match self.ctx.repo.database.load(&oid)? {
+                ParsedObject::Commit(_) | ParsedObject::Tree(_) => Ok(oid),
+                ParsedObject::Blob(_) => self.not_a_tree(),
+            },
+            Err(err) if err.is_revision_error() => self.not_a_tree(),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn not_a_tree(&self) -> Result<String> {
+        let mut stderr = self.ctx.stderr.borrow_mut();
+        writeln!(stderr, "fatal: Not a valid object name {}", self.tree)?;
+
+        Err(Error::Exit(128))
+    }
+}
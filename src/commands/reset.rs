@@ -1,21 +1,35 @@
+use std::collections::BTreeMap;
+use std::io::Write;
 use std::path::{Path, PathBuf};
 
 use crate::commands::{Command, CommandContext};
+use crate::database::entry::Entry as DatabaseEntry;
 use crate::database::tree::TreeEntry;
+use crate::database::tree_diff::{Differ, TreeDiffChanges};
 use crate::errors::{Error, Result};
-use crate::refs::ORIG_HEAD;
+use crate::refs::{HEAD, ORIG_HEAD};
+use crate::repository::ChangeType;
 use crate::revision::{Revision, COMMIT};
 use crate::util::path_to_string;
 
+#[derive(PartialEq, Eq)]
 enum Mode {
     Soft,
     Mixed,
     Hard,
+    /// Update the files that differ between the index and the commit, keeping unstaged changes
+    /// to other files
+    Merge,
+    /// Update the files that differ between `HEAD` and the commit, keeping local changes to
+    /// other files
+    Keep,
 }
 
 pub struct Reset<'a> {
     ctx: CommandContext<'a>,
     commit_oid: Option<String>,
+    /// The revision being reset to, as it was given
+    revision: String,
     mode: Mode,
     /// `jit reset <paths>...`
     paths: Vec<PathBuf>,
@@ -29,9 +43,15 @@ impl<'a> Reset<'a> {
                 soft,
                 _mixed,
                 hard,
+                merge,
+                keep,
             } => {
                 let mode = if *hard {
                     Mode::Hard
+                } else if *merge {
+                    Mode::Merge
+                } else if *keep {
+                    Mode::Keep
                 } else if *soft {
                     Mode::Soft
                 } else {
@@ -47,6 +67,7 @@ impl<'a> Reset<'a> {
         Ok(Self {
             ctx,
             commit_oid: head_oid,
+            revision: String::from(HEAD),
             mode,
             paths,
         })
@@ -55,10 +76,45 @@ impl<'a> Reset<'a> {
     pub fn run(&mut self) -> Result<()> {
         self.select_commit_id()?;
 
+        let mode_name = match self.mode {
+            Mode::Merge => Some("merge"),
+            Mode::Keep => Some("keep"),
+            _ => None,
+        };
+        if let Some(mode_name) = mode_name {
+            if !self.paths.is_empty() {
+                let mut stderr = self.ctx.stderr.borrow_mut();
+                writeln!(stderr, "fatal: Cannot do {} reset with paths.", mode_name)?;
+                return Err(Error::Exit(128));
+            }
+        }
+
         self.ctx.repo.index.load_for_update()?;
-        self.reset_files()?;
+        match self.reset_files() {
+            Ok(()) => (),
+            Err(Error::MigrationConflict) => {
+                self.ctx.repo.index.release_lock()?;
+                return Err(Error::Exit(128));
+            }
+            Err(err) => {
+                self.ctx.repo.index.release_lock()?;
+                return Err(err);
+            }
+        }
+        let unstaged_changes = match self.mode {
+            Mode::Mixed if !self.ctx.quiet() => self.unstaged_changes()?,
+            _ => vec![],
+        };
         self.ctx.repo.index.write_updates()?;
 
+        if !unstaged_changes.is_empty() {
+            let mut stdout = self.ctx.stdout.borrow_mut();
+            writeln!(stdout, "Unstaged changes after reset:")?;
+            for (change, path) in unstaged_changes {
+                writeln!(stdout, "{}\t{}", change, path)?;
+            }
+        }
+
         if let Some(commit_oid) = &self.commit_oid {
             if self.paths.is_empty() {
                 let head_oid = self.ctx.repo.refs.update_head(commit_oid)?;
@@ -77,12 +133,10 @@ impl<'a> Reset<'a> {
             match Revision::new(&self.ctx.repo, &path_to_string(revision)).resolve(Some(COMMIT)) {
                 Ok(commit_oid) => {
                     self.commit_oid = Some(commit_oid);
-                    self.paths.remove(0);
+                    self.revision = path_to_string(&self.paths.remove(0));
                 }
-                Err(err) => match err {
-                    Error::InvalidObject(..) => (),
-                    _ => return Err(err),
-                },
+                Err(err) if err.is_revision_error() => (),
+                Err(err) => return Err(err),
             }
         }
 
@@ -90,13 +144,16 @@ impl<'a> Reset<'a> {
     }
 
     fn reset_files(&mut self) -> Result<()> {
-        if matches!(self.mode, Mode::Soft) {
-            return Ok(());
-        } else if matches!(self.mode, Mode::Hard) {
-            self.ctx
-                .repo
-                .hard_reset(self.commit_oid.as_ref().unwrap())?;
-            return Ok(());
+        match self.mode {
+            Mode::Soft => return Ok(()),
+            Mode::Hard => {
+                self.ctx
+                    .repo
+                    .hard_reset(self.commit_oid.as_ref().unwrap())?;
+                return Ok(());
+            }
+            Mode::Merge | Mode::Keep => self.migrate_workspace()?,
+            Mode::Mixed => (),
         }
 
         if self.paths.is_empty() {
@@ -112,6 +169,119 @@ impl<'a> Reset<'a> {
         Ok(())
     }
 
+    /// Update the workspace like `jit checkout` would, refusing to lose local changes to the
+    /// files that change. `--merge` updates the files whose index entries differ from the
+    /// commit, and `--keep` those that differ between `HEAD` and the commit. The index is then
+    /// reset like `--mixed`.
+    ///
+    /// Like Git, `--merge` also resets conflicted files to the commit outright, throwing away
+    /// their conflict markers, and forgets the merge in progress.
+    fn migrate_workspace(&mut self) -> Result<()> {
+        let commit_oid = self.commit_oid.as_ref().unwrap();
+        let tree_diff = if self.mode == Mode::Merge {
+            self.index_diff(commit_oid)?
+        } else {
+            let head_oid = self.ctx.repo.refs.read_head()?.unwrap();
+            self.ctx
+                .repo
+                .database
+                .tree_diff(&head_oid, commit_oid, None)?
+        };
+
+        let mut migration = self.ctx.repo.migration(tree_diff);
+        if let Err(err) = migration.apply_changes() {
+            if let Error::MigrationConflict = err {
+                let mut stderr = self.ctx.stderr.borrow_mut();
+                for message in &migration.errors {
+                    writeln!(stderr, "error: {}", message)?;
+                }
+                writeln!(
+                    stderr,
+                    "fatal: Could not reset index file to revision '{}'.",
+                    self.revision
+                )?;
+            }
+            return Err(err);
+        }
+
+        if self.mode == Mode::Merge {
+            self.reset_conflicts()?;
+        }
+
+        Ok(())
+    }
+
+    /// Replace each conflicted file in the workspace with its version in the commit, or remove
+    /// it if the commit doesn't have it, and clear the pending merge.
+    fn reset_conflicts(&mut self) -> Result<()> {
+        let repo = &self.ctx.repo;
+        let commit_oid = self.commit_oid.as_ref().unwrap();
+
+        for path in repo.index.conflict_paths() {
+            let path = PathBuf::from(path);
+            repo.workspace.remove(&path)?;
+
+            if let Some(TreeEntry::Entry(entry)) =
+                repo.database.load_tree_entry(commit_oid, Some(&path))?
+            {
+                let blob = repo.database.load_blob(&entry.oid)?;
+                repo.workspace
+                    .write_file(&path, &blob.data, Some(entry.mode()), true)?;
+            }
+        }
+
+        let pending_commit = repo.pending_commit();
+        if let Some(merge_type) = pending_commit.merge_type() {
+            pending_commit.clear(merge_type)?;
+        }
+
+        Ok(())
+    }
+
+    /// The changes from the merged entries in the index to the tree of `commit_oid`. Conflicted
+    /// files are left out, as they're reset separately.
+    fn index_diff(&self, commit_oid: &str) -> Result<TreeDiffChanges> {
+        let repo = &self.ctx.repo;
+        let mut changes = BTreeMap::new();
+
+        let conflicts = repo.index.conflict_paths();
+        let tree = repo.database.load_tree_list(Some(commit_oid), None)?;
+        for (path, entry) in tree.iter().filter(|(path, _)| !conflicts.contains(*path)) {
+            let new = DatabaseEntry::new(entry.oid(), entry.mode());
+            let old = repo.index.entry_for_path(path, 0).map(DatabaseEntry::from);
+            if old.as_ref() != Some(&new) {
+                changes.insert(PathBuf::from(path), (old, Some(new)));
+            }
+        }
+        for entry in repo.index.entries.values() {
+            if entry.stage() == 0 && !tree.contains_key(&entry.path) {
+                changes.insert(
+                    PathBuf::from(&entry.path),
+                    (Some(DatabaseEntry::from(entry)), None),
+                );
+            }
+        }
+
+        Ok(changes.into_iter().collect())
+    }
+
+    /// The files that differ between the index and the workspace once the index is reset, like
+    /// `M\tpath` in Git's output.
+    fn unstaged_changes(&mut self) -> Result<Vec<(&'static str, String)>> {
+        let mut status = self.ctx.repo.status(None);
+        status.initialize()?;
+
+        Ok(status
+            .workspace_changes
+            .into_iter()
+            .filter_map(|(path, change)| match change {
+                ChangeType::Modified => Some(("M", path)),
+                ChangeType::Deleted => Some(("D", path)),
+                _ => None,
+            })
+            .collect())
+    }
+
     fn reset_path(&mut self, pathname: Option<&Path>) -> Result<()> {
         let listing = self
             .ctx
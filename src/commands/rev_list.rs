@@ -0,0 +1,85 @@
+use std::io::Write;
+
+use crate::commands::{Command, CommandContext};
+use crate::database::object::Object;
+use crate::errors::{Error, Result};
+use crate::rev_list::{RevList, RevListOptions, Side};
+
+pub struct RevListCommand<'a> {
+    ctx: CommandContext<'a>,
+    /// `jit rev-list <commit>...`
+    args: Vec<String>,
+    /// `jit rev-list --count`
+    count: bool,
+    /// `jit rev-list --left-right`
+    left_right: bool,
+}
+
+impl<'a> RevListCommand<'a> {
+    pub fn new(ctx: CommandContext<'a>) -> Self {
+        let (args, count, left_right) = match &ctx.opt.cmd {
+            Command::RevList {
+                args,
+                count,
+                left_right,
+            } => (args.to_owned(), *count, *left_right),
+            _ => unreachable!(),
+        };
+
+        Self {
+            ctx,
+            args,
+            count,
+            left_right,
+        }
+    }
+
+    pub fn run(&self) -> Result<()> {
+        let mut rev_list = match RevList::new(&self.ctx.repo, &self.args, RevListOptions::default())
+        {
+            Ok(rev_list) => rev_list,
+            Err(err) if err.is_revision_error() || matches!(err, Error::InvalidArgument(..)) => {
+                let mut stderr = self.ctx.stderr.borrow_mut();
+                writeln!(stderr, "fatal: {}", err)?;
+                return Err(Error::Exit(128));
+            }
+            Err(err) => return Err(err),
+        };
+
+        let mut stdout = self.ctx.stdout.borrow_mut();
+        let (mut left, mut right, mut total) = (0, 0, 0);
+
+        // Iterate with `while let` so `rev_list` can be asked which side each commit is on
+        while let Some(commit) = rev_list.next() {
+            let commit = commit?;
+            let side = rev_list.side(&commit);
+            total += 1;
+            match side {
+                Some(Side::Left) => left += 1,
+                Some(Side::Right) => right += 1,
+                None => (),
+            }
+
+            if self.count {
+                continue;
+            }
+
+            let marker = match side {
+                Some(Side::Left) if self.left_right => "<",
+                Some(Side::Right) if self.left_right => ">",
+                _ => "",
+            };
+            writeln!(stdout, "{}{}", marker, commit.oid())?;
+        }
+
+        if self.count {
+            if self.left_right {
+                writeln!(stdout, "{}\t{}", left, right)?;
+            } else {
+                writeln!(stdout, "{}", total)?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,84 @@
+use std::io::Write;
+
+use crate::commands::{Command, CommandContext};
+use crate::errors::{Error, Result};
+use crate::repository::Repository;
+use crate::util::path_to_string;
+
+pub struct RevParse<'a> {
+    ctx: CommandContext<'a>,
+    /// `jit rev-parse --git-dir`
+    git_dir: bool,
+    /// `jit rev-parse --absolute-git-dir`
+    absolute_git_dir: bool,
+    /// `jit rev-parse --show-toplevel`
+    show_toplevel: bool,
+    /// `jit rev-parse --is-inside-work-tree`
+    is_inside_work_tree: bool,
+}
+
+impl<'a> RevParse<'a> {
+    pub fn new(ctx: CommandContext<'a>) -> Self {
+        let (git_dir, absolute_git_dir, show_toplevel, is_inside_work_tree) = match ctx.opt.cmd {
+            Command::RevParse {
+                git_dir,
+                absolute_git_dir,
+                show_toplevel,
+                is_inside_work_tree,
+            } => (
+                git_dir,
+                absolute_git_dir,
+                show_toplevel,
+                is_inside_work_tree,
+            ),
+            _ => unreachable!(),
+        };
+
+        Self {
+            ctx,
+            git_dir,
+            absolute_git_dir,
+            show_toplevel,
+            is_inside_work_tree,
+        }
+    }
+
+    /// Unlike other commands, which expect to run from the root of the workspace, look for the
+    /// repository in the current directory and its parents, so scripts can locate it.
+    pub fn run(&self) -> Result<()> {
+        let root_path = match Repository::discover(&self.ctx.dir) {
+            Some(root_path) => root_path,
+            None => {
+                let mut stderr = self.ctx.stderr.borrow_mut();
+                writeln!(
+                    stderr,
+                    "fatal: not a git repository (or any of the parent directories): .git"
+                )?;
+                return Err(Error::Exit(128));
+            }
+        };
+        let git_path = Repository::new(root_path.join(".git")).git_path;
+
+        let mut stdout = self.ctx.stdout.borrow_mut();
+
+        if self.git_dir {
+            // Like Git, the path is relative at the root of the workspace
+            if self.ctx.dir == root_path && git_path == root_path.join(".git") {
+                writeln!(stdout, ".git")?;
+            } else {
+                writeln!(stdout, "{}", path_to_string(&git_path))?;
+            }
+        }
+        if self.absolute_git_dir {
+            writeln!(stdout, "{}", path_to_string(&git_path))?;
+        }
+        if self.show_toplevel {
+            writeln!(stdout, "{}", path_to_string(&root_path))?;
+        }
+        if self.is_inside_work_tree {
+            writeln!(stdout, "{}", !self.ctx.dir.starts_with(&git_path))?;
+        }
+
+        Ok(())
+    }
+}
@@ -11,6 +11,7 @@ use crate::config::VariableValue;
 use crate::database::commit::Commit;
 use crate::database::object::Object;
 use crate::database::Database;
+use crate::editor::Editor;
 use crate::errors::{Error, Result};
 use crate::merge::inputs;
 use crate::refs::HEAD;
@@ -23,17 +24,22 @@ pub struct Revert<'a> {
     args: Vec<String>,
     mode: Mode,
     mainline: Option<u32>,
+    no_commit: bool,
+    edit: bool,
 }
 
 impl<'a> Revert<'a> {
     pub fn new(ctx: CommandContext<'a>) -> Self {
-        let (args, mode, mainline) = match &ctx.opt.cmd {
+        let (args, mode, mainline, no_commit, edit) = match &ctx.opt.cmd {
             Command::Revert {
                 args,
                 r#continue,
                 abort,
                 quit,
                 mainline,
+                no_commit,
+                edit,
+                no_edit,
             } => (
                 args.to_owned(),
                 if *r#continue {
@@ -46,6 +52,8 @@ impl<'a> Revert<'a> {
                     Mode::Run
                 },
                 mainline.to_owned(),
+                *no_commit,
+                *edit || !*no_edit && ctx.isatty,
             ),
             _ => unreachable!(),
         };
@@ -55,6 +63,8 @@ impl<'a> Revert<'a> {
             args,
             mode,
             mainline,
+            no_commit,
+            edit,
         }
     }
 
@@ -65,6 +75,9 @@ impl<'a> Revert<'a> {
         if let Some(mainline) = self.mainline {
             options.insert("mainline", VariableValue::Int(mainline as i32));
         }
+        if self.no_commit {
+            options.insert("no-commit", VariableValue::Bool(true));
+        }
 
         match self.mode {
             Mode::Continue => self.handle_continue(&mut sequencer)?,
@@ -91,8 +104,15 @@ impl<'a> Revert<'a> {
 
     fn store_commit_sequence(&self, sequencer: &mut Sequencer) -> Result<()> {
         let args: Vec<_> = self.args.iter().map(|s| s.to_owned()).collect();
-        let commits: Vec<_> =
-            RevList::new(&self.ctx.repo, &args, RevListOptions { walk: false })?.collect();
+        let commits: Vec<_> = RevList::new(
+            &self.ctx.repo,
+            &args,
+            RevListOptions {
+                walk: false,
+                ..Default::default()
+            },
+        )?
+        .collect::<Result<_>>()?;
         for commit in commits.iter() {
             sequencer.revert(commit);
         }
@@ -104,7 +124,10 @@ impl<'a> Revert<'a> {
         let inputs = self.revert_merge_inputs(sequencer, commit)?;
         let message = self.revert_commit_message(commit);
 
-        resolve_merge(&mut self.ctx.repo, &inputs)?;
+        if let Err(err) = resolve_merge(&mut self.ctx.repo, &inputs) {
+            sequencer.quit()?;
+            return Err(err);
+        }
 
         let commit_writer = self.commit_writer();
 
@@ -119,8 +142,20 @@ impl<'a> Revert<'a> {
             )?;
         }
 
+        if self.no_commit {
+            return Ok(());
+        }
+
         let author = commit_writer.current_author();
-        let message = self.edit_revert_message(&message)?.unwrap();
+        let message = match self.edit_revert_message(&message)? {
+            Some(message) => message,
+            None => {
+                let mut stderr = self.ctx.stderr.borrow_mut();
+                writeln!(stderr, "Aborting commit due to empty commit message.")?;
+
+                return Err(Error::Exit(1));
+            }
+        };
         let picked = Commit::new(
             vec![inputs.left_oid],
             commit_writer.write_tree().oid(),
@@ -135,16 +170,22 @@ impl<'a> Revert<'a> {
     }
 
     fn revert_merge_inputs(
-        &self,
+        &mut self,
         sequencer: &mut Sequencer,
         commit: &Commit,
     ) -> Result<inputs::CherryPick> {
         let short = Database::short_oid(&commit.oid());
 
+        // Without committing, each revert applies on top of the ones already staged
         let left_name = HEAD.to_owned();
-        let left_oid = self.ctx.repo.refs.read_head()?.unwrap();
+        let left_oid = if self.no_commit {
+            self.ctx.repo.index.load()?;
+            self.commit_writer().write_tree().oid()
+        } else {
+            self.ctx.repo.refs.read_head()?.unwrap()
+        };
 
-        let right_name = format!("parent of {}... {}", short, commit.title_line().trim());
+        let right_name = format!("parent of {}... {}", short, commit.title_line());
         let right_oid = select_parent(&self.ctx, sequencer, commit)?;
 
         Ok(inputs::CherryPick::new(
@@ -163,27 +204,44 @@ Revert \"{}\"
 
 This reverts commit {}.
 ",
-            commit.title_line().trim(),
+            commit.title_line(),
             commit.oid()
         )
     }
 
+    /// Unlike other messages, the editor opens for `--edit` even when not on a terminal.
     fn edit_revert_message(&self, message: &str) -> Result<Option<String>> {
-        self.ctx
-            .edit_file(&self.commit_writer().commit_message_path(), |editor| {
+        Editor::edit(
+            self.commit_writer().commit_message_path(),
+            self.ctx.editor_command(),
+            |editor| {
                 editor.write(message)?;
                 editor.write("")?;
                 editor.note(COMMIT_NOTES)?;
+                if !self.edit {
+                    editor.close();
+                }
 
                 Ok(())
-            })
+            },
+        )
     }
 
     fn handle_continue(&mut self, sequencer: &mut Sequencer) -> Result<()> {
         self.ctx.repo.index.load()?;
+        self.no_commit = matches!(
+            sequencer.get_option("no-commit")?,
+            Some(VariableValue::Bool(true))
+        );
 
         if self.commit_writer().pending_commit.in_progress() {
-            match self.commit_writer().write_revert_commit() {
+            let result = if self.no_commit {
+                self.commit_writer()
+                    .resolve_pending_commit(PendingCommitType::Revert)
+            } else {
+                self.commit_writer().write_revert_commit(None)
+            };
+            match result {
                 Ok(()) => (),
                 Err(err) => match err {
                     Error::NoMergeInProgress(..) => {
@@ -1,11 +1,14 @@
 use std::fmt::Write;
 use std::fs::read_to_string;
+use std::io::Write as _;
 use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
 
 use chrono::{DateTime, Local};
 
 use crate::commands::commit::COMMIT_NOTES;
 use crate::commands::CommandContext;
+use crate::config::VariableValue;
 use crate::database::author::Author;
 use crate::database::commit::Commit;
 use crate::database::object::Object;
@@ -33,9 +36,14 @@ If this is not correct, please remove the file
 \t.git/CHERRY_PICK_HEAD
 and try again.\n";
 
+/// The program used to sign commits when `gpg.program` isn't set.
+const DEFAULT_GPG_PROGRAM: &str = "gpg";
+
 pub struct CommitWriter<'a> {
     ctx: &'a CommandContext<'a>,
     pub pending_commit: PendingCommit,
+    /// The key to sign new commits with, if they should be signed
+    pub signing_key: Option<String>,
 }
 
 impl<'a> CommitWriter<'a> {
@@ -45,6 +53,7 @@ impl<'a> CommitWriter<'a> {
         Self {
             ctx,
             pending_commit,
+            signing_key: None,
         }
     }
 
@@ -61,6 +70,16 @@ impl<'a> CommitWriter<'a> {
     }
 
     pub fn write_commit(&self, parents: Vec<String>, message: Option<&str>) -> Result<Commit> {
+        self.write_commit_as(parents, message, None)
+    }
+
+    /// Write a commit of the index by `author`, or by the committer if `None`.
+    pub fn write_commit_as(
+        &self,
+        parents: Vec<String>,
+        message: Option<&str>,
+        author: Option<Author>,
+    ) -> Result<Commit> {
         let message = if let Some(message) = message {
             message
         } else {
@@ -73,16 +92,65 @@ impl<'a> CommitWriter<'a> {
         }
 
         let tree = self.write_tree();
-        let author = self.current_author();
-        let committer = author.clone();
+        let committer = self.current_author();
+        let author = author.unwrap_or_else(|| committer.clone());
         let commit = Commit::new(parents, tree.oid(), author, committer, message.to_string());
 
+        self.store_commit(commit)
+    }
+
+    /// Sign `commit` with `signing_key`, if any, then store it and move `HEAD` to it.
+    pub fn store_commit(&self, mut commit: Commit) -> Result<Commit> {
+        if let Some(key) = &self.signing_key {
+            self.sign(&mut commit, key)?;
+        }
+
         self.ctx.repo.database.store(&commit)?;
         self.ctx.repo.refs.update_head(&commit.oid())?;
 
         Ok(commit)
     }
 
+    /// Sign `commit` like `git commit -S`. The program named by `gpg.program` is given the
+    /// commit's content and writes a detached signature, which is stored in a `gpgsig` header.
+    fn sign(&self, commit: &mut Commit, key: &str) -> Result<()> {
+        let program = match self
+            .ctx
+            .repo
+            .config
+            .get(&[String::from("gpg"), String::from("program")])
+        {
+            Some(VariableValue::String(program)) => program,
+            _ => String::from(DEFAULT_GPG_PROGRAM),
+        };
+
+        let signature = Command::new(&program)
+            .args(["--status-fd=2", "-bsau", key])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .and_then(|mut child| {
+                child.stdin.take().unwrap().write_all(&commit.bytes())?;
+                child.wait_with_output()
+            });
+
+        match signature {
+            Ok(output) if output.status.success() => {
+                let signature = String::from_utf8_lossy(&output.stdout);
+                commit
+                    .extra_headers
+                    .push((String::from("gpgsig"), signature.trim_end().to_owned()));
+                Ok(())
+            }
+            _ => {
+                let mut stderr = self.ctx.stderr.borrow_mut();
+                writeln!(stderr, "error: {} failed to sign the data", program)?;
+                writeln!(stderr, "fatal: failed to write commit object")?;
+                Err(Error::Exit(128))
+            }
+        }
+    }
+
     pub fn write_tree(&self) -> Tree {
         let entries = self
             .ctx
@@ -90,6 +158,8 @@ impl<'a> CommitWriter<'a> {
             .index
             .entries
             .values()
+            // Files added with `--intent-to-add` aren't committed until they're staged
+            .filter(|entry| !entry.intent_to_add())
             .map(|entry| entry.to_owned())
             .collect();
         let root = Tree::build(entries);
@@ -136,6 +206,10 @@ impl<'a> CommitWriter<'a> {
     }
 
     pub fn print_commit(&self, commit: &Commit) -> Result<()> {
+        if self.ctx.quiet() {
+            return Ok(());
+        }
+
         let r#ref = self.ctx.repo.refs.current_ref(HEAD)?;
         let mut info = if r#ref.is_head() {
             String::from("detached HEAD")
@@ -144,7 +218,7 @@ impl<'a> CommitWriter<'a> {
         };
         let oid = Database::short_oid(&commit.oid());
 
-        if commit.parent().is_none() {
+        if commit.first_parent().is_none() {
             info.push_str(" (root-commit)");
         }
         write!(info, " {}", oid).unwrap();
@@ -155,24 +229,26 @@ impl<'a> CommitWriter<'a> {
         Ok(())
     }
 
-    pub fn resume_merge(&self, r#type: PendingCommitType) -> Result<()> {
+    /// Commit the pending merge, cherry-pick, or revert. The editor is seeded with `message` if
+    /// one was given, otherwise with the message saved when the merge stopped.
+    pub fn resume_merge(&self, r#type: PendingCommitType, message: Option<&str>) -> Result<()> {
         match r#type {
-            PendingCommitType::Merge => self.write_merge_commit()?,
-            PendingCommitType::CherryPick => self.write_cherry_pick_commit()?,
-            PendingCommitType::Revert => self.write_revert_commit()?,
+            PendingCommitType::Merge => self.write_merge_commit(message)?,
+            PendingCommitType::CherryPick => self.write_cherry_pick_commit(message)?,
+            PendingCommitType::Revert => self.write_revert_commit(message)?,
         }
 
         Err(Error::Exit(0))
     }
 
-    fn write_merge_commit(&self) -> Result<()> {
+    fn write_merge_commit(&self, message: Option<&str>) -> Result<()> {
         self.handle_conflicted_index()?;
 
         let parents = vec![
             self.ctx.repo.refs.read_head()?.unwrap(),
             self.pending_commit.merge_oid(PendingCommitType::Merge)?,
         ];
-        let message = self.compose_merge_message(Some(MERGE_NOTES))?;
+        let message = self.compose_merge_message(message, Some(MERGE_NOTES))?;
         self.write_commit(parents, message.as_deref())?;
 
         self.pending_commit.clear(PendingCommitType::Merge)?;
@@ -180,11 +256,11 @@ impl<'a> CommitWriter<'a> {
         Ok(())
     }
 
-    pub fn write_cherry_pick_commit(&self) -> Result<()> {
+    pub fn write_cherry_pick_commit(&self, message: Option<&str>) -> Result<()> {
         self.handle_conflicted_index()?;
 
         let parents = vec![self.ctx.repo.refs.read_head()?.unwrap()];
-        let message = self.compose_merge_message(Some(CHERRY_PICK_NOTES))?;
+        let message = self.compose_merge_message(message, Some(CHERRY_PICK_NOTES))?;
 
         let pick_oid = self
             .pending_commit
@@ -206,11 +282,11 @@ impl<'a> CommitWriter<'a> {
         Ok(())
     }
 
-    pub fn write_revert_commit(&self) -> Result<()> {
+    pub fn write_revert_commit(&self, message: Option<&str>) -> Result<()> {
         self.handle_conflicted_index()?;
 
         let parents = vec![self.ctx.repo.refs.read_head()?.unwrap()];
-        let message = self.compose_merge_message(None)?;
+        let message = self.compose_merge_message(message, None)?;
         self.write_commit(parents, message.as_deref())?;
 
         self.pending_commit.clear(PendingCommitType::Revert)?;
@@ -218,10 +294,28 @@ impl<'a> CommitWriter<'a> {
         Ok(())
     }
 
-    fn compose_merge_message(&self, notes: Option<&str>) -> Result<Option<String>> {
+    /// Finish a conflicted revert or cherry-pick run with `--no-commit`, leaving the resolved
+    /// changes staged.
+    pub fn resolve_pending_commit(&self, merge_type: PendingCommitType) -> Result<()> {
+        self.handle_conflicted_index()?;
+        self.pending_commit.clear(merge_type)?;
+
+        Ok(())
+    }
+
+    fn compose_merge_message(
+        &self,
+        message: Option<&str>,
+        notes: Option<&str>,
+    ) -> Result<Option<String>> {
+        let message = match message {
+            Some(message) => message.to_owned(),
+            None => self.pending_commit.merge_message()?,
+        };
+
         self.ctx
             .edit_file(&self.commit_message_path(), |editor: &mut Editor| {
-                editor.write(&self.pending_commit.merge_message()?)?;
+                editor.write(&message)?;
                 if let Some(notes) = notes {
                     editor.note(notes)?;
                 }
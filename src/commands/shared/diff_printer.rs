@@ -1,21 +1,32 @@
-use std::cell::RefMut;
+use std::collections::{HashMap, HashSet};
 use std::fmt::Write as _;
 use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::{env, fs};
 
 use colored::Colorize;
 use once_cell::sync::Lazy;
+use regex::Regex;
+use uuid::Uuid;
 
+use crate::config::VariableValue;
 use crate::database::entry::Entry;
-use crate::database::tree_diff::Differ;
-use crate::database::Database;
+use crate::database::tree::TreeEntry;
+use crate::database::tree_diff::{detect_copies, detect_renames, Differ, Rename, TreeDiffChanges};
+use crate::database::{Database, DEFAULT_ABBREV};
 use crate::diff::hunk::{GenericEdit, Hunk};
-use crate::diff::{combined_hunks, diff_hunks, EditType};
-use crate::errors::Result;
+use crate::diff::{combined_hunks, diff, Edit, EditType};
+use crate::errors::{Error, Result};
+use crate::repository::attributes::{AttributeValue, Attributes};
 use crate::repository::Repository;
-use crate::util::path_to_string;
+use crate::util::{path_to_string, plural, quote_path};
 
 static NULL_OID: Lazy<String> = Lazy::new(|| "0".repeat(40));
+static TEXTCONV_KEY: Lazy<Regex> = Lazy::new(|| Regex::new(r"^diff\.(.+)\.textconv$").unwrap());
 const NULL_PATH: &str = "/dev/null";
+const STAT_WIDTH: usize = 80;
+const STAT_MIN_GRAPH_WIDTH: usize = 6;
 
 #[derive(Debug, Clone)]
 pub struct Target {
@@ -43,11 +54,187 @@ impl Target {
     }
 }
 
-pub struct DiffPrinter {}
+/// How the two sides of a `FileDiff` with different paths are related.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Similarity {
+    Rename,
+    Copy,
+}
+
+/// A changed file along with the line edits between its two versions, computed once so the
+/// diffstat and the patch don't diff the file twice.
+pub struct FileDiff {
+    a: Target,
+    b: Target,
+    edits: Vec<Edit>,
+    similarity: Option<(Similarity, u32)>,
+}
+
+impl FileDiff {
+    pub fn new(a: Target, b: Target) -> Self {
+        let edits = edits_between(&a, &b);
+
+        FileDiff {
+            a,
+            b,
+            edits,
+            similarity: None,
+        }
+    }
+
+    /// A file diff between `b` and the file `a` it was renamed or copied from, which are `score`
+    /// percent similar.
+    pub fn paired(a: Target, b: Target, similarity: Similarity, score: u32) -> Self {
+        FileDiff {
+            similarity: Some((similarity, score)),
+            ..FileDiff::new(a, b)
+        }
+    }
+
+    /// The path of the file after the change.
+    pub fn path(&self) -> &str {
+        &self.b.path
+    }
+
+    fn count(&self, r#type: EditType) -> usize {
+        self.edits
+            .iter()
+            .filter(|edit| edit.r#type == r#type)
+            .count()
+    }
+
+    fn insertions(&self) -> usize {
+        self.count(EditType::Ins)
+    }
+
+    fn deletions(&self) -> usize {
+        self.count(EditType::Del)
+    }
+}
+
+fn edits_between(a: &Target, b: &Target) -> Vec<Edit> {
+    if a.oid == b.oid {
+        return vec![];
+    }
+
+    diff(
+        std::str::from_utf8(&a.data).expect("Invalid UTF-8"),
+        std::str::from_utf8(&b.data).expect("Invalid UTF-8"),
+    )
+}
+
+pub struct DiffPrinter {
+    /// Pair added files with the files they were renamed or copied from
+    pub find_copies: bool,
+    /// Leave out hunks that only add or remove blank lines
+    pub ignore_blank_lines: bool,
+    /// Only show files inside this directory, with their paths relative to it
+    pub relative: Option<PathBuf>,
+    /// Quote bytes above `0x7f` in paths, as `core.quotePath` does
+    pub quote_path: bool,
+    /// The length of the object IDs on `index` lines
+    pub abbrev: usize,
+    /// The commands converting files to text before they're diffed, once `load_textconv` has
+    /// read them
+    textconv: Option<TextConv>,
+}
+
+/// The `diff.<driver>.textconv` commands, and the attributes naming the diff driver of each file.
+struct TextConv {
+    attributes: Attributes,
+    commands: HashMap<String, String>,
+}
 
 impl DiffPrinter {
     pub fn new() -> Self {
-        Self {}
+        Self {
+            find_copies: false,
+            ignore_blank_lines: false,
+            relative: None,
+            quote_path: true,
+            abbrev: DEFAULT_ABBREV,
+            textconv: None,
+        }
+    }
+
+    /// Read the `diff.<driver>.textconv` commands and the `diff` attribute of each file, so files
+    /// with a driver that has a command are converted by it before they're diffed.
+    pub fn load_textconv(&mut self, repo: &Repository) -> Result<()> {
+        repo.config.open()?;
+        let commands = repo
+            .config
+            .get_regexp(&TEXTCONV_KEY)
+            .into_iter()
+            .filter_map(|(name, value)| match value {
+                VariableValue::String(command) => {
+                    let captures = TEXTCONV_KEY.captures(&name)?;
+                    Some((captures[1].to_owned(), command))
+                }
+                _ => None,
+            })
+            .collect();
+
+        self.textconv = Some(TextConv {
+            attributes: repo.attributes()?,
+            commands,
+        });
+
+        Ok(())
+    }
+
+    /// The contents `data` of the file at `path` as they're diffed: the output of its textconv
+    /// command, which is given a temporary file holding `data`, if it has one.
+    pub fn convert(&self, path: &str, data: Vec<u8>) -> Result<Vec<u8>> {
+        let command = match &self.textconv {
+            Some(textconv) => match textconv.attributes.get(Path::new(path), "diff") {
+                Some(AttributeValue::Value(driver)) => textconv.commands.get(driver),
+                _ => None,
+            },
+            None => None,
+        };
+        let command = match command {
+            Some(command) => command,
+            None => return Ok(data),
+        };
+
+        let mut argv = match shlex::split(command) {
+            Some(argv) if !argv.is_empty() => argv,
+            _ => return Err(Error::TextConvFailed(command.to_owned())),
+        };
+
+        let temp_path = env::temp_dir().join(format!("jit-textconv-{}", Uuid::new_v4().simple()));
+        fs::write(&temp_path, data)?;
+
+        argv.push(path_to_string(&temp_path));
+        let output = Command::new(&argv[0]).args(&argv[1..]).output();
+        fs::remove_file(&temp_path)?;
+
+        match output {
+            Ok(output) if output.status.success() => Ok(output.stdout),
+            _ => Err(Error::TextConvFailed(command.to_owned())),
+        }
+    }
+
+    /// Whether the file at `path` is inside the `relative` directory, if there is one.
+    pub fn includes(&self, path: &Path) -> bool {
+        self.relative
+            .as_ref()
+            .is_none_or(|dir| path.starts_with(dir))
+    }
+
+    /// `path` quoted for the output if it has unusual characters.
+    pub fn quote(&self, path: &str) -> String {
+        quote_path(path, self.quote_path)
+    }
+
+    /// `path` as it's shown in the output, relative to the `relative` directory.
+    pub fn display_path(&self, path: &str) -> String {
+        match &self.relative {
+            Some(dir) => {
+                path_to_string(Path::new(path).strip_prefix(dir).unwrap_or(Path::new(path)))
+            }
+            None => path.to_owned(),
+        }
     }
 
     pub fn from_entry(
@@ -57,6 +244,14 @@ impl DiffPrinter {
         entry: Option<&Entry>,
     ) -> Result<Target> {
         match entry {
+            // Like Git, a submodule is shown as the commit it points to, which isn't in this
+            // repository's database
+            Some(entry) if entry.is_gitlink() => Ok(Target::new(
+                path.to_string(),
+                entry.oid.clone(),
+                Some(entry.mode()),
+                format!("Subproject commit {}\n", entry.oid).into_bytes(),
+            )),
             Some(entry) => {
                 let blob = repo.database.load_blob(&entry.oid)?;
 
@@ -64,7 +259,7 @@ impl DiffPrinter {
                     path.to_string(),
                     entry.oid.clone(),
                     Some(entry.mode()),
-                    blob.data,
+                    self.convert(path, blob.data.clone())?,
                 ))
             }
             None => Ok(self.from_nothing(path)),
@@ -75,72 +270,303 @@ impl DiffPrinter {
         Target::new(path.to_string(), NULL_OID.to_string(), None, vec![])
     }
 
-    fn header(&self, stdout: &mut RefMut<Box<dyn Write>>, string: String) -> Result<()> {
+    fn header(&self, stdout: &mut dyn Write, string: String) -> Result<()> {
         writeln!(stdout, "{}", string.bold())?;
 
         Ok(())
     }
 
     fn short(&self, oid: &str) -> String {
-        Database::short_oid(oid)
+        Database::abbreviate(oid, self.abbrev)
     }
 
     pub fn print_commit_diff(
         &self,
-        stdout: &mut RefMut<Box<dyn Write>>,
+        stdout: &mut dyn Write,
         repo: &Repository,
-        a: Option<&str>,
+        a: &str,
         b: &str,
         differ: Option<&dyn Differ>,
     ) -> Result<()> {
+        for file_diff in self.commit_diffs(repo, a, b, differ)? {
+            self.print_file_diff(stdout, file_diff)?;
+        }
+
+        Ok(())
+    }
+
+    /// Print the combined diff of the merge commit `b` against all of its `parents`, for each file
+    /// that differs from every parent, like `git show --cc`.
+    pub fn print_merge_diff(
+        &self,
+        stdout: &mut dyn Write,
+        repo: &Repository,
+        parents: &[String],
+        b: &str,
+        differ: &dyn Differ,
+    ) -> Result<()> {
+        let mut diffs = Vec::new();
+        for oid in parents {
+            diffs.push(differ.tree_diff(oid, b, None)?);
+        }
+
+        let paths = diffs[0]
+            .keys()
+            .filter(|path| diffs.iter().all(|diff| diff.contains_key(*path)));
+
+        for path in paths {
+            let path_name = path_to_string(path);
+            let mut r#as = Vec::new();
+            for diff in &diffs {
+                r#as.push(self.from_entry(repo, &path_name, diff[path].0.as_ref())?);
+            }
+            let b = self.from_entry(repo, &path_name, diffs[0][path].1.as_ref())?;
+
+            self.print_combined_diff(stdout, &r#as, &b)?;
+        }
+
+        Ok(())
+    }
+
+    /// Load and diff every file changed between the trees of commits `a` and `b`, sorted by path.
+    pub fn commit_diffs(
+        &self,
+        repo: &Repository,
+        a: &str,
+        b: &str,
+        differ: Option<&dyn Differ>,
+    ) -> Result<Vec<FileDiff>> {
         let diff = if let Some(differ) = differ {
-            differ.tree_diff(a, Some(b), None)?
+            differ.tree_diff(a, b, None)?
         } else {
-            repo.database.tree_diff(a, Some(b), None)?
+            repo.database.tree_diff(a, b, None)?
+        };
+        let sources = if self.find_copies {
+            repo.database.load_tree_list(Some(a), None)?
+        } else {
+            HashMap::new()
         };
-        let mut paths: Vec<_> = diff.keys().collect();
-        paths.sort();
 
-        for path in paths {
-            let (old_entry, new_entry) = &diff[path];
-            let path = path_to_string(path);
+        self.file_diffs(repo, &diff, &sources)
+    }
 
-            self.print_diff(
-                stdout,
-                &mut self.from_entry(repo, &path, old_entry.as_ref())?,
-                &mut self.from_entry(repo, &path, new_entry.as_ref())?,
-            )?;
+    /// Diff the file `rename` moved between the commits `a` and `b`, like a rename found with
+    /// `find_copies`.
+    pub fn rename_diff(
+        &self,
+        repo: &Repository,
+        a: &str,
+        b: &str,
+        rename: &Rename,
+    ) -> Result<FileDiff> {
+        let target = |oid: &str, path: &Path| -> Result<Target> {
+            let entry = repo
+                .database
+                .load_tree_entry(oid, Some(path))?
+                .map(|entry| Entry::new(entry.oid(), entry.mode()));
+            self.from_entry(repo, &path_to_string(path), entry.as_ref())
+        };
+
+        Ok(FileDiff::paired(
+            target(a, &rename.old_path)?,
+            target(b, &rename.new_path)?,
+            Similarity::Rename,
+            rename.score,
+        ))
+    }
+
+    /// Load and diff every file in `changes`, sorted by path. With `find_copies`, added files are
+    /// paired with the file in `sources`, the files before the change, they were renamed or
+    /// copied from.
+    pub fn file_diffs(
+        &self,
+        repo: &Repository,
+        changes: &TreeDiffChanges,
+        sources: &HashMap<String, TreeEntry>,
+    ) -> Result<Vec<FileDiff>> {
+        let changes: TreeDiffChanges = changes
+            .iter()
+            .filter(|(path, _)| self.includes(path))
+            .map(|(path, change)| (path.to_owned(), change.to_owned()))
+            .collect();
+        let changes = &changes;
+
+        let (renames, copies) = if self.find_copies {
+            let renames = detect_renames(&repo.database, changes)?;
+            let copies = detect_copies(&repo.database, changes, &renames, sources)?;
+            (renames, copies)
+        } else {
+            (vec![], vec![])
+        };
+        let renamed: HashSet<_> = renames.iter().map(|rename| &rename.old_path).collect();
+        let pairs: Vec<_> = renames
+            .iter()
+            .map(|rename| (rename, Similarity::Rename))
+            .chain(copies.iter().map(|copy| (copy, Similarity::Copy)))
+            .collect();
+
+        let mut file_diffs = Vec::new();
+        for (path, (old_entry, new_entry)) in changes {
+            if renamed.contains(path) {
+                continue;
+            }
+            let new = self.from_entry(repo, &path_to_string(path), new_entry.as_ref())?;
+
+            let pair = pairs.iter().find(|(pair, _)| &pair.new_path == path);
+            let file_diff = if let Some((pair, similarity)) = pair {
+                let old_path = path_to_string(&pair.old_path);
+                let source = &sources[&old_path];
+                let old_entry = Entry::new(source.oid(), source.mode());
+                let old = self.from_entry(repo, &old_path, Some(&old_entry))?;
+
+                FileDiff::paired(old, new, *similarity, pair.score)
+            } else {
+                let old = self.from_entry(repo, &path_to_string(path), old_entry.as_ref())?;
+
+                FileDiff::new(old, new)
+            };
+            file_diffs.push(file_diff);
         }
+        file_diffs.sort_by(|a, b| Path::new(&a.b.path).cmp(Path::new(&b.b.path)));
 
-        Ok(())
+        Ok(file_diffs)
+    }
+
+    pub fn print_file_diff(&self, stdout: &mut dyn Write, file_diff: FileDiff) -> Result<()> {
+        let FileDiff {
+            mut a,
+            mut b,
+            edits,
+            similarity,
+        } = file_diff;
+
+        self.print_diff_edits(stdout, &mut a, &mut b, edits, similarity)
+    }
+
+    pub fn print_diff(&self, stdout: &mut dyn Write, a: &mut Target, b: &mut Target) -> Result<()> {
+        let edits = edits_between(a, b);
+
+        self.print_diff_edits(stdout, a, b, edits, None)
     }
 
-    pub fn print_diff(
+    fn print_diff_edits(
         &self,
-        stdout: &mut RefMut<Box<dyn Write>>,
+        stdout: &mut dyn Write,
         a: &mut Target,
         b: &mut Target,
+        edits: Vec<Edit>,
+        similarity: Option<(Similarity, u32)>,
     ) -> Result<()> {
-        if a.oid == b.oid && a.mode == b.mode {
+        if a.oid == b.oid && a.mode == b.mode && similarity.is_none() {
             return Ok(());
         }
 
-        a.path = format!("a/{}", a.path);
-        b.path = format!("b/{}", b.path);
+        // Like Git, say nothing about a file whose only changes were ignored
+        let hunks = self.hunks(edits);
+        if hunks.is_empty() && a.mode == b.mode && similarity.is_none() {
+            return Ok(());
+        }
 
-        writeln!(stdout, "diff --git {} {}", a.path, b.path)?;
+        let (old_path, new_path) = (self.display_path(&a.path), self.display_path(&b.path));
+        a.path = format!("a/{}", old_path);
+        b.path = format!("b/{}", new_path);
+
+        writeln!(
+            stdout,
+            "diff --git {} {}",
+            self.quote(&a.path),
+            self.quote(&b.path)
+        )?;
+        if let Some((similarity, score)) = similarity {
+            let kind = match similarity {
+                Similarity::Rename => "rename",
+                Similarity::Copy => "copy",
+            };
+            self.header(stdout, format!("similarity index {}%", score))?;
+            self.header(stdout, format!("{} from {}", kind, self.quote(&old_path)))?;
+            self.header(stdout, format!("{} to {}", kind, self.quote(&new_path)))?;
+        }
         self.print_diff_mode(stdout, a, b)?;
-        self.print_diff_content(stdout, a, b)?;
+        self.print_diff_content(stdout, a, b, hunks)?;
 
         Ok(())
     }
 
-    fn print_diff_mode(
-        &self,
-        stdout: &mut RefMut<Box<dyn Write>>,
-        a: &Target,
-        b: &Target,
-    ) -> Result<()> {
+    /// Print a summary of the lines inserted and deleted in each file, followed by the totals,
+    /// like `git diff --stat`.
+    pub fn print_stat(&self, stdout: &mut dyn Write, file_diffs: &[FileDiff]) -> Result<()> {
+        let name_width = file_diffs
+            .iter()
+            .map(|file_diff| self.quote(&file_diff.b.path).len())
+            .max()
+            .unwrap_or(0);
+        let max_change = file_diffs
+            .iter()
+            .map(|file_diff| file_diff.insertions() + file_diff.deletions())
+            .max()
+            .unwrap_or(0);
+        let number_width = max_change.to_string().len();
+
+        let graph_width = STAT_WIDTH
+            .saturating_sub(name_width + number_width + 6)
+            .max(STAT_MIN_GRAPH_WIDTH);
+
+        let (mut insertions, mut deletions) = (0, 0);
+
+        for file_diff in file_diffs {
+            let (ins, del) = (file_diff.insertions(), file_diff.deletions());
+            insertions += ins;
+            deletions += del;
+
+            let (mut ins_width, mut del_width) = (ins, del);
+            if max_change > graph_width {
+                // Like Git, scale the smaller side so it keeps at least one column
+                let mut total = scale_linear(ins + del, graph_width, max_change);
+                if total < 2 && ins > 0 && del > 0 {
+                    total = 2;
+                }
+                if ins < del {
+                    ins_width = scale_linear(ins, graph_width, max_change);
+                    del_width = total - ins_width;
+                } else {
+                    del_width = scale_linear(del, graph_width, max_change);
+                    ins_width = total - del_width;
+                }
+            }
+
+            let line = format!(
+                " {:name_width$} | {:>number_width$} {}{}",
+                self.quote(&file_diff.b.path),
+                ins + del,
+                "+".repeat(ins_width).green(),
+                "-".repeat(del_width).red(),
+            );
+            writeln!(stdout, "{}", line.trim_end())?;
+        }
+
+        let mut summary = format!(
+            " {} file{} changed",
+            file_diffs.len(),
+            plural(file_diffs.len())
+        );
+        if insertions > 0 || deletions == 0 {
+            write!(
+                summary,
+                ", {} insertion{}(+)",
+                insertions,
+                plural(insertions)
+            )
+            .unwrap();
+        }
+        if deletions > 0 || insertions == 0 {
+            write!(summary, ", {} deletion{}(-)", deletions, plural(deletions)).unwrap();
+        }
+        writeln!(stdout, "{}", summary)?;
+
+        Ok(())
+    }
+
+    fn print_diff_mode(&self, stdout: &mut dyn Write, a: &Target, b: &Target) -> Result<()> {
         if a.mode.is_none() {
             self.header(stdout, format!("new file mode {:o}", b.mode.unwrap()))?;
         } else if b.mode.is_none() {
@@ -155,9 +581,10 @@ impl DiffPrinter {
 
     fn print_diff_content(
         &self,
-        stdout: &mut RefMut<Box<dyn Write>>,
+        stdout: &mut dyn Write,
         a: &Target,
         b: &Target,
+        hunks: Vec<Hunk<Edit>>,
     ) -> Result<()> {
         if a.oid == b.oid {
             return Ok(());
@@ -170,13 +597,14 @@ impl DiffPrinter {
         }
 
         writeln!(stdout, "{}", oid_range)?;
-        writeln!(stdout, "--- {}", a.diff_path())?;
-        writeln!(stdout, "+++ {}", b.diff_path())?;
+        // Like Git, an empty file that's added or deleted has no content to show, so the `---` and
+        // `+++` lines are left out as well
+        if hunks.is_empty() {
+            return Ok(());
+        }
+        writeln!(stdout, "--- {}", self.quote(a.diff_path()))?;
+        writeln!(stdout, "+++ {}", self.quote(b.diff_path()))?;
 
-        let hunks = diff_hunks(
-            std::str::from_utf8(&a.data).expect("Invalid UTF-8"),
-            std::str::from_utf8(&b.data).expect("Invalid UTF-8"),
-        );
         for hunk in hunks {
             self.print_diff_hunk(stdout, &hunk)?;
         }
@@ -184,13 +612,41 @@ impl DiffPrinter {
         Ok(())
     }
 
+    /// Group `edits` into hunks, leaving out the ones that only change blank lines if
+    /// `ignore_blank_lines` is set.
+    fn hunks(&self, edits: Vec<Edit>) -> Vec<Hunk<Edit>> {
+        let hunks = Hunk::filter(edits);
+        if !self.ignore_blank_lines {
+            return hunks;
+        }
+
+        hunks
+            .into_iter()
+            .filter(|hunk| {
+                hunk.edits.iter().any(|edit| {
+                    let line = match edit.r#type {
+                        EditType::Eql => return false,
+                        EditType::Ins => &edit.b_line,
+                        EditType::Del => &edit.a_line,
+                    };
+                    line.as_ref()
+                        .is_some_and(|line| !line.text().trim().is_empty())
+                })
+            })
+            .collect()
+    }
+
     pub fn print_combined_diff(
         &self,
-        stdout: &mut RefMut<Box<dyn Write>>,
+        stdout: &mut dyn Write,
         r#as: &[Target],
         b: &Target,
     ) -> Result<()> {
-        self.header(stdout, format!("diff --cc {}", b.path))?;
+        let b = &Target {
+            path: self.display_path(&b.path),
+            ..b.clone()
+        };
+        self.header(stdout, format!("diff --cc {}", self.quote(&b.path)))?;
 
         let a_oids: Vec<_> = r#as.iter().map(|a| self.short(&a.oid)).collect();
         let oid_range = format!("index {}..{}", a_oids.join(","), self.short(&b.oid));
@@ -210,8 +666,12 @@ impl DiffPrinter {
             )?;
         }
 
-        self.header(stdout, format!("--- a/{}", b.diff_path()))?;
-        self.header(stdout, format!("+++ b/{}", b.diff_path()))?;
+        let (a_path, b_path) = (
+            format!("a/{}", b.diff_path()),
+            format!("b/{}", b.diff_path()),
+        );
+        self.header(stdout, format!("--- {}", self.quote(&a_path)))?;
+        self.header(stdout, format!("+++ {}", self.quote(&b_path)))?;
 
         let hunks = combined_hunks(
             &r#as
@@ -229,7 +689,7 @@ impl DiffPrinter {
 
     fn print_diff_hunk<T: GenericEdit>(
         &self,
-        stdout: &mut RefMut<Box<dyn Write>>,
+        stdout: &mut dyn Write,
         hunk: &Hunk<T>,
     ) -> Result<()> {
         writeln!(stdout, "{}", hunk.header().cyan())?;
@@ -240,11 +700,7 @@ impl DiffPrinter {
         Ok(())
     }
 
-    fn print_diff_edit<T: GenericEdit>(
-        &self,
-        stdout: &mut RefMut<Box<dyn Write>>,
-        edit: &T,
-    ) -> Result<()> {
+    fn print_diff_edit<T: GenericEdit>(&self, stdout: &mut dyn Write, edit: &T) -> Result<()> {
         let text = edit.to_string();
 
         match edit.r#type() {
@@ -256,3 +712,13 @@ impl DiffPrinter {
         Ok(())
     }
 }
+
+/// Scale `it` changes down to fit a graph `width` columns wide, where `max_change` fills the whole
+/// graph. Any non-zero number of changes gets at least one column.
+fn scale_linear(it: usize, width: usize, max_change: usize) -> usize {
+    if it == 0 {
+        0
+    } else {
+        1 + it * (width - 1) / max_change
+    }
+}
@@ -25,7 +25,10 @@ pub enum Mode {
 
 pub fn resolve_merge(repo: &mut Repository, inputs: &inputs::CherryPick) -> Result<()> {
     repo.index.load_for_update()?;
-    Resolve::new(repo, inputs).execute()?;
+    if let Err(err) = Resolve::new(repo, inputs).execute() {
+        repo.index.release_lock()?;
+        return Err(err);
+    }
     repo.index.write_updates()?;
 
     Ok(())
@@ -112,7 +115,20 @@ pub fn select_parent(
 
     if commit.is_merge() {
         if let Some(mainline) = mainline {
-            Ok(commit.parents[mainline - 1].clone())
+            match commit.parent(mainline) {
+                Some(parent) => Ok(parent),
+                None => {
+                    let mut stderr = ctx.stderr.borrow_mut();
+                    writeln!(
+                        stderr,
+                        "error: commit {} does not have parent {}",
+                        commit.oid(),
+                        mainline
+                    )?;
+
+                    Err(Error::Exit(1))
+                }
+            }
         } else {
             let mut stderr = ctx.stderr.borrow_mut();
             writeln!(
@@ -134,7 +150,7 @@ pub fn select_parent(
 
             Err(Error::Exit(1))
         } else {
-            Ok(commit.parent().unwrap())
+            Ok(commit.first_parent().unwrap())
         }
     }
 }
@@ -0,0 +1,106 @@
+use std::io::Write;
+
+use crate::commands::{Command, CommandContext};
+use crate::config::stack::ConfigFile;
+use crate::config::VariableValue;
+use crate::errors::{Error, Result};
+use crate::repository::sparse_checkout::SparseCheckout as Patterns;
+
+pub struct SparseCheckout<'a> {
+    ctx: CommandContext<'a>,
+    /// `jit sparse-checkout [set <pattern>... | list | disable]`
+    args: Vec<String>,
+}
+
+impl<'a> SparseCheckout<'a> {
+    pub fn new(ctx: CommandContext<'a>) -> Self {
+        let args = match &ctx.opt.cmd {
+            Command::SparseCheckout { args } => args.to_owned(),
+            _ => unreachable!(),
+        };
+
+        Self { ctx, args }
+    }
+
+    pub fn run(&mut self) -> Result<()> {
+        if self.args.is_empty() {
+            let mut stderr = self.ctx.stderr.borrow_mut();
+            writeln!(stderr, "usage: jit sparse-checkout (set | list | disable)")?;
+            return Err(Error::Exit(129));
+        }
+
+        match self.args.remove(0).as_str() {
+            "set" => self.set_patterns(),
+            "list" => self.list_patterns(),
+            "disable" => self.disable(),
+            subcommand => {
+                let mut stderr = self.ctx.stderr.borrow_mut();
+                writeln!(stderr, "error: unknown subcommand: {}", subcommand)?;
+                Err(Error::Exit(129))
+            }
+        }
+    }
+
+    fn set_patterns(&mut self) -> Result<()> {
+        let mut patterns = Patterns::new(&self.ctx.repo.git_path);
+        patterns.write(&self.args)?;
+        self.set_enabled(true)?;
+
+        self.update_workspace(Some(&patterns))
+    }
+
+    fn list_patterns(&self) -> Result<()> {
+        match self.ctx.repo.sparse_checkout()? {
+            Some(patterns) => {
+                let mut stdout = self.ctx.stdout.borrow_mut();
+                for line in patterns.lines() {
+                    writeln!(stdout, "{}", line)?;
+                }
+                Ok(())
+            }
+            None => {
+                let mut stderr = self.ctx.stderr.borrow_mut();
+                writeln!(stderr, "fatal: this worktree is not sparse")?;
+                Err(Error::Exit(128))
+            }
+        }
+    }
+
+    fn disable(&mut self) -> Result<()> {
+        self.set_enabled(false)?;
+
+        self.update_workspace(None)
+    }
+
+    fn set_enabled(&mut self, enabled: bool) -> Result<()> {
+        let config = self.ctx.repo.config.file(ConfigFile::Local);
+        let mut config = config.borrow_mut();
+        config.open_for_update()?;
+        config.set(
+            &[String::from("core"), String::from("sparseCheckout")],
+            VariableValue::Bool(enabled),
+        )?;
+        config.save()?;
+
+        Ok(())
+    }
+
+    fn update_workspace(&mut self, patterns: Option<&Patterns>) -> Result<()> {
+        self.ctx.repo.index.load_for_update()?;
+        let not_up_to_date = self.ctx.repo.update_sparse_checkout(patterns)?;
+        self.ctx.repo.index.write_updates()?;
+
+        if !not_up_to_date.is_empty() {
+            let mut stderr = self.ctx.stderr.borrow_mut();
+            writeln!(
+                stderr,
+                "warning: The following paths are not up to date and were left despite sparse patterns:"
+            )?;
+            for path in not_up_to_date {
+                writeln!(stderr, "\t{}", path)?;
+            }
+        }
+
+        Ok(())
+    }
+}
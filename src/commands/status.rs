@@ -1,23 +1,52 @@
 use std::cell::RefMut;
-use std::collections::{BTreeMap, HashMap};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::io::Write;
+use std::path::PathBuf;
 
+use clap::ValueEnum;
 use colored::Colorize;
+use itertools::Itertools;
 use once_cell::sync::Lazy;
 
 use crate::commands::{Command, CommandContext};
+use crate::config::VariableValue;
+use crate::database::entry::Entry as DatabaseEntry;
+use crate::database::tree::GITLINK_MODE;
+use crate::database::tree_diff::{detect_renames, Rename, TreeDiffChanges};
 use crate::database::Database;
-use crate::errors::Result;
+use crate::errors::{Error, Result};
+use crate::index::Entry;
 use crate::refs::HEAD;
 use crate::repository::pending_commit::PendingCommitType;
-use crate::repository::status::Status as RepositoryStatus;
+use crate::repository::status::{Status as RepositoryStatus, UntrackedFiles};
 use crate::repository::ChangeType;
+use crate::util::{path_to_string, quote_path};
+
+static NULL_OID: Lazy<String> = Lazy::new(|| "0".repeat(40));
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PorcelainVersion {
+    V1,
+    V2,
+}
 
 pub struct Status<'a> {
     ctx: CommandContext<'a>,
     status: RepositoryStatus,
-    /// `jit status --porcelain`
-    porcelain: bool,
+    /// `jit status --porcelain[=<version>]`
+    porcelain: Option<PorcelainVersion>,
+    /// `jit status --branch`
+    branch: bool,
+    /// `jit status --untracked-files=<mode>`
+    untracked_files: Option<UntrackedFiles>,
+    /// `jit status -z`
+    null_terminated: bool,
+    /// `core.quotePath`
+    quote_path: bool,
+    /// `jit commit --dry-run`, which fails if there's nothing to commit
+    dry_run: bool,
+    /// `jit commit --dry-run --amend`, which compares the index with `HEAD`'s parent
+    amend: bool,
 }
 
 static SHORT_STATUS: Lazy<HashMap<ChangeType, &'static str>> = Lazy::new(|| {
@@ -60,10 +89,29 @@ static CONFLICT_LABEL_WIDTH: usize = 17;
 
 impl<'a> Status<'a> {
     pub fn new(mut ctx: CommandContext<'a>) -> Self {
-        let porcelain = match ctx.opt.cmd {
-            Command::Status { porcelain } => porcelain,
-            _ => unreachable!(),
-        };
+        let (porcelain, branch, untracked_files, null_terminated, dry_run, amend) =
+            match ctx.opt.cmd {
+                Command::Status {
+                    porcelain,
+                    branch,
+                    untracked_files,
+                    null_terminated,
+                } => (
+                    // `-z` implies the porcelain format
+                    match porcelain {
+                        Some(version) => Some(version.unwrap_or(PorcelainVersion::V1)),
+                        None if null_terminated => Some(PorcelainVersion::V1),
+                        None => None,
+                    },
+                    branch,
+                    untracked_files,
+                    null_terminated,
+                    false,
+                    false,
+                ),
+                Command::Commit { amend, .. } => (None, false, None, false, true, amend),
+                _ => unreachable!(),
+            };
 
         let status = ctx.repo.status(None);
 
@@ -71,43 +119,294 @@ impl<'a> Status<'a> {
             ctx,
             status,
             porcelain,
+            branch,
+            untracked_files,
+            null_terminated,
+            quote_path: true,
+            dry_run,
+            amend,
         }
     }
 
     pub fn run(&mut self) -> Result<()> {
+        self.status.untracked_mode = match self.untracked_files {
+            Some(mode) => mode,
+            None => self.configured_untracked_files()?,
+        };
+        self.quote_path = self.ctx.repo.quote_path()?;
+        if self.amend {
+            self.compare_with_amended_parent()?;
+        }
+
         self.ctx.repo.index.load_for_update()?;
         self.status.initialize()?;
         self.ctx.repo.index.write_updates()?;
 
         self.print_results()?;
 
+        if self.dry_run && self.status.index_changes.is_empty() {
+            return Err(Error::Exit(1));
+        }
+
         Ok(())
     }
 
+    /// Show what `commit --amend` would commit, which replaces `HEAD` and so is compared with its
+    /// first parent, or with nothing for a root commit.
+    fn compare_with_amended_parent(&mut self) -> Result<()> {
+        let head = match self.ctx.repo.refs.read_head()? {
+            Some(head) => head,
+            None => {
+                let mut stderr = self.ctx.stderr.borrow_mut();
+                writeln!(stderr, "fatal: You have nothing to amend.")?;
+                return Err(Error::Exit(128));
+            }
+        };
+        let parent = self
+            .ctx
+            .repo
+            .database
+            .load_commit(&head)?
+            .first_parent_or_empty_tree();
+
+        let untracked_mode = self.status.untracked_mode;
+        self.status = self.ctx.repo.status(Some(&parent));
+        self.status.untracked_mode = untracked_mode;
+
+        Ok(())
+    }
+
+    /// The mode `status.showUntrackedFiles` sets, which may also be a boolean.
+    fn configured_untracked_files(&self) -> Result<UntrackedFiles> {
+        let config = &self.ctx.repo.config;
+        config.open()?;
+
+        let value = config.get(&[String::from("status"), String::from("showUntrackedFiles")]);
+
+        Ok(match value {
+            Some(VariableValue::Bool(false)) => UntrackedFiles::No,
+            Some(VariableValue::String(mode)) => {
+                UntrackedFiles::from_str(&mode, true).unwrap_or(UntrackedFiles::Normal)
+            }
+            _ => UntrackedFiles::Normal,
+        })
+    }
+
     fn print_results(&self) -> Result<()> {
-        if self.porcelain {
-            self.print_porcelain_format()?;
-        } else {
-            self.print_long_format()?;
+        match self.porcelain {
+            Some(PorcelainVersion::V1) => self.print_porcelain_format()?,
+            Some(PorcelainVersion::V2) => self.print_porcelain_v2_format()?,
+            None => self.print_long_format()?,
         }
 
         Ok(())
     }
 
     fn print_porcelain_format(&self) -> Result<()> {
+        if self.branch {
+            self.print_porcelain_headers()?;
+        }
+
         let mut stdout = self.ctx.stdout.borrow_mut();
 
         for path in &self.status.changed {
             let status = self.status_for(path);
-            writeln!(stdout, "{} {}", status, path)?;
+            write!(
+                stdout,
+                "{} {}{}",
+                status,
+                self.quote(path),
+                self.terminator()
+            )?;
         }
         for path in &self.status.untracked_files {
-            writeln!(stdout, "?? {}", path)?;
+            write!(stdout, "?? {}{}", self.quote(path), self.terminator())?;
+        }
+
+        Ok(())
+    }
+
+    /// Print each change with the modes and object IDs of the file in `HEAD`, the index and the
+    /// workspace, like Git's porcelain v2 format. A file renamed in the index is listed once,
+    /// under its new name.
+    fn print_porcelain_v2_format(&self) -> Result<()> {
+        if self.branch {
+            self.print_porcelain_headers()?;
+        }
+
+        let renames = self.index_renames()?;
+        let renamed: HashSet<_> = renames
+            .values()
+            .map(|rename| path_to_string(&rename.old_path))
+            .collect();
+
+        let mut stdout = self.ctx.stdout.borrow_mut();
+
+        for path in &self.status.changed {
+            if let Some(stages) = self.status.conflicts.get(path) {
+                let entries: Vec<_> = (1..=3)
+                    .map(|stage| self.ctx.repo.index.entry_for_path(path, stage))
+                    .collect();
+                write!(
+                    stdout,
+                    "u {} N... {} {:06o} {} {}{}",
+                    CONFLICT_SHORT_STATUS[stages],
+                    entries.iter().map(|entry| index_mode(*entry)).join(" "),
+                    self.workspace_mode(path),
+                    entries.iter().map(|entry| index_oid(*entry)).join(" "),
+                    self.quote(path),
+                    self.terminator(),
+                )?;
+            } else if let Some(rename) = renames.get(path) {
+                let old_path = path_to_string(&rename.old_path);
+                write!(
+                    stdout,
+                    "2 R{} {} R{} {}{}{}{}",
+                    &self.status_for(path)[1..].replace(' ', "."),
+                    self.v2_fields(&old_path, path),
+                    rename.score,
+                    self.quote(path),
+                    // The original path is separated by a tab, or a NUL with `-z`
+                    if self.null_terminated { '\0' } else { '\t' },
+                    self.quote(&old_path),
+                    self.terminator(),
+                )?;
+            } else if !renamed.contains(path) {
+                write!(
+                    stdout,
+                    "1 {} {} {}{}",
+                    self.status_for(path).replace(' ', "."),
+                    self.v2_fields(path, path),
+                    self.quote(path),
+                    self.terminator(),
+                )?;
+            }
+        }
+        for path in &self.status.untracked_files {
+            write!(stdout, "? {}{}", self.quote(path), self.terminator())?;
+        }
+
+        Ok(())
+    }
+
+    /// The `<sub> <mH> <mI> <mW> <hH> <hI>` fields of a porcelain v2 line for a file that was at
+    /// `head_path` in `HEAD`.
+    fn v2_fields(&self, head_path: &str, path: &str) -> String {
+        let head = self.status.head_tree.get(head_path);
+        let index = self.ctx.repo.index.entry_for_path(path, 0);
+
+        // A submodule's `C` flag shows that its `HEAD` differs from the gitlink
+        let sub = if index.is_some_and(|entry| entry.mode == GITLINK_MODE) {
+            match self.status.workspace_changes.get(path) {
+                Some(ChangeType::Modified) => "SC..",
+                _ => "S...",
+            }
+        } else {
+            "N..."
+        };
+
+        format!(
+            "{} {:06o} {} {:06o} {} {}",
+            sub,
+            head.map_or(0, |entry| entry.mode()),
+            index_mode(index),
+            self.workspace_mode(path),
+            head.map_or(NULL_OID.to_owned(), |entry| entry.oid()),
+            index_oid(index),
+        )
+    }
+
+    fn workspace_mode(&self, path: &str) -> u32 {
+        match self.status.stats.get(path) {
+            Some(stat) if stat.is_dir() => GITLINK_MODE,
+            Some(stat) => Entry::mode_for_stat(stat),
+            None => 0,
+        }
+    }
+
+    /// The files renamed between `HEAD` and the index, keyed by their new path.
+    fn index_renames(&self) -> Result<HashMap<String, Rename>> {
+        let mut changes = TreeDiffChanges::new();
+        for path in self.status.index_changes.keys() {
+            let old_entry = self
+                .status
+                .head_tree
+                .get(path)
+                .map(|entry| DatabaseEntry::new(entry.oid(), entry.mode()));
+            let new_entry = self
+                .ctx
+                .repo
+                .index
+                .entry_for_path(path, 0)
+                .map(DatabaseEntry::from);
+
+            changes.insert(PathBuf::from(path), (old_entry, new_entry));
+        }
+
+        Ok(detect_renames(&self.ctx.repo.database, &changes)?
+            .into_iter()
+            .map(|rename| (path_to_string(&rename.new_path), rename))
+            .collect())
+    }
+
+    /// Print the headers of Git's porcelain v2 format, `# branch.oid` and `# branch.head`, followed
+    /// by `# <operation>.oid` naming the commit being merged, cherry-picked, or reverted.
+    fn print_porcelain_headers(&self) -> Result<()> {
+        let head = self.ctx.repo.refs.read_head()?;
+        let current = self.ctx.repo.refs.current_ref(HEAD)?;
+        let pending_commit = self.ctx.repo.pending_commit();
+
+        let mut stdout = self.ctx.stdout.borrow_mut();
+
+        write!(
+            stdout,
+            "# branch.oid {}{}",
+            head.as_deref().unwrap_or("(initial)"),
+            self.terminator(),
+        )?;
+        if current.is_head() {
+            write!(stdout, "# branch.head (detached){}", self.terminator())?;
+        } else {
+            write!(
+                stdout,
+                "# branch.head {}{}",
+                self.ctx.repo.refs.short_name(&current),
+                self.terminator(),
+            )?;
+        }
+
+        if let Some(merge_type) = pending_commit.merge_type() {
+            write!(
+                stdout,
+                "# {}.oid {}{}",
+                operation_name(merge_type),
+                pending_commit.merge_oid(merge_type)?,
+                self.terminator(),
+            )?;
         }
 
         Ok(())
     }
 
+    /// The end of each porcelain record: a NUL with `-z`, so scripts can read any path verbatim.
+    fn terminator(&self) -> char {
+        if self.null_terminated {
+            '\0'
+        } else {
+            '\n'
+        }
+    }
+
+    /// `path` as it's printed: quoted if it has unusual characters, unless records end with a
+    /// NUL, which leaves no need for it.
+    fn quote(&self, path: &str) -> String {
+        if self.null_terminated {
+            path.to_owned()
+        } else {
+            quote_path(path, self.quote_path)
+        }
+    }
+
     fn print_long_format(&self) -> Result<()> {
         self.print_branch_status()?;
         self.print_pending_commit_status()?;
@@ -132,20 +431,26 @@ impl<'a> Status<'a> {
 
     fn print_branch_status(&self) -> Result<()> {
         let current = self.ctx.repo.refs.current_ref(HEAD)?;
+        let head_oid = self.ctx.repo.refs.read_oid(&current)?;
 
         let mut stdout = self.ctx.stdout.borrow_mut();
-        if current.is_head() {
-            writeln!(
+        match (current.is_head(), head_oid) {
+            (true, Some(oid)) => writeln!(
+                stdout,
+                "{} {}",
+                String::from("HEAD detached at").red(),
+                Database::short_oid(&oid)
+            )?,
+            (true, None) => writeln!(
                 stdout,
                 "{}",
                 String::from("Not currently on any branch.").red()
-            )?;
-        } else {
-            writeln!(
+            )?,
+            (false, _) => writeln!(
                 stdout,
                 "On branch {}",
                 self.ctx.repo.refs.short_name(&current)
-            )?;
+            )?,
         }
 
         Ok(())
@@ -181,11 +486,7 @@ impl<'a> Status<'a> {
     fn print_pending_type(&self, merge_type: PendingCommitType) -> Result<()> {
         let oid = self.ctx.repo.pending_commit().merge_oid(merge_type)?;
         let short = Database::short_oid(&oid);
-        let op = match merge_type {
-            PendingCommitType::CherryPick => "cherry-pick",
-            PendingCommitType::Revert => "revert",
-            _ => unreachable!(),
-        };
+        let op = operation_name(merge_type);
 
         let mut stdout = self.ctx.stdout.borrow_mut();
 
@@ -233,7 +534,11 @@ impl<'a> Status<'a> {
         writeln!(stdout)?;
         for (path, change_type) in changeset {
             let status = format!("{:width$}", LONG_STATUS[change_type], width = LABEL_WIDTH);
-            writeln!(stdout, "{}", format!("\t{}{}", status, path).color(style))?;
+            writeln!(
+                stdout,
+                "{}",
+                format!("\t{}{}", status, self.quote(path)).color(style)
+            )?;
         }
         writeln!(stdout)?;
 
@@ -255,8 +560,13 @@ impl<'a> Status<'a> {
                 CONFLICT_LONG_STATUS[r#type],
                 width = CONFLICT_LABEL_WIDTH
             );
-            writeln!(stdout, "{}", format!("\t{}{}", status, path).red())?;
+            writeln!(
+                stdout,
+                "{}",
+                format!("\t{}{}", status, self.quote(path)).red()
+            )?;
         }
+        writeln!(stdout)?;
 
         Ok(())
     }
@@ -271,7 +581,7 @@ impl<'a> Status<'a> {
         writeln!(stdout, "Untracked files:")?;
         writeln!(stdout)?;
         for path in &self.status.untracked_files {
-            writeln!(stdout, "{}", format!("\t{}", path).red())?;
+            writeln!(stdout, "{}", format!("\t{}", self.quote(path)).red())?;
         }
         writeln!(stdout)?;
 
@@ -285,7 +595,7 @@ impl<'a> Status<'a> {
 
         let mut stdout = self.ctx.stdout.borrow_mut();
 
-        if !self.status.workspace_changes.is_empty() {
+        if !self.status.workspace_changes.is_empty() || !self.status.conflicts.is_empty() {
             writeln!(stdout, "no changes added to commit")?;
         } else if !self.status.untracked_files.is_empty() {
             writeln!(
@@ -316,3 +626,27 @@ impl<'a> Status<'a> {
         }
     }
 }
+
+/// The mode of an index entry in a porcelain v2 line. Like Git, a file added with
+/// `--intent-to-add` isn't in the index yet.
+fn index_mode(entry: Option<&Entry>) -> String {
+    match entry.filter(|entry| !entry.intent_to_add()) {
+        Some(entry) => format!("{:06o}", entry.mode),
+        None => String::from("000000"),
+    }
+}
+
+fn index_oid(entry: Option<&Entry>) -> String {
+    match entry.filter(|entry| !entry.intent_to_add()) {
+        Some(entry) => entry.oid.to_owned(),
+        None => NULL_OID.to_owned(),
+    }
+}
+
+fn operation_name(merge_type: PendingCommitType) -> &'static str {
+    match merge_type {
+        PendingCommitType::Merge => "merge",
+        PendingCommitType::CherryPick => "cherry-pick",
+        PendingCommitType::Revert => "revert",
+    }
+}
@@ -0,0 +1,83 @@
+use std::io::Write;
+use std::path::Path;
+
+use crate::commands::{Command, CommandContext};
+use crate::database::tree::GITLINK_MODE;
+use crate::errors::{Error, Result};
+use crate::repository::submodules;
+
+pub struct Submodule<'a> {
+    ctx: CommandContext<'a>,
+    /// `jit submodule [status]`
+    args: Vec<String>,
+}
+
+impl<'a> Submodule<'a> {
+    pub fn new(ctx: CommandContext<'a>) -> Self {
+        let args = match &ctx.opt.cmd {
+            Command::Submodule { args } => args.to_owned(),
+            _ => unreachable!(),
+        };
+
+        Self { ctx, args }
+    }
+
+    pub fn run(&mut self) -> Result<()> {
+        if self.args.is_empty() {
+            return self.status();
+        }
+
+        match self.args.remove(0).as_str() {
+            "status" => self.status(),
+            subcommand => {
+                let mut stderr = self.ctx.stderr.borrow_mut();
+                writeln!(stderr, "error: unknown subcommand: {}", subcommand)?;
+                Err(Error::Exit(129))
+            }
+        }
+    }
+
+    /// List each submodule in the index with the commit checked out in it, prefixed with `-` if
+    /// it hasn't been cloned, or `+` if it's not the commit the index records. Like Git, every
+    /// gitlink must be declared in `.gitmodules`.
+    fn status(&mut self) -> Result<()> {
+        self.ctx.repo.index.load()?;
+        let declared = self.ctx.repo.submodules()?;
+
+        let gitlinks: Vec<_> = self
+            .ctx
+            .repo
+            .index
+            .entries
+            .values()
+            .filter(|entry| entry.stage() == 0 && entry.mode == GITLINK_MODE)
+            .map(|entry| (entry.path.clone(), entry.oid.clone()))
+            .collect();
+
+        for (path, oid) in gitlinks {
+            if !declared
+                .iter()
+                .any(|submodule| submodule.path == Path::new(&path))
+            {
+                let mut stderr = self.ctx.stderr.borrow_mut();
+                writeln!(
+                    stderr,
+                    "fatal: no submodule mapping found in .gitmodules for path '{}'",
+                    path
+                )?;
+                return Err(Error::Exit(128));
+            }
+
+            let (prefix, oid) = match submodules::head(&self.ctx.repo.root_path, Path::new(&path))?
+            {
+                None => ('-', oid),
+                Some(head) if head != oid => ('+', head),
+                Some(head) => (' ', head),
+            };
+            let mut stdout = self.ctx.stdout.borrow_mut();
+            writeln!(stdout, "{}{} {}", prefix, oid, path)?;
+        }
+
+        Ok(())
+    }
+}
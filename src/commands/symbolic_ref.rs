@@ -0,0 +1,78 @@
+use std::io::Write;
+
+use crate::commands::{Command, CommandContext};
+use crate::errors::{Error, Result};
+use crate::refs::Ref;
+
+pub struct SymbolicRef<'a> {
+    ctx: CommandContext<'a>,
+    /// `jit symbolic-ref <name>`
+    name: String,
+    /// `jit symbolic-ref <name> <ref>`
+    target: Option<String>,
+    /// `jit symbolic-ref --short`
+    short: bool,
+}
+
+impl<'a> SymbolicRef<'a> {
+    pub fn new(ctx: CommandContext<'a>) -> Self {
+        let (name, target, short) = match &ctx.opt.cmd {
+            Command::SymbolicRef {
+                name,
+                target,
+                short,
+            } => (name.to_owned(), target.to_owned(), *short),
+            _ => unreachable!(),
+        };
+
+        Self {
+            ctx,
+            name,
+            target,
+            short,
+        }
+    }
+
+    pub fn run(&self) -> Result<()> {
+        match &self.target {
+            Some(target) => self.update_ref(target),
+            None => self.read_ref(),
+        }
+    }
+
+    fn read_ref(&self) -> Result<()> {
+        match self.ctx.repo.refs.read_symbolic_ref(&self.name)? {
+            Some(path) => {
+                let path = if self.short {
+                    self.ctx.repo.refs.short_name(&Ref::SymRef { path })
+                } else {
+                    path
+                };
+
+                let mut stdout = self.ctx.stdout.borrow_mut();
+                writeln!(stdout, "{}", path)?;
+
+                Ok(())
+            }
+            None => {
+                let mut stderr = self.ctx.stderr.borrow_mut();
+                writeln!(stderr, "fatal: ref {} is not a symbolic ref", self.name)?;
+
+                Err(Error::Exit(128))
+            }
+        }
+    }
+
+    fn update_ref(&self, target: &str) -> Result<()> {
+        match self.ctx.repo.refs.update_symbolic_ref(&self.name, target) {
+            Ok(()) => Ok(()),
+            Err(err @ Error::InvalidArgument(..)) => {
+                let mut stderr = self.ctx.stderr.borrow_mut();
+                writeln!(stderr, "fatal: {}", err)?;
+
+                Err(Error::Exit(128))
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
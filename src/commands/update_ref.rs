@@ -0,0 +1,80 @@
+use std::io::Write;
+
+use crate::commands::{Command, CommandContext};
+use crate::errors::{Error, Result};
+use crate::revision::Revision;
+
+const USAGE: &str = "\
+usage: jit update-ref [<options>] -d <refname> [<old-val>]
+   or: jit update-ref [<options>] <refname> <new-val> [<old-val>]";
+
+pub struct UpdateRef<'a> {
+    ctx: CommandContext<'a>,
+    /// `jit update-ref <refname> <new-val> [<old-val>]`
+    args: Vec<String>,
+    /// `jit update-ref -d <refname> [<old-val>]`
+    delete: bool,
+}
+
+impl<'a> UpdateRef<'a> {
+    pub fn new(ctx: CommandContext<'a>) -> Self {
+        let (args, delete) = match &ctx.opt.cmd {
+            Command::UpdateRef { args, delete } => (args.to_owned(), *delete),
+            _ => unreachable!(),
+        };
+
+        Self { ctx, args, delete }
+    }
+
+    pub fn run(&self) -> Result<()> {
+        let (name, new, old) = match (self.delete, self.args.as_slice()) {
+            (true, [name]) => (name, None, None),
+            (true, [name, old]) => (name, None, Some(old)),
+            (false, [name, new]) => (name, Some(new), None),
+            (false, [name, new, old]) => (name, Some(new), Some(old)),
+            _ => {
+                let mut stderr = self.ctx.stderr.borrow_mut();
+                writeln!(stderr, "{}", USAGE)?;
+                return Err(Error::Exit(129));
+            }
+        };
+
+        let new = match new {
+            Some(new) => Some(self.resolve(new)?),
+            None => None,
+        };
+        let old = match old {
+            // An empty or all-zero old value means the ref must not exist yet
+            Some(old) if old.is_empty() || *old == "0".repeat(40) => Some(String::new()),
+            Some(old) => Some(self.resolve(old)?),
+            None => None,
+        };
+
+        match self
+            .ctx
+            .repo
+            .refs
+            .compare_and_swap(name, old.as_deref(), new.as_deref())
+        {
+            Ok(()) => Ok(()),
+            Err(err @ Error::CannotLockRef(..)) => {
+                let mut stderr = self.ctx.stderr.borrow_mut();
+                writeln!(stderr, "fatal: {}", err)?;
+                Err(Error::Exit(128))
+            }
+            Err(err) => Err(err),
+        }
+    }
+
+    fn resolve(&self, value: &str) -> Result<String> {
+        match Revision::new(&self.ctx.repo, value).resolve(None) {
+            Ok(oid) => Ok(oid),
+            Err(err) if err.is_revision_error() => {
+                let mut stderr = self.ctx.stderr.borrow_mut();
+                writeln!(stderr, "fatal: {}: not a valid SHA1", value)?;
+                Err(Error::Exit(128))
+            }
+            Err(err) => Err(err),
+        }
+    }
+}
@@ -0,0 +1,36 @@
+use std::io::Write;
+
+use crate::commands::shared::commit_writer::CommitWriter;
+use crate::commands::CommandContext;
+use crate::database::object::Object;
+use crate::errors::{Error, Result};
+
+pub struct WriteTree<'a> {
+    ctx: CommandContext<'a>,
+}
+
+impl<'a> WriteTree<'a> {
+    pub fn new(ctx: CommandContext<'a>) -> Self {
+        Self { ctx }
+    }
+
+    pub fn run(&mut self) -> Result<()> {
+        self.ctx.repo.index.load()?;
+
+        if self.ctx.repo.index.has_conflict() {
+            let mut stderr = self.ctx.stderr.borrow_mut();
+            for path in self.ctx.repo.index.conflict_paths() {
+                writeln!(stderr, "{}: unmerged", path)?;
+            }
+            writeln!(stderr, "fatal: jit write-tree failed to write a tree")?;
+            return Err(Error::Exit(128));
+        }
+
+        let tree = CommitWriter::new(&self.ctx).write_tree();
+
+        let mut stdout = self.ctx.stdout.borrow_mut();
+        writeln!(stdout, "{}", tree.oid())?;
+
+        Ok(())
+    }
+}
@@ -10,31 +10,32 @@ use regex::{Regex, RegexBuilder};
 
 use crate::errors::{Error, Result};
 use crate::lockfile::Lockfile;
+use crate::util::glob_match;
 
 pub mod stack;
 
-static SECTION_LINE: Lazy<Regex> = Lazy::new(||
-    // TODO: Handle difference between Ruby's \Z and Rust's \z
-    RegexBuilder::new(r#"\A\s*\[([a-z0-9-]+)( "(.+)")?\]\s*(\z|#|;)"#)
+// Lines are matched without their newline, so unlike Ruby's `\Z`, `\z` doesn't need to allow for
+// one at the end. Anything after a section heading must be a comment.
+static SECTION_LINE: Lazy<Regex> = Lazy::new(|| {
+    RegexBuilder::new(r#"\A\s*\[([a-z0-9-]+)( "((?:[^"\\]|\\.)*)")?\]\s*(\z|#|;)"#)
         .case_insensitive(true)
         .build()
-        .unwrap());
-
-static VARIABLE_LINE: Lazy<Regex> =
-    // TODO: Handle difference between Ruby's \Z and Rust's \z
-    Lazy::new(|| {
-        RegexBuilder::new(r#"\A\s*([a-z][a-z0-9-]*)\s*=\s*(.*?)\s*(\z|#|;)"#)
-            .case_insensitive(true)
-            .multi_line(true)
-            .build()
-            .unwrap()
-    });
-
-// TODO: Handle difference between Ruby's \Z and Rust's \z
+        .unwrap()
+});
+
+/// A variable line, which may span several lines joined by backslashes. The raw value is read
+/// by `read_value()`.
+static VARIABLE_LINE: Lazy<Regex> = Lazy::new(|| {
+    RegexBuilder::new(r#"\A\s*([a-z][a-z0-9-]*)\s*=(.*)\z"#)
+        .case_insensitive(true)
+        .dot_matches_new_line(true)
+        .build()
+        .unwrap()
+});
+
 static BLANK_LINE: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\A\s*(\z|#|;)"#).unwrap());
 
-// TODO: Handle difference between Ruby's \Z and Rust's \z
-static INTEGER: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\A-?[1-9][0-9]*\z"#).unwrap());
+static INTEGER: Lazy<Regex> = Lazy::new(|| Regex::new(r#"\A-?(0|[1-9][0-9]*)\z"#).unwrap());
 
 static VALID_SECTION: Lazy<Regex> = Lazy::new(|| {
     RegexBuilder::new(r"^[a-z0-9-]+$")
@@ -66,7 +67,10 @@ impl Variable {
     }
 
     pub fn serialize(name: &str, value: &VariableValue) -> String {
-        format!("\t{} = {}\n", name, value)
+        match value {
+            VariableValue::String(value) => format!("\t{} = {}\n", name, quote_value(value)),
+            _ => format!("\t{} = {}\n", name, value),
+        }
     }
 }
 
@@ -77,6 +81,47 @@ pub enum VariableValue {
     String(String),
 }
 
+impl VariableValue {
+    /// The value as a boolean, where integers are true unless they're 0.
+    pub fn to_bool(&self) -> Option<bool> {
+        match self {
+            VariableValue::Bool(value) => Some(*value),
+            VariableValue::Int(value) => Some(*value != 0),
+            VariableValue::String(_) => None,
+        }
+    }
+
+    /// The value as an integer, which can end with Git's `k`, `m` or `g` suffixes to multiply it
+    /// by 1024, 1024² or 1024³.
+    pub fn to_int(&self) -> Option<i64> {
+        match self {
+            VariableValue::Int(value) => Some(i64::from(*value)),
+            VariableValue::String(value) => {
+                let (number, unit) = match value.char_indices().last()? {
+                    (i, 'k' | 'K') => (&value[..i], 1 << 10),
+                    (i, 'm' | 'M') => (&value[..i], 1 << 20),
+                    (i, 'g' | 'G') => (&value[..i], 1 << 30),
+                    _ => (value.as_str(), 1),
+                };
+                if !INTEGER.is_match(number) {
+                    return None;
+                }
+
+                number.parse::<i64>().ok()?.checked_mul(unit)
+            }
+            VariableValue::Bool(_) => None,
+        }
+    }
+
+    /// The value as a path, with a leading `~/` expanded to the home directory.
+    pub fn to_path(&self) -> Option<PathBuf> {
+        match self {
+            VariableValue::String(value) => Some(expand_path(value, Path::new(""))),
+            _ => None,
+        }
+    }
+}
+
 impl fmt::Display for VariableValue {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
@@ -110,7 +155,8 @@ impl Section {
 
         let mut line = format!("[{}", first);
         if !rest.is_empty() {
-            write!(line, " \"{}\"", rest.join(".")).unwrap();
+            let subsection = rest.join(".").replace('\\', "\\\\").replace('"', "\\\"");
+            write!(line, " \"{}\"", subsection).unwrap();
         }
         line.push_str("]\n");
 
@@ -123,6 +169,8 @@ pub struct Line {
     text: String,
     section: Section,
     variable: Option<Variable>,
+    /// Whether the line was read from an included file, and so is never saved
+    included: bool,
 }
 
 impl Line {
@@ -131,6 +179,7 @@ impl Line {
             text,
             section,
             variable,
+            included: false,
         }
     }
 
@@ -146,6 +195,8 @@ pub struct Config {
     path: PathBuf,
     lockfile: Lockfile,
     lines: IndexMap<Vec<String>, Vec<Line>>,
+    /// The repository's `.git` directory, which `[includeIf "gitdir:<pattern>"]` is matched against
+    git_dir: Option<PathBuf>,
 }
 
 impl Config {
@@ -161,9 +212,14 @@ impl Config {
             path: path.to_owned(),
             lockfile: Lockfile::new(path.to_owned()),
             lines: IndexMap::new(),
+            git_dir: None,
         }
     }
 
+    pub fn set_git_dir(&mut self, git_dir: &Path) {
+        self.git_dir = Some(git_dir.to_owned());
+    }
+
     pub fn open(&mut self) -> Result<()> {
         if self.lines.is_empty() {
             self.read_config_file()?;
@@ -181,7 +237,7 @@ impl Config {
 
     pub fn save(&mut self) -> Result<()> {
         for (_section, lines) in &self.lines {
-            for line in lines {
+            for line in lines.iter().filter(|line| !line.included) {
                 self.lockfile.write(line.text.as_bytes())?;
             }
         }
@@ -194,17 +250,54 @@ impl Config {
         self.get_all(key).last().map(|val| val.to_owned())
     }
 
+    pub fn get_bool(&self, key: &[String]) -> Option<bool> {
+        self.get(key)?.to_bool()
+    }
+
+    pub fn get_int(&self, key: &[String]) -> Option<i64> {
+        self.get(key)?.to_int()
+    }
+
+    pub fn get_path(&self, key: &[String]) -> Option<PathBuf> {
+        self.get(key)?.to_path()
+    }
+
+    /// All the values of `key`, including those from included files, in the order they're read.
     pub fn get_all(&self, key: &[String]) -> Vec<VariableValue> {
         let (key, var) = self.split_key(key);
+        let normal = Variable::normalize(&var);
 
-        let (_, lines) = self.find_lines(&key, &var);
-
-        lines
-            .iter()
+        self.lines
+            .get(&Section::normalize(&key))
+            .into_iter()
+            .flatten()
+            .filter(|line| line.normal_variable().as_deref() == Some(&normal))
             .map(|line| line.variable.as_ref().unwrap().value.to_owned())
             .collect()
     }
 
+    /// The variables whose full names, like `remote.origin.url`, match `pattern`, in file order.
+    /// Section and variable names are lowercase, but subsection names keep their case.
+    pub fn get_regexp(&self, pattern: &Regex) -> Vec<(String, VariableValue)> {
+        self.lines
+            .values()
+            .flatten()
+            .filter_map(|line| {
+                let variable = line.variable.as_ref()?;
+                let mut name = Section::normalize(&line.section.name);
+                name.retain(|part| !part.is_empty());
+                name.push(Variable::normalize(&variable.name));
+                let name = name.join(".");
+
+                if pattern.is_match(&name) {
+                    Some((name, variable.value.to_owned()))
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+
     pub fn add(&mut self, key: &[String], value: VariableValue) {
         let (key, var) = self.split_key(key);
         let (section, _) = self.find_lines(&key, &var);
@@ -265,9 +358,13 @@ impl Config {
             f(&lines)?;
 
             self.remove_all(&section, &lines);
+            // Remove the section if only its heading is left, keeping any included lines
             let lines = self.lines_for(&section);
-            if lines.len() == 1 {
-                self.remove_section(&key);
+            if lines.iter().filter(|line| !line.included).count() == 1 {
+                lines.retain(|line| line.included);
+                if lines.is_empty() {
+                    self.remove_section(&key);
+                }
             }
 
             Ok(())
@@ -303,10 +400,6 @@ impl Config {
         self.lines.contains_key(&key)
     }
 
-    fn line_count(&self) -> usize {
-        self.lines.values().map(|lines| lines.len()).sum::<usize>() + self.lines.len()
-    }
-
     fn lines_for(&mut self, section: &Section) -> &mut Vec<Line> {
         self.lines
             .entry(Section::normalize(&section.name))
@@ -320,15 +413,20 @@ impl Config {
         (key[0..len - 1].to_owned(), var.to_owned())
     }
 
+    /// The section in this file matching `key`, and its lines for `var`. Included files are
+    /// skipped, since they're never changed.
     fn find_lines(&self, key: &[String], var: &str) -> (Option<Section>, Vec<Line>) {
         let name = Section::normalize(key);
 
         if let Some(lines) = self.lines.get(&name) {
-            let section = &lines[0].section;
+            let mut lines = lines.iter().filter(|l| !l.included).peekable();
+            let section = match lines.peek() {
+                Some(line) => line.section.to_owned(),
+                None => return (None, vec![]),
+            };
             let normal = Variable::normalize(var);
 
             let lines: Vec<_> = lines
-                .iter()
                 .filter_map(|l| {
                     if l.normal_variable().as_deref() == Some(&normal) {
                         Some(l.to_owned())
@@ -338,7 +436,7 @@ impl Config {
                 })
                 .collect();
 
-            (Some(section.to_owned()), lines)
+            (Some(section), lines)
         } else {
             (None, vec![])
         }
@@ -391,9 +489,17 @@ impl Config {
     }
 
     fn read_config_file(&mut self) -> Result<()> {
+        let path = self.path.clone();
+        self.read_file(&path, &mut vec![])
+    }
+
+    /// Read the file at `path`, followed at each `include.path` or matching `includeIf.*.path` by
+    /// the lines of the file it names. `reading` holds the files being read, so a file including
+    /// itself, directly or not, is only read once.
+    fn read_file(&mut self, path: &Path, reading: &mut Vec<PathBuf>) -> Result<()> {
         let mut section = Section::new(vec![]);
 
-        let file = match File::open(&self.path) {
+        let file = match File::open(path) {
             Ok(file) => io::BufReader::new(file),
             Err(err) => {
                 if err.kind() == io::ErrorKind::NotFound {
@@ -403,31 +509,139 @@ impl Config {
                 }
             }
         };
+        reading.push(path.to_owned());
+
+        // A variable line continued onto the following lines, and the number of its first line
+        let mut text = String::new();
+        let mut line_number = 0;
 
-        // TODO: Support multi-line strings in config values
-        for line in file.lines() {
-            let mut line = self.parse_line(&section, &line?)?;
+        for (n, line) in file.lines().enumerate() {
+            if text.is_empty() {
+                line_number = n + 1;
+            } else {
+                text.push('\n');
+            }
+            text.push_str(&line?);
+            if is_continued(&text) {
+                continue;
+            }
+
+            let mut line = self.parse_line(&section, &text, path, line_number)?;
+            text.clear();
             // `file.lines()` strips the newline characters
             line.text.push('\n');
+            line.included = reading.len() > 1;
             section = line.section.clone();
 
+            let include = self.include_path(path, &line);
             self.lines_for(&section).push(line);
+
+            if let Some(include) = include {
+                if !reading.contains(&include) {
+                    self.read_file(&include, reading)?;
+                }
+            }
         }
 
+        if !text.is_empty() {
+            return Err(Error::ConfigParseError(line_number, path.to_owned()));
+        }
+        reading.pop();
+
         Ok(())
     }
 
-    fn parse_line(&self, section: &Section, line: &str) -> Result<Line> {
+    /// The file to include if `line` is an include directive in the file at `path`. Relative
+    /// paths are relative to the including file's directory.
+    fn include_path(&self, path: &Path, line: &Line) -> Option<PathBuf> {
+        let variable = line.variable.as_ref()?;
+        let value = match &variable.value {
+            VariableValue::String(value) if Variable::normalize(&variable.name) == "path" => value,
+            _ => return None,
+        };
+
+        let included = match Section::normalize(&line.section.name).as_slice() {
+            [name, subsection] if name == "include" => subsection.is_empty(),
+            [name, condition] if name == "includeif" => {
+                self.include_condition_matches(path, condition)
+            }
+            _ => false,
+        };
+        if !included {
+            return None;
+        }
+
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+        Some(expand_path(value, dir))
+    }
+
+    /// Whether an `[includeIf "<condition>"]` section applies. Only `gitdir:` and `gitdir/i:`
+    /// conditions are supported; their patterns are globs matched against the `.git` directory.
+    fn include_condition_matches(&self, path: &Path, condition: &str) -> bool {
+        let (pattern, ignore_case) = if let Some(pattern) = condition.strip_prefix("gitdir:") {
+            (pattern, false)
+        } else if let Some(pattern) = condition.strip_prefix("gitdir/i:") {
+            (pattern, true)
+        } else {
+            return false;
+        };
+        let git_dir = match &self.git_dir {
+            Some(git_dir) => git_dir,
+            None => return false,
+        };
+
+        // Like Git, `./` is relative to the including file, other relative patterns can match
+        // anywhere, and a trailing `/` matches everything inside the directory
+        let mut pattern = if let Some(rest) = pattern.strip_prefix("./") {
+            let dir = path.parent().unwrap_or_else(|| Path::new(""));
+            dir.join(rest).to_string_lossy().into_owned()
+        } else if pattern.starts_with("~/") || pattern.starts_with('/') {
+            expand_path(pattern, Path::new("/"))
+                .to_string_lossy()
+                .into_owned()
+        } else {
+            format!("**/{}", pattern)
+        };
+        if pattern.ends_with('/') {
+            pattern.push_str("**");
+        }
+
+        let mut git_dirs = vec![git_dir.to_owned()];
+        if let Ok(real_git_dir) = git_dir.canonicalize() {
+            git_dirs.push(real_git_dir);
+        }
+
+        git_dirs.iter().any(|git_dir| {
+            let git_dir = git_dir.to_string_lossy();
+            if ignore_case {
+                glob_match(&pattern.to_lowercase(), &git_dir.to_lowercase())
+            } else {
+                glob_match(&pattern, &git_dir)
+            }
+        })
+    }
+
+    fn parse_line(
+        &self,
+        section: &Section,
+        line: &str,
+        path: &Path,
+        line_number: usize,
+    ) -> Result<Line> {
         if let Some(r#match) = SECTION_LINE.captures(line) {
             let mut name = vec![r#match[1].to_owned()];
             if let Some(r#match) = r#match.get(3) {
-                name.push(r#match.as_str().to_owned());
+                name.push(unescape_subsection(r#match.as_str()));
             }
             let section = Section::new(name);
 
             Ok(Line::new(line.to_owned(), section, None))
         } else if let Some(r#match) = VARIABLE_LINE.captures(line) {
-            let variable = Variable::new(r#match[1].to_owned(), self.parse_value(&r#match[2]));
+            let value = match read_value(&r#match[2]) {
+                RawValue::Value(value) => value,
+                _ => return Err(Error::ConfigParseError(line_number, path.to_owned())),
+            };
+            let variable = Variable::new(r#match[1].to_owned(), self.parse_value(&value));
 
             Ok(Line::new(
                 line.to_owned(),
@@ -437,20 +651,124 @@ impl Config {
         } else if let Some(_match) = BLANK_LINE.captures(line) {
             Ok(Line::new(line.to_owned(), section.to_owned(), None))
         } else {
-            Err(Error::ConfigParseError(
-                self.line_count() + 1,
-                self.path.clone(),
-            ))
+            Err(Error::ConfigParseError(line_number, path.to_owned()))
         }
     }
 
+    /// Booleans are case-insensitive, and integers too large for an `i32` are kept as strings.
     fn parse_value(&self, value: &str) -> VariableValue {
-        match value {
-            "yes" | "on" | "true" => VariableValue::Bool(true),
-            "no" | "off" | "false" => VariableValue::Bool(false),
-            _ if INTEGER.is_match(value) => VariableValue::Int(value.parse().unwrap()),
-            _ => VariableValue::String(value.replace("\\\n", "")),
+        match value.to_lowercase().as_str() {
+            "yes" | "on" | "true" => return VariableValue::Bool(true),
+            "no" | "off" | "false" => return VariableValue::Bool(false),
+            _ => (),
+        }
+
+        match value.parse() {
+            Ok(int) if INTEGER.is_match(value) => VariableValue::Int(int),
+            _ => VariableValue::String(value.to_owned()),
+        }
+    }
+}
+
+/// The value of a variable line, read from the text after its `=`.
+#[derive(Debug, PartialEq, Eq)]
+enum RawValue {
+    Value(String),
+    /// The text ends with a backslash, so the value goes on to the next line
+    Continued,
+    /// The text has an unknown escape or an unclosed quote
+    Invalid,
+}
+
+/// Read a value like Git does. Parts of it can be double-quoted to keep surrounding whitespace
+/// and `#` or `;`, which otherwise start a comment, and `\n`, `\t`, `\b`, `\\` and `\"` are
+/// escapes. A backslash at the end of a line joins it to the next one.
+fn read_value(text: &str) -> RawValue {
+    let mut value = String::new();
+    let mut quoted = false;
+    // The length of `value` without any whitespace after its last quoted or visible character
+    let mut end = 0;
+
+    let mut chars = text.trim_start().chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => quoted = !quoted,
+            '\\' => match chars.next() {
+                None => return RawValue::Continued,
+                Some('\n') => (),
+                Some('n') => value.push('\n'),
+                Some('t') => value.push('\t'),
+                Some('b') => value.push('\u{8}'),
+                Some(c @ ('\\' | '"')) => value.push(c),
+                Some(_) => return RawValue::Invalid,
+            },
+            '#' | ';' if !quoted => break,
+            c if c.is_whitespace() && !quoted => value.push(' '),
+            c => value.push(c),
         }
+        if quoted || !c.is_whitespace() {
+            end = value.len();
+        }
+    }
+
+    if quoted {
+        return RawValue::Invalid;
+    }
+    value.truncate(end);
+
+    RawValue::Value(value)
+}
+
+/// A subsection name from a section heading, where `\` escapes the next character.
+fn unescape_subsection(name: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = name.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => unescaped.extend(chars.next()),
+            c => unescaped.push(c),
+        }
+    }
+
+    unescaped
+}
+
+/// Whether `text` is a variable line whose value goes on to the next line.
+fn is_continued(text: &str) -> bool {
+    VARIABLE_LINE
+        .captures(text)
+        .is_some_and(|r#match| read_value(&r#match[2]) == RawValue::Continued)
+}
+
+/// Write `value` so `read_value()` reads it back unchanged, quoting it if it has surrounding
+/// whitespace or would start a comment.
+fn quote_value(value: &str) -> String {
+    let mut quoted = String::new();
+    for c in value.chars() {
+        match c {
+            '\\' => quoted.push_str("\\\\"),
+            '"' => quoted.push_str("\\\""),
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            '\u{8}' => quoted.push_str("\\b"),
+            c => quoted.push(c),
+        }
+    }
+
+    if value.starts_with(' ') || value.ends_with(' ') || value.contains(['#', ';']) {
+        format!("\"{}\"", quoted)
+    } else {
+        quoted
+    }
+}
+
+/// Expand a leading `~/` in `path` to the home directory, and resolve it relative to `dir`.
+fn expand_path(path: &str, dir: &Path) -> PathBuf {
+    match path.strip_prefix("~/") {
+        Some(rest) => dirs::home_dir()
+            .unwrap_or_else(|| PathBuf::from("/"))
+            .join(rest),
+        None => dir.join(path),
     }
 }
 
@@ -483,12 +801,65 @@ mod tests {
     #[case("false", VariableValue::Bool(false))]
     #[case("-2", VariableValue::Int(-2))]
     #[case("19", VariableValue::Int(19))]
+    #[case("0", VariableValue::Int(0))]
+    #[case("True", VariableValue::Bool(true))]
+    #[case("99999999999", VariableValue::String(String::from("99999999999")))]
     #[case("2.3", VariableValue::String(String::from("2.3")))]
     #[case("hello world", VariableValue::String(String::from("hello world")))]
     fn parse_value(config: Config, #[case] input: &str, #[case] expected: VariableValue) {
         assert_eq!(config.parse_value(input), expected);
     }
 
+    #[rstest]
+    #[case("12", Some(12))]
+    #[case("1k", Some(1024))]
+    #[case("-2M", Some(-2 * 1024 * 1024))]
+    #[case("3g", Some(3 * 1024 * 1024 * 1024))]
+    #[case("k", None)]
+    #[case("1.5k", None)]
+    fn convert_values_to_integers(
+        config: Config,
+        #[case] input: &str,
+        #[case] expected: Option<i64>,
+    ) {
+        assert_eq!(config.parse_value(input).to_int(), expected);
+    }
+
+    #[test]
+    fn convert_values_to_paths() {
+        let home = dirs::home_dir().unwrap();
+
+        assert_eq!(
+            VariableValue::String(String::from("~/.gitignore")).to_path(),
+            Some(home.join(".gitignore"))
+        );
+        assert_eq!(
+            VariableValue::String(String::from("/etc/gitignore")).to_path(),
+            Some(PathBuf::from("/etc/gitignore"))
+        );
+        assert_eq!(VariableValue::Bool(true).to_path(), None);
+    }
+
+    #[rstest]
+    #[case(" plain value  ", "plain value")]
+    #[case("value ; comment", "value")]
+    #[case(r#""  quoted # value "  # comment"#, "  quoted # value ")]
+    #[case(
+        r#"tab\there \"quotes\" back\\slash"#,
+        "tab\there \"quotes\" back\\slash"
+    )]
+    #[case("one \\\n two", "one  two")]
+    fn read_values(#[case] text: &str, #[case] expected: &str) {
+        assert_eq!(read_value(text), RawValue::Value(expected.to_owned()));
+    }
+
+    #[test]
+    fn reject_unclosed_quotes_and_unknown_escapes() {
+        assert_eq!(read_value(r#""open"#), RawValue::Invalid);
+        assert_eq!(read_value(r"\q"), RawValue::Invalid);
+        assert_eq!(read_value(r"continued \"), RawValue::Continued);
+    }
+
     mod in_memory {
         use super::*;
 
@@ -572,6 +943,40 @@ mod tests {
             Ok(())
         }
 
+        #[rstest]
+        fn retrieve_variables_matching_a_pattern(mut config: Config) -> Result<()> {
+            for (remote, url) in [("origin", "ssh://example.com/repo"), ("Fork", "ssh://fork")] {
+                config.set(
+                    &[
+                        String::from("remote"),
+                        remote.to_owned(),
+                        String::from("URL"),
+                    ],
+                    VariableValue::String(url.to_owned()),
+                )?;
+            }
+            config.set(
+                &[String::from("core"), String::from("editor")],
+                VariableValue::String(String::from("ed")),
+            )?;
+
+            assert_eq!(
+                config.get_regexp(&Regex::new(r"^remote\..*\.url$").unwrap()),
+                vec![
+                    (
+                        String::from("remote.origin.url"),
+                        VariableValue::String(String::from("ssh://example.com/repo"))
+                    ),
+                    (
+                        String::from("remote.Fork.url"),
+                        VariableValue::String(String::from("ssh://fork"))
+                    ),
+                ]
+            );
+
+            Ok(())
+        }
+
         #[rstest]
         fn subsections(mut config: Config) -> Result<()> {
             config.set(
@@ -970,6 +1375,79 @@ mod tests {
             Ok(())
         }
 
+        #[rstest]
+        fn quote_values_that_need_it(mut config: Config) -> Result<()> {
+            let key = &[String::from("alias"), String::from("say")];
+            let value = VariableValue::String(String::from(" echo \"hi\" # not a comment"));
+
+            config.set(key, value.clone())?;
+            config.save()?;
+            assert_file(
+                &config,
+                "[alias]\n\tsay = \" echo \\\"hi\\\" # not a comment\"\n",
+            )?;
+
+            let mut config = Config::new(&config.path);
+            config.open()?;
+            assert_eq!(config.get(key), Some(value));
+
+            Ok(())
+        }
+
+        #[test]
+        fn ignore_comments_after_values_and_headings() -> Result<()> {
+            let path = NamedTempFile::new()?.into_temp_path();
+            fs::write(
+                &path,
+                "[core] # editing\n\teditor = ed ; comment\n[remote \"a \\\"b\\\"\"] ; quoted\n\turl = x",
+            )?;
+
+            let mut config = Config::new(&path);
+            config.open()?;
+            assert_eq!(
+                config.get(&[String::from("core"), String::from("editor")]),
+                Some(VariableValue::String(String::from("ed")))
+            );
+            assert_eq!(
+                config.get(&[
+                    String::from("remote"),
+                    String::from("a \"b\""),
+                    String::from("url")
+                ]),
+                Some(VariableValue::String(String::from("x")))
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn read_values_continued_over_several_lines() -> Result<()> {
+            let path = NamedTempFile::new()?.into_temp_path();
+            fs::write(
+                &path,
+                "[alias]\n\tlong = one \\\n\ttwo \\\n\"  three\"\n[core]\n\teditor = ed\n",
+            )?;
+
+            let mut config = Config::new(&path);
+            config.open_for_update()?;
+            assert_eq!(
+                config.get(&[String::from("alias"), String::from("long")]),
+                Some(VariableValue::String(String::from("one  two   three")))
+            );
+
+            config.set(
+                &[String::from("core"), String::from("editor")],
+                VariableValue::String(String::from("vi")),
+            )?;
+            config.save()?;
+            assert_file(
+                &config,
+                "[alias]\n\tlong = one \\\n\ttwo \\\n\"  three\"\n[core]\n\teditor = vi\n",
+            )?;
+
+            Ok(())
+        }
+
         #[rstest]
         fn retain_the_formatting_of_existing_settings(mut config: Config) -> Result<()> {
             config.set(
@@ -1008,4 +1486,100 @@ mod tests {
             Ok(())
         }
     }
+
+    mod includes {
+        use tempfile::TempDir;
+
+        use super::*;
+
+        fn key(name: &str) -> Vec<String> {
+            name.split('.').map(String::from).collect()
+        }
+
+        #[test]
+        fn read_the_variables_of_an_included_file() -> Result<()> {
+            let dir = TempDir::new()?;
+            fs::write(
+                dir.path().join("other"),
+                "[user]\n\tname = Included\n\temail = a@b\n",
+            )?;
+            fs::write(
+                dir.path().join("config"),
+                "[include]\n\tpath = other\n[user]\n\tname = Own\n",
+            )?;
+
+            let mut config = Config::new(&dir.path().join("config"));
+            config.open_for_update()?;
+            assert_eq!(
+                config.get_all(&key("user.name")),
+                vec![
+                    VariableValue::String(String::from("Included")),
+                    VariableValue::String(String::from("Own"))
+                ]
+            );
+            assert_eq!(
+                config.get(&key("user.email")),
+                Some(VariableValue::String(String::from("a@b")))
+            );
+
+            config.set(
+                &key("user.name"),
+                VariableValue::String(String::from("New")),
+            )?;
+            config.save()?;
+            assert_eq!(
+                fs::read_to_string(dir.path().join("config"))?,
+                "[include]\n\tpath = other\n[user]\n\tname = New\n"
+            );
+
+            Ok(())
+        }
+
+        #[test]
+        fn include_a_file_only_for_a_matching_git_dir() -> Result<()> {
+            let dir = TempDir::new()?;
+            fs::write(dir.path().join("work.config"), "[user]\n\tname = Work\n")?;
+            fs::write(
+                dir.path().join("config"),
+                format!(
+                    "[includeIf \"gitdir:{}/work/\"]\n\tpath = work.config\n",
+                    dir.path().display()
+                ),
+            )?;
+
+            let mut config = Config::new(&dir.path().join("config"));
+            config.set_git_dir(&dir.path().join("work/project/.git"));
+            config.open()?;
+            assert_eq!(
+                config.get(&key("user.name")),
+                Some(VariableValue::String(String::from("Work")))
+            );
+
+            let mut config = Config::new(&dir.path().join("config"));
+            config.set_git_dir(&dir.path().join("home/project/.git"));
+            config.open()?;
+            assert_eq!(config.get(&key("user.name")), None);
+
+            Ok(())
+        }
+
+        #[test]
+        fn read_each_file_once_when_includes_form_a_cycle() -> Result<()> {
+            let dir = TempDir::new()?;
+            fs::write(
+                dir.path().join("a"),
+                "[include]\n\tpath = b\n[core]\n\teditor = ed\n",
+            )?;
+            fs::write(dir.path().join("b"), "[include]\n\tpath = a\n")?;
+
+            let mut config = Config::new(&dir.path().join("a"));
+            config.open()?;
+            assert_eq!(
+                config.get_all(&key("core.editor")),
+                vec![VariableValue::String(String::from("ed"))]
+            );
+
+            Ok(())
+        }
+    }
 }
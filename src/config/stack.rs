@@ -3,6 +3,8 @@ use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::rc::Rc;
 
+use regex::Regex;
+
 use crate::config::{Config, VariableValue};
 use crate::errors::Result;
 
@@ -17,6 +19,7 @@ pub enum ConfigFile {
 #[derive(Debug)]
 pub struct Stack {
     configs: HashMap<ConfigFile, Rc<RefCell<Config>>>,
+    git_path: PathBuf,
 }
 
 impl Stack {
@@ -40,7 +43,14 @@ impl Stack {
             ),
         ]);
 
-        Self { configs }
+        for config in configs.values() {
+            config.borrow_mut().set_git_dir(git_path);
+        }
+
+        Self {
+            configs,
+            git_path: git_path.to_owned(),
+        }
     }
 
     pub fn file(&mut self, name: ConfigFile) -> Rc<RefCell<Config>> {
@@ -49,9 +59,11 @@ impl Stack {
             ConfigFile::Global => Rc::clone(&self.configs[&ConfigFile::Global]),
             ConfigFile::System => Rc::clone(&self.configs[&ConfigFile::System]),
             ConfigFile::File(path) => {
+                let mut config = Config::new(&path);
+                config.set_git_dir(&self.git_path);
                 self.configs.insert(
                     ConfigFile::File(path.clone()),
-                    Rc::new(RefCell::new(Config::new(&path))),
+                    Rc::new(RefCell::new(config)),
                 );
                 Rc::clone(&self.configs[&ConfigFile::File(path)])
             }
@@ -71,6 +83,18 @@ impl Stack {
         self.get_all(key).last().map(|val| val.to_owned())
     }
 
+    pub fn get_bool(&self, key: &[String]) -> Option<bool> {
+        self.get(key)?.to_bool()
+    }
+
+    pub fn get_int(&self, key: &[String]) -> Option<i64> {
+        self.get(key)?.to_int()
+    }
+
+    pub fn get_path(&self, key: &[String]) -> Option<PathBuf> {
+        self.get(key)?.to_path()
+    }
+
     pub fn get_all(&self, key: &[String]) -> Vec<VariableValue> {
         [ConfigFile::System, ConfigFile::Global, ConfigFile::Local]
             .iter()
@@ -81,4 +105,15 @@ impl Stack {
             })
             .collect()
     }
+
+    pub fn get_regexp(&self, pattern: &Regex) -> Vec<(String, VariableValue)> {
+        [ConfigFile::System, ConfigFile::Global, ConfigFile::Local]
+            .iter()
+            .flat_map(|name| {
+                let mut config = self.configs[name].borrow_mut();
+                config.open().unwrap();
+                config.get_regexp(pattern)
+            })
+            .collect()
+    }
 }
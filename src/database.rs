@@ -1,16 +1,20 @@
+use std::cell::{Ref, RefCell};
 use std::collections::HashMap;
 use std::fs::OpenOptions;
 use std::io::{Read, Write};
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
 use std::{fs, io};
 
 use flate2::read::ZlibDecoder;
 use flate2::write::ZlibEncoder;
 use flate2::Compression;
 use itertools::Itertools;
+use nix::errno::Errno;
 use uuid::Uuid;
 
 use crate::database::blob::Blob;
+use crate::database::blob_cache::{BlobCache, DEFAULT_BLOB_CACHE_LIMIT};
 use crate::database::commit::Commit;
 use crate::database::entry::Entry;
 use crate::database::object::Object;
@@ -22,24 +26,51 @@ use crate::util::path_to_string;
 
 pub mod author;
 pub mod blob;
+pub mod blob_cache;
 pub mod commit;
 pub mod entry;
 pub mod object;
 pub mod tree;
 pub mod tree_diff;
 
+/// The ID of the tree with no entries. Like Git, the database can load it whether or not it's
+/// been stored, so it can stand in for the parent of a root commit.
+pub const EMPTY_TREE_OID: &str = "4b825dc642cb6eb9a060e54bf8d69288fbee4904";
+/// The length of an abbreviated object ID, unless `core.abbrev` or `--abbrev` say otherwise
+pub const DEFAULT_ABBREV: usize = 7;
+/// Like Git, object IDs are never abbreviated to fewer characters than this
+const MIN_ABBREV: usize = 4;
+
 #[derive(Debug)]
 pub struct Database {
     pathname: PathBuf,
+    blob_cache: RefCell<BlobCache>,
 }
 
 impl Database {
     pub fn new(pathname: PathBuf) -> Self {
-        Database { pathname }
+        Database {
+            pathname,
+            blob_cache: RefCell::new(BlobCache::new(DEFAULT_BLOB_CACHE_LIMIT)),
+        }
+    }
+
+    /// Limit the total size of the blobs kept in memory by `load_blob()` to `limit` bytes.
+    pub fn set_blob_cache_limit(&self, limit: usize) {
+        self.blob_cache.borrow_mut().set_limit(limit);
+    }
+
+    pub fn blob_cache(&self) -> Ref<'_, BlobCache> {
+        self.blob_cache.borrow()
     }
 
     pub fn short_oid(oid: &str) -> String {
-        oid[0..=6].to_string()
+        Self::abbreviate(oid, DEFAULT_ABBREV)
+    }
+
+    /// The first `length` characters of `oid`, or all of them if it's too short.
+    pub fn abbreviate(oid: &str, length: usize) -> String {
+        oid[..length.clamp(MIN_ABBREV, oid.len())].to_string()
     }
 
     pub fn store<T>(&self, object: &T) -> io::Result<()>
@@ -57,9 +88,17 @@ impl Database {
         object.oid()
     }
 
+    /// Whether the object `oid` is stored in the database, checked without reading it.
+    pub fn exists(&self, oid: &str) -> bool {
+        self.object_path(oid).exists()
+    }
+
     /// Load an object ID, returning a `ParsedObject`.
     pub fn load(&self, oid: &str) -> io::Result<ParsedObject> {
         // TODO: Cache this in self.objects
+        if oid == EMPTY_TREE_OID && !self.exists(oid) {
+            return Ok(ParsedObject::Tree(Tree::new(None)));
+        }
         self.read_object(oid)
     }
 
@@ -71,10 +110,19 @@ impl Database {
         }
     }
 
-    /// Load a blob by its object ID, returning a `Blob`.
-    pub fn load_blob(&self, oid: &str) -> io::Result<Blob> {
+    /// Load a blob by its object ID, returning a `Blob`. Blobs are cached, so loading the same
+    /// blob again doesn't read it from disk.
+    pub fn load_blob(&self, oid: &str) -> io::Result<Rc<Blob>> {
+        if let Some(blob) = self.blob_cache.borrow_mut().get(oid) {
+            return Ok(blob);
+        }
+
         match self.load(oid)? {
-            ParsedObject::Blob(blob) => Ok(blob),
+            ParsedObject::Blob(blob) => {
+                let blob = Rc::new(blob);
+                self.blob_cache.borrow_mut().insert(oid, Rc::clone(&blob));
+                Ok(blob)
+            }
             _ => unreachable!(),
         }
     }
@@ -87,13 +135,19 @@ impl Database {
         }
     }
 
+    /// Load the entry at `pathname` from the tree of `oid`, which may name either a commit or a
+    /// tree.
     pub fn load_tree_entry(
         &self,
         oid: &str,
         pathname: Option<&Path>,
     ) -> io::Result<Option<TreeEntry>> {
-        let commit = self.load_commit(oid)?;
-        let root = Entry::new(commit.tree, TREE_MODE);
+        let tree = match self.load(oid)? {
+            ParsedObject::Commit(commit) => commit.tree,
+            ParsedObject::Tree(_) => oid.to_string(),
+            ParsedObject::Blob(_) => unreachable!(),
+        };
+        let root = Entry::new(tree, TREE_MODE);
 
         let mut entry = Some(TreeEntry::Entry(root));
         if pathname.is_none() {
@@ -174,6 +228,74 @@ impl Database {
         Ok(oids)
     }
 
+    /// The number of loose objects, found by scanning the `objects/??/` directories.
+    pub fn count_loose_objects(&self) -> io::Result<usize> {
+        Ok(self.loose_objects()?.len())
+    }
+
+    /// The IDs of the loose objects in the `objects/??/` directories.
+    pub fn loose_objects(&self) -> io::Result<Vec<String>> {
+        let mut oids = vec![];
+
+        for dir in Self::read_dir_if_exists(&self.pathname)? {
+            let dir = dir?;
+            let name = dir.file_name();
+            let is_fanout = name.len() == 2
+                && name
+                    .to_str()
+                    .is_some_and(|name| name.chars().all(|c| c.is_ascii_hexdigit()));
+            if !is_fanout || !dir.file_type()?.is_dir() {
+                continue;
+            }
+
+            for file in fs::read_dir(dir.path())? {
+                let file = file?;
+                // Skip the temporary files objects are written to before they're renamed
+                if file.file_name().len() == 38 {
+                    oids.push(format!(
+                        "{}{}",
+                        name.to_string_lossy(),
+                        file.file_name().to_string_lossy()
+                    ));
+                }
+            }
+        }
+
+        Ok(oids)
+    }
+
+    /// Delete the loose object `oid`, and its `objects/??/` directory if that leaves it empty.
+    pub fn remove_loose_object(&self, oid: &str) -> io::Result<()> {
+        let object_path = self.object_path(oid);
+        fs::remove_file(&object_path)?;
+
+        match fs::remove_dir(object_path.parent().unwrap()) {
+            Err(err) if err.raw_os_error() == Some(Errno::ENOTEMPTY as i32) => Ok(()),
+            result => result,
+        }
+    }
+
+    /// The number of packfiles in `objects/pack/`.
+    pub fn count_packs(&self) -> io::Result<usize> {
+        let mut count = 0;
+
+        for file in Self::read_dir_if_exists(&self.pathname.join("pack"))? {
+            if file?.path().extension().is_some_and(|ext| ext == "pack") {
+                count += 1;
+            }
+        }
+
+        Ok(count)
+    }
+
+    fn read_dir_if_exists(path: &Path) -> io::Result<Vec<io::Result<fs::DirEntry>>> {
+        match fs::read_dir(path) {
+            Ok(entries) => Ok(entries.collect()),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(vec![]),
+            Err(err) => Err(err),
+        }
+    }
+
     fn read_object(&self, oid: &str) -> io::Result<ParsedObject> {
         let compressed_data = fs::read(self.object_path(oid))?;
         let mut data = vec![];
@@ -194,7 +316,7 @@ impl Database {
         match object_type {
             "blob" => Ok(Blob::parse(rest, oid)),
             "tree" => Ok(Tree::parse(rest)),
-            "commit" => Ok(Commit::parse(rest, oid)),
+            "commit" => Commit::parse(rest, oid),
             _ => unreachable!(),
         }
     }
@@ -204,12 +326,12 @@ impl Database {
     }
 
     fn write_object(&self, oid: String, content: Vec<u8>) -> io::Result<()> {
-        let object_path = self.object_path(&oid);
-
-        if object_path.exists() {
+        if self.exists(&oid) {
             return Ok(());
         }
 
+        let object_path = self.object_path(&oid);
+
         let dirname = object_path.parent().unwrap();
         let temp_path = dirname.join(Uuid::new_v4().simple().to_string());
 
@@ -236,12 +358,7 @@ impl Database {
 }
 
 impl Differ for Database {
-    fn tree_diff(
-        &self,
-        a: Option<&str>,
-        b: Option<&str>,
-        filter: Option<&PathFilter>,
-    ) -> Result<TreeDiffChanges> {
+    fn tree_diff(&self, a: &str, b: &str, filter: Option<&PathFilter>) -> Result<TreeDiffChanges> {
         let empty_filter = PathFilter::new(None, None);
 
         let filter = if let Some(filter) = filter {
@@ -250,12 +367,13 @@ impl Differ for Database {
             &empty_filter
         };
         let mut diff = TreeDiff::new(self);
-        diff.compare_oids(a, b, filter)?;
+        diff.compare_oids(Some(a), Some(b), filter)?;
         Ok(diff.changes)
     }
 }
 
 #[derive(Debug)]
+#[allow(clippy::large_enum_variant)]
 pub enum ParsedObject {
     Blob(Blob),
     Commit(Commit),
@@ -282,18 +400,57 @@ impl ParsedObject {
 
 #[cfg(test)]
 mod tests {
+    use tempfile::TempDir;
+
     use super::*;
 
+    #[test]
+    fn check_whether_an_object_is_stored() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let database = Database::new(tmp_dir.path().join("objects"));
+
+        let blob = Blob::new(b"hello".to_vec());
+        assert!(!database.exists(&blob.oid()));
+
+        database.store(&blob)?;
+        assert!(database.exists(&blob.oid()));
+        assert!(!database.exists(&Blob::new(b"other".to_vec()).oid()));
+
+        Ok(())
+    }
+
+    #[test]
+    fn count_loose_objects_and_packs() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let database = Database::new(tmp_dir.path().join("objects"));
+        assert_eq!(database.count_loose_objects()?, 0);
+        assert_eq!(database.count_packs()?, 0);
+
+        for data in ["one", "two", "three"] {
+            database.store(&Blob::new(data.as_bytes().to_vec()))?;
+        }
+        fs::create_dir_all(tmp_dir.path().join("objects").join("info"))?;
+        fs::create_dir_all(tmp_dir.path().join("objects").join("pack"))?;
+        for name in ["pack-1.pack", "pack-1.idx"] {
+            fs::write(tmp_dir.path().join("objects").join("pack").join(name), "")?;
+        }
+
+        assert_eq!(database.count_loose_objects()?, 3);
+        assert_eq!(database.count_packs()?, 1);
+
+        Ok(())
+    }
+
     mod tree_diff {
         use std::collections::{BTreeMap, HashMap};
         use std::path::PathBuf;
 
         use indexmap::IndexMap;
         use rstest::{fixture, rstest};
-        use tempfile::TempDir;
 
         use super::*;
-        use crate::database::tree::TreeEntry;
+        use crate::database::tree::{TreeEntry, GITLINK_MODE};
+        use crate::database::tree_diff::detect_renames;
 
         fn store_tree(database: &Database, contents: HashMap<&str, &str>) -> String {
             let mut entries = BTreeMap::new();
@@ -340,10 +497,7 @@ mod tests {
                 ),
             )]);
 
-            assert_eq!(
-                database.tree_diff(Some(&tree_a), Some(&tree_b), None)?,
-                expected
-            );
+            assert_eq!(database.tree_diff(&tree_a, &tree_b, None)?, expected);
 
             Ok(())
         }
@@ -367,10 +521,7 @@ mod tests {
                 ),
             )]);
 
-            assert_eq!(
-                database.tree_diff(Some(&tree_a), Some(&tree_b), None)?,
-                expected
-            );
+            assert_eq!(database.tree_diff(&tree_a, &tree_b, None)?, expected);
 
             Ok(())
         }
@@ -394,10 +545,7 @@ mod tests {
                 ),
             )]);
 
-            assert_eq!(
-                database.tree_diff(Some(&tree_a), Some(&tree_b), None)?,
-                expected
-            );
+            assert_eq!(database.tree_diff(&tree_a, &tree_b, None)?, expected);
 
             Ok(())
         }
@@ -425,10 +573,7 @@ mod tests {
                 ),
             )]);
 
-            assert_eq!(
-                database.tree_diff(Some(&tree_a), Some(&tree_b), None)?,
-                expected
-            );
+            assert_eq!(database.tree_diff(&tree_a, &tree_b, None)?, expected);
 
             Ok(())
         }
@@ -456,9 +601,59 @@ mod tests {
                 ),
             )]);
 
+            assert_eq!(database.tree_diff(&tree_a, &tree_b, None)?, expected);
+
+            Ok(())
+        }
+
+        #[rstest]
+        fn report_a_changed_gitlink_without_loading_it(database: Database) -> Result<()> {
+            let gitlink = |oid: &str| Entry::new(oid.to_owned(), GITLINK_MODE);
+            let store_gitlinks = |gitlinks: Vec<(&str, &str)>| {
+                let entries = gitlinks
+                    .into_iter()
+                    .map(|(path, oid)| (PathBuf::from(path), TreeEntry::Entry(gitlink(oid))))
+                    .collect();
+                let tree = Tree::new(Some(entries));
+                database.store(&tree).unwrap();
+                tree.oid()
+            };
+
+            let old_oid = "8ab686eafeb1f44702738c8b0f24f2567c36da6d";
+            let new_oid = "e2f1a7b3c4d5e6f708192a3b4c5d6e7f80919a0b";
+            let tree_a = store_gitlinks(vec![("lib", old_oid), ("old", old_oid)]);
+            let tree_b = store_gitlinks(vec![("lib", new_oid), ("new", old_oid)]);
+
+            let changes = database.tree_diff(&tree_a, &tree_b, None)?;
+            assert_eq!(
+                changes,
+                IndexMap::from([
+                    (
+                        PathBuf::from("lib"),
+                        (Some(gitlink(old_oid)), Some(gitlink(new_oid)))
+                    ),
+                    (PathBuf::from("old"), (Some(gitlink(old_oid)), None)),
+                    (PathBuf::from("new"), (None, Some(gitlink(old_oid)))),
+                ])
+            );
+            assert!(detect_renames(&database, &changes)?.is_empty());
+
+            Ok(())
+        }
+
+        #[rstest]
+        fn report_every_file_added_since_the_empty_tree(database: Database) -> Result<()> {
+            assert_eq!(Tree::new(None).oid(), EMPTY_TREE_OID);
+
+            let tree = store_tree(&database, HashMap::from([("alice.txt", "alice")]));
+            let blob = Blob::new(b"alice".to_vec());
+
             assert_eq!(
-                database.tree_diff(Some(&tree_a), Some(&tree_b), None)?,
-                expected
+                database.tree_diff(EMPTY_TREE_OID, &tree, None)?,
+                IndexMap::from([(
+                    PathBuf::from("alice.txt"),
+                    (None, Some(Entry::new(blob.oid(), 0o100644)))
+                )])
             );
 
             Ok(())
@@ -1,10 +1,16 @@
 use std::fmt;
 
-use chrono::{DateTime, FixedOffset};
+use chrono::{DateTime, Duration, FixedOffset, Local, NaiveDate, NaiveDateTime, TimeZone};
 use itertools::Itertools;
+use once_cell::sync::Lazy;
+use regex::Regex;
 
 const TIME_FORMAT: &str = "%s %z";
 
+static RELATIVE_DATE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"^(\d+)[ .]+(second|minute|hour|day|week|month|year)s?[ .]+ago$").unwrap()
+});
+
 #[derive(Debug, Clone)]
 pub struct Author {
     pub name: String,
@@ -39,6 +45,59 @@ impl Author {
     }
 }
 
+/// Parse a date given to `--date`. Git's internal `<timestamp> <offset>` format, RFC 2822, ISO
+/// 8601 and relative dates like `2 days ago` (measured from `now`) are supported.
+pub fn parse_date(input: &str, now: DateTime<FixedOffset>) -> Option<DateTime<FixedOffset>> {
+    let input = input.trim();
+
+    if input == "now" {
+        return Some(now);
+    } else if let Some(r#match) = RELATIVE_DATE.captures(input) {
+        let count: i64 = r#match[1].parse().ok()?;
+        let duration = match &r#match[2] {
+            "second" => Duration::seconds(count),
+            "minute" => Duration::minutes(count),
+            "hour" => Duration::hours(count),
+            "day" => Duration::days(count),
+            "week" => Duration::weeks(count),
+            "month" => Duration::days(count * 30),
+            "year" => Duration::days(count * 365),
+            _ => unreachable!(),
+        };
+        return Some(now - duration);
+    }
+
+    let input = input.strip_prefix('@').unwrap_or(input);
+    if let Ok(time) = DateTime::parse_from_str(input, TIME_FORMAT) {
+        return Some(time);
+    }
+
+    if let Ok(time) = DateTime::parse_from_rfc2822(input) {
+        return Some(time);
+    }
+    if let Ok(time) = DateTime::parse_from_rfc3339(input) {
+        return Some(time);
+    }
+    for format in ["%Y-%m-%d %H:%M:%S %z", "%Y-%m-%dT%H:%M:%S%z"] {
+        if let Ok(time) = DateTime::parse_from_str(input, format) {
+            return Some(time);
+        }
+    }
+
+    // Dates without an offset are in the local timezone
+    let naive = ["%Y-%m-%d %H:%M:%S", "%Y-%m-%dT%H:%M:%S"]
+        .iter()
+        .find_map(|format| NaiveDateTime::parse_from_str(input, format).ok())
+        .or_else(|| {
+            NaiveDate::parse_from_str(input, "%Y-%m-%d")
+                .ok()
+                .and_then(|date| date.and_hms_opt(0, 0, 0))
+        })?;
+    let local = Local.from_local_datetime(&naive).single()?;
+
+    Some(local.with_timezone(local.offset()))
+}
+
 impl fmt::Display for Author {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let timestamp = self.time.format(TIME_FORMAT);
@@ -60,4 +119,43 @@ mod tests {
 
         assert_eq!(author.to_string(), display);
     }
+
+    #[test]
+    fn parse_absolute_dates() {
+        let now = DateTime::parse_from_rfc3339("2021-06-28T17:41:12+10:00").unwrap();
+        let expected = DateTime::parse_from_rfc3339("2020-01-01T00:00:00Z").unwrap();
+
+        for input in [
+            "2020-01-01T00:00:00Z",
+            "2020-01-01 00:00:00 +0000",
+            "Wed, 1 Jan 2020 00:00:00 +0000",
+            "1577836800 +0000",
+            "@1577836800 +0000",
+        ] {
+            assert_eq!(parse_date(input, now), Some(expected), "{}", input);
+        }
+    }
+
+    #[test]
+    fn parse_relative_dates() {
+        let now = DateTime::parse_from_rfc3339("2021-06-28T17:41:12+10:00").unwrap();
+
+        assert_eq!(parse_date("now", now), Some(now));
+        assert_eq!(
+            parse_date("2 days ago", now),
+            Some(DateTime::parse_from_rfc3339("2021-06-26T17:41:12+10:00").unwrap())
+        );
+        assert_eq!(
+            parse_date("1 hour ago", now),
+            Some(DateTime::parse_from_rfc3339("2021-06-28T16:41:12+10:00").unwrap())
+        );
+    }
+
+    #[test]
+    fn reject_invalid_dates() {
+        let now = DateTime::parse_from_rfc3339("2021-06-28T17:41:12+10:00").unwrap();
+
+        assert_eq!(parse_date("not a date", now), None);
+        assert_eq!(parse_date("2020-13-45", now), None);
+    }
 }
@@ -4,7 +4,7 @@ use sha1::{Digest, Sha1};
 use crate::database::object::Object;
 use crate::database::ParsedObject;
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct Blob {
     pub data: Vec<u8>,
     oid: Option<String>,
@@ -0,0 +1,161 @@
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+
+use crate::database::blob::Blob;
+
+/// The default `core.blobCacheLimit`, in bytes.
+pub const DEFAULT_BLOB_CACHE_LIMIT: usize = 32 * 1024 * 1024;
+
+/// A least-recently-used cache of blobs, bounded by the total size of their contents, so blobs
+/// loaded repeatedly during a single command, like the common ancestor's blobs in a merge, are
+/// only read and inflated once.
+#[derive(Debug)]
+pub struct BlobCache {
+    limit: usize,
+    size: usize,
+    /// Each cached blob, with the tick it was last used at
+    blobs: HashMap<String, (Rc<Blob>, u64)>,
+    /// Object IDs by the tick they were last used at, from least to most recently used
+    recent: BTreeMap<u64, String>,
+    tick: u64,
+    hits: usize,
+}
+
+impl BlobCache {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            size: 0,
+            blobs: HashMap::new(),
+            recent: BTreeMap::new(),
+            tick: 0,
+            hits: 0,
+        }
+    }
+
+    pub fn get(&mut self, oid: &str) -> Option<Rc<Blob>> {
+        let tick = self.next_tick();
+        let (blob, used) = self.blobs.get_mut(oid)?;
+        let oid = self.recent.remove(used).unwrap();
+        self.recent.insert(tick, oid);
+        *used = tick;
+        self.hits += 1;
+
+        Some(Rc::clone(blob))
+    }
+
+    /// Add `blob` to the cache, evicting the least recently used blobs to make room for it. Blobs
+    /// larger than the whole cache aren't cached.
+    pub fn insert(&mut self, oid: &str, blob: Rc<Blob>) {
+        if blob.data.len() > self.limit || self.blobs.contains_key(oid) {
+            return;
+        }
+
+        let tick = self.next_tick();
+        self.size += blob.data.len();
+        self.blobs.insert(oid.to_owned(), (blob, tick));
+        self.recent.insert(tick, oid.to_owned());
+
+        self.evict();
+    }
+
+    pub fn set_limit(&mut self, limit: usize) {
+        self.limit = limit;
+        self.evict();
+    }
+
+    pub fn contains(&self, oid: &str) -> bool {
+        self.blobs.contains_key(oid)
+    }
+
+    /// The number of blobs that were loaded from the cache.
+    pub fn hits(&self) -> usize {
+        self.hits
+    }
+
+    /// The total size of the cached blobs, in bytes.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    fn next_tick(&mut self) -> u64 {
+        self.tick += 1;
+        self.tick
+    }
+
+    fn evict(&mut self) {
+        while self.size > self.limit {
+            let (_, oid) = match self.recent.pop_first() {
+                Some(entry) => entry,
+                None => break,
+            };
+            if let Some((blob, _)) = self.blobs.remove(&oid) {
+                self.size -= blob.data.len();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn blob(data: &str) -> Rc<Blob> {
+        Rc::new(Blob::new(data.as_bytes().to_vec()))
+    }
+
+    #[test]
+    fn return_cached_blobs() {
+        let mut cache = BlobCache::new(10);
+        cache.insert("a", blob("abc"));
+
+        assert_eq!(cache.get("a").unwrap().data, b"abc");
+        assert!(cache.get("b").is_none());
+        assert_eq!(cache.hits(), 1);
+    }
+
+    #[test]
+    fn share_cached_blobs_without_copying_them() {
+        let mut cache = BlobCache::new(10);
+        let inserted = blob("abc");
+        cache.insert("a", Rc::clone(&inserted));
+
+        assert!(Rc::ptr_eq(&cache.get("a").unwrap(), &inserted));
+    }
+
+    #[test]
+    fn evict_the_least_recently_used_blobs_when_over_the_limit() {
+        let mut cache = BlobCache::new(10);
+        cache.insert("a", blob("aaaa"));
+        cache.insert("b", blob("bbbb"));
+        cache.get("a");
+        cache.insert("c", blob("cccc"));
+
+        assert!(cache.contains("a"));
+        assert!(!cache.contains("b"));
+        assert!(cache.contains("c"));
+        assert_eq!(cache.size(), 8);
+    }
+
+    #[test]
+    fn skip_blobs_larger_than_the_limit() {
+        let mut cache = BlobCache::new(4);
+        cache.insert("a", blob("aaaa"));
+        cache.insert("b", blob("bbbbb"));
+
+        assert!(cache.contains("a"));
+        assert!(!cache.contains("b"));
+    }
+
+    #[test]
+    fn evict_blobs_when_the_limit_is_lowered() {
+        let mut cache = BlobCache::new(10);
+        cache.insert("a", blob("aaaa"));
+        cache.insert("b", blob("bbbb"));
+        cache.set_limit(4);
+
+        assert!(!cache.contains("a"));
+        assert!(cache.contains("b"));
+        assert_eq!(cache.size(), 4);
+    }
+}
@@ -1,12 +1,13 @@
-use std::collections::HashMap;
+use std::io;
 
 use chrono::{DateTime, FixedOffset};
+use encoding_rs::{Encoding, UTF_8};
 use sha1::digest::Update;
 use sha1::{Digest, Sha1};
 
 use crate::database::author::Author;
 use crate::database::object::Object;
-use crate::database::ParsedObject;
+use crate::database::{ParsedObject, EMPTY_TREE_OID};
 
 #[derive(Debug, Clone)]
 pub struct Commit {
@@ -14,7 +15,14 @@ pub struct Commit {
     pub tree: String,
     pub author: Author,
     pub committer: Author,
+    /// Headers after `committer`, like `encoding`, `mergetag` and `gpgsig`, in the order they're
+    /// stored so a parsed commit is written back with the same oid. Values spanning several
+    /// lines are stored without the leading space of their continuation lines.
+    pub extra_headers: Vec<(String, String)>,
+    /// The message, decoded from the commit's `encoding`
     pub message: String,
+    /// The message exactly as it was stored, which is written back unless `message` is changed
+    raw_message: Option<Vec<u8>>,
     oid: Option<String>,
 }
 
@@ -31,45 +39,98 @@ impl Commit {
             tree,
             author,
             committer,
+            extra_headers: vec![],
             message,
+            raw_message: None,
             oid: None,
         }
     }
 
-    pub fn parse(data: &[u8], oid: &str) -> ParsedObject {
-        let mut data = std::str::from_utf8(data).expect("Invalid UTF-8");
-
-        let mut headers: HashMap<&str, Vec<&str>> = HashMap::new();
-
-        loop {
-            let (line, rest) = data.split_once('\n').unwrap();
-            data = rest;
-            let line = line.trim();
-
-            if line.is_empty() {
-                let parents = headers
-                    .entry("parent")
-                    .or_insert_with(Vec::new)
-                    .iter()
-                    .map(|parent| parent.to_string())
-                    .collect();
-                break ParsedObject::Commit(Commit {
-                    parents,
-                    tree: headers["tree"][0].to_string(),
-                    author: Author::parse(headers["author"][0]),
-                    committer: Author::parse(headers["committer"][0]),
-                    message: data.to_string(),
-                    oid: Some(oid.to_string()),
-                });
+    pub fn parse(data: &[u8], oid: &str) -> io::Result<ParsedObject> {
+        // The first blank line ends the headers, and the message is everything after it
+        let (header_bytes, raw_message) = match data.windows(2).position(|pair| pair == b"\n\n") {
+            Some(end) => (&data[..end], &data[end + 2..]),
+            None => (data, &[][..]),
+        };
+        let encoding = encoding_for(
+            header_bytes
+                .split(|byte| *byte == b'\n')
+                .find_map(|line| line.strip_prefix(b"encoding ")),
+        );
+
+        // Unlike the message, headers are written back from their decoded form, so they must
+        // decode exactly
+        let header_text = encoding
+            .decode_without_bom_handling_and_without_replacement(header_bytes)
+            .ok_or_else(|| corrupt(oid, &format!("headers are not valid {}", encoding.name())))?;
+
+        let mut headers: Vec<(String, String)> = vec![];
+
+        for line in header_text.lines() {
+            // Multi-line headers like `gpgsig` continue on lines starting with a space
+            if let (Some(continuation), Some((_, value))) =
+                (line.strip_prefix(' '), headers.last_mut())
+            {
+                value.push('\n');
+                value.push_str(continuation);
+                continue;
             }
 
-            let (key, value) = line.split_once(' ').unwrap();
-            headers.entry(key).or_insert_with(Vec::new).push(value);
+            let (key, value) = line
+                .split_once(' ')
+                .ok_or_else(|| corrupt(oid, &format!("malformed header '{}'", line)))?;
+            headers.push((key.to_string(), value.to_string()));
+        }
+
+        let mut tree = None;
+        let mut parents = vec![];
+        let mut author = None;
+        let mut committer = None;
+        let mut extra_headers = vec![];
+        for (key, value) in headers {
+            match key.as_str() {
+                "tree" => tree = Some(value),
+                "parent" => parents.push(value),
+                "author" => author = Some(Author::parse(&value)),
+                "committer" => committer = Some(Author::parse(&value)),
+                _ => extra_headers.push((key, value)),
+            }
         }
+
+        Ok(ParsedObject::Commit(Commit {
+            parents,
+            tree: tree.ok_or_else(|| corrupt(oid, "missing tree"))?,
+            author: author.ok_or_else(|| corrupt(oid, "missing author"))?,
+            committer: committer.ok_or_else(|| corrupt(oid, "missing committer"))?,
+            extra_headers,
+            message: decode(raw_message, encoding),
+            raw_message: Some(raw_message.to_vec()),
+            oid: Some(oid.to_string()),
+        }))
     }
 
+    /// The value of the first extra header called `name`.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.extra_headers
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// The ASCII-armored signature of a commit signed with `git commit -S`.
+    pub fn gpgsig(&self) -> Option<&str> {
+        self.header("gpgsig")
+    }
+
+    /// The first line of the message with any text, trimmed, or an empty string if the message
+    /// is blank.
     pub fn title_line(&self) -> String {
-        self.message.lines().next().unwrap().to_string()
+        self.message
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty())
+            .unwrap_or("")
+            .to_string()
     }
 
     pub fn date(&self) -> DateTime<FixedOffset> {
@@ -80,8 +141,22 @@ impl Commit {
         self.parents.len() > 1
     }
 
-    pub fn parent(&self) -> Option<String> {
-        self.parents.first().map(|parent| parent.to_owned())
+    /// The `n`th parent, counting from 1 like `<rev>^<n>` and `cherry-pick -m <n>`, or `None` if
+    /// the commit doesn't have that many parents.
+    pub fn parent(&self, n: usize) -> Option<String> {
+        n.checked_sub(1)
+            .and_then(|i| self.parents.get(i))
+            .map(|parent| parent.to_owned())
+    }
+
+    pub fn first_parent(&self) -> Option<String> {
+        self.parent(1)
+    }
+
+    /// The first parent to diff the commit against, or the empty tree for a root commit.
+    pub fn first_parent_or_empty_tree(&self) -> String {
+        self.first_parent()
+            .unwrap_or_else(|| EMPTY_TREE_OID.to_owned())
     }
 }
 
@@ -106,13 +181,273 @@ impl Object for Commit {
         for parent in &self.parents {
             lines.push(format!("parent {}", parent));
         }
-        lines.append(&mut vec![
-            format!("author {}", &self.author),
-            format!("committer {}", &self.committer),
-            "".to_string(),
-            self.message.clone(),
-        ]);
-
-        lines.join("\n").into_bytes()
+        lines.push(format!("author {}", &self.author));
+        lines.push(format!("committer {}", &self.committer));
+        for (key, value) in &self.extra_headers {
+            lines.push(format!("{} {}", key, value.replace('\n', "\n ")));
+        }
+
+        let encoding = encoding_for(self.header("encoding").map(str::as_bytes));
+        let mut bytes = encode(&lines.join("\n"), encoding);
+        bytes.extend_from_slice(b"\n\n");
+        match &self.raw_message {
+            Some(raw_message) if decode(raw_message, encoding) == self.message => {
+                bytes.extend_from_slice(raw_message)
+            }
+            _ => bytes.extend(encode(&self.message, encoding)),
+        }
+
+        bytes
+    }
+}
+
+fn corrupt(oid: &str, reason: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        format!("corrupt commit {}: {}", oid, reason),
+    )
+}
+
+/// The encoding named by a commit's `encoding` header. Like Git, commits without one, or with
+/// one that isn't recognized, are read as UTF-8.
+fn encoding_for(label: Option<&[u8]>) -> &'static Encoding {
+    label.and_then(Encoding::for_label).unwrap_or(UTF_8)
+}
+
+/// Decode a commit message stored in `encoding`, replacing any malformed sequences.
+fn decode(bytes: &[u8], encoding: &'static Encoding) -> String {
+    encoding.decode_without_bom_handling(bytes).0.into_owned()
+}
+
+/// The reverse of `decode()`, falling back to UTF-8 for text that `encoding` can't represent.
+fn encode(text: &str, encoding: &'static Encoding) -> Vec<u8> {
+    match encoding.encode(text) {
+        (bytes, _, false) => bytes.into_owned(),
+        (_, _, true) => text.as_bytes().to_vec(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SIGNED_COMMIT: &str = "\
+tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904
+parent 1111111111111111111111111111111111111111
+author A. U. Thor <author@example.com> 1577836800 +0000
+committer A. U. Thor <author@example.com> 1577836800 +0000
+gpgsig -----BEGIN PGP SIGNATURE-----
+ 
+ iQEzBAABCAAdFiEEabcdefghijklmnopqrstuvwxyz0123456789
+ =abcd
+ -----END PGP SIGNATURE-----
+
+Signed commit
+
+With a body
+";
+
+    fn commit_with_parents(parents: &[&str]) -> Commit {
+        let author = Author::parse("A. U. Thor <author@example.com> 1577836800 +0000");
+
+        Commit::new(
+            parents.iter().map(|parent| parent.to_string()).collect(),
+            String::from("tree"),
+            author.clone(),
+            author,
+            String::from("message"),
+        )
+    }
+
+    fn commit_with_message(message: &str) -> Commit {
+        let mut commit = commit_with_parents(&[]);
+        commit.message = message.to_owned();
+
+        commit
+    }
+
+    #[test]
+    fn a_root_commit_has_no_parents() {
+        let commit = commit_with_parents(&[]);
+
+        assert!(!commit.is_merge());
+        assert_eq!(commit.first_parent(), None);
+        assert_eq!(commit.parent(1), None);
+    }
+
+    #[test]
+    fn a_normal_commit_has_one_parent() {
+        let commit = commit_with_parents(&["a"]);
+
+        assert!(!commit.is_merge());
+        assert_eq!(commit.first_parent(), Some(String::from("a")));
+        assert_eq!(commit.parent(0), None);
+        assert_eq!(commit.parent(2), None);
+    }
+
+    #[test]
+    fn a_merge_has_several_parents() {
+        let commit = commit_with_parents(&["a", "b"]);
+
+        assert!(commit.is_merge());
+        assert_eq!(commit.first_parent(), Some(String::from("a")));
+        assert_eq!(commit.parent(2), Some(String::from("b")));
+        assert_eq!(commit.parent(3), None);
+    }
+
+    #[test]
+    fn the_title_of_an_empty_message_is_empty() {
+        assert_eq!(commit_with_message("").title_line(), "");
+        assert_eq!(commit_with_message(" \n\n").title_line(), "");
+    }
+
+    #[test]
+    fn skip_leading_blank_lines_in_the_title() {
+        assert_eq!(
+            commit_with_message("\n  \n  Title  \n\nBody\n").title_line(),
+            "Title"
+        );
+    }
+
+    #[test]
+    fn the_title_of_a_message_without_a_newline_is_the_whole_message() {
+        assert_eq!(commit_with_message("Title").title_line(), "Title");
+    }
+
+    #[test]
+    fn parse_a_signed_commit() {
+        let commit = match Commit::parse(SIGNED_COMMIT.as_bytes(), "oid").unwrap() {
+            ParsedObject::Commit(commit) => commit,
+            _ => unreachable!(),
+        };
+
+        assert_eq!(commit.message, "Signed commit\n\nWith a body\n");
+        assert_eq!(commit.author.name, "A. U. Thor");
+        assert_eq!(
+            commit.gpgsig(),
+            Some(
+                "-----BEGIN PGP SIGNATURE-----\n\niQEzBAABCAAdFiEEabcdefghijklmnopqrstuvwxyz0123456789\n=abcd\n-----END PGP SIGNATURE-----"
+            )
+        );
+        assert_eq!(commit.bytes(), SIGNED_COMMIT.as_bytes());
+    }
+
+    #[test]
+    fn keep_the_oid_of_a_commit_with_unknown_headers() {
+        let data = "\
+tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904
+author A. U. Thor <author@example.com> 1577836800 +0000
+committer A. U. Thor <author@example.com> 1577836800 +0000
+encoding ISO-8859-1
+x-custom first
+x-custom second
+
+Message
+";
+        let oid = format!(
+            "{:x}",
+            Sha1::new()
+                .chain(format!("commit {}\0{}", data.len(), data))
+                .finalize()
+        );
+
+        let commit = match Commit::parse(data.as_bytes(), &oid).unwrap() {
+            ParsedObject::Commit(commit) => commit,
+            _ => unreachable!(),
+        };
+        assert_eq!(commit.header("encoding"), Some("ISO-8859-1"));
+
+        let mut rewritten = Commit::new(
+            commit.parents.clone(),
+            commit.tree.clone(),
+            commit.author.clone(),
+            commit.committer.clone(),
+            commit.message.clone(),
+        );
+        rewritten.extra_headers = commit.extra_headers.clone();
+        assert_eq!(rewritten.oid(), oid);
+    }
+
+    #[test]
+    fn preserve_the_bytes_of_a_latin1_message() {
+        let mut data = b"\
+tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904
+author A. U. Thor <author@example.com> 1577836800 +0000
+committer A. U. Thor <author@example.com> 1577836800 +0000
+encoding ISO-8859-1
+
+"
+        .to_vec();
+        data.extend_from_slice(b"Caf\xe9\n");
+
+        let commit = match Commit::parse(&data, "oid").unwrap() {
+            ParsedObject::Commit(commit) => commit,
+            _ => unreachable!(),
+        };
+        assert_eq!(commit.message, "Caf\u{e9}\n");
+        assert_eq!(commit.bytes(), data);
+
+        let mut rewritten = commit.clone();
+        rewritten.message = String::from("Caf\u{e9} cr\u{e8}me\n");
+        assert!(rewritten.bytes().ends_with(b"\n\nCaf\xe9 cr\xe8me\n"));
+    }
+
+    #[test]
+    fn decode_a_message_in_the_declared_encoding() {
+        let mut data = b"\
+tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904
+author A. U. Thor <author@example.com> 1577836800 +0000
+committer A. U. Thor <author@example.com> 1577836800 +0000
+encoding KOI8-R
+
+"
+        .to_vec();
+        data.extend_from_slice(b"\xf0\xd2\xc9\xd7\xc5\xd4\n");
+
+        let commit = match Commit::parse(&data, "oid").unwrap() {
+            ParsedObject::Commit(commit) => commit,
+            _ => unreachable!(),
+        };
+        assert_eq!(
+            commit.message,
+            "\u{41f}\u{440}\u{438}\u{432}\u{435}\u{442}\n"
+        );
+        assert_eq!(commit.bytes(), data);
+    }
+
+    #[test]
+    fn fail_to_parse_a_malformed_header() {
+        let data = "\
+tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904
+author A. U. Thor <author@example.com> 1577836800 +0000
+committer A. U. Thor <author@example.com> 1577836800 +0000
+malformed
+
+Message
+";
+
+        let err = Commit::parse(data.as_bytes(), "oid").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        assert_eq!(
+            err.to_string(),
+            "corrupt commit oid: malformed header 'malformed'"
+        );
+    }
+
+    #[test]
+    fn fail_to_parse_headers_that_are_not_valid_in_the_encoding() {
+        let data = b"\
+tree 4b825dc642cb6eb9a060e54bf8d69288fbee4904
+author A. U. Th\xf6r <author@example.com> 1577836800 +0000
+committer A. U. Thor <author@example.com> 1577836800 +0000
+
+Message
+";
+
+        let err = Commit::parse(data, "oid").unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "corrupt commit oid: headers are not valid UTF-8"
+        );
     }
 }
@@ -1,3 +1,4 @@
+use crate::database::tree::GITLINK_MODE;
 use crate::index;
 use crate::util::is_executable;
 
@@ -19,6 +20,10 @@ impl Entry {
             self.mode
         }
     }
+
+    pub fn is_gitlink(&self) -> bool {
+        self.mode == GITLINK_MODE
+    }
 }
 
 impl From<&index::Entry> for Entry {
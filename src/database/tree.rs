@@ -10,6 +10,9 @@ use crate::index::Entry as IndexEntry;
 use crate::util::path_to_string;
 
 pub const TREE_MODE: u32 = 0o40000;
+/// The mode of a gitlink, an entry pointing at a commit in a submodule rather than at an object in
+/// this repository.
+pub const GITLINK_MODE: u32 = 0o160000;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Tree {
@@ -43,6 +46,10 @@ impl TreeEntry {
             TreeEntry::Tree(_) => true,
         }
     }
+
+    pub fn is_gitlink(&self) -> bool {
+        self.mode() == GITLINK_MODE
+    }
 }
 
 impl Tree {
@@ -194,4 +201,25 @@ mod tests {
             "100644 test.txt\x0040000 test\x00100644 test:txt\x00"
         );
     }
+
+    #[test]
+    fn round_trip_a_gitlink_entry() {
+        let commit_oid = "8ab686eafeb1f44702738c8b0f24f2567c36da6d";
+        let mut data = b"100644 README\0".to_vec();
+        data.extend(hex::decode("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391").unwrap());
+        data.extend(b"160000 vendor\0");
+        data.extend(hex::decode(commit_oid).unwrap());
+
+        let tree = match Tree::parse(&data) {
+            ParsedObject::Tree(tree) => tree,
+            _ => unreachable!(),
+        };
+        let entry = &tree.entries[&PathBuf::from("vendor")];
+
+        assert_eq!(entry.mode(), GITLINK_MODE);
+        assert_eq!(entry.oid(), commit_oid);
+        assert!(entry.is_gitlink());
+        assert!(!entry.is_tree());
+        assert_eq!(tree.bytes(), data);
+    }
 }
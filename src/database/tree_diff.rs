@@ -1,23 +1,148 @@
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::path::PathBuf;
+use std::rc::Rc;
 
 use indexmap::IndexMap;
 
+use crate::database::blob::Blob;
 use crate::database::entry::Entry;
 use crate::database::tree::{Tree, TreeEntry};
 use crate::database::{Database, ParsedObject};
+use crate::diff::similarity;
 use crate::errors::Result;
 use crate::path_filter::PathFilter;
 
 pub type TreeDiffChanges = IndexMap<PathBuf, (Option<Entry>, Option<Entry>)>;
 
+/// The minimum similarity score for a deleted and an added file to be considered a rename.
+pub const RENAME_THRESHOLD: u32 = 50;
+
+/// An added file paired with the file it was renamed or copied from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rename {
+    pub old_path: PathBuf,
+    pub new_path: PathBuf,
+    pub score: u32,
+}
+
+/// Pair each added file in `changes` with the deleted file it is most similar to, provided they
+/// are at least `RENAME_THRESHOLD` percent similar. Each file takes part in at most one rename.
+/// Gitlinks point at commits in another repository, so they're never renamed or copied.
+pub fn detect_renames(database: &Database, changes: &TreeDiffChanges) -> Result<Vec<Rename>> {
+    let deleted: Vec<_> = changes
+        .iter()
+        .filter_map(|(path, change)| match change {
+            (Some(entry), None) if !entry.is_gitlink() => {
+                Some((path.to_owned(), entry.oid.clone()))
+            }
+            _ => None,
+        })
+        .collect();
+
+    best_matches(database, &added_files(changes, &[]), &deleted, true)
+}
+
+/// Pair each added file in `changes` that isn't one of `renames` with the file in `sources` it is
+/// most similar to, like `git diff --find-copies-harder`. Unlike a rename, the source of a copy
+/// may be unchanged, and may be copied to more than one file.
+pub fn detect_copies(
+    database: &Database,
+    changes: &TreeDiffChanges,
+    renames: &[Rename],
+    sources: &HashMap<String, TreeEntry>,
+) -> Result<Vec<Rename>> {
+    let mut sources: Vec<_> = sources
+        .iter()
+        .filter(|(_, entry)| !entry.is_gitlink())
+        .map(|(path, entry)| (PathBuf::from(path), entry.oid()))
+        .collect();
+    sources.sort();
+
+    best_matches(database, &added_files(changes, renames), &sources, false)
+}
+
+fn added_files(changes: &TreeDiffChanges, renames: &[Rename]) -> Vec<(PathBuf, String)> {
+    changes
+        .iter()
+        .filter_map(|(path, change)| match change {
+            (None, Some(entry))
+                if !entry.is_gitlink()
+                    && !renames.iter().any(|rename| &rename.new_path == path) =>
+            {
+                Some((path.to_owned(), entry.oid.clone()))
+            }
+            _ => None,
+        })
+        .collect()
+}
+
+/// Pair each of the `added` files with the most similar of `sources`, provided they are at least
+/// `RENAME_THRESHOLD` percent similar. If `exclusive`, each source is paired at most once.
+fn best_matches(
+    database: &Database,
+    added: &[(PathBuf, String)],
+    sources: &[(PathBuf, String)],
+    exclusive: bool,
+) -> Result<Vec<Rename>> {
+    let mut blobs = HashMap::new();
+    let mut load = |oid: &str| -> Result<Rc<Blob>> {
+        if !blobs.contains_key(oid) {
+            blobs.insert(oid.to_owned(), database.load_blob(oid)?);
+        }
+        Ok(Rc::clone(&blobs[oid]))
+    };
+
+    let mut candidates = Vec::new();
+    for (new_path, new_oid) in added {
+        for (old_path, old_oid) in sources {
+            let score = if old_oid == new_oid {
+                100
+            } else {
+                similarity(&load(old_oid)?.data, &load(new_oid)?.data)
+            };
+
+            if score >= RENAME_THRESHOLD {
+                candidates.push(Rename {
+                    old_path: old_path.to_path_buf(),
+                    new_path: new_path.to_path_buf(),
+                    score,
+                });
+            }
+        }
+    }
+
+    // Take the best matches first, breaking ties by path so the result is deterministic
+    candidates.sort_by(|a, b| {
+        b.score
+            .cmp(&a.score)
+            .then_with(|| a.new_path.cmp(&b.new_path))
+            .then_with(|| a.old_path.cmp(&b.old_path))
+    });
+
+    let mut paired_from = HashSet::new();
+    let mut paired_to = HashSet::new();
+    let mut matches: Vec<_> = candidates
+        .into_iter()
+        .filter(|rename| {
+            if exclusive && paired_from.contains(&rename.old_path)
+                || paired_to.contains(&rename.new_path)
+            {
+                false
+            } else {
+                paired_from.insert(rename.old_path.clone());
+                paired_to.insert(rename.new_path.clone());
+                true
+            }
+        })
+        .collect();
+    matches.sort_by(|a, b| a.new_path.cmp(&b.new_path));
+
+    Ok(matches)
+}
+
 pub trait Differ {
-    fn tree_diff(
-        &self,
-        a: Option<&str>,
-        b: Option<&str>,
-        filter: Option<&PathFilter>,
-    ) -> Result<TreeDiffChanges>;
+    /// The changes between the trees of `a` and `b`, which may name either commits or trees.
+    fn tree_diff(&self, a: &str, b: &str, filter: Option<&PathFilter>) -> Result<TreeDiffChanges>;
 }
 
 pub struct TreeDiff<'a> {
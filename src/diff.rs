@@ -36,6 +36,26 @@ pub fn combined_hunks(r#as: &[&str], b: &str) -> Vec<Hunk<Row>> {
     Hunk::filter(combined(r#as, b))
 }
 
+/// Score how similar two documents are, as the percentage of the larger document's bytes that are
+/// unchanged between them. Identical documents score 100.
+pub fn similarity(a: &[u8], b: &[u8]) -> u32 {
+    if a == b {
+        return 100;
+    }
+
+    let size = a.len().max(b.len());
+    let a = String::from_utf8_lossy(a);
+    let b = String::from_utf8_lossy(b);
+
+    let common: usize = diff(&a, &b)
+        .iter()
+        .filter(|edit| edit.r#type == EditType::Eql)
+        .map(|edit| edit.a_line.as_ref().unwrap().text.len() + 1)
+        .sum();
+
+    (common.min(size) * 100 / size) as u32
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Line {
     pub number: usize,
@@ -49,6 +69,10 @@ impl Line {
             text: text.to_string(),
         }
     }
+
+    pub fn text(&self) -> &str {
+        &self.text
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -339,4 +363,50 @@ cat";
             );
         }
     }
+
+    #[test]
+    fn combine_the_diffs_against_two_parents() {
+        let merged = "\
+<<<<<<< HEAD
+2
+=======
+3
+>>>>>>> topic
+";
+
+        let hunks: Vec<_> = combined_hunks(&["2\n", "3\n"], merged)
+            .iter()
+            .map(|hunk| {
+                (
+                    hunk.header(),
+                    hunk.edits
+                        .iter()
+                        .map(|row| row.to_string())
+                        .collect::<Vec<_>>(),
+                )
+            })
+            .collect();
+
+        assert_eq!(
+            hunks,
+            vec![(
+                String::from("@@@ -1,1 -1,1 +1,5 @@@"),
+                vec![
+                    String::from("++<<<<<<< HEAD"),
+                    String::from(" +2"),
+                    String::from("++======="),
+                    String::from("+ 3"),
+                    String::from("++>>>>>>> topic"),
+                ]
+            )]
+        );
+    }
+
+    #[test]
+    fn score_similarity() {
+        assert_eq!(similarity(b"a\nb\nc\nd\n", b"a\nb\nc\nd\n"), 100);
+        assert_eq!(similarity(b"a\nb\nc\nd\n", b"a\nb\nc\nx\n"), 75);
+        assert_eq!(similarity(b"a\nb\n", b"c\nd\n"), 0);
+        assert_eq!(similarity(b"", b""), 100);
+    }
 }
@@ -1,23 +1,40 @@
 use std::fs;
 use std::fs::{File, OpenOptions};
 use std::io::Write;
-use std::os::unix::io::AsRawFd;
 use std::path::PathBuf;
 use std::process::Command;
 
-use regex::Regex;
+use clap::ValueEnum;
 
 use crate::errors::{Error, Result};
 use crate::util::{path_to_string, LinesWithEndings};
 
 const DEFAULT_EDITOR: &str = "vi";
+const SCISSORS: &str = "------------------------ >8 ------------------------";
+const SCISSORS_NOTES: &str = "\
+Do not modify or remove the line above.
+Everything below it will be ignored.\n";
+
+/// How an edited message is cleaned up, as in `git commit --cleanup=<mode>`.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cleanup {
+    /// Remove comment lines and everything from the scissors line on, then trim whitespace
+    Strip,
+    /// Trim trailing whitespace and surrounding blank lines, keeping comment lines
+    Whitespace,
+    /// Keep the message exactly as it is
+    Verbatim,
+    /// Like `whitespace`, but also remove everything from the scissors line on
+    Scissors,
+}
 
 #[derive(Debug)]
 pub struct Editor {
     path: PathBuf,
     command: String,
     closed: bool,
-    file: File,
+    cleanup: Cleanup,
+    file: Option<File>,
 }
 
 impl Editor {
@@ -32,7 +49,8 @@ impl Editor {
             path,
             command: command.unwrap_or_else(|| DEFAULT_EDITOR.to_owned()),
             closed: false,
-            file,
+            cleanup: Cleanup::Strip,
+            file: Some(file),
         })
     }
 
@@ -49,8 +67,9 @@ impl Editor {
         if self.closed {
             return Ok(());
         }
-        self.file.write_all(string.as_bytes())?;
-        self.file.write_all(b"\n")?;
+        let file = self.file.as_mut().unwrap();
+        file.write_all(string.as_bytes())?;
+        file.write_all(b"\n")?;
 
         Ok(())
     }
@@ -59,22 +78,32 @@ impl Editor {
         if self.closed {
             return Ok(());
         }
+        let file = self.file.as_mut().unwrap();
         for line in LinesWithEndings::from(string) {
-            write!(self.file, "# {}", line)?;
+            write!(file, "# {}", line)?;
         }
 
         Ok(())
     }
 
+    /// Write the scissors line. Everything after it is removed from the edited message.
+    pub fn scissors(&mut self) -> Result<()> {
+        self.note(&format!("{}\n", SCISSORS))?;
+        self.note(SCISSORS_NOTES)
+    }
+
     pub fn close(&mut self) {
         self.closed = true;
     }
 
+    /// Set how the message is cleaned up once it's edited, which is `Cleanup::Strip` by default.
+    pub fn cleanup(&mut self, cleanup: Cleanup) {
+        self.cleanup = cleanup;
+    }
+
     pub fn edit_file(&mut self) -> Result<Option<String>> {
-        let fd = self.file.as_raw_fd();
-        unsafe {
-            libc::close(fd);
-        }
+        // Close the file before handing it to the editor
+        drop(self.file.take());
 
         let mut editor_argv = shlex::split(&self.command).expect("Invalid command");
         editor_argv.push(path_to_string(&self.path));
@@ -88,19 +117,44 @@ impl Editor {
             }
         }
 
-        Ok(self.remove_notes(fs::read_to_string(&self.path)?))
+        Ok(cleanup_message(
+            &fs::read_to_string(&self.path)?,
+            self.cleanup,
+        ))
     }
+}
 
-    fn remove_notes(&self, string: String) -> Option<String> {
-        let lines: Vec<_> = LinesWithEndings::from(&string)
-            .filter(|line| !line.starts_with('#'))
-            .collect();
+/// Clean up `message` according to `cleanup`, returning `None` if nothing but whitespace is left.
+/// Apart from `Cleanup::Verbatim`, this also collapses runs of blank lines like `git stripspace`.
+pub fn cleanup_message(message: &str, cleanup: Cleanup) -> Option<String> {
+    if message.trim().is_empty() {
+        return None;
+    } else if cleanup == Cleanup::Verbatim {
+        return Some(message.to_owned());
+    }
+
+    let scissors = format!("# {}", SCISSORS);
+    let mut lines: Vec<&str> = vec![];
+    for line in message.lines() {
+        if cleanup != Cleanup::Whitespace && line.trim_end() == scissors {
+            break;
+        } else if cleanup == Cleanup::Strip && line.starts_with('#') {
+            continue;
+        }
 
-        let re = Regex::new(r"^\s*$").unwrap();
-        if lines.iter().all(|line| re.is_match(line)) {
-            None
-        } else {
-            Some(format!("{}\n", lines.join("").trim()))
+        let line = line.trim_end();
+        if line.is_empty() && lines.last().is_none_or(|last| last.is_empty()) {
+            continue;
         }
+        lines.push(line);
+    }
+    while lines.last() == Some(&"") {
+        lines.pop();
+    }
+
+    if lines.is_empty() {
+        None
+    } else {
+        Some(format!("{}\n", lines.join("\n")))
     }
 }
@@ -13,14 +13,31 @@ pub enum Error {
     InvalidVersion { expected: u32, got: u32 },
     #[error("Checksum does not match value stored on disk")]
     InvalidChecksum,
+    /// An index listing a file both as merged, at stage 0, and as conflicted
+    #[error("multiple stage entries for merged file '{0}'")]
+    InvalidStage(String),
+    #[error("index uses {0} extension, which we do not understand")]
+    UnknownExtension(String),
+    /// An index whose extensions run into or past its checksum
+    #[error("index file corrupt")]
+    CorruptIndex,
     #[error("Unable to create '{0}': File exists.")]
     LockDenied(PathBuf),
     #[error("{0}")]
     InvalidBranch(String),
+    #[error("ambiguous argument '{0}': unknown revision or path not in the working tree.")]
+    UnknownRevision(String),
+    /// A short object ID matching several objects, which are listed in `Revision::errors`
+    #[error("ambiguous argument '{0}': unknown revision or path not in the working tree.")]
+    AmbiguousRevision(String, Vec<String>),
+    #[error("Needed a single revision")]
+    NotACommit,
     #[error("{0}")]
-    InvalidObject(String),
+    InvalidUpstream(String),
     #[error("MigrationConflict")]
     MigrationConflict,
+    #[error("unable to update '{}': {1}; the workspace was left unchanged", .0.display())]
+    MigrationFailed(PathBuf, String),
     #[error("branch '{0}' not found.")]
     BranchNotFound(String),
     #[error("There is no merge in progress ({0} missing).")]
@@ -33,8 +50,12 @@ pub enum Error {
     RmNotRecursive(String),
     #[error("jit rm: '{0}': Operation not permitted")]
     RmOperationNotPermitted(String),
+    #[error("invalid date format: {0}")]
+    InvalidDate(String),
     #[error("There was a problem with the editor '{0}'")]
     ProblemWithEditor(String),
+    #[error("unable to read files to diff: textconv command '{0}' failed")]
+    TextConvFailed(String),
     #[error("You seem to have moved HEAD. Not rewinding, check your HEAD!")]
     UnsafeRewind,
     #[error("bad config line {0} in file {1}")]
@@ -43,6 +64,20 @@ pub enum Error {
     ConfigConflict(String),
     #[error("{0}")]
     InvalidRemote(String),
+    #[error("{0}")]
+    InvalidArgument(String),
+    #[error("cannot lock ref '{0}': {1}")]
+    CannotLockRef(String, String),
+    #[error("corrupt patch at line {0}")]
+    CorruptPatch(usize),
+    #[error("patch failed: {0}:{1}")]
+    PatchFailed(String, usize),
+    #[error("{0}: does not exist in index")]
+    PatchMissingFile(String),
+    #[error("{0}: already exists in index")]
+    PatchExistingFile(String),
+    #[error("Patch is empty.")]
+    EmptyPatch,
     #[error("'{0}' is not a jit command.")]
     UnknownCommand(String),
     #[error("Exit {0}")]
@@ -55,6 +90,16 @@ pub enum Error {
 
 pub type Result<T> = std::result::Result<T, Error>;
 
+impl Error {
+    /// Whether the error is from failing to resolve a revision with `Revision::resolve()`.
+    pub fn is_revision_error(&self) -> bool {
+        matches!(
+            self,
+            Error::UnknownRevision(..) | Error::AmbiguousRevision(..) | Error::NotACommit
+        )
+    }
+}
+
 impl From<std::array::TryFromSliceError> for Error {
     fn from(err: std::array::TryFromSliceError) -> Error {
         Error::Other(format!("{}", err))
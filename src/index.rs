@@ -4,26 +4,43 @@ use std::convert::TryInto;
 use std::fs::File;
 use std::io::{Read, Write};
 use std::os::unix::fs::MetadataExt;
+use std::os::unix::io::AsRawFd;
 use std::path::{Path, PathBuf};
-use std::{fs, io, str};
+use std::{fs, io, ptr, slice, str};
 
 use hex::ToHex;
+use nix::libc::c_void;
+use nix::sys::mman::{mmap, munmap, MapFlags, ProtFlags};
 use sha1::{Digest, Sha1};
 
 use crate::database::entry::Entry as DatabaseEntry;
 use crate::errors::{Error, Result};
 use crate::lockfile::Lockfile;
 use crate::util::{basename, is_executable, parent_directories, path_to_string};
+use crate::workspace::SYMLINK_MODE;
 
 const MAX_PATH_SIZE: u16 = 0xfff;
+/// Set in an entry's flags when it's followed by a second, 16-bit field of extended flags
+const EXTENDED_FLAG: u16 = 0x4000;
+/// Set in an entry's extended flags when the file is left out of the workspace by sparse checkout
+const SKIP_WORKTREE_FLAG: u16 = 0x4000;
+/// Set in an entry's extended flags when the file was added with `jit add --intent-to-add`
+const INTENT_TO_ADD_FLAG: u16 = 0x2000;
 const CHECKSUM_SIZE: usize = 20;
 const HEADER_SIZE: usize = 12;
+const EXTENSION_HEADER_SIZE: usize = 8;
 
 #[derive(Debug)]
 pub struct Index {
     pathname: PathBuf,
     pub entries: BTreeMap<(String, u16), Entry>,
     parents: HashMap<String, HashSet<String>>,
+    /// With `core.ignoreCase`, the path of each entry and parent directory keyed by its lowercase
+    /// form, so lookups can find it whatever its case
+    folded: HashMap<String, String>,
+    ignore_case: bool,
+    /// Map the index file into memory to read it, rather than reading it a piece at a time
+    mmap: bool,
     lockfile: Lockfile,
     changed: bool,
 }
@@ -34,23 +51,69 @@ impl Index {
             pathname: pathname.clone(),
             entries: BTreeMap::new(),
             parents: HashMap::new(),
+            folded: HashMap::new(),
+            ignore_case: false,
+            mmap: false,
             lockfile: Lockfile::new(pathname),
             changed: false,
         }
     }
 
+    /// Match paths regardless of case, as `core.ignoreCase` asks for on case-insensitive
+    /// filesystems. Set before the index is loaded.
+    pub fn set_ignore_case(&mut self, ignore_case: bool) {
+        self.ignore_case = ignore_case;
+    }
+
+    /// Read the index through a memory map, as the jit-specific `jit.mmapIndex` setting asks for,
+    /// which is faster for large indexes. Files that can't be mapped are still read normally.
+    pub fn set_mmap(&mut self, mmap: bool) {
+        self.mmap = mmap;
+    }
+
+    /// The path the index uses for `path`: with `core.ignoreCase`, that's the casing of a tracked
+    /// file or directory matching it, if there is one.
+    pub fn canonical_path(&self, path: &str) -> String {
+        if self.ignore_case {
+            if let Some(canonical) = self.folded.get(&path.to_lowercase()) {
+                return canonical.to_owned();
+            }
+        }
+
+        path.to_owned()
+    }
+
     pub fn add(&mut self, pathname: PathBuf, oid: String, stat: fs::Metadata) {
-        let pathname = path_to_string(&pathname);
+        let mode = Entry::mode_for_stat(&stat);
+        self.add_with_mode(pathname, oid, stat, mode);
+    }
+
+    /// Add a file checked out from a tree entry with `mode`, which `stat` doesn't show for
+    /// symbolic links checked out as plain files.
+    pub fn add_with_mode(&mut self, pathname: PathBuf, oid: String, stat: fs::Metadata, mode: u32) {
+        let pathname = self.canonical_path(&path_to_string(&pathname));
         for stage in 1..=3 {
             self.remove_entry_with_stage(&pathname, stage);
         }
 
-        let entry = Entry::new(&pathname, oid, stat);
+        let mut entry = Entry::new(&pathname, oid, stat);
+        entry.mode = mode;
         self.discard_conflicts(&entry);
         self.store_entry(entry);
         self.changed = true;
     }
 
+    /// Record that the file at `pathname` will be added later, without staging its contents. The
+    /// entry points at the empty blob `oid`.
+    pub fn add_intent_to_add(&mut self, pathname: PathBuf, oid: String, stat: fs::Metadata) {
+        let key = self.canonical_path(&path_to_string(&pathname));
+        self.add(pathname, oid, stat);
+
+        if let Some(entry) = self.entries.get_mut(&(key, 0)) {
+            entry.extended_flags |= INTENT_TO_ADD_FLAG;
+        }
+    }
+
     pub fn write_updates(&mut self) -> Result<()> {
         if !self.changed {
             self.lockfile.rollback()?;
@@ -59,10 +122,17 @@ impl Index {
 
         let mut writer = Checksum::new(&self.lockfile);
 
+        // Version 3 is only needed for entries with extended flags
+        let version: u32 = if self.entries.values().any(Entry::is_extended) {
+            3
+        } else {
+            2
+        };
+
         // Header
         let mut header: Vec<u8> = vec![];
         header.extend_from_slice(b"DIRC");
-        header.extend_from_slice(&2u32.to_be_bytes()); // version number
+        header.extend_from_slice(&version.to_be_bytes()); // version number
         header.extend_from_slice(&(self.entries.len() as u32).to_be_bytes());
         writer.write(&header)?;
 
@@ -96,10 +166,13 @@ impl Index {
         self._clear();
 
         if let Some(file) = self.open_index_file()? {
-            let mut reader = Checksum::new(file);
-            let count = self.read_header(&mut reader)?;
-            self.read_entries(&mut reader, count)?;
-            reader.verify_checksum()?;
+            match self.mmap.then(|| MappedFile::new(&file)).flatten() {
+                Some(map) => self.read_index(Checksum::new(map.as_slice()), map.len)?,
+                None => {
+                    let size = file.metadata()?.len() as usize;
+                    self.read_index(Checksum::new(file), size)?
+                }
+            }
         }
 
         Ok(())
@@ -112,19 +185,17 @@ impl Index {
     }
 
     pub fn child_paths(&self, path: &Path) -> HashSet<String> {
-        let key = path_to_string(path);
+        let key = self.canonical_path(&path_to_string(path));
         self.parents[&key].clone()
     }
 
     pub fn tracked_file(&self, path: &Path) -> bool {
-        (0..=3).any(|stage| {
-            let key = (path_to_string(path), stage);
-            self.entries.contains_key(&key)
-        })
+        let path = self.canonical_path(&path_to_string(path));
+        (0..=3).any(|stage| self.entries.contains_key(&(path.clone(), stage)))
     }
 
     pub fn tracked_directory(&self, path: &Path) -> bool {
-        let key = path_to_string(path);
+        let key = self.canonical_path(&path_to_string(path));
         self.parents.contains_key(&key)
     }
 
@@ -153,6 +224,16 @@ impl Index {
         self.changed = true;
     }
 
+    /// Mark the entry at `pathname` as left out of, or restored to, the workspace.
+    pub fn set_skip_worktree(&mut self, pathname: &str, skip_worktree: bool) {
+        if let Some(entry) = self.entries.get_mut(&(pathname.to_string(), 0)) {
+            if entry.skip_worktree() != skip_worktree {
+                entry.extended_flags ^= SKIP_WORKTREE_FLAG;
+                self.changed = true;
+            }
+        }
+    }
+
     pub fn update_entry_stat(&mut self, entry: &mut Entry, stat: &fs::Metadata) {
         entry.update_stat(stat);
         self.changed = true;
@@ -178,11 +259,11 @@ impl Index {
     /// * `path`: The path.
     /// * `stage`: The index stage, from `0..=3`.
     pub fn entry_for_path(&self, path: &str, stage: u16) -> Option<&Entry> {
-        self.entries.get(&(path.to_string(), stage))
+        self.entries.get(&(self.canonical_path(path), stage))
     }
 
     pub fn remove(&mut self, pathname: &Path) {
-        let pathname = path_to_string(pathname);
+        let pathname = self.canonical_path(&path_to_string(pathname));
         self.remove_entry(&pathname);
         self.remove_children(&pathname);
         self.changed = true;
@@ -192,6 +273,7 @@ impl Index {
     fn _clear(&mut self) {
         self.entries = BTreeMap::new();
         self.parents = HashMap::new();
+        self.folded = HashMap::new();
         self.changed = false;
     }
 
@@ -207,7 +289,15 @@ impl Index {
         }
     }
 
-    fn read_header(&self, reader: &mut Checksum<File>) -> Result<u32> {
+    /// Read the index from `reader`, which holds `size` bytes.
+    fn read_index<R: Read>(&mut self, mut reader: Checksum<R>, size: usize) -> Result<()> {
+        let count = self.read_header(&mut reader)?;
+        self.read_entries(&mut reader, count)?;
+        self.read_extensions(&mut reader, size)?;
+        reader.verify_checksum()
+    }
+
+    fn read_header<R: Read>(&self, reader: &mut Checksum<R>) -> Result<u32> {
         let data = reader.read(HEADER_SIZE)?;
         let signature = str::from_utf8(&data[0..4])?;
         let version = u32::from_be_bytes(data[4..8].try_into()?);
@@ -219,7 +309,7 @@ impl Index {
                 got: signature.to_string(),
             });
         }
-        if version != 2 {
+        if version != 2 && version != 3 {
             return Err(Error::InvalidVersion {
                 expected: 2,
                 got: version,
@@ -229,15 +319,64 @@ impl Index {
         Ok(count)
     }
 
-    fn read_entries(&mut self, reader: &mut Checksum<File>, count: u32) -> Result<()> {
+    fn read_entries<R: Read>(&mut self, reader: &mut Checksum<R>, count: u32) -> Result<()> {
         for _i in 0..count {
             let mut entry = reader.read(64)?;
+            // The path of an extended entry starts after the 64 bytes read so far
+            let extended = u16::from_be_bytes(entry[60..62].try_into()?) & EXTENDED_FLAG != 0;
+            if extended {
+                entry.extend_from_slice(&reader.read(8)?)
+            }
 
             while entry.last().unwrap() != &0u8 {
                 entry.extend_from_slice(&reader.read(8)?)
             }
 
-            self.store_entry(Entry::parse(&entry)?);
+            let entry = Entry::parse(&entry)?;
+            self.check_stage(&entry)?;
+            self.store_entry(entry);
+        }
+
+        Ok(())
+    }
+
+    /// Skip the extensions between the entries and the checksum, like Git's cached trees in
+    /// `TREE`. Each has a 4-byte signature and a 32-bit size. An extension whose signature starts
+    /// with an uppercase letter is optional, and any other is needed to read the index correctly.
+    fn read_extensions<R: Read>(&self, reader: &mut Checksum<R>, size: usize) -> Result<()> {
+        let remaining = |reader: &Checksum<R>| size.saturating_sub(reader.offset + CHECKSUM_SIZE);
+
+        while remaining(reader) > 0 {
+            if remaining(reader) < EXTENSION_HEADER_SIZE {
+                return Err(Error::CorruptIndex);
+            }
+            let header = reader.read(EXTENSION_HEADER_SIZE)?;
+            let signature = &header[0..4];
+            let length = u32::from_be_bytes(header[4..8].try_into()?) as usize;
+            if length > remaining(reader) {
+                return Err(Error::CorruptIndex);
+            }
+
+            if !signature[0].is_ascii_uppercase() {
+                return Err(Error::UnknownExtension(
+                    String::from_utf8_lossy(signature).into_owned(),
+                ));
+            }
+            reader.read(length)?;
+        }
+
+        Ok(())
+    }
+
+    /// Check that `entry` doesn't clash with the entries read before it. A file is either merged,
+    /// with a single entry at stage 0, or conflicted, with entries at stages 1 to 3, never both.
+    fn check_stage(&self, entry: &Entry) -> Result<()> {
+        let stages = if entry.stage() == 0 { 1..=3 } else { 0..=0 };
+        if stages
+            .into_iter()
+            .any(|stage| self.entries.contains_key(&(entry.path.clone(), stage)))
+        {
+            return Err(Error::InvalidStage(entry.path.clone()));
         }
 
         Ok(())
@@ -247,6 +386,12 @@ impl Index {
         for parent in entry.parent_directories() {
             let parent = path_to_string(&parent);
 
+            if self.ignore_case {
+                self.folded
+                    .entry(parent.to_lowercase())
+                    .or_insert_with(|| parent.clone());
+            }
+
             if let Some(children) = self.parents.get_mut(&parent) {
                 children.insert(entry.path.clone());
             } else {
@@ -256,6 +401,12 @@ impl Index {
             }
         }
 
+        if self.ignore_case {
+            self.folded
+                .entry(entry.path.to_lowercase())
+                .or_insert_with(|| entry.path.clone());
+        }
+
         self.entries.insert(entry.key(), entry);
     }
 
@@ -296,9 +447,21 @@ impl Index {
                     children.remove(pathname);
                     if children.is_empty() {
                         self.parents.remove(&dirname);
+                        self.unfold(&dirname);
                     }
                 }
             }
+
+            if !(0..=3).any(|stage| self.entries.contains_key(&(pathname.to_string(), stage))) {
+                self.unfold(pathname);
+            }
+        }
+    }
+
+    fn unfold(&mut self, path: &str) {
+        let key = path.to_lowercase();
+        if self.folded.get(&key).map(String::as_str) == Some(path) {
+            self.folded.remove(&key);
         }
     }
 }
@@ -318,6 +481,7 @@ pub struct Entry {
     size: u64,
     pub oid: String,
     flags: u16,
+    extended_flags: u16,
     pub path: String,
 }
 
@@ -336,6 +500,7 @@ impl Entry {
             size: stat.size(),
             oid,
             flags: min(pathname.len() as u16, MAX_PATH_SIZE),
+            extended_flags: 0,
             path: pathname.to_string(),
         }
     }
@@ -356,6 +521,7 @@ impl Entry {
             size: 0,
             oid: item.oid.clone(),
             flags,
+            extended_flags: 0,
             path: pathname.to_string(),
         }
     }
@@ -377,7 +543,12 @@ impl Entry {
 
         let oid = data[40..60].to_vec().encode_hex::<String>();
         let flags = u16::from_be_bytes(data[60..62].try_into()?);
-        let path = str::from_utf8(&data[62..])?
+        let (extended_flags, path_start) = if flags & EXTENDED_FLAG != 0 {
+            (u16::from_be_bytes(data[62..64].try_into()?), 64)
+        } else {
+            (0, 62)
+        };
+        let path = str::from_utf8(&data[path_start..])?
             .trim_end_matches('\0')
             .to_string();
 
@@ -393,7 +564,8 @@ impl Entry {
             gid: metadata[8],
             size: u64::from(metadata[9]),
             oid,
-            flags,
+            flags: flags & !EXTENDED_FLAG,
+            extended_flags,
             path,
         })
     }
@@ -406,6 +578,20 @@ impl Entry {
         (self.flags >> 12) & 0x3
     }
 
+    /// Whether the file is left out of the workspace by sparse checkout.
+    pub fn skip_worktree(&self) -> bool {
+        self.extended_flags & SKIP_WORKTREE_FLAG != 0
+    }
+
+    /// Whether the file was added with `jit add --intent-to-add`, so its contents aren't staged.
+    pub fn intent_to_add(&self) -> bool {
+        self.extended_flags & INTENT_TO_ADD_FLAG != 0
+    }
+
+    fn is_extended(&self) -> bool {
+        self.extended_flags != 0
+    }
+
     pub fn parent_directories(&self) -> Vec<PathBuf> {
         parent_directories(Path::new(&self.path))
             .iter()
@@ -436,8 +622,13 @@ impl Entry {
         // 20 bytes (40-char hex-string)
         bytes.extend_from_slice(&hex::decode(&self.oid).unwrap());
 
-        // 16-bit
-        bytes.extend_from_slice(&self.flags.to_be_bytes());
+        // 16-bit, followed by another 16 bits of extended flags if there are any
+        if self.is_extended() {
+            bytes.extend_from_slice(&(self.flags | EXTENDED_FLAG).to_be_bytes());
+            bytes.extend_from_slice(&self.extended_flags.to_be_bytes());
+        } else {
+            bytes.extend_from_slice(&self.flags.to_be_bytes());
+        }
 
         bytes.extend_from_slice(self.path.as_bytes());
         bytes.push(0x0);
@@ -451,7 +642,11 @@ impl Entry {
     }
 
     pub fn stat_match(&self, stat: &fs::Metadata) -> bool {
-        (self.mode == Entry::mode_for_stat(stat)) && (self.size == 0 || self.size == stat.size())
+        // A symbolic link checked out as a plain file, with `core.symlinks=false`, keeps its mode
+        let mode_match =
+            self.mode == Entry::mode_for_stat(stat) || self.mode == SYMLINK_MODE && stat.is_file();
+
+        mode_match && (self.size == 0 || self.size == stat.size())
     }
 
     pub fn times_match(&self, stat: &fs::Metadata) -> bool {
@@ -468,37 +663,92 @@ impl Entry {
         self.mtime_nsec = stat.mtime_nsec();
         self.dev = stat.dev();
         self.ino = stat.ino();
-        self.mode = Entry::mode_for_stat(stat);
+        if !(self.mode == SYMLINK_MODE && stat.is_file()) {
+            self.mode = Entry::mode_for_stat(stat);
+        }
         self.uid = stat.uid();
         self.gid = stat.gid();
         self.size = stat.size();
     }
 }
 
+/// A file mapped read-only into memory, unmapped when dropped.
+///
+/// The mapping is private and read-only, so nothing in jit can write through it. The index is
+/// never changed in place: it's replaced by renaming `index.lock` over it, which leaves the
+/// mapped file, and so the mapped bytes, as they were until the map is dropped.
+#[derive(Debug)]
+struct MappedFile {
+    addr: *mut c_void,
+    len: usize,
+}
+
+impl MappedFile {
+    /// Map all of `file`, or return `None` if it can't be mapped, as empty files can't.
+    fn new(file: &File) -> Option<Self> {
+        let len = usize::try_from(file.metadata().ok()?.len()).ok()?;
+        if len == 0 {
+            return None;
+        }
+
+        // SAFETY: a new mapping is requested at an address the kernel chooses, so no existing
+        // memory is affected. `len` is the file's current, non-zero length and the offset is 0,
+        // so the whole mapping is backed by the file.
+        let addr = unsafe {
+            mmap(
+                ptr::null_mut(),
+                len,
+                ProtFlags::PROT_READ,
+                MapFlags::MAP_PRIVATE,
+                file.as_raw_fd(),
+                0,
+            )
+        }
+        .ok()?;
+
+        Some(Self { addr, len })
+    }
+
+    fn as_slice(&self) -> &[u8] {
+        // SAFETY: `addr` points at `len` readable bytes that stay mapped until `self` is dropped,
+        // which the returned lifetime can't outlive. The map is read-only and private, and the
+        // index is only ever replaced by rename, so the bytes don't change while they're borrowed.
+        unsafe { slice::from_raw_parts(self.addr as *const u8, self.len) }
+    }
+}
+
+impl Drop for MappedFile {
+    fn drop(&mut self) {
+        // SAFETY: `addr` and `len` are exactly the mapping made in `new()`, and no slice from
+        // `as_slice()` can still be borrowed once `self` is being dropped.
+        let _ = unsafe { munmap(self.addr, self.len) };
+    }
+}
+
 #[derive(Debug)]
-struct Checksum<T>
-where
-    T: Read + Write,
-{
+struct Checksum<T> {
     file: T,
     digest: Sha1,
+    /// The number of bytes read so far
+    offset: usize,
 }
 
-impl<T> Checksum<T>
-where
-    T: Read + Write,
-{
+impl<T> Checksum<T> {
     fn new(file: T) -> Self {
         Checksum {
             file,
             digest: Sha1::new(),
+            offset: 0,
         }
     }
+}
 
+impl<T: Read> Checksum<T> {
     fn read(&mut self, size: usize) -> Result<Vec<u8>> {
         let mut data = vec![0; size];
         self.file.read_exact(&mut data)?;
         self.digest.update(&data);
+        self.offset += size;
 
         Ok(data)
     }
@@ -514,7 +764,9 @@ where
 
         Ok(())
     }
+}
 
+impl<T: Write> Checksum<T> {
     fn write(&mut self, data: &[u8]) -> Result<()> {
         self.file.write_all(data)?;
         self.digest.update(data);
@@ -631,4 +883,170 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn reject_a_file_that_is_both_merged_and_conflicted() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let mut index = Index::new(tmp_dir.path().join("index"));
+        index.load_for_update()?;
+
+        let stat = fs::metadata(&tmp_dir)?;
+        index.add(PathBuf::from("alice.txt"), random_oid(), stat);
+        // Bypass `add_conflict_set()`, which would remove the merged entry
+        let item = DatabaseEntry::new(random_oid(), 0o100644);
+        index.store_entry(Entry::create_from_db("alice.txt", &item, 2));
+        index.write_updates()?;
+
+        let mut index = Index::new(tmp_dir.path().join("index"));
+        let error = index.load().unwrap_err();
+        assert!(matches!(&error, Error::InvalidStage(path) if path == "alice.txt"));
+        assert_eq!(
+            error.to_string(),
+            "multiple stage entries for merged file 'alice.txt'"
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn find_entries_in_any_case_with_ignore_case() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let mut index = Index::new(tmp_dir.path().join("index"));
+        index.set_ignore_case(true);
+
+        let stat = fs::metadata(&tmp_dir)?;
+        index.add(PathBuf::from("dir/file.txt"), random_oid(), stat.clone());
+
+        assert!(index.tracked_file(Path::new("DIR/FILE.txt")));
+        assert!(index.tracked_directory(Path::new("Dir")));
+        assert!(index.entry_for_path("dir/File.txt", 0).is_some());
+
+        index.add(PathBuf::from("DIR/FILE.txt"), random_oid(), stat);
+        assert_eq!(
+            index.entries.keys().cloned().collect::<Vec<_>>(),
+            vec![(String::from("dir/file.txt"), 0)],
+        );
+
+        index.remove(Path::new("Dir/FILE.TXT"));
+        assert!(index.entries.is_empty());
+        assert!(!index.tracked(Path::new("dir")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn match_case_exactly_by_default() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let mut index = Index::new(tmp_dir.path().join("index"));
+
+        let stat = fs::metadata(&tmp_dir)?;
+        index.add(PathBuf::from("file.txt"), random_oid(), stat);
+
+        assert!(!index.tracked_file(Path::new("FILE.txt")));
+        assert!(index.entry_for_path("FILE.txt", 0).is_none());
+
+        Ok(())
+    }
+
+    #[test]
+    fn read_the_same_entries_through_a_memory_map() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let mut index = Index::new(tmp_dir.path().join("index"));
+        index.load_for_update()?;
+
+        let stat = fs::metadata(&tmp_dir)?;
+        for i in 0..5000 {
+            let path = format!("dir{}/nested/file-{}.txt", i % 50, i);
+            index.add(PathBuf::from(path), random_oid(), stat.clone());
+        }
+        index.write_updates()?;
+
+        let mut streamed = Index::new(tmp_dir.path().join("index"));
+        streamed.load()?;
+        let mut mapped = Index::new(tmp_dir.path().join("index"));
+        mapped.set_mmap(true);
+        mapped.load()?;
+
+        let bytes =
+            |index: &Index| -> Vec<_> { index.entries.values().map(Entry::bytes).collect() };
+        assert_eq!(mapped.entries.len(), 5000);
+        assert_eq!(bytes(&mapped), bytes(&streamed));
+
+        Ok(())
+    }
+
+    #[test]
+    fn skip_extensions_after_the_entries() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let path = tmp_dir.path().join("index");
+        let mut index = Index::new(path.clone());
+        index.load_for_update()?;
+
+        let stat = fs::metadata(&tmp_dir)?;
+        index.add(PathBuf::from("alice.txt"), random_oid(), stat.clone());
+        index.add(PathBuf::from("nested/bob.txt"), random_oid(), stat);
+        index.write_updates()?;
+
+        // Insert a cached tree for the root, with 2 entries and no subtrees, before the checksum
+        let mut data = fs::read(&path)?;
+        data.truncate(data.len() - CHECKSUM_SIZE);
+        let tree = [b"\x002 0\n".as_slice(), &[0xab; 20]].concat();
+        data.extend_from_slice(b"TREE");
+        data.extend_from_slice(&(tree.len() as u32).to_be_bytes());
+        data.extend_from_slice(&tree);
+        let checksum = Sha1::digest(&data);
+        data.extend_from_slice(&checksum);
+        fs::write(&path, &data)?;
+
+        for mmap in [false, true] {
+            let mut index = Index::new(path.clone());
+            index.set_mmap(mmap);
+            index.load()?;
+            assert_eq!(
+                index.entries.keys().cloned().collect::<Vec<_>>(),
+                vec![
+                    (String::from("alice.txt"), 0),
+                    (String::from("nested/bob.txt"), 0)
+                ],
+            );
+        }
+
+        // Extensions are covered by the checksum too
+        let last = data.len() - CHECKSUM_SIZE - 1;
+        data[last] ^= 0xff;
+        fs::write(&path, &data)?;
+        let mut index = Index::new(path);
+        assert!(matches!(index.load(), Err(Error::InvalidChecksum)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn fail_to_load_an_extension_longer_than_the_index() -> Result<()> {
+        let tmp_dir = TempDir::new()?;
+        let path = tmp_dir.path().join("index");
+        let mut index = Index::new(path.clone());
+        index.load_for_update()?;
+
+        let stat = fs::metadata(&tmp_dir)?;
+        index.add(PathBuf::from("alice.txt"), random_oid(), stat);
+        index.write_updates()?;
+
+        let mut data = fs::read(&path)?;
+        data.truncate(data.len() - CHECKSUM_SIZE);
+        data.extend_from_slice(b"TREE");
+        data.extend_from_slice(&u32::MAX.to_be_bytes());
+        data.extend_from_slice(b"\x002 0\n");
+        let checksum = Sha1::digest(&data);
+        data.extend_from_slice(&checksum);
+        fs::write(&path, &data)?;
+
+        for mmap in [false, true] {
+            let mut index = Index::new(path.clone());
+            index.set_mmap(mmap);
+            assert!(matches!(index.load(), Err(Error::CorruptIndex)));
+        }
+
+        Ok(())
+    }
 }
@@ -9,11 +9,13 @@ pub mod index;
 pub mod lockfile;
 pub mod merge;
 pub mod pager;
+pub mod patch;
 pub mod path_filter;
 pub mod refs;
 pub mod remotes;
 pub mod repository;
 pub mod rev_list;
 pub mod revision;
+pub mod trailers;
 pub mod util;
 pub mod workspace;
@@ -13,6 +13,7 @@ fn main() -> Result<()> {
         env::current_dir()?,
         env::vars().collect::<HashMap<String, String>>(),
         opt,
+        io::BufReader::new(io::stdin()),
         io::stdout(),
         io::stderr(),
         atty::is(atty::Stream::Stdout),
@@ -23,6 +24,10 @@ fn main() -> Result<()> {
                 eprintln!("jit: {}", err);
                 process::exit(1);
             }
+            _ if err.is_revision_error() || matches!(err, Error::InvalidUpstream(..)) => {
+                eprintln!("fatal: {}", err);
+                process::exit(128);
+            }
             Error::Exit(code) => {
                 process::exit(code);
             }
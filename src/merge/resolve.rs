@@ -5,12 +5,20 @@ use crate::database::blob::Blob;
 use crate::database::entry::Entry;
 use crate::database::object::Object;
 use crate::database::tree_diff::{Differ, TreeDiffChanges};
+use crate::database::EMPTY_TREE_OID;
 use crate::errors::Result;
 use crate::merge::diff3;
 use crate::merge::inputs::MergeInputs;
 use crate::repository::Repository;
 use crate::util::{parent_directories, path_to_string};
 
+/// A side of a merge, relative to the base.
+#[derive(Clone, Copy)]
+enum Side {
+    Left,
+    Right,
+}
+
 pub struct Resolve<'a, T>
 where
     T: MergeInputs,
@@ -56,15 +64,15 @@ where
 
     fn prepare_tree_diffs(&mut self) -> Result<()> {
         let base_oids = self.inputs.base_oids();
-        let base_oid = base_oids.first().map(String::as_str);
-        self.left_diff =
-            self.repo
-                .database
-                .tree_diff(base_oid, Some(&self.inputs.left_oid()), None)?;
-        self.right_diff =
-            self.repo
-                .database
-                .tree_diff(base_oid, Some(&self.inputs.right_oid()), None)?;
+        let base_oid = base_oids.first().map_or(EMPTY_TREE_OID, String::as_str);
+        self.left_diff = self
+            .repo
+            .database
+            .tree_diff(base_oid, &self.inputs.left_oid(), None)?;
+        self.right_diff = self
+            .repo
+            .database
+            .tree_diff(base_oid, &self.inputs.right_oid(), None)?;
         self.clean_diff = TreeDiffChanges::new();
         self.conflicts = HashMap::new();
         self.untracked = HashMap::new();
@@ -73,7 +81,7 @@ where
         let left_diff = self.left_diff.clone();
         for (path, (old_item, new_item)) in right_diff {
             if new_item.is_some() {
-                self.file_dir_conflict(&path, &left_diff, &self.inputs.left_name());
+                self.file_dir_conflict(&path, &left_diff, Side::Left);
             }
             self.same_path_conflict(&path, old_item, new_item)?;
         }
@@ -81,7 +89,7 @@ where
         let right_diff = self.right_diff.clone();
         for (path, (_, new_item)) in left_diff {
             if new_item.is_some() {
-                self.file_dir_conflict(&path, &right_diff, &self.inputs.right_name());
+                self.file_dir_conflict(&path, &right_diff, Side::Right);
             }
         }
 
@@ -219,7 +227,9 @@ where
         None
     }
 
-    fn file_dir_conflict(&mut self, path: &Path, diff: &TreeDiffChanges, name: &str) {
+    /// `side` is the side of the merge that `diff` comes from. It's compared by position rather
+    /// than by name, since both sides can have the same label.
+    fn file_dir_conflict(&mut self, path: &Path, diff: &TreeDiffChanges, side: Side) {
         for parent in parent_directories(path) {
             if !diff.contains_key(&parent) {
                 continue;
@@ -230,17 +240,22 @@ where
                 continue;
             }
 
-            if name == self.inputs.left_name() {
-                self.conflicts.insert(
-                    path_to_string(&parent),
-                    vec![old_item.to_owned(), new_item.to_owned(), None],
-                );
-            } else if name == self.inputs.right_name() {
-                self.conflicts.insert(
-                    path_to_string(&parent),
-                    vec![old_item.to_owned(), None, new_item.to_owned()],
-                );
-            }
+            let name = match side {
+                Side::Left => {
+                    self.conflicts.insert(
+                        path_to_string(&parent),
+                        vec![old_item.to_owned(), new_item.to_owned(), None],
+                    );
+                    self.inputs.left_name()
+                }
+                Side::Right => {
+                    self.conflicts.insert(
+                        path_to_string(&parent),
+                        vec![old_item.to_owned(), None, new_item.to_owned()],
+                    );
+                    self.inputs.right_name()
+                }
+            };
 
             self.clean_diff.remove(&parent);
             let rename = format!("{}~{}", path_to_string(&parent), name);
@@ -265,7 +280,7 @@ where
             let blob = self.repo.database.load_blob(&item.oid)?;
             self.repo
                 .workspace
-                .write_file(Path::new(&path), blob.data, None, false)?;
+                .write_file(Path::new(&path), &blob.data, None, false)?;
         }
 
         Ok(())
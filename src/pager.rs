@@ -5,52 +5,154 @@ use std::process::{Child, Command, Stdio};
 
 use once_cell::sync::Lazy;
 
-const PAGER_CMD: &str = "less";
-
 static PAGER_ENV: Lazy<HashMap<&'static str, &'static str>> =
     Lazy::new(|| HashMap::from([("LESS", "FRX"), ("LV", "-c")]));
 
+/// A pager process that is only spawned once there is output to show, so commands that print
+/// nothing don't flash an empty pager.
 pub struct Pager {
-    process: Child,
+    command: String,
+    env: HashMap<String, String>,
+    process: Option<Child>,
 }
 
 impl Pager {
-    pub fn new(env: &HashMap<String, String>) -> Self {
-        // GIT_PAGER || PAGER || PAGER_CMD
-        let cmd = match (env.get("GIT_PAGER"), env.get("PAGER")) {
-            (Some(git_pager), _) => git_pager.to_string(),
-            (_, Some(pager)) => pager.to_string(),
-            _ => PAGER_CMD.to_string(),
-        };
-
-        // Merge `env` with `PAGER_ENV`
+    pub fn new(command: &str, env: &HashMap<String, String>) -> Self {
+        // Merge `env` with `PAGER_ENV`, keeping any values the user has already set
         let mut env = env.clone();
         for (key, val) in PAGER_ENV.iter() {
-            env.insert(key.to_string(), val.to_string());
+            env.entry(key.to_string())
+                .or_insert_with(|| val.to_string());
         }
 
-        let p = Command::new(cmd)
-            .envs(&env)
-            .stdin(Stdio::piped())
-            .spawn()
-            .unwrap();
+        Pager {
+            command: command.to_owned(),
+            env,
+            process: None,
+        }
+    }
 
-        Pager { process: p }
+    fn process(&mut self) -> io::Result<&mut Child> {
+        if self.process.is_none() {
+            let argv = match shlex::split(&self.command) {
+                Some(argv) if !argv.is_empty() => argv,
+                _ => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidInput,
+                        format!("invalid pager command '{}'", self.command),
+                    ))
+                }
+            };
+
+            let process = Command::new(&argv[0])
+                .args(&argv[1..])
+                .envs(&self.env)
+                .stdin(Stdio::piped())
+                .spawn()?;
+            self.process = Some(process);
+        }
+
+        Ok(self.process.as_mut().unwrap())
     }
 }
 
 impl Write for Pager {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        self.process.stdin.as_mut().unwrap().write(buf)
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        self.process()?.stdin.as_mut().unwrap().write(buf)
     }
 
     fn flush(&mut self) -> io::Result<()> {
-        self.process.stdin.as_mut().unwrap().flush()
+        match &mut self.process {
+            Some(process) => process.stdin.as_mut().unwrap().flush(),
+            None => Ok(()),
+        }
     }
 }
 
 impl Drop for Pager {
     fn drop(&mut self) {
-        self.process.wait().unwrap();
+        if let Some(process) = &mut self.process {
+            process.wait().unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::{Path, PathBuf};
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    /// Write a pager script that copies its input to `output` and records `$LESS` in `less`.
+    fn fake_pager(dir: &Path) -> (String, PathBuf, PathBuf) {
+        let script = dir.join("pager");
+        let output = dir.join("output");
+        let less = dir.join("less");
+
+        fs::write(
+            &script,
+            format!(
+                "#!/bin/sh\necho \"$LESS\" > {}\ncat > {}\n",
+                less.display(),
+                output.display()
+            ),
+        )
+        .unwrap();
+
+        (format!("sh {}", script.display()), output, less)
+    }
+
+    #[test]
+    fn do_not_spawn_a_pager_for_empty_output() {
+        let dir = TempDir::new().unwrap();
+        let (script, output, _) = fake_pager(dir.path());
+
+        let mut pager = Pager::new(&script, &HashMap::new());
+        pager.write_all(b"").unwrap();
+        pager.flush().unwrap();
+        drop(pager);
+
+        assert!(!output.exists());
+    }
+
+    #[test]
+    fn spawn_a_pager_for_output() {
+        let dir = TempDir::new().unwrap();
+        let (script, output, less) = fake_pager(dir.path());
+
+        let mut pager = Pager::new(&script, &HashMap::new());
+        pager.write_all(b"hello\n").unwrap();
+        drop(pager);
+
+        assert_eq!(fs::read_to_string(output).unwrap(), "hello\n");
+        assert_eq!(fs::read_to_string(less).unwrap(), "FRX\n");
+    }
+
+    #[test]
+    fn fail_to_spawn_a_pager_with_bad_quoting() {
+        let mut pager = Pager::new("less '", &HashMap::new());
+
+        let err = pager.write_all(b"hello\n").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidInput);
+    }
+
+    #[test]
+    fn keep_less_options_from_the_environment() {
+        let dir = TempDir::new().unwrap();
+        let (script, _, less) = fake_pager(dir.path());
+
+        let env = HashMap::from([(String::from("LESS"), String::from("R"))]);
+        let mut pager = Pager::new(&script, &env);
+        pager.write_all(b"hello\n").unwrap();
+        drop(pager);
+
+        assert_eq!(fs::read_to_string(less).unwrap(), "R\n");
     }
 }
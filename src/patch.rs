@@ -0,0 +1,253 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::errors::{Error, Result};
+
+pub mod mailbox;
+
+static HUNK_HEADER: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^@@ -(\d+)(?:,(\d+))? \+(\d+)(?:,(\d+))? @@").unwrap());
+
+const NULL_PATH: &str = "/dev/null";
+
+/// A patch in the format printed by `jit diff`, made of one `FilePatch` per changed file.
+#[derive(Debug, Default)]
+pub struct Patch {
+    pub files: Vec<FilePatch>,
+}
+
+/// The changes to a single file. A file that's created has no `old_path`, and one that's deleted
+/// has no `new_path`.
+#[derive(Debug, Default)]
+pub struct FilePatch {
+    pub old_path: Option<String>,
+    pub new_path: Option<String>,
+    pub old_mode: Option<u32>,
+    pub new_mode: Option<u32>,
+    /// Whether `new_path` is a copy of `old_path`, which is left in place
+    pub copy: bool,
+    hunks: Vec<PatchHunk>,
+}
+
+#[derive(Debug)]
+struct PatchHunk {
+    a_start: usize,
+    a_lines: Vec<String>,
+    b_lines: Vec<String>,
+}
+
+impl Patch {
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut patch = Patch::default();
+        let mut lines = text.lines().enumerate();
+
+        while let Some((number, line)) = lines.next() {
+            if let Some(paths) = line.strip_prefix("diff --git ") {
+                let (old_path, new_path) = paths
+                    .strip_prefix("a/")
+                    .and_then(|paths| paths.split_once(" b/"))
+                    .ok_or(Error::CorruptPatch(number + 1))?;
+                patch.files.push(FilePatch {
+                    old_path: Some(old_path.to_owned()),
+                    new_path: Some(new_path.to_owned()),
+                    ..Default::default()
+                });
+                continue;
+            }
+
+            let file = match patch.files.last_mut() {
+                Some(file) => file,
+                // Skip anything before the first file, like a diffstat
+                None => continue,
+            };
+
+            if let Some(captures) = HUNK_HEADER.captures(line) {
+                let count = |i: usize| -> usize {
+                    captures.get(i).map_or(1, |m| m.as_str().parse().unwrap())
+                };
+                let (mut a_count, mut b_count) = (count(2), count(4));
+                let mut hunk = PatchHunk {
+                    a_start: captures[1].parse().unwrap(),
+                    a_lines: vec![],
+                    b_lines: vec![],
+                };
+
+                while a_count > 0 || b_count > 0 {
+                    let (number, line) = lines.next().ok_or(Error::CorruptPatch(number + 1))?;
+                    // Mail clients sometimes strip the space from blank context lines
+                    let (r#type, text) = match line.chars().next() {
+                        Some(r#type) => (r#type, line.get(1..).unwrap_or("")),
+                        None => (' ', ""),
+                    };
+                    if matches!(r#type, ' ' | '-') {
+                        hunk.a_lines.push(text.to_owned());
+                        a_count = a_count
+                            .checked_sub(1)
+                            .ok_or(Error::CorruptPatch(number + 1))?;
+                    }
+                    if matches!(r#type, ' ' | '+') {
+                        hunk.b_lines.push(text.to_owned());
+                        b_count = b_count
+                            .checked_sub(1)
+                            .ok_or(Error::CorruptPatch(number + 1))?;
+                    }
+                    if !matches!(r#type, ' ' | '-' | '+') {
+                        return Err(Error::CorruptPatch(number + 1));
+                    }
+                }
+
+                file.hunks.push(hunk);
+            } else if let Some(mode) = line.strip_prefix("new file mode ") {
+                file.old_path = None;
+                file.new_mode = Some(parse_mode(mode, number)?);
+            } else if let Some(mode) = line.strip_prefix("deleted file mode ") {
+                file.new_path = None;
+                file.old_mode = Some(parse_mode(mode, number)?);
+            } else if let Some(mode) = line.strip_prefix("old mode ") {
+                file.old_mode = Some(parse_mode(mode, number)?);
+            } else if let Some(mode) = line.strip_prefix("new mode ") {
+                file.new_mode = Some(parse_mode(mode, number)?);
+            } else if let Some(path) = line
+                .strip_prefix("rename from ")
+                .or_else(|| line.strip_prefix("copy from "))
+            {
+                file.old_path = Some(path.to_owned());
+                file.copy = line.starts_with("copy");
+            } else if let Some(path) = line
+                .strip_prefix("rename to ")
+                .or_else(|| line.strip_prefix("copy to "))
+            {
+                file.new_path = Some(path.to_owned());
+            } else if let Some(path) = line.strip_prefix("--- ") {
+                if path == NULL_PATH {
+                    file.old_path = None;
+                }
+            } else if let Some(path) = line.strip_prefix("+++ ") {
+                if path == NULL_PATH {
+                    file.new_path = None;
+                }
+            }
+        }
+
+        Ok(patch)
+    }
+}
+
+impl FilePatch {
+    /// The path the patch is reported under in errors.
+    pub fn path(&self) -> &str {
+        self.new_path
+            .as_deref()
+            .or(self.old_path.as_deref())
+            .unwrap()
+    }
+
+    /// Apply the hunks to `old`, the contents of the file before the change. Like Git, a hunk may
+    /// apply above or below the line it was made against, but its context must match exactly.
+    pub fn apply(&self, old: &str) -> Result<String> {
+        let old_lines: Vec<_> = old.lines().collect();
+        let mut new_lines: Vec<&str> = vec![];
+        let (mut cursor, mut offset) = (0, 0);
+
+        for hunk in &self.hunks {
+            let expected = (hunk.a_start.saturating_sub(1) as isize + offset).max(0) as usize;
+            let position = hunk
+                .find(&old_lines, cursor, expected)
+                .ok_or_else(|| Error::PatchFailed(self.path().to_owned(), hunk.a_start))?;
+
+            new_lines.extend(&old_lines[cursor..position]);
+            new_lines.extend(hunk.b_lines.iter().map(String::as_str));
+            cursor = position + hunk.a_lines.len();
+            offset = position as isize - hunk.a_start.saturating_sub(1) as isize;
+        }
+        new_lines.extend(&old_lines[cursor..]);
+
+        Ok(new_lines.iter().map(|line| format!("{}\n", line)).collect())
+    }
+}
+
+impl PatchHunk {
+    /// Find where the hunk's old lines appear in `lines`, at or after `start`, choosing the match
+    /// closest to `expected`.
+    fn find(&self, lines: &[&str], start: usize, expected: usize) -> Option<usize> {
+        let last = lines.len().checked_sub(self.a_lines.len())?;
+        let matches = |position: usize| {
+            position >= start
+                && position <= last
+                && lines[position..]
+                    .iter()
+                    .zip(&self.a_lines)
+                    .all(|(line, a_line)| line == a_line)
+        };
+
+        (0..=lines.len()).find_map(|distance| {
+            if matches(expected + distance) {
+                Some(expected + distance)
+            } else {
+                expected
+                    .checked_sub(distance)
+                    .filter(|position| matches(*position))
+            }
+        })
+    }
+}
+
+fn parse_mode(mode: &str, number: usize) -> Result<u32> {
+    u32::from_str_radix(mode.trim(), 8).map_err(|_| Error::CorruptPatch(number + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PATCH: &str = "\
+diff --git a/f.txt b/f.txt
+index 0000000..1111111 100644
+--- a/f.txt
++++ b/f.txt
+@@ -1,3 +1,3 @@
+ a
+-b
++B
+ c
+diff --git a/g.txt b/g.txt
+new file mode 100644
+index 0000000..2222222
+--- /dev/null
++++ b/g.txt
+@@ -0,0 +1,1 @@
++new
+";
+
+    #[test]
+    fn parse_the_files_of_a_patch() {
+        let patch = Patch::parse(PATCH).unwrap();
+
+        assert_eq!(patch.files.len(), 2);
+        assert_eq!(patch.files[0].old_path.as_deref(), Some("f.txt"));
+        assert_eq!(patch.files[0].new_path.as_deref(), Some("f.txt"));
+        assert_eq!(patch.files[1].old_path, None);
+        assert_eq!(patch.files[1].new_mode, Some(0o100644));
+    }
+
+    #[test]
+    fn apply_a_hunk_at_an_offset() {
+        let patch = Patch::parse(PATCH).unwrap();
+
+        assert_eq!(
+            patch.files[0].apply("x\ny\na\nb\nc\n").unwrap(),
+            "x\ny\na\nB\nc\n"
+        );
+        assert_eq!(patch.files[1].apply("").unwrap(), "new\n");
+    }
+
+    #[test]
+    fn fail_when_the_context_does_not_match() {
+        let patch = Patch::parse(PATCH).unwrap();
+
+        assert!(matches!(
+            patch.files[0].apply("a\nx\nc\n"),
+            Err(Error::PatchFailed(path, 1)) if path == "f.txt"
+        ));
+    }
+}
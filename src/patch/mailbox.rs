@@ -0,0 +1,358 @@
+use std::borrow::Cow;
+use std::io::{self, Write};
+
+use chrono::DateTime;
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+
+use crate::database::author::Author;
+use crate::database::commit::Commit;
+use crate::database::object::Object;
+use crate::errors::{Error, Result};
+use crate::patch::Patch;
+
+/// The date Git puts in the `From ` line of every message, so mbox readers recognise it.
+const MBOX_DATE: &str = "Mon Sep 17 00:00:00 2001";
+const RFC2822_FORMAT: &str = "%a, %-d %b %Y %H:%M:%S %z";
+
+/// The line starting each message in an mbox, like `From <oid> Mon Sep 17 00:00:00 2001`.
+static FROM_LINE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^From \S+ +\w{3} \w{3} +\d+ \d\d:\d\d:\d\d \d{4}$").unwrap());
+/// Prefixes like `[PATCH 1/2]` and `Re:` that aren't part of the commit's title.
+static SUBJECT_PREFIX: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(?:\s*(?:\[[^\]]*\]|[Rr][Ee]:))+\s*").unwrap());
+
+/// A header word encoded as in RFC 2047, like `=?UTF-8?q?Caf=C3=A9?=`.
+static ENCODED_WORD: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"=\?[Uu][Tt][Ff]-8\?[Qq]\?([^?]*)\?=").unwrap());
+/// A run of encoded words, which may be separated by whitespace that isn't part of the text.
+static ENCODED_WORDS: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"=\?[Uu][Tt][Ff]-8\?[Qq]\?[^?]*\?=(?:\s*=\?[Uu][Tt][Ff]-8\?[Qq]\?[^?]*\?=)*")
+        .unwrap()
+});
+
+/// The longest an encoded word may be, as RFC 2047 requires.
+const MAX_ENCODED_WORD: usize = 75;
+const ENCODED_WORD_START: &str = "=?UTF-8?q?";
+const ENCODED_WORD_END: &str = "?=";
+
+/// A patch sent by email, as written by `jit format-patch`.
+#[derive(Debug)]
+pub struct Message {
+    pub author: Author,
+    /// The commit message, made from the subject and the body of the email
+    pub message: String,
+    pub patch: Patch,
+}
+
+/// Split an mbox into the text of each of its messages.
+pub fn split(mbox: &str) -> Vec<String> {
+    let mut messages: Vec<String> = vec![];
+
+    for line in mbox.split_inclusive('\n') {
+        if FROM_LINE.is_match(line.trim_end()) || messages.is_empty() {
+            messages.push(String::new());
+        }
+        messages.last_mut().unwrap().push_str(line);
+    }
+
+    messages
+        .into_iter()
+        .filter(|message| !message.trim().is_empty())
+        .collect()
+}
+
+/// Write the `From ` separator and the `From:`, `Date:` and `Subject:` headers of an email
+/// carrying `commit`, followed by the blank line ending the headers. `subject_prefix` is put
+/// before the commit's title, like `[PATCH 1/2]`. Like Git, a commit that isn't plain ASCII
+/// also gets MIME headers declaring its body as 8-bit UTF-8.
+pub fn write_headers(out: &mut dyn Write, commit: &Commit, subject_prefix: &str) -> io::Result<()> {
+    writeln!(out, "From {} {}", commit.oid(), MBOX_DATE)?;
+    writeln!(
+        out,
+        "From: {} <{}>",
+        encode_header(&commit.author.name),
+        commit.author.email
+    )?;
+    writeln!(out, "Date: {}", commit.author.time.format(RFC2822_FORMAT))?;
+    writeln!(
+        out,
+        "Subject: {} {}",
+        subject_prefix,
+        encode_header(&commit.title_line())
+    )?;
+    if !commit.author.name.is_ascii() || !commit.message.is_ascii() {
+        writeln!(out, "MIME-Version: 1.0")?;
+        writeln!(out, "Content-Type: text/plain; charset=UTF-8")?;
+        writeln!(out, "Content-Transfer-Encoding: 8bit")?;
+    }
+    writeln!(out)?;
+
+    Ok(())
+}
+
+/// Encode a header value that isn't plain ASCII as RFC 2047 `=?UTF-8?q?...?=` words, so it
+/// survives mail transport. Words are at most 75 characters, so long values are folded onto
+/// continuation lines, and a character's bytes are never split between words.
+///
+/// ```
+/// use jit::patch::mailbox::encode_header;
+///
+/// assert_eq!(encode_header("Fix it"), "Fix it");
+/// assert_eq!(encode_header("Café au lait"), "=?UTF-8?q?Caf=C3=A9=20au=20lait?=");
+/// ```
+pub fn encode_header(value: &str) -> Cow<'_, str> {
+    if value
+        .bytes()
+        .all(|byte| byte.is_ascii_graphic() || byte == b' ')
+    {
+        return Cow::Borrowed(value);
+    }
+
+    let max_text = MAX_ENCODED_WORD - ENCODED_WORD_START.len() - ENCODED_WORD_END.len();
+    let mut words = vec![String::new()];
+    for c in value.chars() {
+        let mut buf = [0; 4];
+        let mut encoded = String::new();
+        for &byte in c.encode_utf8(&mut buf).as_bytes() {
+            if byte.is_ascii_graphic() && !matches!(byte, b'=' | b'?' | b'_') {
+                encoded.push(byte as char);
+            } else {
+                encoded.push_str(&format!("={:02X}", byte));
+            }
+        }
+
+        let word = words.last_mut().unwrap();
+        if word.len() + encoded.len() > max_text {
+            words.push(encoded);
+        } else {
+            word.push_str(&encoded);
+        }
+    }
+
+    let words: Vec<_> = words
+        .iter()
+        .map(|word| format!("{}{}{}", ENCODED_WORD_START, word, ENCODED_WORD_END))
+        .collect();
+
+    Cow::Owned(words.join("\n "))
+}
+
+/// Decode the RFC 2047 words written by `encode_header()`, leaving any other text alone.
+/// Adjacent words are decoded together, dropping the whitespace between them.
+fn decode_header(value: &str) -> Cow<'_, str> {
+    ENCODED_WORDS.replace_all(value, |run: &Captures| {
+        let mut bytes = vec![];
+        for word in ENCODED_WORD.captures_iter(&run[0]) {
+            let text = word[1].as_bytes();
+            let mut i = 0;
+            while i < text.len() {
+                let hex = text
+                    .get(i + 1..i + 3)
+                    .and_then(|hex| std::str::from_utf8(hex).ok())
+                    .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+                match (text[i], hex) {
+                    (b'=', Some(byte)) => {
+                        bytes.push(byte);
+                        i += 3;
+                    }
+                    (b'_', _) => {
+                        bytes.push(b' ');
+                        i += 1;
+                    }
+                    (byte, _) => {
+                        bytes.push(byte);
+                        i += 1;
+                    }
+                }
+            }
+        }
+        String::from_utf8_lossy(&bytes).into_owned()
+    })
+}
+
+impl Message {
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut lines = text.lines().skip_while(|line| FROM_LINE.is_match(line));
+
+        let mut headers: Vec<(String, String)> = vec![];
+        for line in lines.by_ref() {
+            if line.is_empty() {
+                break;
+            }
+            match (line.strip_prefix(char::is_whitespace), headers.last_mut()) {
+                // Long headers are folded onto lines starting with whitespace
+                (Some(rest), Some((_, value))) => {
+                    value.push(' ');
+                    value.push_str(rest.trim());
+                }
+                _ => {
+                    if let Some((name, value)) = line.split_once(':') {
+                        headers.push((name.to_lowercase(), value.trim().to_owned()));
+                    }
+                }
+            }
+        }
+        let header = |name: &str| {
+            headers
+                .iter()
+                .find(|(header, _)| header == name)
+                .map(|(_, value)| decode_header(value))
+        };
+
+        let from = header("from").unwrap_or_default();
+        let (name, email) = from.split_once('<').ok_or(Error::EmptyPatch)?;
+        let date = header("date")
+            .and_then(|date| DateTime::parse_from_rfc2822(&date).ok())
+            .ok_or(Error::EmptyPatch)?;
+        let author = Author::new(
+            name.trim().trim_matches('"').to_owned(),
+            email.trim_end().trim_end_matches('>').to_owned(),
+            date,
+        );
+
+        let subject = header("subject").unwrap_or_default();
+        let subject = SUBJECT_PREFIX.replace(&subject, "");
+
+        let mut body = vec![];
+        let mut diff = vec![];
+        for line in lines.by_ref() {
+            if line == "---" || line.starts_with("diff --git ") {
+                diff.push(line);
+                break;
+            }
+            body.push(line);
+        }
+        // Anything after the last hunk, like the signature, is ignored by `Patch::parse()`
+        diff.extend(lines);
+
+        let diff = diff.join("\n");
+        if !diff.contains("diff --git ") {
+            return Err(Error::EmptyPatch);
+        }
+
+        let body = body.join("\n");
+        let body = body.trim_start_matches('\n').trim_end();
+        let message = if body.is_empty() {
+            format!("{}\n", subject)
+        } else {
+            format!("{}\n\n{}\n", subject, body)
+        };
+
+        Ok(Message {
+            author,
+            message,
+            patch: Patch::parse(&diff)?,
+        })
+    }
+
+    pub fn title_line(&self) -> &str {
+        self.message.lines().next().unwrap_or("")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MBOX: &str = "\
+From 1111111111111111111111111111111111111111 Mon Sep 17 00:00:00 2001
+From: A. U. Thor <author@example.com>
+Date: Thu, 2 Jan 2020 03:04:05 +0100
+Subject: [PATCH 1/2] Change f
+
+Body line
+---
+ f.txt | 1 +
+ 1 file changed, 1 insertion(+)
+
+diff --git a/f.txt b/f.txt
+index 0000000..1111111 100644
+--- a/f.txt
++++ b/f.txt
+@@ -1,1 +1,2 @@
+ one
++two
+--\x20
+0.1.0
+
+From 2222222222222222222222222222222222222222 Mon Sep 17 00:00:00 2001
+From: \"Other Person\" <other@example.com>
+Date: Fri, 3 Jan 2020 03:04:05 +0100
+Subject: [PATCH 2/2] Change g
+ over two lines
+
+---
+diff --git a/g.txt b/g.txt
+new file mode 100644
+--- /dev/null
++++ b/g.txt
+@@ -0,0 +1,1 @@
++new
+--\x20
+0.1.0
+
+";
+
+    #[test]
+    fn split_an_mbox_into_messages() {
+        let messages = split(MBOX);
+
+        assert_eq!(messages.len(), 2);
+        assert!(messages[1].starts_with("From 2222"));
+    }
+
+    #[test]
+    fn parse_the_author_message_and_patch() {
+        let messages = split(MBOX);
+
+        let message = Message::parse(&messages[0]).unwrap();
+        assert_eq!(message.author.name, "A. U. Thor");
+        assert_eq!(message.author.email, "author@example.com");
+        assert_eq!(
+            message.author.readable_time(),
+            "Thu Jan 2 03:04:05 2020 +0100"
+        );
+        assert_eq!(message.message, "Change f\n\nBody line\n");
+        assert_eq!(message.patch.files.len(), 1);
+
+        let message = Message::parse(&messages[1]).unwrap();
+        assert_eq!(message.author.name, "Other Person");
+        assert_eq!(message.message, "Change g over two lines\n");
+        assert_eq!(message.patch.files[0].old_path, None);
+    }
+
+    #[test]
+    fn decode_encoded_headers() {
+        let text = MBOX
+            .replacen("A. U. Thor", &encode_header("Jörg Thor"), 1)
+            .replacen("Change f", &encode_header("Change ƒ"), 1);
+        let messages = split(&text);
+
+        let message = Message::parse(&messages[0]).unwrap();
+        assert_eq!(message.author.name, "Jörg Thor");
+        assert_eq!(message.message, "Change ƒ\n\nBody line\n");
+    }
+
+    #[test]
+    fn fold_long_encoded_headers() {
+        let subject = "Ünïcödé ".repeat(8);
+        let encoded = encode_header(&subject);
+
+        let words: Vec<_> = encoded.split("\n ").collect();
+        assert!(words.len() > 1);
+        assert!(words.iter().all(|word| word.len() <= MAX_ENCODED_WORD));
+        assert!(words
+            .iter()
+            .all(|word| word.starts_with("=?UTF-8?q?") && word.ends_with("?=")));
+        assert_eq!(decode_header(&encoded.replace("\n ", " ")), subject);
+    }
+
+    #[test]
+    fn fail_for_a_message_without_a_patch() {
+        assert!(matches!(
+            Message::parse("From: A <a@example.com>\nDate: Thu, 2 Jan 2020 03:04:05 +0100\n\nhi\n"),
+            Err(Error::EmptyPatch)
+        ));
+    }
+}
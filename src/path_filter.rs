@@ -17,6 +17,8 @@ impl Trie {
         }
     }
 
+    /// Build a trie matching any of `paths`, or everything if there are none. A path inside
+    /// another is already matched by it, so it's dropped, leaving each file matched once.
     pub fn from_paths(paths: &[PathBuf]) -> Self {
         let mut root = Trie::node();
 
@@ -25,21 +27,17 @@ impl Trie {
         }
 
         for path in paths {
-            let names: Vec<_> = path.iter().map(PathBuf::from).collect();
-
-            let mut trie = root
-                .children
-                .entry(names[0].clone())
-                .or_insert_with(Trie::node);
-
-            for name in &names[1..] {
-                trie = trie
-                    .children
-                    .entry(name.to_owned())
-                    .or_insert_with(Trie::node);
+            let mut trie = &mut root;
+
+            for name in path.iter().map(PathBuf::from) {
+                if trie.matched {
+                    break;
+                }
+                trie = trie.children.entry(name).or_insert_with(Trie::node);
             }
 
             trie.matched = true;
+            trie.children.clear();
         }
 
         root
@@ -53,6 +51,8 @@ impl Trie {
     }
 }
 
+/// Selects the tree entries at or inside any of a set of paths, so a commit is shown by `jit log`
+/// if it changes any of them.
 #[derive(Debug, Clone)]
 pub struct PathFilter {
     routes: Trie,
@@ -93,3 +93,25 @@ impl PathFilter {
         PathFilter::new(Some(next_routes), Some(self.path.join(name)))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(trie: &Trie) -> Vec<PathBuf> {
+        let mut names: Vec<_> = trie.children.keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn drop_paths_inside_other_paths() {
+        let paths = ["b/c/2.txt", "b", "b/c", "a/1.txt"].map(PathBuf::from);
+        let trie = Trie::from_paths(&paths);
+
+        assert_eq!(names(&trie), vec![PathBuf::from("a"), PathBuf::from("b")]);
+        let b = &trie.children[&PathBuf::from("b")];
+        assert!(b.matched);
+        assert!(b.children.is_empty());
+    }
+}
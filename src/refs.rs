@@ -20,6 +20,8 @@ static SYMREF: Lazy<Regex> = Lazy::new(|| Regex::new(r"^ref: (.+)$").unwrap());
 static REFS_DIR: Lazy<PathBuf> = Lazy::new(|| PathBuf::from("refs"));
 pub static HEADS_DIR: Lazy<PathBuf> = Lazy::new(|| REFS_DIR.join("heads"));
 pub static REMOTES_DIR: Lazy<PathBuf> = Lazy::new(|| REFS_DIR.join("remotes"));
+pub static TAGS_DIR: Lazy<PathBuf> = Lazy::new(|| REFS_DIR.join("tags"));
+static STASH: &str = "refs/stash";
 
 #[derive(Debug, PartialEq, Eq)]
 pub enum Ref {
@@ -42,6 +44,23 @@ pub struct Refs {
     refs_path: PathBuf,
     heads_path: PathBuf,
     remotes_path: PathBuf,
+    tags_path: PathBuf,
+}
+
+/// Where a ref named `path` goes among the decorations of a commit.
+fn decoration_rank(path: &str) -> usize {
+    let path = Path::new(path);
+    let namespaces = [&*HEADS_DIR, &*TAGS_DIR, &*REMOTES_DIR];
+
+    if path == Path::new(HEAD) {
+        0
+    } else if let Some(i) = namespaces.iter().position(|dir| path.starts_with(dir)) {
+        i + 1
+    } else if path == Path::new(STASH) {
+        4
+    } else {
+        5
+    }
 }
 
 impl Refs {
@@ -49,12 +68,14 @@ impl Refs {
         let refs_path = pathname.join(&*REFS_DIR);
         let heads_path = pathname.join(&*HEADS_DIR);
         let remotes_path = pathname.join(&*REMOTES_DIR);
+        let tags_path = pathname.join(&*TAGS_DIR);
 
         Refs {
             pathname,
             refs_path,
             heads_path,
             remotes_path,
+            tags_path,
         }
     }
 
@@ -78,6 +99,55 @@ impl Refs {
         self.update_ref_file(self.pathname.join(name), oid)
     }
 
+    /// Point the ref `name` at `new`, or delete it if `new` is `None`, following symbolic refs.
+    /// If `old` is given, the ref's current value is checked against it while holding the ref's
+    /// lock, and an empty `old` requires that the ref does not exist yet.
+    pub fn compare_and_swap(&self, name: &str, old: Option<&str>, new: Option<&str>) -> Result<()> {
+        let path = self.resolve_symbolic_path(self.pathname.join(name))?;
+
+        let mut lockfile = Lockfile::new(path.clone());
+        match lockfile.hold_for_update() {
+            Ok(()) => (),
+            Err(Error::Io(err)) if err.kind() == io::ErrorKind::NotFound => {
+                fs::create_dir_all(path.parent().unwrap())?;
+                lockfile.hold_for_update()?;
+            }
+            Err(err) => return Err(err),
+        }
+
+        let current = match self.read_oid_or_symref(&path)? {
+            Some(Ref::Ref { oid }) => Some(oid),
+            _ => None,
+        };
+
+        let mismatch = match (old, &current) {
+            (None, _) => None,
+            (Some(""), Some(_)) => Some(String::from("reference already exists")),
+            (Some(""), None) => None,
+            (Some(_), None) => Some(format!("unable to resolve reference '{}'", name)),
+            (Some(old), Some(current)) if old != current => {
+                Some(format!("is at {} but expected {}", current, old))
+            }
+            (Some(_), Some(_)) => None,
+        };
+        if let Some(reason) = mismatch {
+            lockfile.rollback()?;
+            return Err(Error::CannotLockRef(name.to_string(), reason));
+        }
+
+        match new {
+            Some(new) => self.write_lockfile(&mut lockfile, new),
+            None => {
+                if current.is_some() {
+                    fs::remove_file(&path)?;
+                }
+                lockfile.rollback()?;
+
+                self.delete_parent_directories(&path)
+            }
+        }
+    }
+
     pub fn create_branch(&self, branch_name: &str, start_oid: String) -> Result<()> {
         let path = self.heads_path.join(branch_name);
 
@@ -114,6 +184,25 @@ impl Refs {
         Ok(())
     }
 
+    /// Read the ref that the symbolic ref `name` points at, without following it any further.
+    pub fn read_symbolic_ref(&self, name: &str) -> Result<Option<String>> {
+        match self.read_oid_or_symref(&self.pathname.join(name))? {
+            Some(Ref::SymRef { path }) => Ok(Some(path)),
+            Some(Ref::Ref { .. }) | None => Ok(None),
+        }
+    }
+
+    pub fn update_symbolic_ref(&self, name: &str, target: &str) -> Result<()> {
+        if !target.starts_with("refs/") {
+            return Err(Error::InvalidArgument(format!(
+                "Refusing to point {} outside of refs/",
+                name
+            )));
+        }
+
+        self.update_ref_file(self.pathname.join(name), &format!("ref: {}", target))
+    }
+
     pub fn current_ref(&self, source: &str) -> Result<Ref> {
         let r#ref = self.read_oid_or_symref(&self.pathname.join(source))?;
 
@@ -132,10 +221,21 @@ impl Refs {
         }
     }
 
+    /// The branches under `refs/heads`, sorted by name.
     pub fn list_branches(&self) -> Result<Vec<Ref>> {
         self.list_refs(&self.heads_path)
     }
 
+    /// The tags under `refs/tags`, sorted by name.
+    pub fn list_tags(&self) -> Result<Vec<Ref>> {
+        self.list_refs(&self.tags_path)
+    }
+
+    /// The remote-tracking branches under `refs/remotes`, sorted by name.
+    pub fn list_remotes(&self) -> Result<Vec<Ref>> {
+        self.list_refs(&self.remotes_path)
+    }
+
     pub fn short_name(&self, r#ref: &Ref) -> String {
         match r#ref {
             Ref::SymRef { path } => {
@@ -187,6 +287,8 @@ impl Refs {
         }
     }
 
+    /// The refs pointing at each commit, ordered like Git's decorations: `HEAD`, branches, tags,
+    /// remotes, then the stash.
     pub fn reverse_refs(&self) -> Result<HashMap<String, Vec<Ref>>> {
         let mut table = HashMap::new();
 
@@ -200,6 +302,12 @@ impl Refs {
                 table.get_mut(&oid).unwrap().push(r#ref);
             }
         }
+        for refs in table.values_mut() {
+            refs.sort_by_cached_key(|r#ref| match r#ref {
+                Ref::SymRef { path } => (decoration_rank(path), path.to_owned()),
+                Ref::Ref { .. } => unreachable!(),
+            });
+        }
 
         Ok(table)
     }
@@ -208,6 +316,7 @@ impl Refs {
         let prefixes = [
             self.pathname.clone(),
             self.refs_path.clone(),
+            self.tags_path.clone(),
             self.heads_path.clone(),
             self.remotes_path.clone(),
         ];
@@ -241,6 +350,15 @@ impl Refs {
         self.write_lockfile(&mut lockfile, oid)
     }
 
+    /// Follow the chain of symbolic refs starting at `path` to the file that holds an object ID,
+    /// or would hold one if it existed.
+    fn resolve_symbolic_path(&self, path: PathBuf) -> Result<PathBuf> {
+        match self.read_oid_or_symref(&path)? {
+            Some(Ref::SymRef { path }) => self.resolve_symbolic_path(self.pathname.join(path)),
+            Some(Ref::Ref { .. }) | None => Ok(path),
+        }
+    }
+
     fn read_oid_or_symref(&self, path: &Path) -> Result<Option<Ref>> {
         if path.exists() {
             let mut data = String::new();
@@ -308,9 +426,18 @@ impl Refs {
     fn list_refs(&self, dirname: &Path) -> Result<Vec<Ref>> {
         let mut result = vec![];
 
-        for name in fs::read_dir(self.pathname.join(dirname))? {
-            let path = name?.path();
-
+        let entries = match fs::read_dir(self.pathname.join(dirname)) {
+            Ok(entries) => entries,
+            // Namespaces like `refs/remotes` only exist once a ref is written to them
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(result),
+            Err(err) => return Err(err.into()),
+        };
+        let mut paths = entries
+            .map(|entry| Ok(entry?.path()))
+            .collect::<io::Result<Vec<_>>>()?;
+        paths.sort();
+
+        for path in paths {
             if path.is_dir() {
                 result.append(&mut self.list_refs(&path)?);
             } else {
@@ -324,7 +451,8 @@ impl Refs {
         Ok(result)
     }
 
-    fn list_all_refs(&self) -> Result<Vec<Ref>> {
+    /// `HEAD`, followed by every ref under `refs`, sorted by name.
+    pub fn list_all_refs(&self) -> Result<Vec<Ref>> {
         let mut result = vec![Ref::SymRef {
             path: HEAD.to_string(),
         }];
@@ -335,7 +463,8 @@ impl Refs {
 
     fn delete_parent_directories(&self, path: &Path) -> Result<()> {
         for dir in parent_directories(path) {
-            if dir == self.heads_path {
+            // Keep `refs/` and the namespaces directly inside it, like `refs/heads/`
+            if dir == self.refs_path || dir.parent() == Some(&self.refs_path) {
                 break;
             }
 
@@ -354,3 +483,75 @@ impl Refs {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    fn paths(refs: Vec<Ref>) -> Vec<String> {
+        refs.into_iter()
+            .map(|r#ref| match r#ref {
+                Ref::SymRef { path } => path,
+                Ref::Ref { .. } => unreachable!(),
+            })
+            .collect()
+    }
+
+    #[test]
+    fn list_branches_tags_and_remotes() -> Result<()> {
+        let git_dir = TempDir::new()?;
+        let refs = Refs::new(git_dir.path().to_path_buf());
+        let oid = "a".repeat(40);
+
+        refs.update_head("ref: refs/heads/main")?;
+        for name in [
+            "refs/heads/main",
+            "refs/heads/feature/one",
+            "refs/remotes/origin/main",
+            "refs/tags/v1.0",
+        ] {
+            refs.update_ref(name, &oid)?;
+        }
+
+        assert_eq!(
+            paths(refs.list_branches()?),
+            vec!["refs/heads/feature/one", "refs/heads/main"]
+        );
+        assert_eq!(paths(refs.list_tags()?), vec!["refs/tags/v1.0"]);
+        assert_eq!(
+            paths(refs.list_remotes()?),
+            vec!["refs/remotes/origin/main"]
+        );
+        assert_eq!(
+            paths(refs.list_all_refs()?),
+            vec![
+                "HEAD",
+                "refs/heads/feature/one",
+                "refs/heads/main",
+                "refs/remotes/origin/main",
+                "refs/tags/v1.0",
+            ]
+        );
+
+        let main = Ref::SymRef {
+            path: String::from("refs/heads/main"),
+        };
+        assert_eq!(refs.short_name(&main), "main");
+        assert_eq!(refs.read_oid(&main)?, Some(oid));
+
+        Ok(())
+    }
+
+    #[test]
+    fn list_nothing_in_a_missing_namespace() -> Result<()> {
+        let git_dir = TempDir::new()?;
+        let refs = Refs::new(git_dir.path().to_path_buf());
+
+        assert_eq!(refs.list_remotes()?, vec![]);
+        assert_eq!(refs.list_tags()?, vec![]);
+
+        Ok(())
+    }
+}
@@ -7,6 +7,7 @@ use remote::Remote;
 use crate::config::{Config, VariableValue};
 use crate::errors::{Error, Result};
 use crate::refs::{HEADS_DIR, REMOTES_DIR};
+use crate::util::path_to_string;
 
 mod refspec;
 mod remote;
@@ -97,6 +98,56 @@ impl Remotes {
         Ok(config.subsections("remote"))
     }
 
+    /// The remote-tracking ref that `branch` is set to merge from, as in `<branch>@{upstream}`.
+    pub fn get_upstream(&self, branch: &str) -> Result<Option<String>> {
+        let name = self.branch_config(branch, "remote")?;
+
+        Ok(match name {
+            Some(name) => self
+                .get(&name)?
+                .and_then(|remote| remote.get_upstream(branch)),
+            None => None,
+        })
+    }
+
+    /// The remote-tracking ref for where `branch` would be pushed to, as in `<branch>@{push}`,
+    /// or `None` if it has no remote to push to.
+    pub fn get_push_target(&self, branch: &str) -> Result<Option<String>> {
+        let name = match self.branch_config(branch, "pushRemote")? {
+            Some(name) => Some(name),
+            None => match self.config_value(&["remote", "pushDefault"])? {
+                Some(name) => Some(name),
+                None => self.branch_config(branch, "remote")?,
+            },
+        };
+        let remote = match name {
+            Some(name) => self.get(&name)?,
+            None => None,
+        };
+
+        Ok(match remote {
+            Some(remote)
+                if self.config_value(&["push", "default"])?.as_deref() == Some("upstream") =>
+            {
+                remote.get_upstream(branch)
+            }
+            Some(remote) => remote.tracking_ref(&path_to_string(&HEADS_DIR.join(branch))),
+            None => None,
+        })
+    }
+
+    fn branch_config(&self, branch: &str, name: &str) -> Result<Option<String>> {
+        self.config_value(&["branch", branch, name])
+    }
+
+    fn config_value(&self, key: &[&str]) -> Result<Option<String>> {
+        let mut config = self.config.borrow_mut();
+        config.open()?;
+
+        let key: Vec<_> = key.iter().map(|part| part.to_string()).collect();
+        Ok(config.get(&key).map(|value| value.to_string()))
+    }
+
     pub fn get(&self, name: &str) -> Result<Option<Remote>> {
         {
             let mut config = self.config.borrow_mut();
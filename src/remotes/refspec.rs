@@ -1,8 +1,13 @@
 use std::fmt;
 use std::path::PathBuf;
 
+use once_cell::sync::Lazy;
+use regex::Regex;
+
 use crate::util::path_to_string;
 
+static REFSPEC_FORMAT: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\+?)([^:]+):([^:]+)$").unwrap());
+
 pub struct Refspec {
     source: PathBuf,
     target: PathBuf,
@@ -17,6 +22,31 @@ impl Refspec {
             forced,
         }
     }
+
+    pub fn parse(spec: &str) -> Option<Self> {
+        let captures = REFSPEC_FORMAT.captures(spec)?;
+
+        Some(Self::new(
+            PathBuf::from(&captures[2]),
+            PathBuf::from(&captures[3]),
+            &captures[1] == "+",
+        ))
+    }
+
+    /// The target ref that `name` is mapped to if it matches the source, like
+    /// `refs/remotes/origin/main` for `refs/heads/main` with `refs/heads/*:refs/remotes/origin/*`.
+    pub fn map_ref(&self, name: &str) -> Option<String> {
+        let source = path_to_string(&self.source);
+        let target = path_to_string(&self.target);
+
+        match source.split_once('*') {
+            Some((prefix, suffix)) => {
+                let matched = name.strip_prefix(prefix)?.strip_suffix(suffix)?;
+                Some(target.replacen('*', matched, 1))
+            }
+            None => (source == name).then_some(target),
+        }
+    }
 }
 
 impl fmt::Display for Refspec {
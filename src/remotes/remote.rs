@@ -1,6 +1,7 @@
 use std::cell::RefCell;
 use std::rc::Rc;
 
+use super::Refspec;
 use crate::config::{Config, VariableValue};
 use crate::errors::Result;
 
@@ -47,6 +48,31 @@ impl Remote {
         }
     }
 
+    /// The remote-tracking ref for the branch `branch` merges from, from `branch.<name>.merge`.
+    pub fn get_upstream(&self, branch: &str) -> Option<String> {
+        let merge = self.config.borrow().get(&[
+            String::from("branch"),
+            branch.to_string(),
+            String::from("merge"),
+        ])?;
+
+        self.tracking_ref(&merge.to_string())
+    }
+
+    /// The ref that fetching `name` from this remote updates, according to its fetch refspecs.
+    pub fn tracking_ref(&self, name: &str) -> Option<String> {
+        self.config
+            .borrow()
+            .get_all(&[
+                String::from("remote"),
+                self.name.to_string(),
+                String::from("fetch"),
+            ])
+            .iter()
+            .filter_map(|spec| Refspec::parse(&spec.to_string()))
+            .find_map(|spec| spec.map_ref(name))
+    }
+
     pub fn uploader(&self) -> Option<VariableValue> {
         self.config.borrow().get(&[
             String::from("remote"),
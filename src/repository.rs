@@ -1,27 +1,48 @@
+use std::collections::HashSet;
 use std::fs;
 use std::path::{Path, PathBuf};
 
 use crate::config::stack::{ConfigFile, Stack as ConfigStack};
+use crate::config::VariableValue;
 use crate::database::blob::Blob;
-use crate::database::tree::TreeEntry;
+use crate::database::tree::{TreeEntry, GITLINK_MODE, TREE_MODE};
 use crate::database::tree_diff::TreeDiffChanges;
-use crate::database::Database;
+use crate::database::{Database, DEFAULT_ABBREV};
 use crate::errors::Result;
 use crate::index::{Entry as IndexEntry, Index};
-use crate::refs::Refs;
+use crate::patch::Patch;
+use crate::refs::{Refs, ORIG_HEAD};
 use crate::remotes::Remotes;
+use crate::repository::attributes::Attributes;
+use crate::repository::ignore::Ignore;
 use crate::repository::pending_commit::PendingCommit;
+use crate::repository::sequencer::Sequencer;
+use crate::repository::sparse_checkout::SparseCheckout;
 use crate::workspace::Workspace;
 
+mod apply_patch;
+pub mod attributes;
 mod hard_reset;
+pub mod ignore;
 pub mod migration;
+pub mod notes;
 pub mod pending_commit;
 pub mod sequencer;
+pub mod sparse_checkout;
 pub mod status;
+pub mod submodules;
 
+use apply_patch::ApplyPatch;
 use hard_reset::HardReset;
 use migration::Migration;
+use notes::Notes;
 use status::Status;
+use submodules::Submodule;
+
+/// The default number of loose objects `gc --auto` allows before it has work to do.
+const DEFAULT_GC_AUTO: i64 = 6700;
+/// The default number of packs `gc --auto` allows before it has work to do.
+const DEFAULT_GC_AUTO_PACK_LIMIT: i64 = 50;
 
 #[derive(Debug, PartialEq, Eq, Hash)]
 pub enum ChangeType {
@@ -52,40 +73,323 @@ pub struct Repository {
 impl Repository {
     pub fn new(git_path: PathBuf) -> Self {
         let root_path = git_path.parent().unwrap().to_path_buf();
+        let git_path = Self::resolve_gitfile(&root_path, git_path);
         let mut config = ConfigStack::new(&git_path);
         let remotes = Remotes::new(config.file(ConfigFile::Local));
 
+        let database = Database::new(git_path.join("objects"));
+        let mut workspace = Workspace::new(root_path.clone(), git_path.clone());
+        let mut index = Index::new(git_path.join("index"));
+        Self::apply_core_config(&git_path, &database, &mut workspace, &mut index);
+
         Repository {
             root_path,
             git_path: git_path.clone(),
-            database: Database::new(git_path.join("objects")),
-            index: Index::new(git_path.join("index")),
+            database,
+            index,
             refs: Refs::new(git_path.clone()),
-            workspace: Workspace::new(git_path.parent().unwrap().to_path_buf()),
+            workspace,
             config,
             remotes,
         }
     }
 
+    /// Apply `core.blobCacheLimit`, `core.symlinks`, `core.ignoreCase` and the jit-specific
+    /// `jit.mmapIndex`, ignoring invalid config files. A separate config stack is used so `Repository.config` is only read when a command
+    /// needs it.
+    fn apply_core_config(
+        git_path: &Path,
+        database: &Database,
+        workspace: &mut Workspace,
+        index: &mut Index,
+    ) {
+        let config = ConfigStack::new(git_path);
+        if config.open().is_err() {
+            return;
+        }
+        let key = |name: &str| [String::from("core"), name.to_owned()];
+
+        if let Some(limit) = config.get_int(&key("blobCacheLimit")) {
+            if let Ok(limit) = usize::try_from(limit) {
+                database.set_blob_cache_limit(limit);
+            }
+        }
+        if let Some(symlinks) = config.get_bool(&key("symlinks")) {
+            workspace.set_symlinks(symlinks);
+        }
+        if let Some(ignore_case) = config.get_bool(&key("ignoreCase")) {
+            index.set_ignore_case(ignore_case);
+        }
+        if let Some(mmap) = config.get_bool(&[String::from("jit"), String::from("mmapIndex")]) {
+            index.set_mmap(mmap);
+        }
+    }
+
+    /// The root of the workspace `dir` is in: the closest of `dir` and its parents that has a
+    /// `.git` directory or file.
+    pub fn discover(dir: &Path) -> Option<PathBuf> {
+        dir.ancestors()
+            .find(|dir| dir.join(".git").exists())
+            .map(Path::to_path_buf)
+    }
+
+    /// If `git_path` is a file containing `gitdir: <path>`, as used by linked worktrees and
+    /// submodules, return the directory it points to. Relative paths are resolved against
+    /// `root_path`.
+    fn resolve_gitfile(root_path: &Path, git_path: PathBuf) -> PathBuf {
+        if !git_path.is_file() {
+            return git_path;
+        }
+
+        match fs::read_to_string(&git_path) {
+            Ok(contents) => match contents.trim_end().strip_prefix("gitdir: ") {
+                Some(path) => root_path.join(path),
+                None => git_path,
+            },
+            Err(_) => git_path,
+        }
+    }
+
     pub fn hard_reset(&mut self, oid: &str) -> Result<()> {
         HardReset::new(self, oid).execute()?;
 
         Ok(())
     }
 
+    pub fn apply_patch(&mut self, patch: &Patch) -> Result<()> {
+        ApplyPatch::new(self, patch).execute()
+    }
+
     pub fn migration(&mut self, tree_diff: TreeDiffChanges) -> Migration {
         Migration::new(self, tree_diff)
     }
 
+    pub fn notes(&self) -> Notes<'_> {
+        Notes::new(&self.database, &self.refs)
+    }
+
     pub fn pending_commit(&self) -> PendingCommit {
         PendingCommit::new(&self.git_path)
     }
 
+    /// Commits that operations in progress still need, though no branch or tag may reach them:
+    /// `ORIG_HEAD`, the commit being merged, cherry-picked or reverted, and the commits left in
+    /// the sequencer's todo list.
+    pub fn extra_roots(&self) -> Result<Vec<String>> {
+        let mut roots: Vec<_> = self.refs.read_ref(ORIG_HEAD)?.into_iter().collect();
+
+        let pending_commit = self.pending_commit();
+        if let Some(merge_type) = pending_commit.merge_type() {
+            roots.push(pending_commit.merge_oid(merge_type)?);
+        }
+        for oid in Sequencer::new(self).todo_oids()? {
+            if !roots.contains(&oid) {
+                roots.push(oid);
+            }
+        }
+
+        Ok(roots)
+    }
+
+    /// The objects reachable from the refs, `HEAD`, the `extra_roots()` and the index, which
+    /// must be loaded first. Submodule commits are left out as they belong to another
+    /// repository.
+    pub fn reachable_objects(&self) -> Result<HashSet<String>> {
+        let mut reachable: HashSet<_> = self
+            .index
+            .entries
+            .values()
+            .filter(|entry| entry.mode != GITLINK_MODE)
+            .map(|entry| entry.oid.clone())
+            .collect();
+
+        let mut commits = self.extra_roots()?;
+        for r#ref in self.refs.list_all_refs()? {
+            commits.extend(self.refs.read_oid(&r#ref)?);
+        }
+
+        while let Some(oid) = commits.pop() {
+            if !reachable.insert(oid.clone()) {
+                continue;
+            }
+
+            let commit = self.database.load_commit(&oid)?;
+            self.mark_tree_reachable(&commit.tree, &mut reachable)?;
+            commits.extend(commit.parents);
+        }
+
+        Ok(reachable)
+    }
+
+    fn mark_tree_reachable(&self, oid: &str, reachable: &mut HashSet<String>) -> Result<()> {
+        if !reachable.insert(oid.to_owned()) {
+            return Ok(());
+        }
+
+        for entry in self.database.load_tree(oid)?.entries.values() {
+            match entry.mode() {
+                TREE_MODE => self.mark_tree_reachable(&entry.oid(), reachable)?,
+                GITLINK_MODE => (),
+                _ => {
+                    reachable.insert(entry.oid());
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether `gc --auto` has housekeeping to do: there are more than `gc.auto` loose objects, or
+    /// more than `gc.autoPackLimit` packs. Setting either to 0 turns that check off, and with
+    /// `gc.auto` at 0 nothing is ever due.
+    pub fn needs_auto_gc(&self) -> Result<bool> {
+        self.config.open()?;
+        let gc_auto = self
+            .config
+            .get_int(&[String::from("gc"), String::from("auto")])
+            .unwrap_or(DEFAULT_GC_AUTO);
+        let gc_auto_pack_limit = self
+            .config
+            .get_int(&[String::from("gc"), String::from("autoPackLimit")])
+            .unwrap_or(DEFAULT_GC_AUTO_PACK_LIMIT);
+
+        if gc_auto <= 0 {
+            return Ok(false);
+        }
+        if gc_auto_pack_limit > 0 && self.database.count_packs()? as i64 > gc_auto_pack_limit {
+            return Ok(true);
+        }
+
+        Ok(self.database.count_loose_objects()? as i64 > gc_auto)
+    }
+
+    /// The patterns naming the untracked files `jit add` leaves out.
+    pub fn ignore(&self) -> Result<Ignore> {
+        let mut ignore = Ignore::new(&self.root_path, &self.git_path);
+        ignore.load()?;
+
+        Ok(ignore)
+    }
+
+    /// The attributes `.gitattributes` files give each path, like its diff driver.
+    pub fn attributes(&self) -> Result<Attributes> {
+        let mut attributes = Attributes::new(&self.root_path, &self.git_path);
+        attributes.load()?;
+
+        Ok(attributes)
+    }
+
+    /// The length `core.abbrev` gives abbreviated object IDs. `auto` uses the default, and `no`
+    /// leaves them whole.
+    pub fn abbrev(&self) -> Result<usize> {
+        self.config.open()?;
+
+        Ok(
+            match self
+                .config
+                .get(&[String::from("core"), String::from("abbrev")])
+            {
+                Some(VariableValue::Int(length)) => {
+                    usize::try_from(length).unwrap_or(DEFAULT_ABBREV)
+                }
+                Some(VariableValue::Bool(false)) => usize::MAX,
+                _ => DEFAULT_ABBREV,
+            },
+        )
+    }
+
+    /// Whether paths in the output quote bytes above `0x7f`, which `core.quotePath` can turn off.
+    pub fn quote_path(&self) -> Result<bool> {
+        self.config.open()?;
+
+        Ok(self
+            .config
+            .get_bool(&[String::from("core"), String::from("quotePath")])
+            .unwrap_or(true))
+    }
+
+    /// The sparse checkout patterns, if `core.sparseCheckout` is enabled.
+    pub fn sparse_checkout(&self) -> Result<Option<SparseCheckout>> {
+        self.config.open()?;
+        let enabled = self
+            .config
+            .get_bool(&[String::from("core"), String::from("sparseCheckout")]);
+        if enabled != Some(true) {
+            return Ok(None);
+        }
+
+        let mut sparse_checkout = SparseCheckout::new(&self.git_path);
+        sparse_checkout.load()?;
+        Ok(Some(sparse_checkout))
+    }
+
+    /// Remove the files `sparse_checkout` excludes from the workspace, marking them as
+    /// skip-worktree in the index, and restore the ones it includes. With no patterns, every file
+    /// is restored. Files with uncommitted changes are left in place, and their paths returned.
+    pub fn update_sparse_checkout(
+        &mut self,
+        sparse_checkout: Option<&SparseCheckout>,
+    ) -> Result<Vec<String>> {
+        let entries: Vec<_> = self
+            .index
+            .entries
+            .values()
+            .filter(|entry| entry.stage() == 0)
+            .cloned()
+            .collect();
+        let mut not_up_to_date = vec![];
+
+        for entry in entries {
+            let path = Path::new(&entry.path);
+            let included = sparse_checkout.is_none_or(|patterns| patterns.includes(path));
+
+            if included && entry.skip_worktree() {
+                let blob = self.database.load_blob(&entry.oid)?;
+                self.workspace
+                    .write_file(path, &blob.data, Some(entry.mode), true)?;
+                let stat = self.workspace.stat_file(path)?.unwrap();
+                self.index
+                    .add_with_mode(path.to_path_buf(), entry.oid, stat, entry.mode);
+            } else if !included && !entry.skip_worktree() {
+                let stat = self.workspace.stat_file(path)?;
+                if stat.is_some()
+                    && self
+                        .compare_index_to_workspace(Some(&entry), stat.as_ref())?
+                        .is_some()
+                {
+                    not_up_to_date.push(entry.path);
+                    continue;
+                }
+
+                self.workspace.remove(path)?;
+                self.index.set_skip_worktree(&entry.path, true);
+            }
+        }
+
+        Ok(not_up_to_date)
+    }
+
     pub fn status(&mut self, commit_oid: Option<&str>) -> Status {
         Status::new(self, commit_oid)
     }
 
-    fn trackable_file(&self, path: &Path, stat: &fs::Metadata) -> Result<bool> {
+    /// The submodules declared in `.gitmodules`.
+    pub fn submodules(&self) -> Result<Vec<Submodule>> {
+        submodules::load(&self.root_path)
+    }
+
+    /// Whether `path` is an untracked file, or a directory holding one. Files matched by
+    /// `ignore` don't count, if it's given.
+    fn trackable_file(
+        &self,
+        path: &Path,
+        stat: &fs::Metadata,
+        ignore: Option<&Ignore>,
+    ) -> Result<bool> {
+        if ignore.is_some_and(|ignore| ignore.ignores(path, stat.is_dir())) {
+            return Ok(false);
+        }
+
         if stat.is_file() {
             return Ok(!self.index.tracked_file(path));
         } else if !stat.is_dir() {
@@ -97,7 +401,7 @@ impl Repository {
         let dirs = items.iter().filter(|(_, item_stat)| item_stat.is_dir());
 
         for (item_path, item_stat) in files.chain(dirs) {
-            if self.trackable_file(item_path, item_stat)? {
+            if self.trackable_file(item_path, item_stat, ignore)? {
                 return Ok(true);
             }
         }
@@ -112,12 +416,29 @@ impl Repository {
     ) -> Result<Option<ChangeType>> {
         if entry.is_none() {
             return Ok(Some(ChangeType::Untracked));
-        } else if stat.is_none() {
-            return Ok(Some(ChangeType::Deleted));
         }
 
         let entry = entry.unwrap();
-        let stat = stat.unwrap();
+        let stat = match stat {
+            Some(stat) => stat,
+            // Files left out by sparse checkout are expected to be missing from the workspace
+            None if entry.skip_worktree() => return Ok(None),
+            None => return Ok(Some(ChangeType::Deleted)),
+        };
+
+        // The contents of a file added with `--intent-to-add` are new to the index
+        if entry.intent_to_add() {
+            return Ok(Some(ChangeType::Added));
+        }
+
+        // A submodule is modified when its `HEAD` has moved away from the commit the gitlink
+        // records. Like Git, a submodule that hasn't been cloned isn't reported.
+        if entry.mode == GITLINK_MODE {
+            return match submodules::head(&self.root_path, Path::new(&entry.path))? {
+                Some(oid) if oid != entry.oid => Ok(Some(ChangeType::Modified)),
+                _ => Ok(None),
+            };
+        }
 
         if !entry.stat_match(stat) {
             return Ok(Some(ChangeType::Modified));
@@ -141,6 +462,9 @@ impl Repository {
         item: Option<&TreeEntry>,
         entry: Option<&IndexEntry>,
     ) -> Option<ChangeType> {
+        // A file added with `--intent-to-add` isn't staged yet
+        let entry = entry.filter(|entry| !entry.intent_to_add());
+
         if item.is_none() && entry.is_none() {
             return None;
         } else if item.is_none() {
@@ -0,0 +1,82 @@
+use std::path::Path;
+
+use crate::database::blob::Blob;
+use crate::database::object::Object;
+use crate::errors::{Error, Result};
+use crate::patch::{FilePatch, Patch};
+use crate::repository::Repository;
+
+const DEFAULT_MODE: u32 = 0o100644;
+
+pub struct ApplyPatch<'a> {
+    repo: &'a mut Repository,
+    patch: &'a Patch,
+}
+
+impl<'a> ApplyPatch<'a> {
+    pub fn new(repo: &'a mut Repository, patch: &'a Patch) -> Self {
+        Self { repo, patch }
+    }
+
+    /// Apply the patch to the files in the index, and write the results to both the index and
+    /// the workspace. Every file is patched before anything is written, so a patch that doesn't
+    /// apply leaves the index and workspace as they were.
+    pub fn execute(&mut self) -> Result<()> {
+        let mut results = vec![];
+        for file in &self.patch.files {
+            results.push(self.patch_file(file)?);
+        }
+
+        for (file, result) in self.patch.files.iter().zip(results) {
+            if let Some(old_path) = &file.old_path {
+                if file.new_path.as_ref() != Some(old_path) && !file.copy {
+                    self.repo.index.remove(Path::new(old_path));
+                    self.repo.workspace.remove(Path::new(old_path))?;
+                }
+            }
+
+            if let (Some(new_path), Some((data, mode))) = (&file.new_path, result) {
+                let path = Path::new(new_path);
+                let blob = Blob::new(data.into_bytes());
+                self.repo.database.store(&blob)?;
+
+                self.repo
+                    .workspace
+                    .write_file(path, &blob.data, Some(mode), true)?;
+                let stat = self.repo.workspace.stat_file(path)?.unwrap();
+                self.repo.index.add(path.to_path_buf(), blob.oid(), stat);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// The new contents and mode of the file, or `None` if it's deleted.
+    fn patch_file(&self, file: &FilePatch) -> Result<Option<(String, u32)>> {
+        let (old, old_mode) = match &file.old_path {
+            Some(path) => {
+                let entry = self
+                    .repo
+                    .index
+                    .entry_for_path(path, 0)
+                    .ok_or_else(|| Error::PatchMissingFile(path.to_owned()))?;
+                let blob = self.repo.database.load_blob(&entry.oid)?;
+
+                (String::from_utf8_lossy(&blob.data).into_owned(), entry.mode)
+            }
+            None => (String::new(), DEFAULT_MODE),
+        };
+
+        let new_path = match &file.new_path {
+            Some(path) => path,
+            None => return Ok(None),
+        };
+        if file.old_path.as_ref() != Some(new_path)
+            && self.repo.index.tracked_file(Path::new(new_path))
+        {
+            return Err(Error::PatchExistingFile(new_path.to_owned()));
+        }
+
+        Ok(Some((file.apply(&old)?, file.new_mode.unwrap_or(old_mode))))
+    }
+}
@@ -0,0 +1,146 @@
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+use crate::errors::Result;
+use crate::repository::ignore::Pattern;
+use crate::util::path_to_string;
+
+/// The state a line of a `.gitattributes`-style file gives an attribute.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttributeValue {
+    /// `name`
+    Set,
+    /// `-name`
+    Unset,
+    /// `name=value`
+    Value(String),
+}
+
+/// A line of a `.gitattributes`-style file: a pattern followed by the attributes of the files it
+/// matches. `!name` leaves an attribute unspecified, overriding earlier lines.
+#[derive(Debug)]
+struct Rule {
+    pattern: Pattern,
+    attributes: Vec<(String, Option<AttributeValue>)>,
+}
+
+impl Rule {
+    fn parse(line: &str) -> Option<Self> {
+        let mut words = line.split_whitespace();
+        let glob = words.next().filter(|glob| !glob.starts_with('#'))?;
+        let pattern = Pattern::parse(glob)?;
+
+        let attributes = words
+            .map(|word| {
+                if let Some(name) = word.strip_prefix('-') {
+                    (name.to_owned(), Some(AttributeValue::Unset))
+                } else if let Some(name) = word.strip_prefix('!') {
+                    (name.to_owned(), None)
+                } else if let Some((name, value)) = word.split_once('=') {
+                    (
+                        name.to_owned(),
+                        Some(AttributeValue::Value(value.to_owned())),
+                    )
+                } else {
+                    (word.to_owned(), Some(AttributeValue::Set))
+                }
+            })
+            .collect();
+
+        Some(Self {
+            pattern,
+            attributes,
+        })
+    }
+}
+
+/// The attributes in the `.gitattributes` at the root of the workspace and
+/// `.git/info/attributes`, which name things like the diff driver of each file.
+#[derive(Debug)]
+pub struct Attributes {
+    paths: Vec<PathBuf>,
+    rules: Vec<Rule>,
+}
+
+impl Attributes {
+    pub fn new(root_path: &Path, git_path: &Path) -> Self {
+        Self {
+            // Lines in `.git/info/attributes` come last so they take precedence
+            paths: vec![
+                root_path.join(".gitattributes"),
+                git_path.join("info").join("attributes"),
+            ],
+            rules: vec![],
+        }
+    }
+
+    pub fn load(&mut self) -> Result<()> {
+        self.rules.clear();
+
+        for path in &self.paths {
+            let contents = match fs::read_to_string(path) {
+                Ok(contents) => contents,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+                Err(err) => return Err(err.into()),
+            };
+            self.rules.extend(contents.lines().filter_map(Rule::parse));
+        }
+
+        Ok(())
+    }
+
+    /// The value of the attribute `name` for the file at `path`, relative to the root of the
+    /// workspace, as given by the last line matching the file that mentions it.
+    pub fn get(&self, path: &Path, name: &str) -> Option<&AttributeValue> {
+        let path = path_to_string(path);
+
+        self.rules
+            .iter()
+            .rev()
+            .filter(|rule| rule.pattern.matches(&path, false))
+            .find_map(|rule| {
+                rule.attributes
+                    .iter()
+                    .rev()
+                    .find(|(attribute, _)| attribute == name)
+            })
+            .and_then(|(_, value)| value.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attributes(lines: &[&str]) -> Attributes {
+        Attributes {
+            paths: vec![],
+            rules: lines.iter().filter_map(|line| Rule::parse(line)).collect(),
+        }
+    }
+
+    #[test]
+    fn find_the_last_line_setting_an_attribute() {
+        let attributes = attributes(&[
+            "# comment",
+            "*.png diff=image",
+            "*.bin -diff",
+            "docs/*.png text !diff",
+        ]);
+
+        assert_eq!(
+            attributes.get(Path::new("img/logo.png"), "diff"),
+            Some(&AttributeValue::Value(String::from("image")))
+        );
+        assert_eq!(
+            attributes.get(Path::new("data.bin"), "diff"),
+            Some(&AttributeValue::Unset)
+        );
+        assert_eq!(attributes.get(Path::new("docs/logo.png"), "diff"), None);
+        assert_eq!(
+            attributes.get(Path::new("docs/logo.png"), "text"),
+            Some(&AttributeValue::Set)
+        );
+        assert_eq!(attributes.get(Path::new("README.md"), "diff"), None);
+    }
+}
@@ -42,10 +42,12 @@ impl<'a> HardReset<'a> {
             let blob = self.repo.database.load_blob(&entry.oid())?;
             self.repo
                 .workspace
-                .write_file(path, blob.data, Some(entry.mode()), true)?;
+                .write_file(path, &blob.data, Some(entry.mode()), true)?;
 
             let stat = self.repo.workspace.stat_file(path)?.unwrap();
-            self.repo.index.add(path.to_path_buf(), entry.oid(), stat);
+            self.repo
+                .index
+                .add_with_mode(path.to_path_buf(), entry.oid(), stat, entry.mode());
         }
 
         Ok(())
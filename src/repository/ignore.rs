@@ -0,0 +1,223 @@
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+use crate::errors::Result;
+use crate::util::{glob_match, parent_directories, path_to_string};
+
+/// A line of a `.gitignore`-style file.
+#[derive(Debug)]
+pub(crate) struct Pattern {
+    glob: String,
+    /// `!pattern` excludes the paths it matches
+    pub(crate) negated: bool,
+    /// `pattern/` only matches directories
+    directory: bool,
+    /// A pattern containing a `/` matches the whole path, not just the filename
+    anchored: bool,
+}
+
+impl Pattern {
+    pub(crate) fn parse(line: &str) -> Option<Self> {
+        let line = line.trim_end();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        let (negated, line) = match line.strip_prefix('!') {
+            Some(line) => (true, line),
+            None => (false, line),
+        };
+        let (directory, line) = match line.strip_suffix('/') {
+            Some(line) => (true, line),
+            None => (false, line),
+        };
+
+        Some(Self {
+            glob: line.strip_prefix('/').unwrap_or(line).to_owned(),
+            negated,
+            directory,
+            anchored: line.contains('/'),
+        })
+    }
+
+    pub(crate) fn matches(&self, path: &str, is_dir: bool) -> bool {
+        if self.directory && !is_dir {
+            return false;
+        }
+
+        if self.anchored {
+            glob_match(&self.glob, path)
+        } else {
+            let filename = path.rsplit('/').next().unwrap();
+            glob_match(&self.glob, filename)
+        }
+    }
+}
+
+/// The pattern deciding what happens to the file at `path`. The last pattern matching the file
+/// or any of its parent directories wins, and a deeper match overrides its parent's.
+pub(crate) fn last_match<'a>(patterns: &'a [Pattern], path: &Path) -> Option<&'a Pattern> {
+    let directories = parent_directories(path).into_iter().rev();
+    let candidates = directories
+        .map(|dir| (path_to_string(&dir), true))
+        .chain([(path_to_string(path), false)]);
+
+    let mut last = None;
+    for (candidate, is_dir) in candidates {
+        let pattern = patterns
+            .iter()
+            .rev()
+            .find(|pattern| pattern.matches(&candidate, is_dir));
+        if pattern.is_some() {
+            last = pattern;
+        }
+    }
+
+    last
+}
+
+/// The patterns in `.git/info/exclude` and the `.gitignore` files in the workspace, which name
+/// untracked files that shouldn't be added.
+#[derive(Debug)]
+pub struct Ignore {
+    root_path: PathBuf,
+    exclude_path: PathBuf,
+    /// The patterns of `.git/info/exclude`, which match paths from the root of the workspace
+    exclude: Vec<Pattern>,
+    /// The patterns of each `.gitignore`, keyed by its directory, which they match paths from
+    directories: HashMap<PathBuf, Vec<Pattern>>,
+}
+
+impl Ignore {
+    pub fn new(root_path: &Path, git_path: &Path) -> Self {
+        Self {
+            root_path: root_path.to_path_buf(),
+            exclude_path: git_path.join("info").join("exclude"),
+            exclude: vec![],
+            directories: HashMap::new(),
+        }
+    }
+
+    pub fn load(&mut self) -> Result<()> {
+        self.exclude = read_patterns(&self.exclude_path)?;
+        self.directories.clear();
+
+        self.load_directory(Path::new(""))
+    }
+
+    /// Read the `.gitignore` in `dir`, then those in each of its subdirectories that isn't
+    /// excluded, as nothing inside an excluded directory can be included again.
+    fn load_directory(&mut self, dir: &Path) -> Result<()> {
+        let full_path = self.root_path.join(dir);
+
+        let patterns = read_patterns(&full_path.join(".gitignore"))?;
+        if !patterns.is_empty() {
+            self.directories.insert(dir.to_path_buf(), patterns);
+        }
+
+        for entry in fs::read_dir(&full_path)? {
+            let entry = entry?;
+            if !entry.file_type()?.is_dir() || entry.file_name() == ".git" {
+                continue;
+            }
+
+            let path = dir.join(entry.file_name());
+            if !self.excluded(&path, true) {
+                self.load_directory(&path)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether the file or directory at `path`, relative to the root of the workspace, is
+    /// ignored. Like Git, a file inside an ignored directory is always ignored.
+    pub fn ignores(&self, path: &Path, is_dir: bool) -> bool {
+        let mut directories = parent_directories(path);
+        directories.reverse();
+
+        directories.iter().any(|dir| self.excluded(dir, true)) || self.excluded(path, is_dir)
+    }
+
+    /// Whether the last pattern matching `path` itself excludes it. The `.gitignore` in the
+    /// deepest directory takes precedence, and `.git/info/exclude` comes last.
+    fn excluded(&self, path: &Path, is_dir: bool) -> bool {
+        let directories = parent_directories(path).into_iter().chain([PathBuf::new()]);
+        let per_directory = directories.filter_map(|dir| {
+            let patterns = self.directories.get(&dir)?;
+            let relative = path_to_string(path.strip_prefix(&dir).unwrap());
+
+            patterns
+                .iter()
+                .rev()
+                .find(|pattern| pattern.matches(&relative, is_dir))
+        });
+
+        let mut matches = per_directory.chain(
+            self.exclude
+                .iter()
+                .rev()
+                .find(|pattern| pattern.matches(&path_to_string(path), is_dir)),
+        );
+
+        matches.next().is_some_and(|pattern| !pattern.negated)
+    }
+}
+
+fn read_patterns(path: &Path) -> Result<Vec<Pattern>> {
+    match fs::read_to_string(path) {
+        Ok(contents) => Ok(contents.lines().filter_map(Pattern::parse).collect()),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(vec![]),
+        Err(err) => Err(err.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ignore(directories: &[(&str, &[&str])]) -> Ignore {
+        let mut ignore = Ignore::new(Path::new(""), Path::new(".git"));
+        for (dir, lines) in directories {
+            let patterns = lines
+                .iter()
+                .filter_map(|line| Pattern::parse(line))
+                .collect();
+            ignore.directories.insert(PathBuf::from(dir), patterns);
+        }
+
+        ignore
+    }
+
+    #[test]
+    fn ignore_files_and_directories_matching_a_pattern() {
+        let ignore = ignore(&[("", &["*.log", "/build/", "!keep.log"])]);
+
+        assert!(ignore.ignores(Path::new("debug.log"), false));
+        assert!(ignore.ignores(Path::new("src/debug.log"), false));
+        assert!(ignore.ignores(Path::new("build"), true));
+        assert!(ignore.ignores(Path::new("build/out.o"), false));
+        assert!(!ignore.ignores(Path::new("keep.log"), false));
+        assert!(!ignore.ignores(Path::new("src/build/out.o"), false));
+        assert!(!ignore.ignores(Path::new("README.md"), false));
+    }
+
+    #[test]
+    fn match_patterns_relative_to_their_directory() {
+        let ignore = ignore(&[("", &["*.tmp"]), ("sub", &["/secret", "!keep.tmp"])]);
+
+        assert!(ignore.ignores(Path::new("sub/secret"), false));
+        assert!(!ignore.ignores(Path::new("secret"), false));
+        assert!(!ignore.ignores(Path::new("sub/dir/secret"), false));
+        assert!(ignore.ignores(Path::new("sub/a.tmp"), false));
+        assert!(!ignore.ignores(Path::new("sub/keep.tmp"), false));
+    }
+
+    #[test]
+    fn never_include_files_inside_an_ignored_directory() {
+        let ignore = ignore(&[("", &["build/", "!build/keep.txt"])]);
+
+        assert!(ignore.ignores(Path::new("build/keep.txt"), false));
+    }
+}
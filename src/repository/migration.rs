@@ -1,14 +1,17 @@
-use std::collections::{BTreeSet, HashMap};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::rc::Rc;
 
 use once_cell::sync::Lazy;
 
+use crate::database::blob::Blob;
 use crate::database::entry::Entry;
 use crate::database::tree::TreeEntry;
 use crate::database::tree_diff::TreeDiffChanges;
 use crate::errors::{Error, Result};
 use crate::index::Entry as IndexEntry;
+use crate::repository::sparse_checkout::SparseCheckout;
 use crate::repository::Repository;
 use crate::util::{parent_directories, path_to_string};
 
@@ -51,8 +54,12 @@ pub struct Migration<'a> {
     pub changes: HashMap<Action, Vec<(PathBuf, Option<Entry>)>>,
     pub mkdirs: BTreeSet<PathBuf>,
     pub rmdirs: BTreeSet<PathBuf>,
+    /// Files excluded by sparse checkout, which are added to the index but not the workspace
+    skipped: Vec<(PathBuf, Entry)>,
+    sparse_checkout: Option<SparseCheckout>,
     pub errors: Vec<String>,
-    pub conflicts: HashMap<ConflictType, BTreeSet<PathBuf>>,
+    /// Every conflicting path, so they're all reported together rather than one at a time
+    pub conflicts: BTreeMap<ConflictType, BTreeSet<PathBuf>>,
 }
 
 #[derive(Debug, PartialEq, Eq, Hash)]
@@ -62,7 +69,8 @@ pub enum Action {
     Update,
 }
 
-#[derive(Debug, PartialEq, Eq, Hash)]
+/// The kinds of conflict that stop a migration, in the order they're reported.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum ConflictType {
     StaleFile,
     StaleDirectory,
@@ -78,7 +86,7 @@ impl<'a> Migration<'a> {
             (Action::Update, vec![]),
         ]);
 
-        let conflicts = HashMap::from([
+        let conflicts = BTreeMap::from([
             (ConflictType::StaleFile, BTreeSet::new()),
             (ConflictType::StaleDirectory, BTreeSet::new()),
             (ConflictType::UntrackedOverwritten, BTreeSet::new()),
@@ -91,6 +99,8 @@ impl<'a> Migration<'a> {
             changes,
             mkdirs: BTreeSet::new(),
             rmdirs: BTreeSet::new(),
+            skipped: vec![],
+            sparse_checkout: None,
             errors: Vec::new(),
             conflicts,
         }
@@ -104,11 +114,13 @@ impl<'a> Migration<'a> {
         Ok(())
     }
 
-    pub fn blob_data(&self, oid: &str) -> Result<Vec<u8>> {
-        Ok(self.repo.database.load_blob(oid)?.data)
+    pub fn blob(&self, oid: &str) -> Result<Rc<Blob>> {
+        Ok(self.repo.database.load_blob(oid)?)
     }
 
     fn plan_changes(&mut self) -> Result<()> {
+        self.sparse_checkout = self.repo.sparse_checkout()?;
+
         // TODO: Pass `diff` as an argument to `apply_changes()` instead of cloning?
         for (path, (old_item, new_item)) in &self.diff.clone() {
             self.check_for_conflict(path, old_item, new_item)?;
@@ -121,6 +133,13 @@ impl<'a> Migration<'a> {
     }
 
     fn record_change(&mut self, path: &Path, old_item: &Option<Entry>, new_item: &Option<Entry>) {
+        if let (Some(sparse_checkout), Some(new_item)) = (&self.sparse_checkout, new_item) {
+            if !sparse_checkout.includes(path) {
+                self.skipped.push((path.to_path_buf(), new_item.to_owned()));
+                return;
+            }
+        }
+
         let ancestors = path
             .ancestors()
             .map(|path| path.to_path_buf())
@@ -151,6 +170,9 @@ impl<'a> Migration<'a> {
 
     fn update_workspace(&self) -> Result<()> {
         self.repo.workspace.apply_migration(self)?;
+        for (path, _) in &self.skipped {
+            self.repo.workspace.remove(path)?;
+        }
         Ok(())
     }
 
@@ -161,15 +183,24 @@ impl<'a> Migration<'a> {
 
         for action in [Action::Create, Action::Update] {
             for (path, entry) in &self.changes[&action] {
+                let entry = entry.as_ref().unwrap();
                 let stat = self.repo.workspace.stat_file(path)?.unwrap();
-                self.repo.index.add(
+                self.repo.index.add_with_mode(
                     path.to_path_buf(),
-                    entry.as_ref().unwrap().oid.clone(),
+                    entry.oid.clone(),
                     stat,
+                    entry.mode(),
                 );
             }
         }
 
+        for (path, entry) in &self.skipped {
+            let pathname = path_to_string(path);
+            self.repo.index.remove(path);
+            self.repo.index.add_from_db(&pathname, entry);
+            self.repo.index.set_skip_worktree(&pathname, true);
+        }
+
         Ok(())
     }
 
@@ -211,7 +242,7 @@ impl<'a> Migration<'a> {
                 self.insert_conflict(error_type, path);
             }
         } else if stat.as_ref().unwrap().is_dir() {
-            let trackable = self.repo.trackable_file(path, &stat.unwrap())?;
+            let trackable = self.repo.trackable_file(path, &stat.unwrap(), None)?;
             if trackable {
                 self.insert_conflict(error_type, path);
             }
@@ -245,7 +276,7 @@ impl<'a> Migration<'a> {
                     continue;
                 }
 
-                if self.repo.trackable_file(&parent, &parent_stat)? {
+                if self.repo.trackable_file(&parent, &parent_stat, None)? {
                     return Ok(Some(parent.to_path_buf()));
                 }
             }
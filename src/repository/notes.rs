@@ -0,0 +1,89 @@
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::database::author::Author;
+use crate::database::blob::Blob;
+use crate::database::commit::Commit;
+use crate::database::entry::Entry as DatabaseEntry;
+use crate::database::object::Object;
+use crate::database::tree::{Tree, TreeEntry};
+use crate::database::Database;
+use crate::errors::Result;
+use crate::refs::Refs;
+use crate::util::path_to_string;
+
+pub const NOTES_REF: &str = "refs/notes/commits";
+const NOTE_MODE: u32 = 0o100644;
+
+/// Notes are stored as blobs in a tree keyed by the annotated commit's object ID. Each change to
+/// the tree is recorded as a new commit on `refs/notes/commits`.
+#[derive(Debug)]
+pub struct Notes<'a> {
+    database: &'a Database,
+    refs: &'a Refs,
+}
+
+impl<'a> Notes<'a> {
+    pub fn new(database: &'a Database, refs: &'a Refs) -> Self {
+        Self { database, refs }
+    }
+
+    /// Return a map of annotated commit IDs to the object IDs of their note blobs.
+    pub fn list(&self) -> Result<BTreeMap<String, String>> {
+        let tree = self.load_tree()?;
+
+        Ok(tree
+            .entries
+            .iter()
+            .map(|(name, entry)| (path_to_string(name), entry.oid()))
+            .collect())
+    }
+
+    pub fn read(&self, oid: &str) -> Result<Option<String>> {
+        match self.list()?.get(oid) {
+            Some(note_oid) => Ok(Some(self.read_blob(note_oid)?)),
+            None => Ok(None),
+        }
+    }
+
+    pub fn read_blob(&self, note_oid: &str) -> Result<String> {
+        let blob = self.database.load_blob(note_oid)?;
+
+        Ok(String::from_utf8_lossy(&blob.data).into_owned())
+    }
+
+    pub fn add(&self, oid: &str, message: &str, author: Author) -> Result<()> {
+        let blob = Blob::new(message.as_bytes().to_vec());
+        self.database.store(&blob)?;
+
+        let mut tree = self.load_tree()?;
+        tree.entries.insert(
+            PathBuf::from(oid),
+            TreeEntry::Entry(DatabaseEntry::new(blob.oid(), NOTE_MODE)),
+        );
+        self.database.store(&tree)?;
+
+        let parents = self.refs.read_ref(NOTES_REF)?.into_iter().collect();
+        let commit = Commit::new(
+            parents,
+            tree.oid(),
+            author.clone(),
+            author,
+            String::from("Notes added by 'jit notes add'\n"),
+        );
+        self.database.store(&commit)?;
+        self.refs.update_ref(NOTES_REF, &commit.oid())?;
+
+        Ok(())
+    }
+
+    fn load_tree(&self) -> Result<Tree> {
+        match self.refs.read_ref(NOTES_REF)? {
+            Some(commit_oid) => {
+                let commit = self.database.load_commit(&commit_oid)?;
+                Ok(self.database.load_tree(&commit.tree)?)
+            }
+            None => Ok(Tree::new(None)),
+        }
+    }
+}
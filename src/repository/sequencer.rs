@@ -2,7 +2,7 @@ use std::collections::HashMap;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 use std::str::FromStr;
-use std::{fmt, fs};
+use std::{fmt, fs, io};
 
 use once_cell::sync::Lazy;
 use regex::Regex;
@@ -10,10 +10,10 @@ use regex::Regex;
 use crate::config::{Config, VariableValue};
 use crate::database::commit::Commit;
 use crate::database::object::Object;
-use crate::database::Database;
+use crate::database::{Database, EMPTY_TREE_OID};
 use crate::errors::{Error, Result};
 use crate::lockfile::Lockfile;
-use crate::refs::ORIG_HEAD;
+use crate::refs::{HEAD, ORIG_HEAD};
 use crate::repository::Repository;
 
 static LOAD_LINE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(\S+) (\S+) (.*)$").unwrap());
@@ -53,6 +53,7 @@ pub struct Sequencer {
     abort_path: PathBuf,
     head_path: PathBuf,
     todo_path: PathBuf,
+    mailbox_path: PathBuf,
     todo_file: Option<Lockfile>,
     config: Config,
     commands: Vec<(Action, Commit)>,
@@ -60,18 +61,30 @@ pub struct Sequencer {
 
 impl Sequencer {
     pub fn new(repo: &Repository) -> Self {
-        let pathname = repo.git_path.join("sequencer");
+        Self::with_dir(repo, "sequencer")
+    }
+
+    /// The state of `jit am`, kept in `.git/rebase-apply` like Git's so that applying patches
+    /// can't clash with a cherry-pick or revert that's stopped on a conflict.
+    pub fn for_am(repo: &Repository) -> Self {
+        Self::with_dir(repo, "rebase-apply")
+    }
+
+    fn with_dir(repo: &Repository, name: &str) -> Self {
+        let pathname = repo.git_path.join(name);
         let abort_path = pathname.join("abort-safety");
         let head_path = pathname.join("head");
         let todo_path = pathname.join("todo");
+        let mailbox_path = pathname.join("mailbox");
         let config_path = pathname.join("opts");
 
         Self {
-            repo: Repository::new(repo.git_path.clone()),
+            repo: Repository::new(repo.root_path.join(".git")),
             pathname,
             abort_path,
             head_path,
             todo_path,
+            mailbox_path,
             todo_file: None,
             config: Config::new(&config_path),
             commands: Vec::new(),
@@ -81,9 +94,11 @@ impl Sequencer {
     pub fn start(&mut self, options: &HashMap<&str, VariableValue>) -> Result<()> {
         fs::create_dir(&self.pathname)?;
 
-        let head_oid = self.repo.refs.read_head()?.unwrap();
-        self.write_file(&self.head_path, &head_oid)?;
-        self.write_file(&self.abort_path, &head_oid)?;
+        // On an unborn branch there's no commit to go back to, so no abort point is recorded
+        if let Some(head_oid) = self.repo.refs.read_head()? {
+            self.write_file(&self.head_path, &head_oid)?;
+            self.write_file(&self.abort_path, &head_oid)?;
+        }
 
         self.config.open_for_update()?;
         for (key, value) in options {
@@ -122,11 +137,56 @@ impl Sequencer {
 
     pub fn drop_command(&mut self) -> Result<()> {
         self.commands.remove(0);
-        self.write_file(&self.abort_path, &self.repo.refs.read_head()?.unwrap())?;
+        self.update_abort_safety()?;
+
+        Ok(())
+    }
+
+    /// Record the current `HEAD`, so `abort()` can tell whether it has been moved since.
+    pub fn update_abort_safety(&self) -> Result<()> {
+        match self.repo.refs.read_head()? {
+            Some(head_oid) => self.write_file(&self.abort_path, &head_oid),
+            None => Ok(()),
+        }
+    }
+
+    /// Save the mbox messages `jit am` has yet to apply.
+    pub fn save_mailbox(&self, messages: &[String]) -> Result<()> {
+        let mut lockfile = Lockfile::new(self.mailbox_path.clone());
+        lockfile.hold_for_update()?;
+        lockfile.write_all(messages.concat().as_bytes())?;
+        lockfile.commit()?;
 
         Ok(())
     }
 
+    /// The mbox messages saved by `save_mailbox()`, if `jit am` is in progress.
+    pub fn load_mailbox(&self) -> Result<Option<String>> {
+        if !self.mailbox_path.is_file() {
+            return Ok(None);
+        }
+
+        Ok(Some(fs::read_to_string(&self.mailbox_path)?))
+    }
+
+    /// The commits left in the todo list, read without taking its lock. Lines that don't name a
+    /// commit are skipped.
+    pub fn todo_oids(&self) -> Result<Vec<String>> {
+        if !self.todo_path.is_file() {
+            return Ok(vec![]);
+        }
+
+        let mut oids = vec![];
+        for line in fs::read_to_string(&self.todo_path)?.lines() {
+            if let Some(captures) = LOAD_LINE.captures(line) {
+                let matches = self.repo.database.prefix_match(&captures[2])?;
+                oids.extend(matches.into_iter().take(1));
+            }
+        }
+
+        Ok(oids)
+    }
+
     pub fn load(&mut self) -> Result<()> {
         self.open_todo_file()?;
 
@@ -162,9 +222,9 @@ impl Sequencer {
     }
 
     pub fn abort(&mut self) -> Result<()> {
-        let head_oid = fs::read_to_string(&self.head_path)?.trim().to_owned();
-        let expected = fs::read_to_string(&self.abort_path)?.trim().to_owned();
-        let actual = self.repo.refs.read_head()?.unwrap();
+        let head_oid = Self::read_file(&self.head_path)?;
+        let expected = Self::read_file(&self.abort_path)?;
+        let actual = self.repo.refs.read_head()?;
 
         self.quit()?;
 
@@ -172,9 +232,18 @@ impl Sequencer {
             return Err(Error::UnsafeRewind);
         }
 
-        self.repo.hard_reset(&head_oid)?;
-        let orig_head = self.repo.refs.update_head(&head_oid)?.unwrap();
-        self.repo.refs.update_ref(ORIG_HEAD, &orig_head)?;
+        match head_oid {
+            Some(head_oid) => {
+                self.repo.hard_reset(&head_oid)?;
+                let orig_head = self.repo.refs.update_head(&head_oid)?.unwrap();
+                self.repo.refs.update_ref(ORIG_HEAD, &orig_head)?;
+            }
+            // Started on an unborn branch, which is left unborn again
+            None => {
+                self.repo.hard_reset(EMPTY_TREE_OID)?;
+                self.repo.refs.compare_and_swap(HEAD, None, None)?;
+            }
+        }
 
         Ok(())
     }
@@ -185,6 +254,14 @@ impl Sequencer {
         Ok(())
     }
 
+    fn read_file(path: &Path) -> Result<Option<String>> {
+        match fs::read_to_string(path) {
+            Ok(content) => Ok(Some(content.trim().to_owned())),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err.into()),
+        }
+    }
+
     fn write_file(&self, path: &Path, content: &str) -> Result<()> {
         let mut lockfile = Lockfile::new(path.to_owned());
         lockfile.hold_for_update()?;
@@ -194,6 +271,10 @@ impl Sequencer {
         Ok(())
     }
 
+    pub fn in_progress(&self) -> bool {
+        self.pathname.is_dir()
+    }
+
     fn open_todo_file(&mut self) -> Result<()> {
         if !self.pathname.is_dir() {
             return Ok(());
@@ -0,0 +1,105 @@
+use std::path::{Path, PathBuf};
+use std::{fs, io};
+
+use crate::errors::Result;
+use crate::lockfile::Lockfile;
+use crate::repository::ignore::{last_match, Pattern};
+
+/// The patterns in `.git/info/sparse-checkout` choosing which files are written to the workspace
+/// when `core.sparseCheckout` is enabled.
+#[derive(Debug)]
+pub struct SparseCheckout {
+    pathname: PathBuf,
+    lines: Vec<String>,
+    patterns: Vec<Pattern>,
+}
+
+impl SparseCheckout {
+    pub fn new(git_path: &Path) -> Self {
+        Self {
+            pathname: git_path.join("info").join("sparse-checkout"),
+            lines: vec![],
+            patterns: vec![],
+        }
+    }
+
+    pub fn load(&mut self) -> Result<()> {
+        let contents = match fs::read_to_string(&self.pathname) {
+            Ok(contents) => contents,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err.into()),
+        };
+        self.parse(contents.lines().map(str::to_owned).collect());
+
+        Ok(())
+    }
+
+    pub fn write(&mut self, lines: &[String]) -> Result<()> {
+        fs::create_dir_all(self.pathname.parent().unwrap())?;
+
+        let mut lockfile = Lockfile::new(self.pathname.clone());
+        lockfile.hold_for_update()?;
+        for line in lines {
+            lockfile.write(format!("{}\n", line).as_bytes())?;
+        }
+        lockfile.commit()?;
+
+        self.parse(lines.to_owned());
+
+        Ok(())
+    }
+
+    /// The lines of the file, without blank lines.
+    pub fn lines(&self) -> &[String] {
+        &self.lines
+    }
+
+    /// Whether the file at `path` belongs in the workspace. Like `.gitignore`, the last pattern
+    /// matching the file or any of its parent directories wins, and a deeper match overrides
+    /// its parent's.
+    pub fn includes(&self, path: &Path) -> bool {
+        last_match(&self.patterns, path).is_some_and(|pattern| !pattern.negated)
+    }
+
+    fn parse(&mut self, lines: Vec<String>) {
+        self.lines = lines
+            .into_iter()
+            .filter(|line| !line.trim().is_empty())
+            .collect();
+        self.patterns = self
+            .lines
+            .iter()
+            .filter_map(|line| Pattern::parse(line))
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sparse_checkout(lines: &[&str]) -> SparseCheckout {
+        let mut sparse_checkout = SparseCheckout::new(Path::new(".git"));
+        sparse_checkout.parse(lines.iter().map(|line| line.to_string()).collect());
+        sparse_checkout
+    }
+
+    #[test]
+    fn include_files_in_a_directory() {
+        let sparse_checkout = sparse_checkout(&["/*", "!/*/", "/src/"]);
+
+        assert!(sparse_checkout.includes(Path::new("README.md")));
+        assert!(sparse_checkout.includes(Path::new("src/lib.rs")));
+        assert!(sparse_checkout.includes(Path::new("src/commands/add.rs")));
+        assert!(!sparse_checkout.includes(Path::new("tests/add_test.rs")));
+    }
+
+    #[test]
+    fn match_unanchored_patterns_against_the_filename() {
+        let sparse_checkout = sparse_checkout(&["# Only Rust files", "*.rs", "!main.rs"]);
+
+        assert!(sparse_checkout.includes(Path::new("src/lib.rs")));
+        assert!(!sparse_checkout.includes(Path::new("src/main.rs")));
+        assert!(!sparse_checkout.includes(Path::new("Cargo.toml")));
+    }
+}
@@ -2,12 +2,26 @@ use std::collections::{BTreeMap, BTreeSet, HashMap};
 use std::fs;
 use std::path::{Path, MAIN_SEPARATOR};
 
-use crate::database::tree::TreeEntry;
+use clap::ValueEnum;
+
+use crate::database::tree::{TreeEntry, GITLINK_MODE};
 use crate::errors::Result;
 use crate::index::Entry as IndexEntry;
+use crate::repository::ignore::Ignore;
 use crate::repository::{ChangeKind, ChangeType, Repository};
 use crate::util::path_to_string;
 
+/// Which untracked files are listed, as in `git status --untracked-files=<mode>`.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UntrackedFiles {
+    /// Don't look for untracked files
+    No,
+    /// List untracked files, and untracked directories as a single entry
+    Normal,
+    /// List every untracked file, including those inside untracked directories
+    All,
+}
+
 #[derive(Debug)]
 pub struct Status {
     repo: *mut Repository,
@@ -18,6 +32,8 @@ pub struct Status {
     pub conflicts: BTreeMap<String, Vec<u16>>,
     pub workspace_changes: BTreeMap<String, ChangeType>,
     pub untracked_files: BTreeSet<String>,
+    /// Which files go in `untracked_files`. Set this before calling `status.initialize()`.
+    pub untracked_mode: UntrackedFiles,
     pub head_tree: HashMap<String, TreeEntry>,
 }
 
@@ -34,6 +50,7 @@ impl Status {
             conflicts: BTreeMap::new(),
             workspace_changes: BTreeMap::new(),
             untracked_files: BTreeSet::new(),
+            untracked_mode: UntrackedFiles::Normal,
             head_tree: HashMap::new(),
         }
     }
@@ -51,7 +68,8 @@ impl Status {
                 .database
                 .load_tree_list(commit_oid.as_deref(), None)?;
 
-            self.scan_workspace(&(*self.repo).root_path)?;
+            let ignore = (*self.repo).ignore()?;
+            self.scan_workspace(&(*self.repo).root_path, &ignore)?;
         }
         self.check_index_entries()?;
         self.collect_deleted_head_files();
@@ -70,16 +88,28 @@ impl Status {
         changes.insert(path.to_string(), r#type);
     }
 
-    fn scan_workspace(&mut self, prefix: &Path) -> Result<()> {
+    /// Record the stats of the tracked files under `prefix`, and list the untracked files that
+    /// aren't ignored.
+    fn scan_workspace(&mut self, prefix: &Path, ignore: &Ignore) -> Result<()> {
         unsafe {
             for (path, stat) in &(*self.repo).workspace.list_dir(prefix)? {
                 if (*self.repo).index.tracked(path) {
-                    if stat.is_file() {
-                        self.stats.insert(path_to_string(path), stat.clone());
+                    // A submodule's files belong to its own repository, so don't descend into it
+                    // Stats are keyed by the index's path, whose case may differ with
+                    // `core.ignoreCase`
+                    if stat.is_file() || self.is_gitlink(path) {
+                        let path = (*self.repo).index.canonical_path(&path_to_string(path));
+                        self.stats.insert(path, stat.clone());
                     } else if stat.is_dir() {
-                        self.scan_workspace(path)?;
+                        self.scan_workspace(path, ignore)?;
                     }
-                } else if (*self.repo).trackable_file(path, stat)? {
+                } else if ignore.ignores(path, stat.is_dir()) {
+                    continue;
+                } else if self.untracked_mode == UntrackedFiles::All && stat.is_dir() {
+                    self.scan_workspace(path, ignore)?;
+                } else if self.untracked_mode != UntrackedFiles::No
+                    && (*self.repo).trackable_file(path, stat, Some(ignore))?
+                {
                     let mut path = path_to_string(path);
                     if stat.is_dir() {
                         path.push(MAIN_SEPARATOR);
@@ -92,6 +122,15 @@ impl Status {
         Ok(())
     }
 
+    fn is_gitlink(&self, path: &Path) -> bool {
+        unsafe {
+            (*self.repo)
+                .index
+                .entry_for_path(&path_to_string(path), 0)
+                .is_some_and(|entry| entry.mode == GITLINK_MODE)
+        }
+    }
+
     fn check_index_entries(&mut self) -> Result<()> {
         unsafe {
             for entry in (*self.repo).index.entries.values_mut() {
@@ -118,7 +157,13 @@ impl Status {
 
             match status {
                 Some(status) => self.record_change(&entry.path, ChangeKind::Workspace, status),
-                None => (*self.repo).index.update_entry_stat(entry, stat.unwrap()),
+                None => {
+                    // Skip-worktree files are unchanged without being in the workspace, and a
+                    // gitlink's mode mustn't be replaced by its directory's
+                    if let Some(stat) = stat.filter(|_| entry.mode != GITLINK_MODE) {
+                        (*self.repo).index.update_entry_stat(entry, stat)
+                    }
+                }
             }
         }
 
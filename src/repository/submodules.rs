@@ -0,0 +1,115 @@
+use std::path::{Path, PathBuf};
+
+use crate::config::{Config, VariableValue};
+use crate::errors::Result;
+use crate::refs::Refs;
+use crate::repository::Repository;
+
+/// A submodule declared in `.gitmodules`, like:
+///
+/// ```text
+/// [submodule "vendor"]
+///     path = lib/vendor
+///     url = https://example.com/vendor.git
+/// ```
+#[derive(Debug, PartialEq, Eq)]
+pub struct Submodule {
+    pub name: String,
+    pub path: PathBuf,
+    pub url: Option<String>,
+}
+
+/// The submodules declared in the `.gitmodules` file at `root_path`, in name order. Submodules
+/// without a `path` are skipped.
+pub fn load(root_path: &Path) -> Result<Vec<Submodule>> {
+    let mut config = Config::new(&root_path.join(".gitmodules"));
+    config.open()?;
+
+    let get = |name: &str, key: &str| match config.get(&[
+        String::from("submodule"),
+        name.to_owned(),
+        key.to_owned(),
+    ]) {
+        Some(VariableValue::String(value)) => Some(value),
+        _ => None,
+    };
+
+    let mut submodules: Vec<_> = config
+        .subsections("submodule")
+        .into_iter()
+        .filter_map(|name| {
+            let path = PathBuf::from(get(&name, "path")?);
+            let url = get(&name, "url");
+            Some(Submodule { name, path, url })
+        })
+        .collect();
+    submodules.sort_by(|a, b| a.name.cmp(&b.name));
+
+    Ok(submodules)
+}
+
+/// The commit checked out in the submodule at `path`, or `None` if it hasn't been cloned. Its
+/// `.git` may be a directory or a gitfile pointing into the superproject's `.git/modules`.
+pub fn head(root_path: &Path, path: &Path) -> Result<Option<String>> {
+    let submodule_path = root_path.join(path);
+    let git_path = Repository::resolve_gitfile(&submodule_path, submodule_path.join(".git"));
+    if !git_path.is_dir() {
+        return Ok(None);
+    }
+
+    Refs::new(git_path).read_head()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn map_submodule_names_to_paths_and_urls() -> Result<()> {
+        let root = TempDir::new()?;
+        fs::write(
+            root.path().join(".gitmodules"),
+            "\
+[submodule \"vendor\"]
+\tpath = lib/vendor
+\turl = https://example.com/vendor.git
+[submodule \"docs\"]
+\tpath = docs
+[submodule \"broken\"]
+\turl = https://example.com/broken.git
+",
+        )?;
+
+        assert_eq!(
+            load(root.path())?,
+            vec![
+                Submodule {
+                    name: String::from("docs"),
+                    path: PathBuf::from("docs"),
+                    url: None,
+                },
+                Submodule {
+                    name: String::from("vendor"),
+                    path: PathBuf::from("lib/vendor"),
+                    url: Some(String::from("https://example.com/vendor.git")),
+                },
+            ]
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn load_nothing_without_a_gitmodules_file() -> Result<()> {
+        let root = TempDir::new()?;
+
+        assert_eq!(load(root.path())?, vec![]);
+        assert_eq!(head(root.path(), Path::new("vendor"))?, None);
+
+        Ok(())
+    }
+}
@@ -6,13 +6,18 @@ use once_cell::sync::Lazy;
 use regex::Regex;
 
 use crate::database::commit::Commit;
+use crate::database::entry::Entry;
 use crate::database::object::Object;
-use crate::database::tree_diff::{Differ, TreeDiffChanges};
-use crate::errors::Result;
+use crate::database::tree_diff::{detect_renames, Differ, Rename, TreeDiffChanges};
+use crate::database::EMPTY_TREE_OID;
+use crate::diff::{diff, EditType};
+use crate::errors::{Error, Result};
+use crate::merge::bases::Bases;
 use crate::path_filter::PathFilter;
 use crate::repository::Repository;
 use crate::revision::{Revision, COMMIT, HEAD};
 
+static SYMMETRIC_RANGE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(.*)\.\.\.(.*)$").unwrap());
 static RANGE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(.*)\.\.(.*)$").unwrap());
 static EXCLUDE: Lazy<Regex> = Lazy::new(|| Regex::new(r"^\^(.+)$").unwrap());
 
@@ -22,16 +27,73 @@ enum Flag {
     Seen,
     Uninteresting,
     Treesame,
+    Left,
+    Right,
+}
+
+/// Which side of a symmetric range `A...B` a commit was reached from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+/// Limits the listed commits to those whose changes involve a string or pattern.
+#[derive(Debug, Clone)]
+pub enum Pickaxe {
+    /// `-S<string>`: the number of occurrences of the string in a file changed
+    String(String),
+    /// `-G<regex>`: an added or removed line matches the pattern
+    Regex(Regex),
+}
+
+impl Pickaxe {
+    fn matches(&self, old: &str, new: &str) -> bool {
+        match self {
+            Pickaxe::String(string) => {
+                old.matches(string.as_str()).count() != new.matches(string.as_str()).count()
+            }
+            Pickaxe::Regex(regex) => diff(old, new).iter().any(|edit| {
+                let line = match edit.r#type {
+                    EditType::Eql => return false,
+                    EditType::Ins => &edit.b_line,
+                    EditType::Del => &edit.a_line,
+                };
+                line.as_ref()
+                    .is_some_and(|line| regex.is_match(line.text()))
+            }),
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct RevListOptions {
     pub walk: bool,
+    /// Without `walk`, still list the given commits newest first rather than in the order given
+    pub sort_unwalked: bool,
+    /// Continue listing the history of a single path beyond renames
+    pub follow: bool,
+    pub pickaxe: Option<Pickaxe>,
+    /// Never list a commit before all of its children, like `--topo-order`
+    pub topo_order: bool,
+    /// Only list commits that refs point at, merges, and commits history branches off from,
+    /// rewriting their parents to skip the commits in between
+    pub simplify_by_decoration: bool,
+    /// Only list commits descending from the excluded commits, like the `A` of `A..B`
+    pub ancestry_path: bool,
 }
 
 impl Default for RevListOptions {
     fn default() -> Self {
-        Self { walk: true }
+        Self {
+            walk: true,
+            sort_unwalked: false,
+            follow: false,
+            pickaxe: None,
+            topo_order: false,
+            simplify_by_decoration: false,
+            ancestry_path: false,
+        }
     }
 }
 
@@ -43,10 +105,20 @@ pub struct RevList<'a> {
     queue: VecDeque<Commit>,
     limited: bool,
     prune: Vec<PathBuf>,
-    diffs: RefCell<HashMap<(Option<String>, String), TreeDiffChanges>>,
+    diffs: RefCell<HashMap<(String, String), TreeDiffChanges>>,
     output: VecDeque<Commit>,
     filter: PathFilter,
     walk: bool,
+    sort_unwalked: bool,
+    follow: bool,
+    topo_order: bool,
+    simplify_by_decoration: bool,
+    ancestry_path: bool,
+    /// The commits excluded from the list by the user, whose descendants `ancestry_path` keeps
+    bottoms: Vec<String>,
+    /// The renames `--follow` crossed, keyed by the ID of the commit that made them
+    followed_renames: HashMap<String, Rename>,
+    pickaxe: Option<Pickaxe>,
 }
 
 impl<'a> RevList<'a> {
@@ -56,13 +128,23 @@ impl<'a> RevList<'a> {
             commits: HashMap::new(),
             flags: RefCell::new(HashMap::new()),
             queue: VecDeque::new(),
-            limited: false,
+            // Sorting the commits topologically, finding where history branches or which
+            // commits descend from others needs all of them up front
+            limited: options.topo_order || options.simplify_by_decoration || options.ancestry_path,
             prune: Vec::new(),
             diffs: RefCell::new(HashMap::new()),
             output: VecDeque::new(),
             // A temporary `PathFilter` that will be replaced later in this function
             filter: PathFilter::new(None, None),
             walk: options.walk,
+            sort_unwalked: options.sort_unwalked,
+            follow: options.follow,
+            topo_order: options.topo_order,
+            simplify_by_decoration: options.simplify_by_decoration,
+            ancestry_path: options.ancestry_path,
+            bottoms: Vec::new(),
+            followed_renames: HashMap::new(),
+            pickaxe: options.pickaxe,
         };
 
         for rev in revs {
@@ -72,6 +154,12 @@ impl<'a> RevList<'a> {
             rev_list.handle_revision(HEAD)?;
         }
 
+        if rev_list.follow && rev_list.prune.len() != 1 {
+            return Err(Error::InvalidArgument(String::from(
+                "--follow requires exactly one pathspec",
+            )));
+        }
+
         rev_list.filter = PathFilter::build(&rev_list.prune);
 
         Ok(rev_list)
@@ -85,12 +173,17 @@ impl<'a> RevList<'a> {
             .is_some()
         {
             self.prune.push(PathBuf::from(rev));
+        } else if let Some(r#match) = SYMMETRIC_RANGE.captures(rev) {
+            self.set_symmetric_range(&r#match[1], &r#match[2])?;
+            self.walk = true;
         } else if let Some(r#match) = RANGE.captures(rev) {
-            self.set_start_point(&r#match[1], false)?;
+            let bottom = self.set_start_point(&r#match[1], false)?;
+            self.bottoms.push(bottom);
             self.set_start_point(&r#match[2], true)?;
             self.walk = true;
         } else if let Some(r#match) = EXCLUDE.captures(rev) {
-            self.set_start_point(&r#match[1], false)?;
+            let bottom = self.set_start_point(&r#match[1], false)?;
+            self.bottoms.push(bottom);
             self.walk = true;
         } else {
             self.set_start_point(rev, true)?;
@@ -99,7 +192,22 @@ impl<'a> RevList<'a> {
         Ok(())
     }
 
-    fn set_start_point(&mut self, rev: &str, interesting: bool) -> Result<()> {
+    /// List the commits reachable from either `left` or `right` but not both, by excluding their
+    /// merge bases and labelling each commit with the side it was reached from.
+    fn set_symmetric_range(&mut self, left: &str, right: &str) -> Result<()> {
+        let left = self.set_start_point(left, true)?;
+        let right = self.set_start_point(right, true)?;
+        self.mark(&left, Flag::Left);
+        self.mark(&right, Flag::Right);
+
+        for base in Bases::new(&self.repo.database, &left, &right)?.find()? {
+            self.set_start_point(&base, false)?;
+        }
+
+        Ok(())
+    }
+
+    fn set_start_point(&mut self, rev: &str, interesting: bool) -> Result<String> {
         let rev = if rev.is_empty() { HEAD } else { rev };
 
         let oid = Revision::new(self.repo, rev).resolve(Some(COMMIT))?;
@@ -113,7 +221,7 @@ impl<'a> RevList<'a> {
             self.mark_parents_uninteresting(commit.as_ref());
         }
 
-        Ok(())
+        Ok(oid)
     }
 
     fn enqueue_commit(&mut self, commit: Option<&Commit>) {
@@ -124,7 +232,7 @@ impl<'a> RevList<'a> {
 
         // We're seeing this commit for the first time
         if !self.mark(&commit.oid(), Flag::Seen) {
-            if self.walk {
+            if self.walk || self.sort_unwalked {
                 let index = self
                     .queue
                     .iter()
@@ -155,6 +263,131 @@ impl<'a> RevList<'a> {
         Ok(())
     }
 
+    /// Reorder the queue so no commit comes before any of its children. Like Git, each line of
+    /// history is listed in full before moving on to the next, starting from the newest.
+    fn sort_topologically(&mut self) {
+        let oids: HashSet<_> = self.queue.iter().map(|commit| commit.oid()).collect();
+        let mut children: HashMap<String, usize> = HashMap::new();
+        for commit in &self.queue {
+            for parent in commit
+                .parents
+                .iter()
+                .filter(|parent| oids.contains(*parent))
+            {
+                *children.entry(parent.to_owned()).or_default() += 1;
+            }
+        }
+
+        let mut stack: Vec<_> = self
+            .queue
+            .iter()
+            .rev()
+            .filter(|commit| !children.contains_key(&commit.oid()))
+            .map(|commit| commit.oid())
+            .collect();
+        let mut commits: HashMap<_, _> = self
+            .queue
+            .drain(..)
+            .map(|commit| (commit.oid(), commit))
+            .collect();
+
+        while let Some(oid) = stack.pop() {
+            let commit = commits.remove(&oid).unwrap();
+            for parent in commit.parents.iter().rev() {
+                if let Some(count) = children.get_mut(parent) {
+                    *count -= 1;
+                    if *count == 0 {
+                        stack.push(parent.to_owned());
+                    }
+                }
+            }
+            self.queue.push_back(commit);
+        }
+    }
+
+    /// Drop every commit from the queue except those a ref points at, merges, and commits with
+    /// more than one child.
+    fn simplify_by_decoration(&mut self) -> Result<()> {
+        let decorated = self.repo.refs.reverse_refs()?;
+
+        let mut children: HashMap<String, usize> = HashMap::new();
+        let oids: HashSet<_> = self.queue.iter().map(|commit| commit.oid()).collect();
+        for commit in &self.queue {
+            for parent in commit.parents.iter().filter(|p| oids.contains(*p)) {
+                *children.entry(parent.to_owned()).or_default() += 1;
+            }
+        }
+
+        self.rewrite_parents(|commit| {
+            let oid = commit.oid();
+            decorated.contains_key(&oid)
+                || commit.is_merge()
+                || children.get(&oid).copied().unwrap_or(0) > 1
+        });
+
+        Ok(())
+    }
+
+    /// Drop every commit from the queue that isn't `kept`, rewriting the parents of the commits
+    /// left to the nearest commits kept below them, so `--graph` still joins them up.
+    fn rewrite_parents(&mut self, kept: impl Fn(&Commit) -> bool) {
+        let commits: HashMap<_, _> = self
+            .queue
+            .iter()
+            .map(|commit| (commit.oid(), commit.to_owned()))
+            .collect();
+
+        let mut queue = VecDeque::new();
+        for mut commit in self.queue.drain(..).filter(|commit| kept(commit)) {
+            let mut parents = vec![];
+            let mut pending: Vec<_> = commit.parents.iter().rev().cloned().collect();
+            let mut visited = HashSet::new();
+
+            while let Some(oid) = pending.pop() {
+                let parent = match commits.get(&oid) {
+                    Some(parent) if visited.insert(oid.clone()) => parent,
+                    _ => continue,
+                };
+                if kept(parent) {
+                    if !parents.contains(&oid) {
+                        parents.push(oid);
+                    }
+                } else {
+                    pending.extend(parent.parents.iter().rev().cloned());
+                }
+            }
+
+            commit.parents = parents;
+            queue.push_back(commit);
+        }
+        self.queue = queue;
+    }
+
+    /// Drop every commit from the queue that doesn't descend from one of the bottoms, found by
+    /// walking forward from them through the children of the commits in the queue.
+    fn limit_to_ancestry_path(&mut self) {
+        let mut children: HashMap<&str, Vec<String>> = HashMap::new();
+        for commit in &self.queue {
+            for parent in &commit.parents {
+                children.entry(parent).or_default().push(commit.oid());
+            }
+        }
+
+        let mut descendants = HashSet::new();
+        let mut pending: Vec<_> = self.bottoms.iter().map(String::as_str).collect();
+        while let Some(oid) = pending.pop() {
+            for child in children.get(oid).into_iter().flatten() {
+                if descendants.insert(child.as_str()) {
+                    pending.push(child);
+                }
+            }
+        }
+
+        let descendants: HashSet<_> = descendants.into_iter().map(str::to_owned).collect();
+        self.queue
+            .retain(|commit| descendants.contains(&commit.oid()));
+    }
+
     fn still_interesting(&self) -> bool {
         if self.queue.is_empty() {
             return false;
@@ -201,6 +434,14 @@ impl<'a> RevList<'a> {
                 .collect()
         };
 
+        for parent in parents.iter().flatten() {
+            for flag in [Flag::Left, Flag::Right] {
+                if self.is_marked(&commit.oid(), flag.clone()) {
+                    self.mark(&parent.oid(), flag);
+                }
+            }
+        }
+
         for parent in &parents {
             self.enqueue_commit(parent.as_ref());
         }
@@ -208,6 +449,17 @@ impl<'a> RevList<'a> {
         Ok(())
     }
 
+    /// The side of a symmetric range that `commit` was reached from, if any.
+    pub fn side(&self, commit: &Commit) -> Option<Side> {
+        if self.is_marked(&commit.oid(), Flag::Left) {
+            Some(Side::Left)
+        } else if self.is_marked(&commit.oid(), Flag::Right) {
+            Some(Side::Right)
+        } else {
+            None
+        }
+    }
+
     fn mark_parents_uninteresting(&mut self, commit: Option<&Commit>) {
         if commit.is_none() {
             return;
@@ -271,36 +523,155 @@ impl<'a> RevList<'a> {
             return Ok(commit.parents.clone());
         }
 
-        let parents = if !commit.parents.is_empty() {
-            commit
-                .parents
-                .iter()
-                .map(String::as_str)
-                .map(Some)
-                .collect()
-        } else {
-            vec![None]
-        };
+        if commit.parents.is_empty() {
+            if self
+                .tree_diff(EMPTY_TREE_OID, &commit.oid(), None)?
+                .is_empty()
+            {
+                self.mark(&commit.oid(), Flag::Treesame);
+            }
+            return Ok(vec![]);
+        }
 
-        for oid in parents {
-            if self.tree_diff(oid, Some(&commit.oid()), None)?.is_empty() {
+        for oid in &commit.parents {
+            if self.tree_diff(oid, &commit.oid(), None)?.is_empty() {
                 self.mark(&commit.oid(), Flag::Treesame);
-                return Ok(vec![oid.unwrap().to_string()]);
+                return Ok(vec![oid.to_owned()]);
             }
         }
 
         Ok(commit.parents.clone())
     }
+
+    /// If `commit` added the followed path by renaming another file, follow the old name in
+    /// older commits.
+    fn follow_renames(&mut self, commit: &Commit) -> Result<()> {
+        let path = &self.prune[0];
+        let parent = commit.first_parent_or_empty_tree();
+
+        let diff = self.tree_diff(&parent, &commit.oid(), None)?;
+        if !matches!(diff.get(path), Some((None, Some(_)))) {
+            return Ok(());
+        }
+
+        let changes = self.repo.database.tree_diff(&parent, &commit.oid(), None)?;
+        let renames = detect_renames(&self.repo.database, &changes)?;
+
+        if let Some(rename) = renames.into_iter().find(|rename| &rename.new_path == path) {
+            self.prune = vec![rename.old_path.clone()];
+            self.filter = PathFilter::build(&self.prune);
+            self.followed_renames.insert(commit.oid(), rename);
+        }
+
+        Ok(())
+    }
+
+    /// The rename of the followed path made by the commit `oid`, if `--follow` crossed one there.
+    pub fn followed_rename(&self, oid: &str) -> Option<&Rename> {
+        self.followed_renames.get(oid)
+    }
+
+    /// Check whether any file changed by `commit` is matched by the pickaxe, if there is one.
+    /// Like Git, merge commits are never matched.
+    fn pickaxe_matches(&self, commit: &Commit) -> Result<bool> {
+        let pickaxe = match &self.pickaxe {
+            Some(pickaxe) => pickaxe,
+            None => return Ok(true),
+        };
+        if commit.is_merge() {
+            return Ok(false);
+        }
+
+        let diff = self.tree_diff(&commit.first_parent_or_empty_tree(), &commit.oid(), None)?;
+        for (old_entry, new_entry) in diff.values() {
+            let old = self.blob_text(old_entry.as_ref())?;
+            let new = self.blob_text(new_entry.as_ref())?;
+
+            if pickaxe.matches(&old, &new) {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+
+    /// The text of the blob at `entry`. Like Git, a submodule has no text for the pickaxe to
+    /// search, since the commit it points to isn't in this repository.
+    fn blob_text(&self, entry: Option<&Entry>) -> Result<String> {
+        match entry {
+            Some(entry) if !entry.is_gitlink() => {
+                let blob = self.repo.database.load_blob(&entry.oid)?;
+                Ok(String::from_utf8_lossy(&blob.data).into_owned())
+            }
+            _ => Ok(String::new()),
+        }
+    }
+
+    /// Pop commits off the queue until one should be shown, or `None` when the walk is done.
+    fn next_commit(&mut self) -> Result<Option<Commit>> {
+        if self.limited {
+            self.limit_list()?;
+
+            // Limiting the list again keeps the queue in the same order, so it's only sorted and
+            // simplified once
+            if self.ancestry_path {
+                self.limit_to_ancestry_path();
+                self.ancestry_path = false;
+            }
+            if self.topo_order {
+                // Like Git, the commits a pathspec hides are skipped over when joining up the
+                // lanes of `--graph`
+                if !self.prune.is_empty() {
+                    let shown: HashSet<_> = self
+                        .queue
+                        .iter()
+                        .map(|commit| commit.oid())
+                        .filter(|oid| !self.is_marked(oid, Flag::Treesame))
+                        .collect();
+                    self.rewrite_parents(|commit| shown.contains(&commit.oid()));
+                }
+                self.sort_topologically();
+                self.topo_order = false;
+            }
+            if self.simplify_by_decoration {
+                self.simplify_by_decoration()?;
+                self.simplify_by_decoration = false;
+            }
+        }
+
+        while let Some(commit) = self.queue.pop_front() {
+            if !self.limited {
+                self.add_parents(&commit)?;
+            }
+
+            if self.is_marked(&commit.oid(), Flag::Uninteresting)
+                || self.is_marked(&commit.oid(), Flag::Treesame)
+            {
+                continue;
+            }
+
+            let matches = self.pickaxe_matches(&commit)?;
+            if self.follow {
+                self.follow_renames(&commit)?;
+            }
+
+            if matches {
+                return Ok(Some(commit));
+            }
+        }
+
+        Ok(None)
+    }
 }
 
 impl<'a> Differ for RevList<'a> {
     fn tree_diff(
         &self,
-        old_oid: Option<&str>,
-        new_oid: Option<&str>,
+        old_oid: &str,
+        new_oid: &str,
         _filter: Option<&PathFilter>,
     ) -> Result<TreeDiffChanges> {
-        let key = (old_oid.map(|s| s.to_owned()), new_oid.unwrap().to_string());
+        let key = (old_oid.to_owned(), new_oid.to_owned());
 
         let mut diffs = self.diffs.borrow_mut();
 
@@ -317,27 +688,9 @@ impl<'a> Differ for RevList<'a> {
 }
 
 impl<'a> Iterator for RevList<'a> {
-    type Item = Commit;
+    type Item = Result<Commit>;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.limited {
-            self.limit_list().unwrap();
-        }
-
-        if let Some(commit) = self.queue.pop_front() {
-            if !self.limited {
-                self.add_parents(&commit).unwrap();
-            }
-
-            if self.is_marked(&commit.oid(), Flag::Uninteresting)
-                || self.is_marked(&commit.oid(), Flag::Treesame)
-            {
-                self.next()
-            } else {
-                Some(commit)
-            }
-        } else {
-            None
-        }
+        self.next_commit().transpose()
     }
 }
@@ -3,9 +3,11 @@ use std::collections::HashMap;
 use once_cell::sync::Lazy;
 use regex::{Regex, RegexSet};
 
+use crate::database::object::Object;
 use crate::database::{Database, ParsedObject};
 use crate::errors::{Error, Result};
 use crate::repository::Repository;
+use crate::rev_list::RevList;
 
 static INVALID_NAME: Lazy<RegexSet> = Lazy::new(|| {
     RegexSet::new(&[
@@ -20,19 +22,27 @@ static INVALID_NAME: Lazy<RegexSet> = Lazy::new(|| {
     ])
     .unwrap()
 });
+static PEEL: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^(.+)\^\{(commit|tree|blob|object|)\}$").unwrap());
+static MESSAGE_SEARCH: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(.+)\^\{/(.*)\}$").unwrap());
 static PARENT: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(.+)\^(\d*)$").unwrap());
 static ANCESTOR: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(.+)~(\d+)$").unwrap());
+static UPSTREAM: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(.*)@\{(?i:u|upstream)\}$").unwrap());
+static PUSH: Lazy<Regex> = Lazy::new(|| Regex::new(r"^(.*)@\{(?i:push)\}$").unwrap());
 static REF_ALIASES: Lazy<HashMap<&'static str, &'static str>> =
     Lazy::new(|| HashMap::from([("@", HEAD)]));
 
 pub const COMMIT: &str = "commit";
 pub const HEAD: &str = "HEAD";
+pub const TREE: &str = "tree";
 
 #[derive(Debug)]
 pub struct Revision<'a> {
     repo: &'a Repository,
     expr: String,
     query: Option<Rev>,
+    /// The objects matching the short object ID in `expr`, if there were several
+    candidates: Vec<String>,
     pub errors: Vec<HintedError>,
 }
 
@@ -42,6 +52,7 @@ impl<'a> Revision<'a> {
             repo,
             expr: expr.to_string(),
             query: Self::parse(expr),
+            candidates: vec![],
             errors: vec![],
         }
     }
@@ -50,28 +61,38 @@ impl<'a> Revision<'a> {
         !INVALID_NAME.is_match(revision)
     }
 
+    /// Resolve the revision to an object ID, failing with `Error::UnknownRevision`,
+    /// `Error::AmbiguousRevision`, or `Error::NotACommit` if a commit was asked for and the
+    /// revision names a different type of object.
     pub fn resolve(&mut self, r#type: Option<&str>) -> Result<String> {
         if self.query.is_some() {
             let query = self.query.as_ref().unwrap().clone();
-            let mut oid = query.resolve(self)?;
-
-            if r#type.is_some()
-                && self
-                    .load_typed_object(oid.as_ref(), r#type.unwrap())?
-                    .is_none()
-            {
-                oid = None;
-            }
-
-            if let Some(oid) = oid {
-                return Ok(oid);
+            let oid = query.resolve(self)?;
+
+            match r#type {
+                Some(r#type) if oid.is_some() => {
+                    if self.load_typed_object(oid.as_ref(), r#type)?.is_some() {
+                        return Ok(oid.unwrap());
+                    } else if r#type == COMMIT {
+                        return Err(Error::NotACommit);
+                    }
+                }
+                _ => {
+                    if let Some(oid) = oid {
+                        return Ok(oid);
+                    }
+                }
             }
         }
 
-        return Err(Error::InvalidObject(format!(
-            "Not a valid object name: '{}'.",
-            self.expr
-        )));
+        if self.candidates.is_empty() {
+            Err(Error::UnknownRevision(self.expr.clone()))
+        } else {
+            Err(Error::AmbiguousRevision(
+                self.expr.clone(),
+                self.candidates.clone(),
+            ))
+        }
     }
 
     pub fn read_ref(&mut self, name: &str) -> Result<Option<String>> {
@@ -97,13 +118,7 @@ impl<'a> Revision<'a> {
             Some(oid) => {
                 let commit = self.load_typed_object(Some(&oid), COMMIT)?;
                 match commit {
-                    Some(ParsedObject::Commit(commit)) => {
-                        if commit.parents.is_empty() {
-                            Ok(None)
-                        } else {
-                            Ok(Some(commit.parents[n - 1].clone()))
-                        }
-                    }
+                    Some(ParsedObject::Commit(commit)) => Ok(commit.parent(n)),
                     _ => Ok(None),
                 }
             }
@@ -111,8 +126,100 @@ impl<'a> Revision<'a> {
         }
     }
 
+    /// The remote-tracking ref `branch` merges from, or `branch` would be pushed to.
+    fn tracking_ref(&mut self, branch: &str, push: bool) -> Result<Option<String>> {
+        let branch = self.branch_name(branch)?;
+        let r#ref = if push {
+            self.repo.remotes.get_push_target(&branch)?.ok_or_else(|| {
+                Error::InvalidUpstream(format!("branch '{}' has no remote for pushing", branch))
+            })?
+        } else {
+            self.repo.remotes.get_upstream(&branch)?.ok_or_else(|| {
+                Error::InvalidUpstream(format!("no upstream configured for branch '{}'", branch))
+            })?
+        };
+
+        self.repo.refs.read_ref(&r#ref)
+    }
+
+    /// The branch named by the part of `<branch>@{upstream}` before the `@`, which is the current
+    /// branch if it's empty.
+    fn branch_name(&self, branch: &str) -> Result<String> {
+        if !branch.is_empty() && branch != HEAD {
+            return Ok(branch.to_string());
+        }
+
+        let current_ref = self.repo.refs.current_ref(HEAD)?;
+        if current_ref.is_head() {
+            return Err(Error::InvalidUpstream(String::from(
+                "HEAD does not point to a branch",
+            )));
+        }
+
+        Ok(self.repo.refs.short_name(&current_ref))
+    }
+
+    /// Peel `oid` to an object of `type`, as in `<rev>^{tree}`. Without annotated tags, only a
+    /// commit can be peeled, to its tree, and `<rev>^{}` is the object itself.
+    fn peel(&mut self, oid: Option<String>, r#type: &str) -> Result<Option<String>> {
+        let oid = match oid {
+            Some(oid) => oid,
+            None => return Ok(None),
+        };
+
+        match r#type {
+            "" | "object" => Ok(Some(oid)),
+            TREE => match self.repo.database.load(&oid)? {
+                ParsedObject::Commit(commit) => Ok(Some(commit.tree)),
+                ParsedObject::Tree(_) => Ok(Some(oid)),
+                ParsedObject::Blob(_) => {
+                    let message = format!("object {} is a blob, not a tree", oid);
+                    self.errors.push(HintedError::new(message, vec![]));
+                    Ok(None)
+                }
+            },
+            _ => Ok(self
+                .load_typed_object(Some(&oid), r#type)?
+                .map(|_object| oid)),
+        }
+    }
+
+    /// The most recent commit reachable from `oid` whose message contains `text`.
+    fn search_message(&self, oid: Option<String>, text: &str) -> Result<Option<String>> {
+        let oid = match oid {
+            Some(oid) => oid,
+            None => return Ok(None),
+        };
+
+        for commit in RevList::new(self.repo, &[oid], Default::default())? {
+            let commit = commit?;
+            if commit.message.contains(text) {
+                return Ok(Some(commit.oid()));
+            }
+        }
+
+        Ok(None)
+    }
+
     fn parse(revision: &str) -> Option<Rev> {
-        if let Some(r#match) = PARENT.captures(revision) {
+        if let Some(text) = revision.strip_prefix(":/") {
+            Some(Rev::MessageSearch {
+                rev: Box::new(Rev::Ref {
+                    name: HEAD.to_string(),
+                }),
+                text: text.to_string(),
+            })
+        } else if let Some(r#match) = PEEL.captures(revision) {
+            Revision::parse(&r#match[1]).map(|rev| Rev::Peel {
+                rev: Box::new(rev),
+                r#type: r#match[2].to_string(),
+            })
+        } else if let Some(r#match) = MESSAGE_SEARCH.captures(revision) {
+            Revision::parse(&r#match[1]).map(|rev| Rev::MessageSearch {
+                rev: Box::new(rev),
+                text: r#match[2].to_string(),
+            })
+        } else if let Some(r#match) = PARENT.captures(revision) {
             Revision::parse(&r#match[1]).map(|rev| Rev::Parent {
                 rev: Box::new(rev),
                 n: r#match[2].parse().unwrap_or(1),
@@ -122,6 +229,14 @@ impl<'a> Revision<'a> {
                 rev: Box::new(rev),
                 n: r#match[2].parse().unwrap(),
             })
+        } else if let Some(r#match) = UPSTREAM.captures(revision) {
+            Some(Rev::Upstream {
+                branch: r#match[1].to_string(),
+            })
+        } else if let Some(r#match) = PUSH.captures(revision) {
+            Some(Rev::Push {
+                branch: r#match[1].to_string(),
+            })
         } else if Revision::valid_ref(revision) {
             let name = match REF_ALIASES.get(revision) {
                 Some(name) => name,
@@ -160,6 +275,7 @@ impl<'a> Revision<'a> {
         let mut hint = vec![String::from("The candidates are:")];
 
         candidates.sort();
+        self.candidates = candidates.clone();
         for oid in candidates {
             let object = self.repo.database.load(&oid)?;
             let short = Database::short_oid(&object.oid());
@@ -188,6 +304,10 @@ enum Rev {
     Ref { name: String },
     Parent { rev: Box<Rev>, n: usize },
     Ancestor { rev: Box<Rev>, n: i32 },
+    Peel { rev: Box<Rev>, r#type: String },
+    MessageSearch { rev: Box<Rev>, text: String },
+    Upstream { branch: String },
+    Push { branch: String },
 }
 
 impl Rev {
@@ -205,6 +325,16 @@ impl Rev {
                 }
                 Ok(oid)
             }
+            Rev::Peel { rev, r#type } => {
+                let oid = rev.resolve(context)?;
+                context.peel(oid, r#type)
+            }
+            Rev::MessageSearch { rev, text } => {
+                let oid = rev.resolve(context)?;
+                context.search_message(oid, text)
+            }
+            Rev::Upstream { branch } => context.tracking_ref(branch, false),
+            Rev::Push { branch } => context.tracking_ref(branch, true),
         }
     }
 }
@@ -348,4 +478,84 @@ mod tests {
             },
         );
     }
+
+    #[test]
+    fn parse_an_upstream_ref() {
+        assert_parse(
+            "@{u}",
+            Rev::Upstream {
+                branch: String::new(),
+            },
+        );
+        assert_parse(
+            "topic@{upstream}~2",
+            Rev::Ancestor {
+                rev: Box::new(Rev::Upstream {
+                    branch: String::from("topic"),
+                }),
+                n: 2,
+            },
+        );
+    }
+
+    #[test]
+    fn parse_a_push_ref() {
+        assert_parse(
+            "main@{push}",
+            Rev::Push {
+                branch: String::from("main"),
+            },
+        );
+    }
+
+    #[test]
+    fn parse_a_message_search() {
+        assert_parse(
+            ":/fix ^typo~",
+            Rev::MessageSearch {
+                rev: Box::new(Rev::Ref {
+                    name: String::from("HEAD"),
+                }),
+                text: String::from("fix ^typo~"),
+            },
+        );
+        assert_parse(
+            "main~2^{/banana}",
+            Rev::MessageSearch {
+                rev: Box::new(Rev::Ancestor {
+                    rev: Box::new(Rev::Ref {
+                        name: String::from("main"),
+                    }),
+                    n: 2,
+                }),
+                text: String::from("banana"),
+            },
+        );
+    }
+
+    #[test]
+    fn parse_a_peeled_ref() {
+        assert_parse(
+            "HEAD^^{tree}",
+            Rev::Peel {
+                rev: Box::new(Rev::Parent {
+                    rev: Box::new(Rev::Ref {
+                        name: String::from("HEAD"),
+                    }),
+                    n: 1,
+                }),
+                r#type: String::from("tree"),
+            },
+        );
+        assert_parse(
+            "v1.0^{}",
+            Rev::Peel {
+                rev: Box::new(Rev::Ref {
+                    name: String::from("v1.0"),
+                }),
+                r#type: String::new(),
+            },
+        );
+        assert_eq!(Revision::parse("HEAD^{tag}"), None);
+    }
 }
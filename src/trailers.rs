@@ -0,0 +1,178 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+/// A trailer line at the end of a commit message, like `Signed-off-by: A. U. Thor <a@example.com>`.
+static TRAILER_LINE: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^([A-Za-z0-9-]+)\s*:\s*(.*)$").unwrap());
+
+/// The start of the line `jit cherry-pick -x` adds to a message.
+const CHERRY_PICK_ORIGIN: &str = "(cherry picked from commit ";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Trailer {
+    pub key: String,
+    pub value: String,
+}
+
+impl Trailer {
+    pub fn new(key: &str, value: &str) -> Self {
+        Self {
+            key: key.to_owned(),
+            value: value.to_owned(),
+        }
+    }
+
+    /// Parse a trailer given as `Key: value` or `Key=value`, as in `jit commit --trailer`.
+    pub fn parse(text: &str) -> Option<Self> {
+        let (key, value) = text.split_once([':', '='])?;
+        let key = key.trim();
+        if key.is_empty() || !key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+            return None;
+        }
+
+        Some(Self::new(key, value.trim()))
+    }
+
+    fn parse_line(line: &str) -> Option<Self> {
+        let captures = TRAILER_LINE.captures(line)?;
+        Some(Self::new(&captures[1], captures[2].trim()))
+    }
+}
+
+impl std::fmt::Display for Trailer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.key, self.value)
+    }
+}
+
+/// Append `trailers` to `message`. They join the trailer block the message ends with, if there is
+/// one, or start a new block after a blank line. Like Git, a trailer identical to the last one in
+/// the block isn't repeated.
+pub fn add_trailers(message: &str, trailers: &[Trailer]) -> String {
+    if trailers.is_empty() {
+        return message.to_owned();
+    }
+
+    let message = message.trim_end();
+    let mut block = trailer_block(message);
+    let mut result = message.to_owned();
+    if block.is_empty() && !message.is_empty() {
+        result.push('\n');
+    }
+
+    for trailer in trailers {
+        if block.last() == Some(trailer) {
+            continue;
+        }
+        if !result.is_empty() {
+            result.push('\n');
+        }
+        result.push_str(&trailer.to_string());
+        block.push(trailer.to_owned());
+    }
+    result.push('\n');
+
+    result
+}
+
+/// Append the `(cherry picked from commit <oid>)` line `jit cherry-pick -x` records. Like a
+/// trailer, it joins the trailer block the message ends with, if there is one.
+pub fn add_cherry_pick_origin(message: &str, oid: &str) -> String {
+    let message = message.trim_end();
+    let mut result = message.to_owned();
+    if !message.is_empty() {
+        result.push('\n');
+        if !ends_with_trailer_block(message) {
+            result.push('\n');
+        }
+    }
+    result.push_str(&format!("{}{})\n", CHERRY_PICK_ORIGIN, oid));
+
+    result
+}
+
+/// Whether the last paragraph of `message` is made of trailers and cherry-pick origin lines.
+fn ends_with_trailer_block(message: &str) -> bool {
+    match message.rsplit_once("\n\n") {
+        Some((_, paragraph)) => paragraph
+            .lines()
+            .all(|line| line.starts_with(CHERRY_PICK_ORIGIN) || TRAILER_LINE.is_match(line)),
+        None => false,
+    }
+}
+
+/// The trailers in the last paragraph of `message`, if every line of it is a trailer. The first
+/// paragraph is the title, so it's never a trailer block.
+fn trailer_block(message: &str) -> Vec<Trailer> {
+    let paragraph = match message.rsplit_once("\n\n") {
+        Some((_, paragraph)) => paragraph,
+        None => return vec![],
+    };
+
+    paragraph
+        .lines()
+        .map(Trailer::parse_line)
+        .collect::<Option<Vec<_>>>()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signoff() -> Trailer {
+        Trailer::new("Signed-off-by", "A. U. Thor <author@example.com>")
+    }
+
+    #[test]
+    fn start_a_trailer_block_after_the_body() {
+        assert_eq!(
+            add_trailers("Title\n\nBody\n", &[signoff()]),
+            "Title\n\nBody\n\nSigned-off-by: A. U. Thor <author@example.com>\n"
+        );
+        assert_eq!(
+            add_trailers("Title\n", &[signoff()]),
+            "Title\n\nSigned-off-by: A. U. Thor <author@example.com>\n"
+        );
+    }
+
+    #[test]
+    fn merge_into_an_existing_trailer_block() {
+        assert_eq!(
+            add_trailers("Title\n\nReviewed-by: Someone <s@example.com>\n", &[signoff()]),
+            "Title\n\nReviewed-by: Someone <s@example.com>\nSigned-off-by: A. U. Thor <author@example.com>\n"
+        );
+    }
+
+    #[test]
+    fn skip_a_trailer_repeating_the_last_one() {
+        let message = "Title\n\nSigned-off-by: A. U. Thor <author@example.com>\n";
+
+        assert_eq!(add_trailers(message, &[signoff()]), message);
+    }
+
+    #[test]
+    fn add_the_cherry_pick_origin() {
+        assert_eq!(
+            add_cherry_pick_origin("Title\n\nBody\n", "abc123"),
+            "Title\n\nBody\n\n(cherry picked from commit abc123)\n"
+        );
+        assert_eq!(
+            add_cherry_pick_origin(
+                "Title\n\nSigned-off-by: A. U. Thor <author@example.com>\n",
+                "abc123"
+            ),
+            "Title\n\nSigned-off-by: A. U. Thor <author@example.com>\n(cherry picked from commit abc123)\n"
+        );
+    }
+
+    #[test]
+    fn parse_trailer_arguments() {
+        assert_eq!(
+            Trailer::parse("Co-authored-by=Other <other@example.com>"),
+            Some(Trailer::new("Co-authored-by", "Other <other@example.com>"))
+        );
+        assert_eq!(Trailer::parse("no separator"), None);
+        assert_eq!(Trailer::parse("bad key: value"), None);
+    }
+}
@@ -34,6 +34,64 @@ pub fn path_to_string(path: &Path) -> String {
     path.to_str().unwrap().to_string()
 }
 
+/// The suffix that makes a noun plural for `count` of it.
+pub fn plural(count: usize) -> &'static str {
+    if count == 1 {
+        ""
+    } else {
+        "s"
+    }
+}
+
+/// Quote `path` in C style like Git does, if it contains a control character, a double quote or a
+/// backslash. Bytes above `0x7f` are written in octal as well unless `core.quotePath` turns
+/// `quote_high_bytes` off. Other paths are returned as they are, e.g.:
+///
+/// ```
+/// # use jit::util::quote_path;
+/// assert_eq!(quote_path("a\tb.txt", true), "\"a\\tb.txt\"");
+/// assert_eq!(quote_path("café.txt", true), "\"caf\\303\\251.txt\"");
+/// assert_eq!(quote_path("café.txt", false), "café.txt");
+/// ```
+pub fn quote_path(path: &str, quote_high_bytes: bool) -> String {
+    let needs_quoting = |byte: u8| {
+        byte < 0x20
+            || byte == b'"'
+            || byte == b'\\'
+            || byte == 0x7f
+            || (quote_high_bytes && byte > 0x7f)
+    };
+    if !path.bytes().any(needs_quoting) {
+        return path.to_owned();
+    }
+
+    let mut quoted = String::from("\"");
+    for c in path.chars() {
+        match c {
+            '\x07' => quoted.push_str("\\a"),
+            '\x08' => quoted.push_str("\\b"),
+            '\t' => quoted.push_str("\\t"),
+            '\n' => quoted.push_str("\\n"),
+            '\x0b' => quoted.push_str("\\v"),
+            '\x0c' => quoted.push_str("\\f"),
+            '\r' => quoted.push_str("\\r"),
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            c if c.is_ascii() && !needs_quoting(c as u8) => quoted.push(c),
+            c if !c.is_ascii() && !quote_high_bytes => quoted.push(c),
+            c => {
+                let mut bytes = [0; 4];
+                for byte in c.encode_utf8(&mut bytes).bytes() {
+                    quoted.push_str(&format!("\\{:03o}", byte));
+                }
+            }
+        }
+    }
+    quoted.push('"');
+
+    quoted
+}
+
 /// Iterator yielding every line in a string. The line includes newline character(s).
 ///
 /// From <https://stackoverflow.com/a/40457615/609144>
@@ -92,6 +150,79 @@ pub fn transpose<T: Clone>(ary: Vec<Vec<T>>) -> Vec<Vec<T>> {
     result
 }
 
+/// Match `path` against a shell glob, as used in `.gitignore`-style patterns. `*` and `?` don't
+/// match `/`, `**` matches across directories, and `[...]` matches a set of characters:
+///
+/// ```
+/// # use jit::util::glob_match;
+/// assert!(glob_match("src/*.rs", "src/lib.rs"));
+/// assert!(!glob_match("src/*.rs", "src/commands/add.rs"));
+/// assert!(glob_match("src/**/*.rs", "src/commands/add.rs"));
+/// ```
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    let pattern: Vec<_> = pattern.chars().collect();
+    let path: Vec<_> = path.chars().collect();
+
+    glob_match_chars(&pattern, &path)
+}
+
+fn glob_match_chars(pattern: &[char], path: &[char]) -> bool {
+    match pattern {
+        [] => path.is_empty(),
+        ['*', '*', '/', rest @ ..] => (0..=path.len())
+            .filter(|&i| i == 0 || path[i - 1] == '/')
+            .any(|i| glob_match_chars(rest, &path[i..])),
+        ['*', '*', rest @ ..] => (0..=path.len()).any(|i| glob_match_chars(rest, &path[i..])),
+        ['*', rest @ ..] => {
+            let end = path.iter().position(|&c| c == '/').unwrap_or(path.len());
+            (0..=end).any(|i| glob_match_chars(rest, &path[i..]))
+        }
+        ['?', rest @ ..] => {
+            matches!(path.first(), Some(c) if *c != '/') && glob_match_chars(rest, &path[1..])
+        }
+        ['[', class @ ..] => match (match_class(class, path.first()), path.first()) {
+            (Some((matched, rest)), _) => matched && glob_match_chars(rest, &path[1..]),
+            // A `[` without a closing `]` matches itself
+            (None, Some('[')) => glob_match_chars(class, &path[1..]),
+            (None, _) => false,
+        },
+        ['\\', c, rest @ ..] | [c, rest @ ..] => {
+            path.first() == Some(c) && glob_match_chars(rest, &path[1..])
+        }
+    }
+}
+
+/// Match `c` against the character class at the start of `class`, which follows a `[`. Returns
+/// whether it matched and the rest of the pattern after the closing `]`, or `None` if the class
+/// isn't closed.
+fn match_class<'a>(class: &'a [char], c: Option<&char>) -> Option<(bool, &'a [char])> {
+    let (negated, class) = match class {
+        ['!' | '^', rest @ ..] => (true, rest),
+        _ => (false, class),
+    };
+    // A `]` straight after the `[` is part of the class
+    let end = 1 + class.get(1..)?.iter().position(|&c| c == ']')?;
+    let (set, rest) = (&class[..end], &class[end + 1..]);
+
+    let c = match c {
+        Some(c) if *c != '/' => *c,
+        _ => return Some((false, rest)),
+    };
+    let mut matched = false;
+    let mut i = 0;
+    while i < set.len() {
+        if i + 2 < set.len() && set[i + 1] == '-' {
+            matched |= set[i] <= c && c <= set[i + 2];
+            i += 3;
+        } else {
+            matched |= set[i] == c;
+            i += 1;
+        }
+    }
+
+    Some((matched != negated, rest))
+}
+
 #[cfg(test)]
 pub mod tests {
     use rand::distributions::Alphanumeric;
@@ -135,4 +266,23 @@ pub mod tests {
 
         assert_eq!(parent_directories(Path::new(input)), expected);
     }
+
+    #[rstest]
+    #[case("*.txt", "f.txt", true)]
+    #[case("*.txt", "dir/f.txt", false)]
+    #[case("f?.txt", "f1.txt", true)]
+    #[case("f?.txt", "f/.txt", false)]
+    #[case("**/f.txt", "f.txt", true)]
+    #[case("**/f.txt", "a/b/f.txt", true)]
+    #[case("a/**/f.txt", "a/f.txt", true)]
+    #[case("a/**", "a/b/c", true)]
+    #[case("f[0-9].txt", "f7.txt", true)]
+    #[case("f[!0-9].txt", "f7.txt", false)]
+    #[case("f[]x].txt", "f].txt", true)]
+    #[case("f[.txt", "f[.txt", true)]
+    #[case("f\\*.txt", "f*.txt", true)]
+    #[case("f\\*.txt", "fx.txt", false)]
+    fn glob_match_works(#[case] pattern: &str, #[case] path: &str, #[case] expected: bool) {
+        assert_eq!(glob_match(pattern, path), expected);
+    }
 }
@@ -1,27 +1,63 @@
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::ffi::OsStr;
 use std::fs::OpenOptions;
 use std::io::Write;
-use std::os::unix::fs::PermissionsExt;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::{symlink, PermissionsExt};
 use std::path::{Path, PathBuf};
 use std::{fs, io};
 
 use nix::errno::Errno;
+use uuid::Uuid;
 
+use crate::database::entry::Entry;
 use crate::errors::{Error, Result};
 use crate::repository::migration::{Action, Migration};
 use crate::util::parent_directories;
 
 // TODO: Remove `target` once we have .gitignore support
-const IGNORE: &[&str] = &[".", "..", ".git", "target"];
+const IGNORE: &[&str] = &[".", "..", "target"];
+/// Git never tracks a `.git` directory or gitfile, at any depth of the workspace.
+const GIT_DIR: &str = ".git";
+
+/// The mode of entries that are symbolic links, whose blob holds the link's target.
+pub const SYMLINK_MODE: u32 = 0o120000;
+const REGULAR_MODE: u32 = 0o100644;
+
+/// What was at a path in the workspace before a migration changed it.
+#[derive(Debug)]
+enum Backup {
+    Missing,
+    Directory,
+    Symlink(PathBuf),
+    /// A file, with the path of its copy in the backup directory
+    File(PathBuf),
+}
 
 #[derive(Debug)]
 pub struct Workspace {
     pathname: PathBuf,
+    /// The repository's `.git` directory, which may have been moved by a gitfile
+    git_path: PathBuf,
+    /// Whether symbolic links can be created, from `core.symlinks`
+    symlinks: bool,
 }
 
 impl Workspace {
-    pub fn new(pathname: PathBuf) -> Self {
-        Workspace { pathname }
+    pub fn new(pathname: PathBuf, git_path: PathBuf) -> Self {
+        Workspace {
+            pathname,
+            git_path,
+            symlinks: true,
+        }
+    }
+
+    pub fn set_symlinks(&mut self, symlinks: bool) {
+        self.symlinks = symlinks;
+    }
+
+    pub fn symlinks(&self) -> bool {
+        self.symlinks
     }
 
     pub fn list_files(&self, path: &Path) -> Result<Vec<PathBuf>> {
@@ -100,7 +136,7 @@ impl Workspace {
     pub fn write_file(
         &self,
         path: &Path,
-        data: Vec<u8>,
+        data: &[u8],
         mode: Option<u32>,
         mkdir: bool,
     ) -> Result<()> {
@@ -110,25 +146,32 @@ impl Workspace {
             fs::create_dir_all(full_path.parent().unwrap())?;
         }
 
+        if mode == Some(SYMLINK_MODE) {
+            self.remove_entry(&full_path)?;
+            return self.create_file(&full_path, data, SYMLINK_MODE);
+        }
+
         let mut file = OpenOptions::new()
             .write(true)
             .create(true)
             .truncate(true)
             .open(&full_path)?;
-        file.write_all(&data)?;
+        file.write_all(data)?;
 
         if let Some(mode) = mode {
-            let mut perms = fs::metadata(&path)?.permissions();
+            let mut perms = fs::metadata(&full_path)?.permissions();
             perms.set_mode(mode);
-            fs::set_permissions(&path, perms)?;
+            fs::set_permissions(&full_path, perms)?;
         }
 
         Ok(())
     }
 
     pub fn remove(&self, path: &Path) -> Result<()> {
-        if path.is_file() {
-            match fs::remove_file(path) {
+        let full_path = self.pathname.join(path);
+
+        if full_path.is_file() {
+            match fs::remove_file(&full_path) {
                 Ok(()) => (),
                 Err(err) => {
                     if err.kind() != io::ErrorKind::NotFound {
@@ -136,8 +179,8 @@ impl Workspace {
                     }
                 }
             }
-        } else if path.is_dir() {
-            fs::remove_dir_all(path)?;
+        } else if full_path.is_dir() {
+            fs::remove_dir_all(&full_path)?;
         }
 
         for dirname in parent_directories(path) {
@@ -147,14 +190,40 @@ impl Workspace {
         Ok(())
     }
 
+    /// Apply `migration` to the workspace. The paths it touches are backed up first, copying
+    /// files into a directory under `.git`, so if any change fails, the ones already made are
+    /// undone and the workspace is left as it was.
     pub fn apply_migration(&self, migration: &Migration) -> Result<()> {
+        let backup_dir = self
+            .git_path
+            .join(format!("migration-backup-{}", Uuid::new_v4().simple()));
+
+        let result = self.back_up(migration, &backup_dir).and_then(|backups| {
+            match self.apply_migration_changes(migration) {
+                Ok(()) => Ok(()),
+                Err(err) => {
+                    self.restore(migration, &backups)?;
+                    Err(err)
+                }
+            }
+        });
+
+        match fs::remove_dir_all(&backup_dir) {
+            Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err.into()),
+            _ => result,
+        }
+    }
+
+    fn apply_migration_changes(&self, migration: &Migration) -> Result<()> {
         self.apply_change_list(migration, Action::Delete)?;
         for dir in migration.rmdirs.iter().rev() {
-            self.remove_directory(dir)?;
+            self.remove_directory(dir)
+                .map_err(|err| Error::MigrationFailed(dir.to_owned(), err.to_string()))?;
         }
 
         for dir in &migration.mkdirs {
-            self.make_directory(dir)?;
+            self.make_directory(dir)
+                .map_err(|err| Error::MigrationFailed(dir.to_owned(), err.to_string()))?;
         }
         self.apply_change_list(migration, Action::Update)?;
         self.apply_change_list(migration, Action::Create)?;
@@ -162,42 +231,142 @@ impl Workspace {
         Ok(())
     }
 
-    fn should_ignore(&self, path: &Path) -> bool {
-        IGNORE
+    /// Record what's in the workspace at each path `migration` changes, and at each directory it
+    /// creates, in path order. Files are copied into `backup_dir` rather than read into memory.
+    fn back_up(
+        &self,
+        migration: &Migration,
+        backup_dir: &Path,
+    ) -> Result<BTreeMap<PathBuf, Backup>> {
+        let paths = migration
+            .changes
+            .values()
+            .flatten()
+            .map(|(path, _)| path)
+            .chain(&migration.mkdirs);
+
+        let mut backups = BTreeMap::new();
+        for path in paths {
+            let full_path = self.pathname.join(path);
+            let backup = match fs::symlink_metadata(&full_path) {
+                Ok(stat) if stat.is_dir() => Backup::Directory,
+                Ok(stat) if stat.file_type().is_symlink() => {
+                    Backup::Symlink(fs::read_link(&full_path)?)
+                }
+                Ok(_) => {
+                    fs::create_dir_all(backup_dir)?;
+                    let copy = backup_dir.join(backups.len().to_string());
+                    fs::copy(&full_path, &copy)?;
+                    Backup::File(copy)
+                }
+                Err(_) => Backup::Missing,
+            };
+            backups.insert(path.to_owned(), backup);
+        }
+
+        Ok(backups)
+    }
+
+    /// Undo a partly applied `migration`, putting back what `backups` recorded.
+    fn restore(&self, migration: &Migration, backups: &BTreeMap<PathBuf, Backup>) -> Result<()> {
+        for (path, _) in migration.changes.values().flatten() {
+            self.remove_entry(&self.pathname.join(path))?;
+        }
+        for dir in migration.mkdirs.iter().rev() {
+            if !matches!(backups.get(dir), Some(Backup::Directory)) {
+                self.remove_directory(dir)?;
+            }
+        }
+
+        let existing = backups
             .iter()
-            .any(|ignore_path| path == PathBuf::from(ignore_path))
+            .filter(|(_, backup)| !matches!(backup, Backup::Missing));
+        for (path, backup) in existing {
+            let full_path = self.pathname.join(path);
+            fs::create_dir_all(full_path.parent().unwrap())?;
+
+            match backup {
+                Backup::Missing => unreachable!(),
+                Backup::Directory => fs::create_dir_all(&full_path)?,
+                Backup::Symlink(target) => symlink(target, &full_path)?,
+                Backup::File(copy) => {
+                    fs::copy(copy, &full_path)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn should_ignore(&self, path: &Path) -> bool {
+        path.file_name() == Some(OsStr::new(GIT_DIR))
+            || self.pathname.join(path) == self.git_path
+            || IGNORE
+                .iter()
+                .any(|ignore_path| path == PathBuf::from(ignore_path))
     }
 
     fn apply_change_list(&self, migration: &Migration, action: Action) -> Result<()> {
         for (filename, entry) in &migration.changes[&action] {
-            let path = self.pathname.join(filename);
+            self.apply_change(migration, filename, entry.as_ref())
+                .map_err(|err| Error::MigrationFailed(filename.to_owned(), err.to_string()))?;
+        }
 
-            if path.is_dir() {
-                fs::remove_dir_all(&path)?;
-            } else if path.is_file() {
-                fs::remove_file(&path)?;
-            }
-            if action == Action::Delete {
-                continue;
-            }
+        Ok(())
+    }
+
+    /// Remove the file at `filename`, and replace it with `entry` if there is one.
+    fn apply_change(
+        &self,
+        migration: &Migration,
+        filename: &Path,
+        entry: Option<&Entry>,
+    ) -> Result<()> {
+        let path = self.pathname.join(filename);
 
-            let entry = entry.as_ref().unwrap();
-            let data = migration.blob_data(&entry.oid)?;
+        self.remove_entry(&path)?;
+        if let Some(entry) = entry {
+            let blob = migration.blob(&entry.oid)?;
+            self.create_file(&path, &blob.data, entry.mode())?;
+        }
 
-            let mut file = OpenOptions::new()
-                .write(true)
-                .create_new(true)
-                .open(&path)?;
-            file.write_all(&data)?;
+        Ok(())
+    }
 
-            let mut perms = fs::metadata(&path)?.permissions();
-            perms.set_mode(entry.mode());
-            fs::set_permissions(&path, perms)?;
+    /// Remove the file, directory or symbolic link at `path`, without following links.
+    fn remove_entry(&self, path: &Path) -> Result<()> {
+        match fs::symlink_metadata(path) {
+            Ok(stat) if stat.is_dir() => fs::remove_dir_all(path)?,
+            Ok(_) => fs::remove_file(path)?,
+            Err(_) => (),
         }
 
         Ok(())
     }
 
+    /// Create the file at `path` for an entry with `mode`. Without `core.symlinks`, a symbolic
+    /// link is created as a plain file containing its target, like Git does.
+    fn create_file(&self, path: &Path, data: &[u8], mode: u32) -> Result<()> {
+        if mode == SYMLINK_MODE && self.symlinks {
+            symlink(OsStr::from_bytes(data), path)?;
+            return Ok(());
+        }
+
+        let mut file = OpenOptions::new().write(true).create_new(true).open(path)?;
+        file.write_all(data)?;
+
+        let mode = if mode == SYMLINK_MODE {
+            REGULAR_MODE
+        } else {
+            mode
+        };
+        let mut perms = fs::metadata(path)?.permissions();
+        perms.set_mode(mode);
+        fs::set_permissions(path, perms)?;
+
+        Ok(())
+    }
+
     fn remove_directory(&self, dirname: &Path) -> Result<()> {
         match fs::remove_dir(self.pathname.join(dirname)) {
             Ok(()) => Ok(()),
@@ -102,6 +102,102 @@ fn add_the_repository_root_to_the_index(mut helper: CommandHelper) -> Result<()>
     Ok(())
 }
 
+#[rstest]
+fn add_a_file_with_intent_to_add(mut helper: CommandHelper) -> Result<()> {
+    helper.write_file("tracked.txt", "one")?;
+    helper.jit_cmd(&["add", "."]);
+    helper.commit("first");
+
+    helper.write_file("new.txt", "hello\n")?;
+    helper
+        .jit_cmd(&["add", "--intent-to-add", "new.txt"])
+        .assert()
+        .code(0);
+
+    helper.assert_diff(
+        "\
+diff --git a/new.txt b/new.txt
+new file mode 100644
+index 0000000..ce01362
+--- /dev/null
++++ b/new.txt
+@@ -0,0 +1,1 @@
++hello
+",
+    );
+    helper.assert_diff_cached("");
+    helper.assert_status(" A new.txt\n");
+
+    helper.write_file("tracked.txt", "two")?;
+    helper.jit_cmd(&["add", "tracked.txt"]);
+    helper.commit("second");
+
+    let tree = helper.load_commit("@")?.tree;
+    let entries = helper.repo.database.load_tree_list(Some(&tree), None)?;
+    assert_eq!(entries.keys().collect::<Vec<_>>(), vec!["tracked.txt"]);
+    helper.assert_status(" A new.txt\n");
+
+    Ok(())
+}
+
+#[rstest]
+fn refuse_to_add_an_ignored_file_without_force(mut helper: CommandHelper) -> Result<()> {
+    helper.write_file(".gitignore", "ignored.txt\n")?;
+    helper.write_file("ignored.txt", "ignored")?;
+    helper.write_file("kept.txt", "kept")?;
+
+    helper
+        .jit_cmd(&["add", "ignored.txt", "kept.txt"])
+        .assert()
+        .code(1)
+        .stderr(
+            "\
+The following paths are ignored by one of your .gitignore files:
+ignored.txt
+hint: Use -f if you really want to add them.
+",
+        );
+    assert_index(&mut helper, vec![(0o100644, "kept.txt")])?;
+
+    helper
+        .jit_cmd(&["add", "-f", "ignored.txt"])
+        .assert()
+        .code(0);
+    assert_index(
+        &mut helper,
+        vec![(0o100644, "ignored.txt"), (0o100644, "kept.txt")],
+    )?;
+
+    Ok(())
+}
+
+#[rstest]
+fn skip_ignored_files_in_a_directory(mut helper: CommandHelper) -> Result<()> {
+    helper.write_file(".gitignore", "*.log\n")?;
+    helper.write_file("dir/debug.log", "ignored")?;
+    helper.write_file("dir/file.txt", "kept")?;
+
+    helper.jit_cmd(&["add", "dir"]).assert().code(0).stderr("");
+    assert_index(&mut helper, vec![(0o100644, "dir/file.txt")])?;
+
+    Ok(())
+}
+
+#[rstest]
+fn skip_files_ignored_by_a_nested_gitignore(mut helper: CommandHelper) -> Result<()> {
+    helper.write_file("sub/.gitignore", "/secret\n")?;
+    helper.write_file("sub/secret", "ignored")?;
+    helper.write_file("secret", "kept")?;
+
+    helper.jit_cmd(&["add", "."]).assert().code(0).stderr("");
+    assert_index(
+        &mut helper,
+        vec![(0o100644, "secret"), (0o100644, "sub/.gitignore")],
+    )?;
+
+    Ok(())
+}
+
 #[rstest]
 fn silent_on_success(mut helper: CommandHelper) -> Result<()> {
     helper.write_file("hello.txt", "hello")?;
@@ -0,0 +1,264 @@
+mod common;
+
+use std::collections::HashMap;
+use std::fs;
+
+use assert_cmd::prelude::OutputAssertExt;
+pub use common::CommandHelper;
+use jit::errors::Result;
+use rstest::{fixture, rstest};
+
+#[fixture]
+fn helper() -> CommandHelper {
+    formatted_patches()
+}
+
+/// Commit two changes by different authors, format them as an mbox, then undo them so the mbox
+/// can be applied with `jit am`.
+fn formatted_patches() -> CommandHelper {
+    let mut helper = CommandHelper::new();
+    helper.init();
+
+    helper.write_file("f.txt", "one\ntwo\nthree\n").unwrap();
+    helper.jit_cmd(&["add", "."]);
+    helper.commit("First");
+
+    helper.write_file("f.txt", "one\n2\nthree\n").unwrap();
+    helper.jit_cmd(&["add", "."]);
+    helper.commit("Change f\n\nWith a body");
+
+    helper.write_file("g/h.txt", "new\n").unwrap();
+    helper.jit_cmd(&["add", "."]);
+    helper
+        .env
+        .insert(String::from("GIT_AUTHOR_NAME"), String::from("Other"));
+    helper.env.insert(
+        String::from("GIT_AUTHOR_EMAIL"),
+        String::from("other@example.com"),
+    );
+    helper.jit_cmd(&["commit", "-m", "Add h"]).assert().code(0);
+
+    let output = helper.jit_cmd(&["format-patch", "--stdout", "@^^"]);
+    helper.stdin = String::from_utf8(output.stdout).unwrap();
+
+    helper.jit_cmd(&["reset", "--hard", "@^^"]).assert().code(0);
+
+    helper
+}
+
+#[rstest]
+fn apply_each_patch_as_a_commit_by_its_author(mut helper: CommandHelper) -> Result<()> {
+    helper
+        .jit_cmd(&["am"])
+        .assert()
+        .code(0)
+        .stdout("Applying: Change f\nApplying: Add h\n");
+
+    let second = helper.load_commit("@^")?;
+    assert_eq!(second.message, "Change f\n\nWith a body\n");
+    assert_eq!(second.author.name, "A. U. Thor");
+    assert_eq!(second.author.email, "author@example.com");
+
+    let third = helper.load_commit("@")?;
+    assert_eq!(third.message, "Add h\n");
+    assert_eq!(third.author.name, "Other");
+    assert_eq!(third.author.email, "other@example.com");
+
+    helper.assert_workspace(&HashMap::from([
+        ("f.txt", "one\n2\nthree\n"),
+        ("g/h.txt", "new\n"),
+    ]))?;
+    helper.assert_status("");
+
+    Ok(())
+}
+
+#[rstest]
+fn refuse_to_start_over_a_leftover_state_directory(mut helper: CommandHelper) {
+    fs::create_dir(helper.repo_path.join(".git/rebase-apply")).unwrap();
+
+    helper.jit_cmd(&["am"]).assert().code(128).stderr(
+        "fatal: previous rebase directory .git/rebase-apply still exists but mbox given.\n",
+    );
+}
+
+mod with_a_patch_that_does_not_apply {
+    use super::*;
+
+    #[fixture]
+    fn helper() -> CommandHelper {
+        let mut helper = formatted_patches();
+        helper.write_file("f.txt", "one\nTWO\nthree\n").unwrap();
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("Conflicting");
+
+        helper
+    }
+
+    #[rstest]
+    fn stop_without_changing_anything(mut helper: CommandHelper) -> Result<()> {
+        let head = helper.resolve_revision("@")?;
+
+        helper
+            .jit_cmd(&["am"])
+            .assert()
+            .code(1)
+            .stderr("error: patch failed: f.txt:1\nPatch failed at Change f\n");
+
+        assert_eq!(helper.resolve_revision("@")?, head);
+        helper.assert_status("");
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn keep_its_state_apart_from_cherry_picks_and_reverts(mut helper: CommandHelper) {
+        helper.jit_cmd(&["am"]);
+
+        assert!(helper.repo_path.join(".git/rebase-apply").is_dir());
+        assert!(!helper.repo_path.join(".git/sequencer").exists());
+    }
+
+    #[rstest]
+    fn commit_the_resolution_and_continue(mut helper: CommandHelper) -> Result<()> {
+        helper.jit_cmd(&["am"]);
+
+        helper.write_file("f.txt", "one\n2\nthree\n").unwrap();
+        helper.jit_cmd(&["add", "."]);
+        helper
+            .jit_cmd(&["am", "--continue"])
+            .assert()
+            .code(0)
+            .stdout("Applying: Add h\n");
+
+        assert_eq!(
+            helper.load_commit("@^")?.message,
+            "Change f\n\nWith a body\n"
+        );
+        assert_eq!(helper.load_commit("@")?.message, "Add h\n");
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn refuse_to_continue_without_changes(mut helper: CommandHelper) {
+        helper.jit_cmd(&["am"]);
+
+        helper.jit_cmd(&["am", "--continue"]).assert().code(1);
+    }
+
+    #[rstest]
+    fn skip_the_patch(mut helper: CommandHelper) -> Result<()> {
+        helper.jit_cmd(&["am"]);
+
+        helper.jit_cmd(&["am", "--skip"]).assert().code(0);
+
+        assert_eq!(helper.load_commit("@")?.message, "Add h\n");
+        assert_eq!(helper.load_commit("@^")?.message, "Conflicting\n");
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn abort_to_the_original_head(mut helper: CommandHelper) -> Result<()> {
+        let head = helper.resolve_revision("@")?;
+        helper.jit_cmd(&["am"]);
+
+        helper.jit_cmd(&["am", "--abort"]).assert().code(0);
+
+        assert_eq!(helper.resolve_revision("@")?, head);
+        helper
+            .jit_cmd(&["am", "--continue"])
+            .assert()
+            .code(128)
+            .stderr("fatal: Resolve operation not in progress, we are not resuming.\n");
+
+        Ok(())
+    }
+}
+
+mod in_an_empty_repository {
+    use super::*;
+
+    /// Format a patch adding a file and one changing a file that only exists in the original
+    /// repository, then start an empty repository to apply them to.
+    #[fixture]
+    fn helper() -> CommandHelper {
+        let mut original = CommandHelper::new();
+        original.init();
+
+        original.write_file("f.txt", "one\n").unwrap();
+        original.jit_cmd(&["add", "."]);
+        original.commit("First");
+
+        original.write_file("g/h.txt", "new\n").unwrap();
+        original.jit_cmd(&["add", "."]);
+        original.commit("Add h");
+
+        original.write_file("f.txt", "1\n").unwrap();
+        original.jit_cmd(&["add", "."]);
+        original.commit("Change f");
+
+        let output = original.jit_cmd(&["format-patch", "--stdout", "@^^"]);
+
+        let mut helper = CommandHelper::new();
+        helper.init();
+        helper.env = original.env.clone();
+        helper.stdin = String::from_utf8(output.stdout).unwrap();
+
+        helper
+    }
+
+    #[rstest]
+    fn apply_a_patch_adding_a_file(mut helper: CommandHelper) -> Result<()> {
+        let mbox = helper.stdin.clone();
+        helper.stdin = mailbox_with_first_message(&mbox);
+
+        helper
+            .jit_cmd(&["am"])
+            .assert()
+            .code(0)
+            .stdout("Applying: Add h\n");
+
+        let head = helper.load_commit("@")?;
+        assert_eq!(head.message, "Add h\n");
+        assert!(head.parents.is_empty());
+        helper.assert_workspace(&HashMap::from([("g/h.txt", "new\n")]))?;
+        helper.assert_status("");
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn abort_back_to_the_unborn_branch(mut helper: CommandHelper) -> Result<()> {
+        helper.jit_cmd(&["am"]).assert().code(1);
+        assert_eq!(helper.load_commit("@")?.message, "Add h\n");
+
+        helper.jit_cmd(&["am", "--abort"]).assert().code(0);
+
+        assert!(helper.repo.refs.read_head()?.is_none());
+        helper.assert_workspace(&HashMap::new())?;
+        helper.assert_status("");
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn skip_a_patch_on_the_unborn_branch(mut helper: CommandHelper) -> Result<()> {
+        let mbox = helper.stdin.clone();
+        helper.stdin = mbox[mbox.find("\nFrom ").unwrap() + 1..].to_owned();
+        helper.jit_cmd(&["am"]).assert().code(1);
+
+        helper.jit_cmd(&["am", "--skip"]).assert().code(0);
+
+        assert!(helper.repo.refs.read_head()?.is_none());
+        helper.assert_workspace(&HashMap::new())?;
+
+        Ok(())
+    }
+
+    fn mailbox_with_first_message(mbox: &str) -> String {
+        let second = mbox.find("\nFrom ").unwrap();
+        mbox[..=second].to_owned()
+    }
+}
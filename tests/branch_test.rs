@@ -68,7 +68,7 @@ mod with_a_chain_of_commits {
 
         assert_eq!(
             &helper.repo.refs.read_ref("topic")?.unwrap(),
-            head.parent().as_ref().unwrap(),
+            head.first_parent().as_ref().unwrap(),
         );
 
         Ok(())
@@ -86,11 +86,11 @@ mod with_a_chain_of_commits {
         let parent = helper
             .repo
             .database
-            .load_commit(head.parent().as_ref().unwrap())?;
+            .load_commit(head.first_parent().as_ref().unwrap())?;
 
         assert_eq!(
             &helper.repo.refs.read_ref("topic")?.unwrap(),
-            parent.parent().as_ref().unwrap(),
+            parent.first_parent().as_ref().unwrap(),
         );
 
         Ok(())
@@ -295,6 +295,59 @@ fatal: Not a valid object name: '{}^^'.
         Ok(())
     }
 
+    #[rstest]
+    fn delete_a_merged_branch(mut helper: CommandHelper) -> Result<()> {
+        let oid = helper.resolve_revision("@^")?;
+
+        helper.jit_cmd(&["branch", "old-feature", "@^"]);
+
+        helper
+            .jit_cmd(&["branch", "-d", "old-feature"])
+            .assert()
+            .code(0)
+            .stdout(format!(
+                "Deleted branch old-feature (was {}).\n",
+                Database::short_oid(&oid)
+            ));
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn refuse_to_delete_an_unmerged_branch_without_force(mut helper: CommandHelper) -> Result<()> {
+        helper.jit_cmd(&["branch", "topic"]);
+        helper.jit_cmd(&["checkout", "topic"]);
+        helper.write_file("file.txt", "fourth")?;
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("fourth");
+        helper.jit_cmd(&["checkout", "main"]);
+        let oid = helper.resolve_revision("topic")?;
+
+        helper
+            .jit_cmd(&["branch", "-d", "topic"])
+            .assert()
+            .code(1)
+            .stdout("")
+            .stderr(
+                "\
+error: The branch 'topic' is not fully merged.
+If you are sure you want to delete it, run 'jit branch -D topic'.
+",
+            );
+        assert_eq!(helper.resolve_revision("topic")?, oid);
+
+        helper
+            .jit_cmd(&["branch", "-D", "topic"])
+            .assert()
+            .code(0)
+            .stdout(format!(
+                "Deleted branch topic (was {}).\n",
+                Database::short_oid(&oid)
+            ));
+
+        Ok(())
+    }
+
     #[rstest]
     fn fail_to_delete_a_non_existent_branch(mut helper: CommandHelper) {
         helper
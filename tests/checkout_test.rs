@@ -1,10 +1,13 @@
 mod common;
 
 use std::collections::HashMap;
+use std::fs;
 use std::process::Output;
 
 use assert_cmd::prelude::OutputAssertExt;
 pub use common::CommandHelper;
+use jit::database::blob::Blob;
+use jit::database::object::Object;
 use jit::database::Database;
 use jit::errors::Result;
 use jit::refs::Ref;
@@ -219,6 +222,43 @@ Aborting\n",
         Ok(())
     }
 
+    #[rstest]
+    fn restore_the_workspace_when_an_update_fails(mut helper: CommandHelper) -> Result<()> {
+        helper.write_file("1.txt", "changed")?;
+        helper.delete("outer/inner")?;
+        helper.write_file("outer/inner", "in")?;
+        helper.write_file("z.txt", "new")?;
+        commit_all(&mut helper)?;
+
+        let oid = Blob::new(b"3".to_vec()).oid();
+        helper.delete(&format!(".git/objects/{}/{}", &oid[0..2], &oid[2..]))?;
+
+        helper
+            .jit_cmd(&["checkout", "@^"])
+            .assert()
+            .code(1)
+            .stderr(
+                "fatal: unable to update 'outer/inner/3.txt': No such file or directory (os error 2); the workspace was left unchanged\n",
+            );
+
+        helper.assert_workspace(&HashMap::from([
+            ("1.txt", "changed"),
+            ("outer/2.txt", "2"),
+            ("outer/inner", "in"),
+            ("z.txt", "new"),
+        ]))?;
+        helper.assert_status("");
+        assert!(
+            !fs::read_dir(helper.repo_path.join(".git"))?.any(|entry| entry
+                .unwrap()
+                .file_name()
+                .to_string_lossy()
+                .starts_with("migration-backup-"))
+        );
+
+        Ok(())
+    }
+
     #[rstest]
     fn maintain_workspace_modifications(mut helper: CommandHelper) -> Result<()> {
         helper.write_file("1.txt", "changed")?;
@@ -718,6 +758,32 @@ D  outer/inner/3.txt\n",
         Ok(())
     }
 
+    #[rstest]
+    fn list_every_conflicting_file(mut helper: CommandHelper) -> Result<()> {
+        helper.write_file("1.txt", "changed")?;
+        helper.write_file("outer/inner/3.txt", "changed")?;
+        helper.delete("outer/2.txt")?;
+        commit_all(&mut helper)?;
+
+        helper.write_file("1.txt", "conflict")?;
+        helper.write_file("outer/inner/3.txt", "conflict")?;
+        helper.write_file("outer/2.txt", "conflict")?;
+
+        helper.jit_cmd(&["checkout", "@^"]).assert().code(1).stderr(
+            "\
+error: Your local changes to the following files would be overwritten by checkout:
+\t1.txt
+\touter/inner/3.txt
+Please commit your changes or stash them before you switch branches.
+error: The following untracked working tree files would be overwritten by checkout:
+\touter/2.txt
+Please move or remove them before you switch branches.
+Aborting\n",
+        );
+
+        Ok(())
+    }
+
     #[rstest]
     fn fail_to_add_an_untracked_file(mut helper: CommandHelper) -> Result<()> {
         helper.delete("outer/2.txt")?;
@@ -819,6 +885,28 @@ D  outer/inner/3.txt\n",
 
         Ok(())
     }
+
+    #[rstest]
+    fn restore_a_path_from_a_previous_commit(mut helper: CommandHelper) -> Result<()> {
+        helper.write_file("1.txt", "changed")?;
+        helper.write_file("outer/2.txt", "changed")?;
+        commit_all(&mut helper)?;
+        let head_oid = helper.resolve_revision("@")?;
+
+        helper
+            .jit_cmd(&["checkout", "@^", "--", "1.txt"])
+            .assert()
+            .code(0);
+
+        let mut expected = BASE_FILES.clone();
+        expected.insert("outer/2.txt", "changed");
+        helper.assert_workspace(&expected)?;
+        helper.assert_index(&expected)?;
+        assert_eq!(helper.resolve_revision("@")?, head_oid);
+        helper.assert_status("M  1.txt\n");
+
+        Ok(())
+    }
 }
 
 mod with_a_chain_of_commits {
@@ -888,6 +976,59 @@ mod with_a_chain_of_commits {
                 .stderr("Switched to branch 'second'\n");
         }
 
+        #[rstest]
+        fn compare_the_branch_with_its_upstream(mut helper: CommandHelper) -> Result<()> {
+            helper.jit_cmd(&["remote", "add", "origin", "ssh://example.com/repo"]);
+            for branch in ["topic", "second"] {
+                helper.jit_cmd(&["config", &format!("branch.{}.remote", branch), "origin"]);
+                helper.jit_cmd(&[
+                    "config",
+                    &format!("branch.{}.merge", branch),
+                    &format!("refs/heads/{}", branch),
+                ]);
+            }
+            let second = helper.resolve_revision("second")?;
+            let topic = helper.resolve_revision("topic")?;
+            helper
+                .repo
+                .refs
+                .update_ref("refs/remotes/origin/topic", &second)?;
+            helper
+                .repo
+                .refs
+                .update_ref("refs/remotes/origin/second", &topic)?;
+
+            helper
+                .jit_cmd(&["checkout", "second"])
+                .assert()
+                .code(0)
+                .stdout(
+                    "\
+Your branch is behind 'origin/second' by 1 commit, and can be fast-forwarded.
+  (use \"jit pull\" to update your local branch)
+",
+                );
+            helper
+                .jit_cmd(&["checkout", "topic"])
+                .assert()
+                .code(0)
+                .stderr("Switched to branch 'topic'\n")
+                .stdout(
+                    "\
+Your branch is ahead of 'origin/topic' by 1 commit.
+  (use \"jit push\" to publish your local commits)
+",
+                );
+            helper
+                .jit_cmd(&["--quiet", "checkout", "second"])
+                .assert()
+                .code(0)
+                .stdout("")
+                .stderr("");
+
+            Ok(())
+        }
+
         #[rstest]
         fn print_a_warning_when_detaching_head(mut helper: CommandHelper) -> Result<()> {
             let short_oid = Database::short_oid(&helper.resolve_revision("@")?);
@@ -910,6 +1051,32 @@ HEAD is now at {} third\n",
 
             Ok(())
         }
+
+        #[rstest]
+        fn report_the_detached_commit_in_the_status(mut helper: CommandHelper) -> Result<()> {
+            let oid = helper.resolve_revision("@^")?;
+            helper.jit_cmd(&["checkout", &oid]).assert().code(0);
+
+            helper.jit_cmd(&["status"]).assert().code(0).stdout(format!(
+                "HEAD detached at {}\nnothing to commit, working tree clean\n",
+                Database::short_oid(&oid)
+            ));
+
+            Ok(())
+        }
+
+        #[rstest]
+        fn skip_the_detached_head_advice_when_disabled(mut helper: CommandHelper) -> Result<()> {
+            helper.jit_cmd(&["config", "advice.detachedHead", "false"]);
+
+            let short_oid = Database::short_oid(&helper.resolve_revision("@")?);
+            helper
+                .jit_cmd(&["checkout", "@"])
+                .assert()
+                .stderr(format!("HEAD is now at {} third\n", short_oid));
+
+            Ok(())
+        }
     }
 
     mod checking_out_a_relative_revision {
@@ -994,3 +1161,230 @@ Switched to branch 'topic'\n",
         }
     }
 }
+
+mod with_a_symlink {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use jit::database::blob::Blob;
+    use jit::database::entry::Entry;
+    use jit::database::object::Object;
+    use jit::database::tree::{Tree, TreeEntry};
+
+    use super::*;
+
+    /// Commit a tree holding `a.txt` and a symlink `link` pointing at it, which `jit add` can't
+    /// stage, and return the commit's ID.
+    fn commit_symlink(helper: &mut CommandHelper) -> Result<String> {
+        let target = Blob::new(b"a.txt".to_vec());
+        helper.repo.database.store(&target)?;
+
+        let mut tree = helper
+            .repo
+            .database
+            .load_tree(&helper.load_commit("@")?.tree)?;
+        tree.entries.insert(
+            PathBuf::from("link"),
+            TreeEntry::Entry(Entry::new(target.oid(), 0o120000)),
+        );
+        let tree = Tree::new(Some(tree.entries));
+        helper.repo.database.store(&tree)?;
+
+        let output = helper.jit_cmd(&["commit-tree", &tree.oid(), "-p", "@", "-m", "link"]);
+
+        Ok(String::from_utf8(output.stdout).unwrap().trim().to_owned())
+    }
+
+    #[fixture]
+    fn helper() -> CommandHelper {
+        let mut helper = CommandHelper::new();
+        helper.init();
+
+        helper.write_file("a.txt", "hello\n").unwrap();
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("first");
+
+        helper
+    }
+
+    #[rstest]
+    fn check_out_a_symlink(mut helper: CommandHelper) -> Result<()> {
+        let commit = commit_symlink(&mut helper)?;
+
+        helper.jit_cmd(&["checkout", &commit]).assert().code(0);
+
+        let path = helper.repo_path.join("link");
+        assert!(fs::symlink_metadata(&path)?.file_type().is_symlink());
+        assert_eq!(fs::read_link(&path)?, PathBuf::from("a.txt"));
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn check_out_a_symlink_as_a_file_without_core_symlinks(
+        mut helper: CommandHelper,
+    ) -> Result<()> {
+        let commit = commit_symlink(&mut helper)?;
+        helper
+            .jit_cmd(&["config", "core.symlinks", "false"])
+            .assert()
+            .code(0);
+
+        helper.jit_cmd(&["checkout", &commit]).assert().code(0);
+
+        let path = helper.repo_path.join("link");
+        assert!(fs::symlink_metadata(&path)?.file_type().is_file());
+        assert_eq!(fs::read_to_string(&path)?, "a.txt");
+        helper.assert_status("");
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn keep_the_mode_of_a_symlink_added_without_core_symlinks(
+        mut helper: CommandHelper,
+    ) -> Result<()> {
+        let commit = commit_symlink(&mut helper)?;
+        helper
+            .jit_cmd(&["config", "core.symlinks", "false"])
+            .assert()
+            .code(0);
+        helper.jit_cmd(&["checkout", &commit]).assert().code(0);
+
+        helper.jit_cmd(&["add", "."]).assert().code(0);
+
+        helper.assert_status("");
+        helper.repo.index.load()?;
+        let entry = helper.repo.index.entry_for_path("link", 0).unwrap();
+        assert_eq!(entry.mode, 0o120000);
+
+        Ok(())
+    }
+}
+
+mod with_a_branch_named_like_a_file {
+    use super::*;
+
+    #[fixture]
+    fn helper() -> CommandHelper {
+        let mut helper = CommandHelper::new();
+        helper.init();
+
+        helper.write_file("topic", "committed").unwrap();
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("first");
+        helper.jit_cmd(&["branch", "topic"]);
+
+        helper.write_file("topic", "changed").unwrap();
+
+        helper
+    }
+
+    #[rstest]
+    fn fail_when_the_name_is_both_a_revision_and_a_path(mut helper: CommandHelper) -> Result<()> {
+        helper
+            .jit_cmd(&["checkout", "topic"])
+            .assert()
+            .code(128)
+            .stderr(
+                "\
+fatal: ambiguous argument 'topic': both revision and filename
+Use '--' to separate paths from revisions, like this:
+'jit <command> [<revision>...] -- [<file>...]'\n",
+            );
+
+        helper.assert_workspace(&HashMap::from([("topic", "changed")]))?;
+        assert_eq!(
+            helper.repo.refs.current_ref("HEAD")?,
+            Ref::SymRef {
+                path: String::from("refs/heads/main")
+            }
+        );
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn restore_the_path_from_the_index_after_a_separator(mut helper: CommandHelper) -> Result<()> {
+        helper
+            .jit_cmd(&["checkout", "--", "topic"])
+            .assert()
+            .code(0)
+            .stderr("Updated 1 path from the index\n");
+
+        helper.assert_workspace(&HashMap::from([("topic", "committed")]))?;
+        helper.assert_status("");
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn restore_a_name_that_is_only_a_path(mut helper: CommandHelper) -> Result<()> {
+        helper.jit_cmd(&["branch", "-D", "topic"]);
+
+        helper.jit_cmd(&["checkout", "topic"]).assert().code(0);
+
+        helper.assert_workspace(&HashMap::from([("topic", "committed")]))?;
+
+        Ok(())
+    }
+}
+
+mod with_pathspecs_naming_directories {
+    use super::*;
+
+    #[fixture]
+    fn helper() -> CommandHelper {
+        let mut helper = CommandHelper::new();
+        helper.init();
+
+        helper.write_file("a.txt", "1").unwrap();
+        helper.write_file("dir/b.txt", "2").unwrap();
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("first");
+
+        helper.write_file("a.txt", "changed").unwrap();
+        helper.write_file("dir/b.txt", "changed").unwrap();
+
+        helper
+    }
+
+    #[rstest]
+    fn restore_every_path_from_the_index_with_a_dot(mut helper: CommandHelper) -> Result<()> {
+        helper
+            .jit_cmd(&["checkout", "--", "."])
+            .assert()
+            .code(0)
+            .stderr("Updated 2 paths from the index\n");
+
+        helper.assert_workspace(&HashMap::from([("a.txt", "1"), ("dir/b.txt", "2")]))?;
+        helper.assert_status("");
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn restore_a_directory_with_a_trailing_slash(mut helper: CommandHelper) -> Result<()> {
+        helper
+            .jit_cmd(&["checkout", "--", "dir/"])
+            .assert()
+            .code(0)
+            .stderr("Updated 1 path from the index\n");
+
+        helper.assert_workspace(&HashMap::from([("a.txt", "changed"), ("dir/b.txt", "2")]))?;
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn restore_a_directory_from_a_commit(mut helper: CommandHelper) -> Result<()> {
+        helper
+            .jit_cmd(&["checkout", "@", "--", "./dir/"])
+            .assert()
+            .code(0);
+
+        helper.assert_workspace(&HashMap::from([("a.txt", "changed"), ("dir/b.txt", "2")]))?;
+
+        Ok(())
+    }
+}
@@ -61,10 +61,13 @@ mod with_two_branches {
     fn apply_a_commit_on_top_of_the_current_head(mut helper: CommandHelper) -> Result<()> {
         helper.jit_cmd(&["cherry-pick", "topic~3"]).assert().code(0);
 
-        let revs = RevList::new(&helper.repo, &[String::from("@~3..")], Default::default())?;
+        let revs: Vec<_> =
+            RevList::new(&helper.repo, &[String::from("@~3..")], Default::default())?
+                .collect::<Result<_>>()?;
 
         assert_eq!(
-            revs.map(|commit| commit.message.trim().to_owned())
+            revs.iter()
+                .map(|commit| commit.message.trim().to_owned())
                 .collect::<Vec<_>>(),
             vec![
                 String::from("five"),
@@ -82,6 +85,53 @@ mod with_two_branches {
         Ok(())
     }
 
+    #[rstest]
+    fn record_the_commit_a_commit_was_picked_from(mut helper: CommandHelper) -> Result<()> {
+        let picked = helper.resolve_revision("topic~3")?;
+
+        helper
+            .jit_cmd(&["cherry-pick", "-x", "topic~3"])
+            .assert()
+            .code(0);
+
+        assert_eq!(
+            helper.load_commit("@")?.message,
+            format!("five\n\n(cherry picked from commit {})\n", picked)
+        );
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn record_the_origin_of_commits_picked_after_continuing(
+        mut helper: CommandHelper,
+    ) -> Result<()> {
+        let conflicted = helper.resolve_revision("topic^^")?;
+        let picked = helper.resolve_revision("topic~3")?;
+
+        helper
+            .jit_cmd(&["cherry-pick", "-x", "topic^^", "topic~3"])
+            .assert()
+            .code(1);
+        helper.write_file("f.txt", "six")?;
+        helper.jit_cmd(&["add", "f.txt"]);
+        helper
+            .jit_cmd(&["cherry-pick", "--continue"])
+            .assert()
+            .code(0);
+
+        assert_eq!(
+            helper.load_commit("@^")?.message,
+            format!("six\n\n(cherry picked from commit {})\n", conflicted)
+        );
+        assert_eq!(
+            helper.load_commit("@")?.message,
+            format!("five\n\n(cherry picked from commit {})\n", picked)
+        );
+
+        Ok(())
+    }
+
     #[rstest]
     fn fail_to_apply_a_content_conflict(mut helper: CommandHelper) -> Result<()> {
         helper.jit_cmd(&["cherry-pick", "topic^^"]).assert().code(1);
@@ -124,6 +174,64 @@ six>>>>>>> {}... six
         Ok(())
     }
 
+    #[rstest]
+    fn show_the_cherry_pick_in_progress_in_the_status(mut helper: CommandHelper) -> Result<()> {
+        helper.jit_cmd(&["cherry-pick", "topic^^"]).assert().code(1);
+
+        let oid = helper.resolve_revision("topic^^")?;
+        let short = Database::short_oid(&oid);
+
+        helper.jit_cmd(&["status"]).assert().code(0).stdout(format!(
+            "\
+On branch main
+You are currently cherry-picking commit {}.
+  (fix conflicts and run 'jit cherry-pick --continue')
+  (use 'jit cherry-pick --abort' to cancel the cherry-pick operation)
+
+Unmerged paths:
+
+\tboth modified:   f.txt
+
+no changes added to commit
+",
+            short
+        ));
+
+        helper
+            .jit_cmd(&["status", "--porcelain", "--branch"])
+            .assert()
+            .code(0)
+            .stdout(format!(
+                "\
+# branch.oid {}
+# branch.head main
+# cherry-pick.oid {}
+UU f.txt
+",
+                helper.resolve_revision("main")?,
+                oid
+            ));
+
+        helper.jit_cmd(&["add", "f.txt"]);
+
+        helper.jit_cmd(&["status"]).assert().code(0).stdout(format!(
+            "\
+On branch main
+You are currently cherry-picking commit {}.
+  (all conflicts fixed: run 'jit cherry-pick --continue')
+  (use 'jit cherry-pick --abort' to cancel the cherry-pick operation)
+
+Changes to be committed:
+
+\tmodified:   f.txt
+
+",
+            short
+        ));
+
+        Ok(())
+    }
+
     #[rstest]
     fn continue_a_conflicted_cherry_pick(mut helper: CommandHelper) -> Result<()> {
         helper.jit_cmd(&["cherry-pick", "topic"]);
@@ -135,7 +243,8 @@ six>>>>>>> {}... six
             .code(0);
 
         let commits: Vec<_> =
-            RevList::new(&helper.repo, &[String::from("@~3..")], Default::default())?.collect();
+            RevList::new(&helper.repo, &[String::from("@~3..")], Default::default())?
+                .collect::<Result<_>>()?;
         assert_eq!(commits[0].parents, vec![commits[1].oid()]);
 
         assert_eq!(
@@ -167,7 +276,8 @@ six>>>>>>> {}... six
         helper.jit_cmd(&["commit"]).assert().code(0);
 
         let commits: Vec<_> =
-            RevList::new(&helper.repo, &[String::from("@~3..")], Default::default())?.collect();
+            RevList::new(&helper.repo, &[String::from("@~3..")], Default::default())?
+                .collect::<Result<_>>()?;
         assert_eq!(commits[0].parents, vec![commits[1].oid()]);
 
         assert_eq!(
@@ -192,10 +302,13 @@ six>>>>>>> {}... six
             .assert()
             .code(0);
 
-        let revs = RevList::new(&helper.repo, &[String::from("@~4..")], Default::default())?;
+        let revs: Vec<_> =
+            RevList::new(&helper.repo, &[String::from("@~4..")], Default::default())?
+                .collect::<Result<_>>()?;
 
         assert_eq!(
-            revs.map(|commit| commit.message.trim().to_owned())
+            revs.iter()
+                .map(|commit| commit.message.trim().to_owned())
                 .collect::<Vec<_>>(),
             vec![
                 String::from("eight"),
@@ -281,10 +394,13 @@ fatal: Exiting because of an unresolved conflict.
             .assert()
             .code(0);
 
-        let revs = RevList::new(&helper.repo, &[String::from("@~5..")], Default::default())?;
+        let revs: Vec<_> =
+            RevList::new(&helper.repo, &[String::from("@~5..")], Default::default())?
+                .collect::<Result<_>>()?;
 
         assert_eq!(
-            revs.map(|commit| commit.message.trim().to_owned())
+            revs.iter()
+                .map(|commit| commit.message.trim().to_owned())
                 .collect::<Vec<_>>(),
             vec![
                 String::from("eight"),
@@ -317,10 +433,13 @@ fatal: Exiting because of an unresolved conflict.
             .assert()
             .code(0);
 
-        let revs = RevList::new(&helper.repo, &[String::from("@~5..")], Default::default())?;
+        let revs: Vec<_> =
+            RevList::new(&helper.repo, &[String::from("@~5..")], Default::default())?
+                .collect::<Result<_>>()?;
 
         assert_eq!(
-            revs.map(|commit| commit.message.trim().to_owned())
+            revs.iter()
+                .map(|commit| commit.message.trim().to_owned())
                 .collect::<Vec<_>>(),
             vec![
                 String::from("eight"),
@@ -495,6 +614,21 @@ mod with_merges {
         Ok(())
     }
 
+    #[rstest]
+    fn record_the_origin_of_a_merge(mut helper: CommandHelper) -> Result<()> {
+        let picked = helper.resolve_revision("topic^")?;
+
+        helper
+            .jit_cmd(&["cherry-pick", "-x", "-m", "1", "topic^"])
+            .assert()
+            .code(0);
+
+        let message = helper.load_commit("@")?.message;
+        assert!(message.ends_with(&format!("\n\n(cherry picked from commit {})\n", picked)));
+
+        Ok(())
+    }
+
     #[rstest]
     fn cherry_pick_a_merge_based_on_its_second_parent(mut helper: CommandHelper) -> Result<()> {
         helper
@@ -530,10 +664,13 @@ mod with_merges {
             .assert()
             .code(0);
 
-        let revs = RevList::new(&helper.repo, &[String::from("@~3..")], Default::default())?;
+        let revs: Vec<_> =
+            RevList::new(&helper.repo, &[String::from("@~3..")], Default::default())?
+                .collect::<Result<_>>()?;
 
         assert_eq!(
-            revs.map(|commit| commit.message.trim().to_owned())
+            revs.iter()
+                .map(|commit| commit.message.trim().to_owned())
                 .collect::<Vec<_>>(),
             vec![
                 String::from("merge side^"),
@@ -0,0 +1,140 @@
+mod common;
+
+use std::collections::HashMap;
+
+use assert_cmd::prelude::OutputAssertExt;
+pub use common::CommandHelper;
+use jit::errors::Result;
+use rstest::{fixture, rstest};
+
+#[fixture]
+fn helper() -> CommandHelper {
+    let mut helper = CommandHelper::new();
+    helper.init();
+
+    helper.write_file("tracked.txt", "tracked").unwrap();
+    helper.jit_cmd(&["add", "."]);
+    helper.commit("first");
+
+    helper.write_file("a.txt", "a").unwrap();
+    helper.write_file("b.log", "b").unwrap();
+    helper.write_file("c.txt", "c").unwrap();
+    helper.write_file("outer/d.txt", "d").unwrap();
+
+    helper
+}
+
+#[rstest]
+fn refuse_to_clean_without_force(mut helper: CommandHelper) {
+    helper.jit_cmd(&["clean"]).assert().code(128).stderr(
+        "fatal: clean.requireForce defaults to true and neither -i, -n, nor -f given; refusing to clean\n",
+    );
+}
+
+#[rstest]
+fn list_the_files_that_would_be_removed(mut helper: CommandHelper) -> Result<()> {
+    helper
+        .jit_cmd(&["clean", "-n"])
+        .assert()
+        .code(0)
+        .stdout("Would remove a.txt\nWould remove b.log\nWould remove c.txt\n");
+
+    assert_eq!(
+        helper.repo.workspace.list_files(&helper.repo_path)?.len(),
+        5
+    );
+
+    Ok(())
+}
+
+#[rstest]
+fn remove_untracked_files_and_directories(mut helper: CommandHelper) -> Result<()> {
+    helper
+        .jit_cmd(&["clean", "-f", "-d"])
+        .assert()
+        .code(0)
+        .stdout("Removing a.txt\nRemoving b.log\nRemoving c.txt\nRemoving outer/\n");
+
+    helper.assert_workspace(&HashMap::from([("tracked.txt", "tracked")]))?;
+
+    Ok(())
+}
+
+#[rstest]
+fn remove_the_files_selected_by_number(mut helper: CommandHelper) -> Result<()> {
+    helper.stdin = String::from("3\n1 3\n1\n");
+
+    helper.jit_cmd(&["clean", "-i"]).assert().code(0).stdout(
+        "\
+Would remove the following items:
+  a.txt
+  b.log
+  c.txt
+*** Commands ***
+    1: clean                2: filter by pattern    3: select by numbers    4: quit
+What now>   1: a.txt
+  2: b.log
+  3: c.txt
+Select items to delete>> Would remove the following items:
+  a.txt
+  c.txt
+*** Commands ***
+    1: clean                2: filter by pattern    3: select by numbers    4: quit
+What now> Removing a.txt
+Removing c.txt
+",
+    );
+
+    helper.assert_workspace(&HashMap::from([
+        ("b.log", "b"),
+        ("outer/d.txt", "d"),
+        ("tracked.txt", "tracked"),
+    ]))?;
+
+    Ok(())
+}
+
+#[rstest]
+fn remove_the_files_left_by_a_filter(mut helper: CommandHelper) -> Result<()> {
+    helper.stdin = String::from("filter by pattern\n*.txt\nclean\n");
+
+    helper.jit_cmd(&["clean", "-i"]).assert().code(0);
+
+    helper.assert_workspace(&HashMap::from([
+        ("a.txt", "a"),
+        ("c.txt", "c"),
+        ("outer/d.txt", "d"),
+        ("tracked.txt", "tracked"),
+    ]))?;
+
+    Ok(())
+}
+
+#[rstest]
+fn accept_abbreviated_menu_choices(mut helper: CommandHelper) -> Result<()> {
+    helper.stdin = String::from("f\n*.log\nc\n");
+
+    helper.jit_cmd(&["clean", "-i"]).assert().code(0);
+
+    helper.assert_workspace(&HashMap::from([
+        ("b.log", "b"),
+        ("outer/d.txt", "d"),
+        ("tracked.txt", "tracked"),
+    ]))?;
+
+    Ok(())
+}
+
+#[rstest]
+fn remove_nothing_when_quitting(mut helper: CommandHelper) -> Result<()> {
+    helper.stdin = String::from("q\n");
+
+    helper.jit_cmd(&["clean", "-i"]).assert().code(0);
+
+    assert_eq!(
+        helper.repo.workspace.list_files(&helper.repo_path)?.len(),
+        5
+    );
+
+    Ok(())
+}
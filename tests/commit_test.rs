@@ -220,9 +220,11 @@ mod reusing_messages {
         helper.jit_cmd(&["add", "."]);
         helper.jit_cmd(&["commit", "-C", "@"]).assert().code(0);
 
-        let revs = RevList::new(&helper.repo, &[String::from("HEAD")], Default::default())?;
+        let revs: Vec<_> = RevList::new(&helper.repo, &[String::from("HEAD")], Default::default())?
+            .collect::<Result<_>>()?;
         assert_eq!(
-            revs.map(|commit| commit.message.trim().to_owned())
+            revs.iter()
+                .map(|commit| commit.message.trim().to_owned())
                 .collect::<Vec<_>>(),
             vec![String::from("first"), String::from("first")]
         );
@@ -231,6 +233,233 @@ mod reusing_messages {
     }
 }
 
+mod setting_the_author_date {
+    use super::*;
+
+    #[fixture]
+    fn helper() -> CommandHelper {
+        let mut helper = CommandHelper::new();
+        helper.init();
+
+        helper
+    }
+
+    #[rstest]
+    fn use_the_date_given_on_the_command_line(mut helper: CommandHelper) -> Result<()> {
+        helper.write_file("file.txt", "1")?;
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("first");
+
+        helper.write_file("file.txt", "2")?;
+        helper.jit_cmd(&["add", "."]);
+        helper
+            .jit_cmd(&["commit", "-m", "second", "--date=2020-01-01T00:00:00Z"])
+            .assert()
+            .code(0);
+
+        let commit = helper.load_commit("@")?;
+        assert_eq!(
+            commit.author.readable_time(),
+            "Wed Jan 1 00:00:00 2020 +0000"
+        );
+        assert_ne!(commit.committer.time, commit.author.time);
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn set_the_date_of_an_amended_commit(mut helper: CommandHelper) -> Result<()> {
+        helper.write_file("file.txt", "1")?;
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("first");
+
+        helper
+            .jit_cmd(&["commit", "--amend", "--date", "1577836800 +0100"])
+            .assert()
+            .code(0);
+
+        let commit = helper.load_commit("@")?;
+        assert_eq!(
+            commit.author.readable_time(),
+            "Wed Jan 1 01:00:00 2020 +0100"
+        );
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn reject_an_invalid_date(mut helper: CommandHelper) -> Result<()> {
+        helper.write_file("file.txt", "1")?;
+        helper.jit_cmd(&["add", "."]);
+        helper
+            .jit_cmd(&["commit", "-m", "first", "--date", "someday"])
+            .assert()
+            .code(128)
+            .stderr("fatal: invalid date format: someday\n");
+
+        assert_eq!(helper.repo.refs.read_head()?, None);
+
+        Ok(())
+    }
+}
+
+mod committing_verbosely {
+    use super::*;
+
+    #[fixture]
+    fn helper() -> CommandHelper {
+        let mut helper = CommandHelper::new();
+        helper.init();
+
+        helper.write_file("file.txt", "one\n").unwrap();
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("first");
+
+        helper
+    }
+
+    #[rstest]
+    fn include_the_staged_diff_below_the_scissors(mut helper: CommandHelper) -> Result<()> {
+        helper.write_file("file.txt", "two\n")?;
+        helper.jit_cmd(&["add", "."]);
+        helper
+            .jit_cmd(&["commit", "--verbose", "-m", "second"])
+            .assert()
+            .code(0);
+
+        let template = std::fs::read_to_string(helper.repo_path.join(".git/COMMIT_EDITMSG"))?;
+        assert!(template.contains(
+            "\
+# ------------------------ >8 ------------------------
+# Do not modify or remove the line above.
+# Everything below it will be ignored.
+diff --git a/file.txt b/file.txt
+index 5626abf..f719efd 100644
+--- a/file.txt
++++ b/file.txt
+@@ -1,1 +1,1 @@
+-one
++two
+"
+        ));
+
+        assert_eq!(helper.load_commit("@")?.message, "second\n");
+
+        Ok(())
+    }
+}
+
+mod committing_with_a_dry_run {
+    use super::*;
+
+    #[fixture]
+    fn helper() -> CommandHelper {
+        let mut helper = CommandHelper::new();
+        helper.init();
+
+        helper.write_file("file.txt", "one\n").unwrap();
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("first");
+
+        helper
+    }
+
+    #[rstest]
+    fn list_the_staged_changes_without_committing(mut helper: CommandHelper) -> Result<()> {
+        let head_before = helper.resolve_revision("@")?;
+
+        helper.write_file("file.txt", "two\n")?;
+        helper.write_file("new.txt", "new\n")?;
+        helper.jit_cmd(&["add", "file.txt"]);
+
+        helper
+            .jit_cmd(&["commit", "--dry-run", "-m", "second"])
+            .assert()
+            .code(0)
+            .stdout(
+                "\
+On branch main
+Changes to be committed:
+
+\tmodified:   file.txt
+
+Untracked files:
+
+\tnew.txt
+
+",
+            );
+
+        assert_eq!(helper.resolve_revision("@")?, head_before);
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn fail_when_nothing_is_staged(mut helper: CommandHelper) {
+        helper
+            .jit_cmd(&["commit", "--dry-run", "-m", "second"])
+            .assert()
+            .code(1)
+            .stdout("On branch main\nnothing to commit, working tree clean\n");
+    }
+
+    #[rstest]
+    fn compare_an_amend_with_the_parent_of_head(mut helper: CommandHelper) -> Result<()> {
+        helper.write_file("file.txt", "two\n")?;
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("second");
+
+        helper
+            .jit_cmd(&["commit", "--dry-run", "--amend"])
+            .assert()
+            .code(0)
+            .stdout(
+                "\
+On branch main
+Changes to be committed:
+
+\tmodified:   file.txt
+
+",
+            );
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn list_every_file_when_amending_a_root_commit(mut helper: CommandHelper) {
+        helper
+            .jit_cmd(&["commit", "--dry-run", "--amend"])
+            .assert()
+            .code(0)
+            .stdout(
+                "\
+On branch main
+Changes to be committed:
+
+\tnew file:   file.txt
+
+",
+            );
+    }
+
+    #[rstest]
+    fn fail_when_an_amend_would_leave_nothing_to_commit(mut helper: CommandHelper) -> Result<()> {
+        helper.write_file("other.txt", "other\n")?;
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("second");
+        helper.jit_cmd(&["rm", "--cached", "-q", "other.txt"]);
+
+        helper
+            .jit_cmd(&["commit", "--dry-run", "--amend"])
+            .assert()
+            .code(1);
+
+        Ok(())
+    }
+}
+
 mod amending_commits {
     use super::*;
 
@@ -254,10 +483,12 @@ mod amending_commits {
             .jit_cmd(&["commit", "--amend", "--message", "third [amended]"])
             .assert()
             .code(0);
-        let revs = RevList::new(&helper.repo, &[String::from("HEAD")], Default::default())?;
+        let revs: Vec<_> = RevList::new(&helper.repo, &[String::from("HEAD")], Default::default())?
+            .collect::<Result<_>>()?;
 
         assert_eq!(
-            revs.map(|commit| commit.message.trim().to_owned())
+            revs.iter()
+                .map(|commit| commit.message.trim().to_owned())
                 .collect::<Vec<_>>(),
             vec![
                 String::from("third [amended]"),
@@ -277,8 +508,8 @@ mod amending_commits {
 
         let commit = helper.load_commit("HEAD")?;
         let diff = helper.repo.database.tree_diff(
-            commit.parent().as_deref(),
-            Some(&commit.oid()),
+            &commit.first_parent_or_empty_tree(),
+            &commit.oid(),
             None,
         )?;
 
@@ -292,3 +523,270 @@ mod amending_commits {
         Ok(())
     }
 }
+
+mod adding_trailers {
+    use super::*;
+
+    #[fixture]
+    fn helper() -> CommandHelper {
+        let mut helper = CommandHelper::new();
+        helper.init();
+
+        helper.write_file("file.txt", "1").unwrap();
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("first");
+
+        helper.write_file("file.txt", "2").unwrap();
+        helper.jit_cmd(&["add", "."]);
+
+        helper
+    }
+
+    #[rstest]
+    fn sign_off_as_the_committer(mut helper: CommandHelper) -> Result<()> {
+        helper
+            .jit_cmd(&["commit", "-s", "-m", "second\n\nWith a body"])
+            .assert()
+            .code(0);
+
+        assert_eq!(
+            helper.load_commit("@")?.message,
+            "second\n\nWith a body\n\nSigned-off-by: A. U. Thor <author@example.com>\n"
+        );
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn merge_trailers_into_an_existing_block(mut helper: CommandHelper) -> Result<()> {
+        helper
+            .jit_cmd(&[
+                "commit",
+                "-m",
+                "second\n\nReviewed-by: Someone <someone@example.com>",
+                "--trailer",
+                "Co-authored-by: Other <other@example.com>",
+                "--signoff",
+            ])
+            .assert()
+            .code(0);
+
+        assert_eq!(
+            helper.load_commit("@")?.message,
+            "\
+second
+
+Reviewed-by: Someone <someone@example.com>
+Co-authored-by: Other <other@example.com>
+Signed-off-by: A. U. Thor <author@example.com>
+"
+        );
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn reject_an_invalid_trailer(mut helper: CommandHelper) -> Result<()> {
+        helper
+            .jit_cmd(&["commit", "-m", "second", "--trailer", "not a trailer"])
+            .assert()
+            .code(128)
+            .stderr("fatal: invalid trailer 'not a trailer'\n");
+
+        assert_eq!(helper.load_commit("@")?.message, "first\n");
+
+        Ok(())
+    }
+}
+
+mod cleaning_up_messages {
+    use super::*;
+
+    const MESSAGE: &str = "second  \n# a comment\n\n\n\nWith a body\n\n";
+
+    #[fixture]
+    fn helper() -> CommandHelper {
+        let mut helper = CommandHelper::new();
+        helper.init();
+
+        helper.write_file("file.txt", "1").unwrap();
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("first");
+
+        helper.write_file("file.txt", "2").unwrap();
+        helper.jit_cmd(&["add", "."]);
+
+        helper
+    }
+
+    #[rstest]
+    fn strip_comments_and_whitespace_by_default(mut helper: CommandHelper) -> Result<()> {
+        helper.jit_cmd(&["commit", "-m", MESSAGE]).assert().code(0);
+
+        assert_eq!(helper.load_commit("@")?.message, "second\n\nWith a body\n");
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn only_strip_whitespace(mut helper: CommandHelper) -> Result<()> {
+        helper
+            .jit_cmd(&["commit", "--cleanup=whitespace", "-m", MESSAGE])
+            .assert()
+            .code(0);
+
+        assert_eq!(
+            helper.load_commit("@")?.message,
+            "second\n# a comment\n\nWith a body\n"
+        );
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn keep_the_message_verbatim(mut helper: CommandHelper) -> Result<()> {
+        helper
+            .jit_cmd(&[
+                "commit",
+                "--cleanup",
+                "verbatim",
+                "-m",
+                "second  \n# a comment",
+            ])
+            .assert()
+            .code(0);
+
+        assert_eq!(helper.load_commit("@")?.message, "second  \n# a comment\n");
+
+        Ok(())
+    }
+}
+
+mod signing_commits {
+    use std::fs;
+
+    use jit::database::commit::Commit;
+
+    use super::*;
+
+    const SIGNER: &str = "\
+#!/bin/sh
+cat > \"$(dirname \"$0\")/payload\"
+echo '-----BEGIN PGP SIGNATURE-----'
+echo
+echo \"signed by $3\"
+echo '-----END PGP SIGNATURE-----'
+";
+
+    #[fixture]
+    fn helper() -> CommandHelper {
+        let mut helper = CommandHelper::new();
+        helper.init();
+
+        helper.write_file("file.txt", "1").unwrap();
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("first");
+
+        helper.write_file(".git/fake-gpg", SIGNER).unwrap();
+        helper.make_executable(".git/fake-gpg").unwrap();
+        let program = path_to_string(&helper.repo_path.join(".git/fake-gpg"));
+        helper.jit_cmd(&["config", "gpg.program", &program]);
+
+        helper.write_file("file.txt", "2").unwrap();
+        helper.jit_cmd(&["add", "."]);
+
+        helper
+    }
+
+    #[rstest]
+    fn store_the_signature_of_the_commit_content(mut helper: CommandHelper) -> Result<()> {
+        helper
+            .jit_cmd(&["commit", "-S", "-m", "signed"])
+            .assert()
+            .code(0);
+
+        let commit = helper.load_commit("@")?;
+        assert_eq!(commit.message, "signed\n");
+        assert_eq!(
+            commit.gpgsig(),
+            Some(
+                "-----BEGIN PGP SIGNATURE-----\n\nsigned by A. U. Thor <author@example.com>\n-----END PGP SIGNATURE-----"
+            )
+        );
+
+        // The signature covers the commit without its `gpgsig` header, which is part of the oid
+        let mut unsigned = Commit::new(
+            commit.parents.clone(),
+            commit.tree.clone(),
+            commit.author.clone(),
+            commit.committer.clone(),
+            commit.message.clone(),
+        );
+        assert_eq!(
+            fs::read(helper.repo_path.join(".git/payload"))?,
+            unsigned.bytes()
+        );
+        unsigned.extra_headers = commit.extra_headers.clone();
+        assert_eq!(unsigned.oid(), commit.oid());
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn sign_with_the_given_key_when_configured(mut helper: CommandHelper) -> Result<()> {
+        helper.jit_cmd(&["config", "commit.gpgSign", "true"]);
+
+        helper
+            .jit_cmd(&["commit", "--gpg-sign=ABC123", "-m", "signed"])
+            .assert()
+            .code(0);
+        assert!(helper
+            .load_commit("@")?
+            .gpgsig()
+            .unwrap()
+            .contains("signed by ABC123"));
+
+        helper.write_file("file.txt", "3")?;
+        helper.jit_cmd(&["add", "."]);
+        helper
+            .jit_cmd(&["commit", "--no-gpg-sign", "-m", "unsigned"])
+            .assert()
+            .code(0);
+        assert_eq!(helper.load_commit("@")?.gpgsig(), None);
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn sign_an_amended_commit(mut helper: CommandHelper) -> Result<()> {
+        helper
+            .jit_cmd(&["commit", "--amend", "-S", "-m", "amended"])
+            .assert()
+            .code(0);
+
+        let commit = helper.load_commit("@")?;
+        assert_eq!(commit.message, "amended\n");
+        assert!(commit
+            .gpgsig()
+            .unwrap()
+            .contains("signed by A. U. Thor <author@example.com>"));
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn abort_when_signing_fails(mut helper: CommandHelper) -> Result<()> {
+        helper.jit_cmd(&["config", "gpg.program", "/bin/false"]);
+
+        helper
+            .jit_cmd(&["commit", "-S", "-m", "signed"])
+            .assert()
+            .code(128)
+            .stderr(
+                "error: /bin/false failed to sign the data\nfatal: failed to write commit object\n",
+            );
+        assert_eq!(helper.load_commit("@")?.message, "first\n");
+
+        Ok(())
+    }
+}
@@ -0,0 +1,102 @@
+mod common;
+
+use assert_cmd::prelude::OutputAssertExt;
+pub use common::CommandHelper;
+use jit::errors::Result;
+use rstest::{fixture, rstest};
+
+const TREE: &str = "4cf9f177c4c015836fca6a31f9c3917e89ae29ec";
+const ROOT: &str = "84a466faf075b9974e49fa113e681eff56c2bfba";
+
+#[fixture]
+fn helper() -> CommandHelper {
+    let mut helper = CommandHelper::new();
+    helper.init();
+
+    helper.write_file("file.txt", "hello\n").unwrap();
+    helper.jit_cmd(&["add", "."]);
+    helper.commit("first");
+
+    helper.env.insert(
+        String::from("GIT_AUTHOR_DATE"),
+        String::from("Mon, 1 Jan 2024 00:00:00 +0000"),
+    );
+
+    helper
+}
+
+#[rstest]
+fn create_a_root_commit_from_a_tree(mut helper: CommandHelper) -> Result<()> {
+    assert_eq!(helper.load_commit("@")?.tree, TREE);
+
+    helper
+        .jit_cmd(&["commit-tree", TREE, "-m", "made by hand"])
+        .assert()
+        .code(0)
+        .stdout(format!("{}\n", ROOT));
+
+    let commit = helper.repo.database.load_commit(ROOT)?;
+    assert_eq!(commit.tree, TREE);
+    assert!(commit.parents.is_empty());
+    assert_eq!(commit.message, "made by hand\n");
+
+    Ok(())
+}
+
+#[rstest]
+fn create_a_commit_with_parents(mut helper: CommandHelper) -> Result<()> {
+    let head = helper.resolve_revision("@")?;
+    helper.jit_cmd(&["commit-tree", TREE, "-m", "made by hand"]);
+
+    let output = helper.jit_cmd(&[
+        "commit-tree",
+        TREE,
+        "-p",
+        ROOT,
+        "-p",
+        "@",
+        "-m",
+        "first",
+        "-m",
+        "second",
+    ]);
+    output.clone().assert().code(0);
+
+    let oid = String::from_utf8(output.stdout).unwrap();
+    let commit = helper.repo.database.load_commit(oid.trim())?;
+
+    assert_eq!(commit.tree, TREE);
+    assert_eq!(commit.parents, vec![ROOT.to_owned(), head.clone()]);
+    assert_eq!(commit.message, "first\n\nsecond\n");
+
+    assert_eq!(helper.resolve_revision("@")?, head);
+
+    Ok(())
+}
+
+#[rstest]
+fn read_the_message_from_stdin(mut helper: CommandHelper) {
+    helper.stdin = String::from("made by hand\n");
+
+    helper
+        .jit_cmd(&["commit-tree", TREE])
+        .assert()
+        .code(0)
+        .stdout(format!("{}\n", ROOT));
+}
+
+#[rstest]
+fn fail_for_a_commit_given_as_the_tree(mut helper: CommandHelper) -> Result<()> {
+    let head = helper.resolve_revision("@")?;
+
+    helper
+        .jit_cmd(&["commit-tree", "@", "-m", "message"])
+        .assert()
+        .code(128)
+        .stderr(format!(
+            "error: object {} is a commit, not a tree\nfatal: Not a valid object name: '@'.\n",
+            head
+        ));
+
+    Ok(())
+}
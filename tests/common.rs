@@ -132,10 +132,15 @@ impl CommandHelper {
     }
 
     pub fn jit_cmd(&mut self, argv: &[&str]) -> Output {
+        self.jit_cmd_in("", argv)
+    }
+
+    /// Run jit from the directory `dir` inside the repository.
+    pub fn jit_cmd_in(&mut self, dir: &str, argv: &[&str]) -> Output {
         let result = Command::cargo_bin(env!("CARGO_PKG_NAME"))
             .unwrap()
             .args(argv)
-            .current_dir(&self.repo_path)
+            .current_dir(self.repo_path.join(dir))
             .envs(&self.env)
             .write_stdin(self.stdin.as_bytes())
             .output()
@@ -2,6 +2,7 @@ mod common;
 
 use assert_cmd::prelude::OutputAssertExt;
 pub use common::CommandHelper;
+use jit::errors::Result;
 use rstest::{fixture, rstest};
 
 #[fixture]
@@ -208,3 +209,52 @@ fn remove_a_subsection(mut helper: CommandHelper) {
         .assert()
         .code(1);
 }
+
+#[rstest]
+fn list_the_variables_matching_a_pattern(mut helper: CommandHelper) {
+    helper.jit_cmd(&["config", "core.editor", "ed"]);
+    helper.jit_cmd(&["config", "remote.origin.url", "ssh://example.com/repo"]);
+    helper.jit_cmd(&["config", "remote.origin.fetch", "+refs/heads/*"]);
+    helper.jit_cmd(&["config", "remote.fork.url", "ssh://example.com/fork"]);
+
+    helper
+        .jit_cmd(&["config", "--local", "--get-regexp", r"^remote\..*\.url$"])
+        .assert()
+        .code(0)
+        .stdout(
+            "remote.origin.url ssh://example.com/repo\nremote.fork.url ssh://example.com/fork\n",
+        );
+
+    helper
+        .jit_cmd(&["config", "--local", "--get-regexp", "^nothing"])
+        .assert()
+        .code(1)
+        .stdout("");
+}
+
+#[rstest]
+fn fail_for_an_invalid_pattern(mut helper: CommandHelper) {
+    helper
+        .jit_cmd(&["config", "--get-regexp", "remote.("])
+        .assert()
+        .code(6)
+        .stderr("error: invalid key pattern: remote.(\n");
+}
+
+#[rstest]
+fn read_a_file_included_for_the_repository(mut helper: CommandHelper) -> Result<()> {
+    helper.write_file("work.config", "[user]\n\tname = Work\n")?;
+    helper.jit_cmd(&[
+        "config",
+        &format!("includeIf.gitdir:{}/.path", helper.repo_path.display()),
+        "../work.config",
+    ]);
+
+    helper
+        .jit_cmd(&["config", "user.name"])
+        .assert()
+        .code(0)
+        .stdout("Work\n");
+
+    Ok(())
+}
@@ -217,6 +217,116 @@ index 0000000..b6fc4c6
 
         Ok(())
     }
+
+    #[rstest]
+    fn quote_the_paths_of_a_file_with_unusual_characters(mut helper: CommandHelper) -> Result<()> {
+        helper.write_file("tab\there.txt", "hello")?;
+        helper.jit_cmd(&["add", "."]);
+
+        helper.assert_diff_cached(
+            "\
+diff --git \"a/tab\\there.txt\" \"b/tab\\there.txt\"
+new file mode 100644
+index 0000000..b6fc4c6
+--- /dev/null
++++ \"b/tab\\there.txt\"
+@@ -0,0 +1,1 @@
++hello
+",
+        );
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn diff_an_added_empty_file(mut helper: CommandHelper) -> Result<()> {
+        helper.write_file("empty.txt", "")?;
+        helper.jit_cmd(&["add", "."]);
+
+        helper.assert_diff_cached(
+            "\
+diff --git a/empty.txt b/empty.txt
+new file mode 100644
+index 0000000..e69de29
+",
+        );
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn diff_a_deleted_empty_file(mut helper: CommandHelper) -> Result<()> {
+        helper.write_file("empty.txt", "")?;
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("add empty file");
+
+        helper.delete("empty.txt")?;
+
+        helper.assert_diff(
+            "\
+diff --git a/empty.txt b/empty.txt
+deleted file mode 100644
+index e69de29..0000000
+",
+        );
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn diff_staged_is_an_alias_of_cached(mut helper: CommandHelper) -> Result<()> {
+        helper.write_file("file.txt", "changed")?;
+        helper.write_file("another.txt", "hello")?;
+        helper.jit_cmd(&["add", "."]);
+
+        let cached = helper.jit_cmd(&["diff", "--cached"]);
+        let staged = helper.jit_cmd(&["diff", "--staged"]);
+
+        assert_eq!(cached.status.code(), Some(0));
+        assert!(!cached.stdout.is_empty());
+        assert_eq!(staged.stdout, cached.stdout);
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn exit_with_0_without_differences(mut helper: CommandHelper) {
+        for args in [["diff", "--exit-code"], ["diff", "--quiet"]] {
+            helper.jit_cmd(&args).assert().code(0).stdout("");
+        }
+        helper
+            .jit_cmd(&["diff", "--cached", "--exit-code"])
+            .assert()
+            .code(0)
+            .stdout("");
+    }
+
+    #[rstest]
+    fn exit_with_1_with_a_staged_change(mut helper: CommandHelper) -> Result<()> {
+        helper.write_file("file.txt", "changed")?;
+        helper.jit_cmd(&["add", "."]);
+
+        helper.jit_cmd(&["diff", "--exit-code"]).assert().code(0);
+        helper
+            .jit_cmd(&["diff", "--cached", "--quiet"])
+            .assert()
+            .code(1)
+            .stdout("");
+
+        let output = helper.jit_cmd(&["diff", "--cached", "--exit-code"]);
+        assert_eq!(output.status.code(), Some(1));
+        assert!(!output.stdout.is_empty());
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn reject_cached_with_a_conflict_stage(mut helper: CommandHelper) {
+        helper
+            .jit_cmd(&["diff", "--staged", "--ours"])
+            .assert()
+            .code(2);
+    }
 }
 
 ///   o---o---o
@@ -259,3 +369,323 @@ index 96d80cd..8c7e5a6 100644
             );
     }
 }
+
+mod with_copied_files {
+    use super::*;
+
+    #[fixture]
+    fn helper() -> CommandHelper {
+        let mut helper = CommandHelper::new();
+        helper.init();
+
+        helper
+            .write_file("a.txt", "one\ntwo\nthree\nfour\nfive\n")
+            .unwrap();
+        helper.write_file("b.txt", "x\n").unwrap();
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("first");
+
+        helper
+            .write_file("c.txt", "one\ntwo\nthree\nfour\nfive\n")
+            .unwrap();
+        helper
+            .write_file("d.txt", "one\ntwo\nthree\nfour\nsix\n")
+            .unwrap();
+        helper.jit_cmd(&["add", "."]);
+
+        helper
+    }
+
+    #[rstest]
+    fn report_copies_of_unchanged_files(mut helper: CommandHelper) {
+        helper
+            .jit_cmd(&["diff", "--cached", "-C"])
+            .assert()
+            .code(0)
+            .stdout(
+                "\
+diff --git a/a.txt b/c.txt
+similarity index 100%
+copy from a.txt
+copy to c.txt
+diff --git a/a.txt b/d.txt
+similarity index 79%
+copy from a.txt
+copy to d.txt
+index b2f931a..d172ff5 100644
+--- a/a.txt
++++ b/d.txt
+@@ -2,4 +2,4 @@
+ two
+ three
+ four
+-five
++six
+",
+            );
+    }
+
+    #[rstest]
+    fn report_copies_and_renames_between_commits(mut helper: CommandHelper) -> Result<()> {
+        helper.delete("b.txt")?;
+        helper.write_file("e.txt", "x\n")?;
+        helper.jit_cmd(&["rm", "--cached", "b.txt"]);
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("second");
+
+        helper
+            .jit_cmd(&["diff", "--find-copies", "@^", "@"])
+            .assert()
+            .code(0)
+            .stdout(
+                "\
+diff --git a/a.txt b/c.txt
+similarity index 100%
+copy from a.txt
+copy to c.txt
+diff --git a/a.txt b/d.txt
+similarity index 79%
+copy from a.txt
+copy to d.txt
+index b2f931a..d172ff5 100644
+--- a/a.txt
++++ b/d.txt
+@@ -2,4 +2,4 @@
+ two
+ three
+ four
+-five
++six
+diff --git a/b.txt b/e.txt
+similarity index 100%
+rename from b.txt
+rename to e.txt
+",
+            );
+
+        Ok(())
+    }
+}
+
+mod with_blank_line_changes {
+    use super::*;
+
+    #[fixture]
+    fn helper() -> CommandHelper {
+        let mut helper = CommandHelper::new();
+        helper.init();
+
+        helper
+            .write_file("f.txt", "a\nb\nc\nd\ne\nf\ng\nh\ni\nj\n")
+            .unwrap();
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("first");
+
+        helper
+    }
+
+    #[rstest]
+    fn show_added_blank_lines_by_default(mut helper: CommandHelper) -> Result<()> {
+        helper.write_file("f.txt", "a\n\n\nb\nc\nd\ne\nf\ng\nh\ni\nj\n")?;
+
+        helper.assert_diff(
+            "\
+diff --git a/f.txt b/f.txt
+index 92dfa21..346c01e 100644
+--- a/f.txt
++++ b/f.txt
+@@ -1,4 +1,6 @@
+ a
++
++
+ b
+ c
+ d
+",
+        );
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn ignore_hunks_that_only_add_blank_lines(mut helper: CommandHelper) -> Result<()> {
+        helper.write_file("f.txt", "a\n\n\nb\nc\nd\ne\nf\ng\nh\ni\nj\n")?;
+
+        helper
+            .jit_cmd(&["diff", "--ignore-blank-lines"])
+            .assert()
+            .code(0)
+            .stdout("");
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn show_other_hunks_when_ignoring_blank_lines(mut helper: CommandHelper) -> Result<()> {
+        helper.write_file("f.txt", "a\n\n\nb\nc\nd\ne\nf\ng\nh\nX\nj\n")?;
+
+        helper
+            .jit_cmd(&["diff", "--ignore-blank-lines"])
+            .assert()
+            .code(0)
+            .stdout(
+                "\
+diff --git a/f.txt b/f.txt
+index 92dfa21..120d385 100644
+--- a/f.txt
++++ b/f.txt
+@@ -6,5 +8,5 @@
+ f
+ g
+ h
+-i
++X
+ j
+",
+            );
+
+        Ok(())
+    }
+}
+
+mod with_changes_in_a_subdirectory {
+    use super::*;
+
+    #[fixture]
+    fn helper() -> CommandHelper {
+        let mut helper = CommandHelper::new();
+        helper.init();
+
+        helper.write_file("outside.txt", "one\n").unwrap();
+        helper.write_file("sub/inside.txt", "one\n").unwrap();
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("first");
+
+        helper.write_file("outside.txt", "two\n").unwrap();
+        helper.write_file("sub/inside.txt", "two\n").unwrap();
+
+        helper
+    }
+
+    #[rstest]
+    fn show_only_changes_inside_the_directory_relative_to_it(mut helper: CommandHelper) {
+        helper
+            .jit_cmd(&["diff", "--relative=sub"])
+            .assert()
+            .code(0)
+            .stdout(
+                "\
+diff --git a/inside.txt b/inside.txt
+index 5626abf..f719efd 100644
+--- a/inside.txt
++++ b/inside.txt
+@@ -1,1 +1,1 @@
+-one
++two
+",
+            );
+    }
+
+    #[rstest]
+    fn report_no_differences_when_the_directory_is_unchanged(mut helper: CommandHelper) {
+        helper.jit_cmd(&["add", "sub"]);
+
+        helper
+            .jit_cmd(&["diff", "--exit-code", "--relative=sub"])
+            .assert()
+            .code(0)
+            .stdout("");
+    }
+}
+
+mod with_a_textconv_driver {
+    use jit::util::path_to_string;
+
+    use super::*;
+
+    const UPCASE: &str = "\
+#!/bin/sh
+tr a-z A-Z < \"$1\"
+";
+
+    #[fixture]
+    fn helper() -> CommandHelper {
+        let mut helper = CommandHelper::new();
+        helper.init();
+
+        helper.write_file(".git/upcase", UPCASE).unwrap();
+        helper.make_executable(".git/upcase").unwrap();
+        let program = path_to_string(&helper.repo_path.join(".git/upcase"));
+        helper.jit_cmd(&["config", "diff.upcase.textconv", &program]);
+
+        helper
+            .write_file(".gitattributes", "*.dat diff=upcase\n")
+            .unwrap();
+        helper.write_file("file.dat", "one\n").unwrap();
+        helper.write_file("file.txt", "one\n").unwrap();
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("first");
+
+        helper.write_file("file.dat", "two\n").unwrap();
+        helper.write_file("file.txt", "two\n").unwrap();
+
+        helper
+    }
+
+    #[rstest]
+    fn diff_the_converted_text_of_files_with_a_driver(mut helper: CommandHelper) {
+        helper.assert_diff(
+            "\
+diff --git a/file.dat b/file.dat
+index 5626abf..f719efd 100644
+--- a/file.dat
++++ b/file.dat
+@@ -1,1 +1,1 @@
+-ONE
++TWO
+diff --git a/file.txt b/file.txt
+index 5626abf..f719efd 100644
+--- a/file.txt
++++ b/file.txt
+@@ -1,1 +1,1 @@
+-one
++two
+",
+        );
+    }
+
+    #[rstest]
+    fn fail_for_a_textconv_command_that_cannot_be_split(mut helper: CommandHelper) {
+        helper.jit_cmd(&["config", "diff.upcase.textconv", "tr a-z A-Z '"]);
+
+        helper.jit_cmd(&["diff"]).assert().code(1).stderr(
+            "fatal: unable to read files to diff: textconv command 'tr a-z A-Z '' failed\n",
+        );
+    }
+
+    #[rstest]
+    fn diff_the_original_contents_with_no_textconv(mut helper: CommandHelper) {
+        helper
+            .jit_cmd(&["diff", "--no-textconv"])
+            .assert()
+            .code(0)
+            .stdout(
+                "\
+diff --git a/file.dat b/file.dat
+index 5626abf..f719efd 100644
+--- a/file.dat
++++ b/file.dat
+@@ -1,1 +1,1 @@
+-one
++two
+diff --git a/file.txt b/file.txt
+index 5626abf..f719efd 100644
+--- a/file.txt
++++ b/file.txt
+@@ -1,1 +1,1 @@
+-one
++two
+",
+            );
+    }
+}
@@ -0,0 +1,108 @@
+mod common;
+
+use assert_cmd::prelude::OutputAssertExt;
+use chrono::{Duration, Local};
+pub use common::CommandHelper;
+use jit::errors::Result;
+use rstest::{fixture, rstest};
+
+#[fixture]
+fn helper() -> CommandHelper {
+    let mut helper = CommandHelper::new();
+    helper.init();
+
+    for (message, days_ago) in [("first", 2), ("second", 1)] {
+        let time = Local::now() - Duration::days(days_ago);
+        helper.env.insert(
+            String::from("GIT_AUTHOR_DATE"),
+            time.format("%a, %d %b %Y %H:%M:%S %z").to_string(),
+        );
+        helper.write_file("file.txt", message).unwrap();
+        helper.jit_cmd(&["add", "."]);
+        helper.commit(message);
+    }
+
+    // `zebra` points at the older commit so sorting by date and by name disagree
+    helper.jit_cmd(&["branch", "zebra", "@^"]);
+    helper.jit_cmd(&["branch", "apple"]);
+
+    helper
+}
+
+#[rstest]
+fn list_refs_with_the_default_format(mut helper: CommandHelper) -> Result<()> {
+    let first = helper.resolve_revision("@^")?;
+    let second = helper.resolve_revision("@")?;
+
+    helper
+        .jit_cmd(&["for-each-ref"])
+        .assert()
+        .code(0)
+        .stdout(format!(
+            "\
+{second} commit\trefs/heads/apple
+{second} commit\trefs/heads/main
+{first} commit\trefs/heads/zebra
+",
+            first = first,
+            second = second,
+        ));
+
+    Ok(())
+}
+
+#[rstest]
+fn format_branches_sorted_by_name(mut helper: CommandHelper) -> Result<()> {
+    helper
+        .jit_cmd(&[
+            "for-each-ref",
+            "--format=%(refname:short) %(objectname:short) %(subject) 100%%",
+            "--sort=-refname",
+            "refs/heads",
+        ])
+        .assert()
+        .code(0)
+        .stdout(format!(
+            "\
+zebra {} first 100%
+main {} second 100%
+apple {} second 100%
+",
+            &helper.resolve_revision("@^")?[0..7],
+            &helper.resolve_revision("@")?[0..7],
+            &helper.resolve_revision("@")?[0..7],
+        ));
+
+    Ok(())
+}
+
+#[rstest]
+fn sort_refs_by_committer_date(mut helper: CommandHelper) {
+    helper
+        .jit_cmd(&[
+            "for-each-ref",
+            "--format=%(refname)",
+            "--sort=committerdate",
+        ])
+        .assert()
+        .code(0)
+        .stdout("refs/heads/zebra\nrefs/heads/apple\nrefs/heads/main\n");
+}
+
+#[rstest]
+fn only_match_whole_path_components(mut helper: CommandHelper) {
+    helper
+        .jit_cmd(&["for-each-ref", "--format=%(refname)", "refs/heads/ma"])
+        .assert()
+        .code(0)
+        .stdout("");
+}
+
+#[rstest]
+fn reject_unknown_fields(mut helper: CommandHelper) {
+    helper
+        .jit_cmd(&["for-each-ref", "--format=%(nope)"])
+        .assert()
+        .code(128)
+        .stderr("fatal: unknown field name: nope\n");
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use std::fs;
+
+use assert_cmd::prelude::OutputAssertExt;
+pub use common::CommandHelper;
+use jit::database::object::Object;
+use jit::errors::Result;
+use rstest::{fixture, rstest};
+
+#[fixture]
+fn helper() -> CommandHelper {
+    let mut helper = CommandHelper::new();
+    helper.init();
+
+    helper.write_file("f.txt", "one\n").unwrap();
+    helper.jit_cmd(&["add", "."]);
+    helper.commit("First");
+
+    helper.write_file("f.txt", "one\ntwo\n").unwrap();
+    helper.jit_cmd(&["add", "."]);
+    helper.commit("Second: change f\n\nBody line");
+
+    helper.write_file("g.txt", "new\n").unwrap();
+    helper.jit_cmd(&["add", "."]);
+    helper.commit("Third");
+
+    helper
+}
+
+fn diff(helper: &mut CommandHelper, a: &str, b: &str) -> String {
+    let output = helper.jit_cmd(&["diff", a, b]);
+
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[rstest]
+fn write_one_numbered_patch_file_per_commit(mut helper: CommandHelper) -> Result<()> {
+    helper
+        .jit_cmd(&["format-patch", "@^^"])
+        .assert()
+        .code(0)
+        .stdout("0001-Second-change-f.patch\n0002-Third.patch\n");
+
+    let first = fs::read_to_string(helper.repo_path.join("0001-Second-change-f.patch"))?;
+    let second = fs::read_to_string(helper.repo_path.join("0002-Third.patch"))?;
+
+    let commit = helper.load_commit("@^")?;
+    let date = commit.author.time.format("%a, %-d %b %Y %H:%M:%S %z");
+    assert_eq!(
+        first,
+        format!(
+            "\
+From {} Mon Sep 17 00:00:00 2001
+From: A. U. Thor <author@example.com>
+Date: {}
+Subject: [PATCH 1/2] Second: change f
+
+Body line
+---
+ f.txt | 1 +
+ 1 file changed, 1 insertion(+)
+
+{}-- \n{}\n\n",
+            commit.oid(),
+            date,
+            diff(&mut helper, "@^^", "@^"),
+            env!("CARGO_PKG_VERSION"),
+        )
+    );
+
+    assert!(second.contains("\nSubject: [PATCH 2/2] Third\n\n---\n"));
+    assert!(second.contains(&format!("\n\n{}-- \n", diff(&mut helper, "@^", "@"))));
+
+    Ok(())
+}
+
+#[rstest]
+fn print_the_patches_with_stdout(mut helper: CommandHelper) -> Result<()> {
+    let output = helper.jit_cmd(&["format-patch", "--stdout", "@^^..@^"]);
+    let stdout = String::from_utf8(output.stdout.clone()).unwrap();
+    output.assert().code(0);
+
+    assert!(stdout.contains("\nSubject: [PATCH] Second: change f\n"));
+    assert!(!stdout.contains("Third"));
+    assert!(!helper.repo_path.join("0001-Second-change-f.patch").exists());
+
+    Ok(())
+}
+
+#[rstest]
+fn write_the_patches_to_an_output_directory(mut helper: CommandHelper) -> Result<()> {
+    helper
+        .jit_cmd(&["format-patch", "-o", "patches", "@^"])
+        .assert()
+        .code(0)
+        .stdout("patches/0001-Third.patch\n");
+
+    assert!(helper.repo_path.join("patches/0001-Third.patch").exists());
+
+    Ok(())
+}
+
+#[rstest]
+fn fail_for_an_unknown_revision(mut helper: CommandHelper) {
+    helper
+        .jit_cmd(&["format-patch", "nope"])
+        .assert()
+        .code(128)
+        .stderr(
+            "fatal: ambiguous argument 'nope': unknown revision or path not in the working tree.\n",
+        );
+}
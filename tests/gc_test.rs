@@ -0,0 +1,145 @@
+mod common;
+
+use std::fs;
+
+use assert_cmd::prelude::OutputAssertExt;
+pub use common::CommandHelper;
+use jit::database::blob::Blob;
+use jit::database::object::Object;
+use jit::errors::Result;
+use rstest::{fixture, rstest};
+
+#[fixture]
+fn helper() -> CommandHelper {
+    let mut helper = CommandHelper::new();
+    helper.init();
+
+    helper.write_file("file.txt", "1").unwrap();
+    helper.jit_cmd(&["add", "."]);
+    helper.commit("first");
+
+    helper
+}
+
+fn commit_many_files(helper: &mut CommandHelper) -> Result<()> {
+    for i in 0..20 {
+        helper.write_file(&format!("file-{}.txt", i), &i.to_string())?;
+    }
+    helper.jit_cmd(&["add", "."]);
+    helper.commit("second");
+
+    Ok(())
+}
+
+#[rstest]
+fn do_nothing_in_a_small_repository(mut helper: CommandHelper) {
+    helper
+        .jit_cmd(&["gc", "--auto"])
+        .assert()
+        .code(0)
+        .stdout("");
+}
+
+#[rstest]
+fn report_more_loose_objects_than_gc_auto(mut helper: CommandHelper) -> Result<()> {
+    helper.jit_cmd(&["config", "gc.auto", "10"]);
+    helper
+        .jit_cmd(&["gc", "--auto"])
+        .assert()
+        .code(0)
+        .stdout("");
+
+    commit_many_files(&mut helper)?;
+
+    helper
+        .jit_cmd(&["gc", "--auto"])
+        .assert()
+        .code(0)
+        .stdout("Housekeeping is needed: 24 loose objects and 0 packs\n");
+
+    Ok(())
+}
+
+#[rstest]
+fn never_report_with_gc_auto_turned_off(mut helper: CommandHelper) -> Result<()> {
+    helper.jit_cmd(&["config", "gc.auto", "0"]);
+    commit_many_files(&mut helper)?;
+
+    helper
+        .jit_cmd(&["gc", "--auto"])
+        .assert()
+        .code(0)
+        .stdout("");
+
+    Ok(())
+}
+
+#[rstest]
+fn report_more_packs_than_gc_auto_pack_limit(mut helper: CommandHelper) -> Result<()> {
+    helper.jit_cmd(&["config", "gc.autoPackLimit", "1"]);
+    let pack_path = helper.repo_path.join(".git").join("objects").join("pack");
+    fs::create_dir_all(&pack_path)?;
+    fs::write(pack_path.join("pack-1.pack"), "")?;
+    helper
+        .jit_cmd(&["gc", "--auto"])
+        .assert()
+        .code(0)
+        .stdout("");
+
+    fs::write(pack_path.join("pack-2.pack"), "")?;
+    helper
+        .jit_cmd(&["gc", "--auto"])
+        .assert()
+        .code(0)
+        .stdout("Housekeeping is needed: 3 loose objects and 2 packs\n");
+
+    Ok(())
+}
+
+#[rstest]
+fn delete_unreachable_loose_objects(mut helper: CommandHelper) -> Result<()> {
+    let blob = Blob::new(b"unreachable".to_vec());
+    helper.repo.database.store(&blob)?;
+    helper.write_file("staged.txt", "staged")?;
+    helper.jit_cmd(&["add", "staged.txt"]);
+
+    helper.jit_cmd(&["gc"]).assert().code(0).stdout("");
+
+    assert!(!helper.repo.database.exists(&blob.oid()));
+    assert_eq!(helper.repo.database.count_loose_objects()?, 4);
+    helper.jit_cmd(&["show", "--stat", "@"]).assert().code(0);
+
+    Ok(())
+}
+
+#[rstest]
+fn keep_the_commits_of_a_conflicted_cherry_pick(mut helper: CommandHelper) -> Result<()> {
+    helper.jit_cmd(&["branch", "topic"]);
+    helper.write_file("file.txt", "2")?;
+    helper.jit_cmd(&["add", "."]);
+    helper.commit("second");
+
+    helper.jit_cmd(&["checkout", "topic"]);
+    for content in ["3", "4"] {
+        helper.write_file("file.txt", content)?;
+        helper.jit_cmd(&["add", "."]);
+        helper.commit(content);
+    }
+    let picked = helper.load_commit("topic^")?;
+    let todo = helper.load_commit("topic")?;
+
+    helper.jit_cmd(&["checkout", "main"]);
+    helper.jit_cmd(&["branch", "-D", "topic"]);
+    helper
+        .jit_cmd(&["cherry-pick", &picked.oid(), &todo.oid()])
+        .assert()
+        .code(1);
+
+    helper.jit_cmd(&["gc"]).assert().code(0);
+
+    for oid in [picked.oid(), picked.tree, todo.oid(), todo.tree] {
+        assert!(helper.repo.database.exists(&oid));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,102 @@
+mod common;
+
+use assert_cmd::prelude::OutputAssertExt;
+pub use common::CommandHelper;
+use jit::errors::Result;
+use rstest::{fixture, rstest};
+
+const HELLO: &str = "ce013625030ba8dba906f756967f9e9ca394464a";
+const WORLD: &str = "cc628ccd10742baea8241c5924df992b5c019f71";
+
+#[fixture]
+fn helper() -> CommandHelper {
+    let mut helper = CommandHelper::new();
+    helper.init();
+
+    helper.write_file("hello.txt", "hello\n").unwrap();
+    helper.write_file("world.txt", "world\n").unwrap();
+
+    helper
+}
+
+#[rstest]
+fn print_the_oids_of_files_without_storing_them(mut helper: CommandHelper) {
+    helper
+        .jit_cmd(&["hash-object", "hello.txt", "world.txt"])
+        .assert()
+        .code(0)
+        .stdout(format!("{}\n{}\n", HELLO, WORLD));
+
+    assert!(helper.repo.database.load(HELLO).is_err());
+}
+
+#[rstest]
+fn hash_standard_input_before_any_files(mut helper: CommandHelper) {
+    helper.stdin = String::from("world\n");
+
+    helper
+        .jit_cmd(&["hash-object", "--stdin", "hello.txt"])
+        .assert()
+        .code(0)
+        .stdout(format!("{}\n{}\n", WORLD, HELLO));
+}
+
+#[rstest]
+fn hash_empty_standard_input(mut helper: CommandHelper) {
+    helper.stdin = String::new();
+
+    helper
+        .jit_cmd(&["hash-object", "--stdin"])
+        .assert()
+        .code(0)
+        .stdout("e69de29bb2d1d6434b8b29ae775ad8c2e48c5391\n");
+}
+
+#[rstest]
+fn store_objects_with_write(mut helper: CommandHelper) -> Result<()> {
+    helper
+        .jit_cmd(&["hash-object", "-w", "hello.txt"])
+        .assert()
+        .code(0)
+        .stdout(format!("{}\n", HELLO));
+
+    assert_eq!(helper.repo.database.load_blob(HELLO)?.data, b"hello\n");
+
+    Ok(())
+}
+
+#[rstest]
+fn hash_data_as_another_type(mut helper: CommandHelper) -> Result<()> {
+    helper.stdin = String::from(
+        "\
+tree 4cf9f177c4c015836fca6a31f9c3917e89ae29ec
+author A <a@b> 0 +0000
+committer A <a@b> 0 +0000
+
+raw
+",
+    );
+
+    helper
+        .jit_cmd(&["hash-object", "-t", "commit", "-w", "--stdin"])
+        .assert()
+        .code(0)
+        .stdout("788ef9082825a894010dfd2fb174edc2a35163c5\n");
+
+    let commit = helper
+        .repo
+        .database
+        .load_commit("788ef9082825a894010dfd2fb174edc2a35163c5")?;
+    assert_eq!(commit.message, "raw\n");
+
+    Ok(())
+}
+
+#[rstest]
+fn fail_for_a_missing_file(mut helper: CommandHelper) {
+    helper
+        .jit_cmd(&["hash-object", "missing.txt"])
+        .assert()
+        .code(128)
+        .stderr("fatal: could not open 'missing.txt' for reading: No such file or directory (os error 2)\n");
+}
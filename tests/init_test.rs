@@ -0,0 +1,55 @@
+mod common;
+
+pub use common::CommandHelper;
+use jit::errors::Result;
+use jit::refs::Ref;
+use jit::util::path_to_string;
+use rstest::rstest;
+use tempfile::TempDir;
+
+#[rstest]
+fn start_on_the_main_branch_by_default() -> Result<()> {
+    let mut helper = CommandHelper::new();
+    helper.init();
+
+    assert_eq!(
+        helper.repo.refs.current_ref("HEAD")?,
+        Ref::SymRef {
+            path: String::from("refs/heads/main")
+        }
+    );
+
+    Ok(())
+}
+
+#[rstest]
+fn start_on_the_configured_default_branch() -> Result<()> {
+    let home = TempDir::new()?;
+    std::fs::write(
+        home.path().join(".gitconfig"),
+        "[init]\n\tdefaultBranch = trunk\n",
+    )?;
+
+    let mut helper = CommandHelper::new();
+    helper
+        .env
+        .insert(String::from("HOME"), path_to_string(home.path()));
+    helper.init();
+
+    helper.write_file("file.txt", "one")?;
+    helper.jit_cmd(&["add", "."]);
+    helper.commit("first");
+
+    assert_eq!(
+        helper.repo.refs.current_ref("HEAD")?,
+        Ref::SymRef {
+            path: String::from("refs/heads/trunk")
+        }
+    );
+    assert_eq!(
+        helper.repo.refs.read_ref("refs/heads/trunk")?,
+        Some(helper.resolve_revision("@")?)
+    );
+
+    Ok(())
+}
@@ -127,6 +127,80 @@ Date:   {}
             ));
     }
 
+    #[rstest]
+    fn print_a_log_in_raw_format(mut helper: CommandHelper) {
+        let commits = commits(&helper);
+        let raw_time = commits[0].author.time.format("%s %z");
+
+        helper
+            .jit_cmd(&["log", "--format=raw", "@^..@"])
+            .assert()
+            .code(0)
+            .stdout(format!(
+                "\
+commit {}
+tree {}
+parent {}
+author A. U. Thor <author@example.com> {}
+committer A. U. Thor <author@example.com> {}
+
+    C\n",
+                commits[0].oid(),
+                commits[0].tree,
+                commits[1].oid(),
+                raw_time,
+                commits[0].committer.time.format("%s %z"),
+            ));
+    }
+
+    #[rstest]
+    fn print_a_log_in_email_format(mut helper: CommandHelper) {
+        let commits = commits(&helper);
+
+        helper
+            .jit_cmd(&["log", "--format=email", "@^..@"])
+            .assert()
+            .code(0)
+            .stdout(format!(
+                "\
+From {} Mon Sep 17 00:00:00 2001
+From: A. U. Thor <author@example.com>
+Date: {}
+Subject: [PATCH] C
+
+",
+                commits[0].oid(),
+                commits[0].author.time.format("%a, %-d %b %Y %H:%M:%S %z"),
+            ));
+    }
+
+    #[rstest]
+    fn print_mime_headers_for_a_non_ascii_email(mut helper: CommandHelper) -> Result<()> {
+        commit_file(&mut helper, "Café")?;
+        let head = helper.load_commit("@")?;
+
+        helper
+            .jit_cmd(&["log", "--format=email", "@^..@"])
+            .assert()
+            .code(0)
+            .stdout(format!(
+                "\
+From {} Mon Sep 17 00:00:00 2001
+From: A. U. Thor <author@example.com>
+Date: {}
+Subject: [PATCH] =?UTF-8?q?Caf=C3=A9?=
+MIME-Version: 1.0
+Content-Type: text/plain; charset=UTF-8
+Content-Transfer-Encoding: 8bit
+
+",
+                head.oid(),
+                head.author.time.format("%a, %-d %b %Y %H:%M:%S %z"),
+            ));
+
+        Ok(())
+    }
+
     #[rstest]
     fn print_a_log_in_oneline_format(mut helper: CommandHelper) {
         let commits = commits(&helper);
@@ -146,6 +220,47 @@ Date:   {}
             ));
     }
 
+    #[rstest]
+    #[case(vec!["log", "--oneline", "--abbrev=12"], 12)]
+    #[case(vec!["log", "--oneline", "--abbrev=40"], 40)]
+    #[case(vec!["log", "--oneline", "--no-abbrev"], 40)]
+    fn print_a_log_with_a_given_abbreviation_length(
+        #[case] cmd: Vec<&str>,
+        #[case] length: usize,
+        mut helper: CommandHelper,
+    ) {
+        let commits = commits(&helper);
+
+        helper.jit_cmd(&cmd).assert().code(0).stdout(format!(
+            "\
+{} C
+{} B
+{} A\n",
+            &commits[0].oid()[..length],
+            &commits[1].oid()[..length],
+            &commits[2].oid()[..length],
+        ));
+    }
+
+    #[rstest]
+    fn abbreviate_commit_ids_to_the_configured_length(mut helper: CommandHelper) {
+        let commits = commits(&helper);
+
+        helper.jit_cmd(&["config", "core.abbrev", "10"]);
+        helper
+            .jit_cmd(&["log", "--oneline", "@^^"])
+            .assert()
+            .code(0)
+            .stdout(format!("{} A\n", &commits[2].oid()[..10]));
+
+        helper.jit_cmd(&["config", "core.abbrev", "auto"]);
+        helper
+            .jit_cmd(&["log", "--oneline", "@^^"])
+            .assert()
+            .code(0)
+            .stdout(format!("{} A\n", Database::short_oid(&commits[2].oid())));
+    }
+
     #[rstest]
     #[case(vec!["log", "--pretty=oneline"])]
     #[case(vec!["log", "--oneline", "--no-abbrev-commit"])]
@@ -183,6 +298,35 @@ Date:   {}
             ));
     }
 
+    #[rstest]
+    fn print_only_the_given_commits_without_walking(mut helper: CommandHelper) {
+        let commits = commits(&helper);
+
+        helper
+            .jit_cmd(&["log", "--pretty=oneline", "--no-walk", "@", "@^^"])
+            .assert()
+            .code(0)
+            .stdout(format!(
+                "\
+{} C
+{} A\n",
+                &commits[0].oid(),
+                &commits[2].oid(),
+            ));
+
+        helper
+            .jit_cmd(&["log", "--pretty=oneline", "--no-walk=unsorted", "@^^", "@"])
+            .assert()
+            .code(0)
+            .stdout(format!(
+                "\
+{} A
+{} C\n",
+                &commits[2].oid(),
+                &commits[0].oid(),
+            ));
+    }
+
     #[rstest]
     fn print_a_log_with_short_decorations(mut helper: CommandHelper) {
         let commits = commits(&helper);
@@ -241,6 +385,35 @@ Date:   {}
             ));
     }
 
+    #[rstest]
+    fn print_a_log_with_the_stash(mut helper: CommandHelper) -> Result<()> {
+        let commits = commits(&helper);
+        helper
+            .repo
+            .refs
+            .update_ref("refs/stash", &commits[1].oid())?;
+        helper
+            .repo
+            .refs
+            .update_ref("refs/tags/v1.0", &commits[2].oid())?;
+
+        helper
+            .jit_cmd(&["log", "--pretty=oneline", "--decorate=full"])
+            .assert()
+            .code(0)
+            .stdout(format!(
+                "\
+{} (HEAD -> refs/heads/main) C
+{} (refs/stash) B
+{} (refs/heads/topic, refs/tags/v1.0) A\n",
+                &commits[0].oid(),
+                &commits[1].oid(),
+                &commits[2].oid(),
+            ));
+
+        Ok(())
+    }
+
     #[rstest]
     fn print_a_log_with_patches(mut helper: CommandHelper) {
         let commits = commits(&helper);
@@ -364,6 +537,25 @@ mod with_commits_changing_different_files {
             ));
     }
 
+    #[rstest]
+    fn log_each_commit_once_for_overlapping_paths(mut helper: CommandHelper) {
+        let commits = commits(&helper);
+
+        helper
+            .jit_cmd(&["log", "--pretty=oneline", "b/c/2.txt", "b", "b/c", "b"])
+            .assert()
+            .code(0)
+            .stdout(format!(
+                "\
+{} third
+{} second
+{} first\n",
+                commits[2].oid(),
+                commits[1].oid(),
+                commits[0].oid(),
+            ));
+    }
+
     #[rstest]
     fn log_commits_that_change_a_nested_directory(mut helper: CommandHelper) {
         let commits = commits(&helper);
@@ -667,6 +859,24 @@ mod with_a_graph_of_commits {
             ));
     }
 
+    #[rstest]
+    fn log_only_the_commits_descending_from_the_start_of_a_range(mut helper: CommandHelper) {
+        let main = main_commits(&helper);
+
+        helper
+            .jit_cmd(&["log", "--pretty=oneline", "--ancestry-path", "main~3..main"])
+            .assert()
+            .code(0)
+            .stdout(format!(
+                "\
+{} K
+{} J
+{} D
+",
+                main[0], main[1], main[2],
+            ));
+    }
+
     #[rstest]
     fn log_the_first_parent_of_a_merge(mut helper: CommandHelper) {
         let main = main_commits(&helper);
@@ -895,3 +1105,465 @@ index 4e5ce14..4139691 100644
         }
     }
 }
+
+//   A   B   C   M
+//   o---o---o---o [main]
+//        \     /
+//         o---o [topic]
+//         D   E
+mod with_a_branching_history {
+    use super::*;
+
+    #[fixture]
+    fn helper() -> CommandHelper {
+        let mut helper = CommandHelper::new();
+        helper.init();
+
+        for message in ["A", "B"] {
+            commit_tree(&mut helper, message, HashMap::from([("a.txt", message)])).unwrap();
+        }
+        helper.jit_cmd(&["branch", "topic"]);
+        commit_tree(&mut helper, "C", HashMap::from([("a.txt", "C")])).unwrap();
+
+        helper.jit_cmd(&["checkout", "topic"]);
+        commit_tree(&mut helper, "D", HashMap::from([("b.txt", "D")])).unwrap();
+        commit_tree(&mut helper, "E", HashMap::from([("b.txt", "E\nE")])).unwrap();
+
+        helper.jit_cmd(&["checkout", "main"]);
+        helper
+            .jit_cmd(&["merge", "topic", "-m", "M"])
+            .assert()
+            .code(0);
+
+        helper
+    }
+
+    #[rstest]
+    fn draw_the_graph_beside_each_line_of_a_commit(mut helper: CommandHelper) {
+        let oids: Vec<_> = ["main", "main^", "topic", "topic^", "main~2", "main~3"]
+            .iter()
+            .map(|rev| Database::short_oid(&helper.resolve_revision(rev).unwrap()))
+            .collect();
+
+        helper
+            .jit_cmd(&["log", "--graph", "--oneline", "--stat"])
+            .assert()
+            .code(0)
+            .stdout(format!(
+                "\
+* {} M
+|\\
+* | {} C
+| |  a.txt | 2 +-
+| |  1 file changed, 1 insertion(+), 1 deletion(-)
+| * {} E
+| |  b.txt | 3 ++-
+| |  1 file changed, 2 insertions(+), 1 deletion(-)
+| * {} D
+| |  b.txt | 1 +
+| |  1 file changed, 1 insertion(+)
+|/
+* {} B
+|  a.txt | 2 +-
+|  1 file changed, 1 insertion(+), 1 deletion(-)
+* {} A
+   a.txt | 1 +
+   1 file changed, 1 insertion(+)
+",
+                oids[0], oids[1], oids[2], oids[3], oids[4], oids[5],
+            ));
+    }
+
+    #[rstest]
+    fn show_only_decorated_commits_and_where_history_branches(
+        mut helper: CommandHelper,
+    ) -> Result<()> {
+        let root = helper.resolve_revision("main~3")?;
+        helper.repo.refs.update_ref("refs/tags/v1.0", &root)?;
+
+        let oids: Vec<_> = ["main", "topic", "main~2", "main~3"]
+            .iter()
+            .map(|rev| Database::short_oid(&helper.resolve_revision(rev).unwrap()))
+            .collect();
+
+        helper
+            .jit_cmd(&["log", "--graph", "--oneline", "--simplify-by-decoration"])
+            .assert()
+            .code(0)
+            .stdout(format!(
+                "\
+* {} M
+|\\
+| * {} E
+|/
+* {} B
+* {} A
+",
+                oids[0], oids[1], oids[2], oids[3],
+            ));
+
+        Ok(())
+    }
+}
+
+//   A   B   C   M
+//   o---o---o---o [main]
+//        \     /
+//         o---o [topic]
+//         D   E
+//
+// Only A, D and E change `b.txt`.
+mod with_a_graph_filtered_by_path {
+    use super::*;
+
+    #[fixture]
+    fn helper() -> CommandHelper {
+        let mut helper = CommandHelper::new();
+        helper.init();
+
+        let tree = HashMap::from([("a.txt", "A"), ("b.txt", "A")]);
+        commit_tree(&mut helper, "A", tree).unwrap();
+        commit_tree(&mut helper, "B", HashMap::from([("a.txt", "B")])).unwrap();
+        helper.jit_cmd(&["branch", "topic"]);
+        commit_tree(&mut helper, "C", HashMap::from([("a.txt", "C")])).unwrap();
+
+        helper.jit_cmd(&["checkout", "topic"]);
+        commit_tree(&mut helper, "D", HashMap::from([("b.txt", "D")])).unwrap();
+        commit_tree(&mut helper, "E", HashMap::from([("b.txt", "E")])).unwrap();
+
+        helper.jit_cmd(&["checkout", "main"]);
+        helper
+            .jit_cmd(&["merge", "topic", "-m", "M"])
+            .assert()
+            .code(0);
+
+        helper
+    }
+
+    #[rstest]
+    fn join_the_lanes_of_the_commits_left_by_a_pathspec(mut helper: CommandHelper) {
+        let oids: Vec<_> = ["topic", "topic^", "main~3"]
+            .iter()
+            .map(|rev| Database::short_oid(&helper.resolve_revision(rev).unwrap()))
+            .collect();
+
+        helper
+            .jit_cmd(&["log", "--graph", "--oneline", "--", "b.txt"])
+            .assert()
+            .code(0)
+            .stdout(format!(
+                "\
+* {} E
+* {} D
+* {} A
+",
+                oids[0], oids[1], oids[2],
+            ));
+    }
+}
+
+mod with_a_renamed_file {
+    use super::*;
+
+    #[fixture]
+    fn helper() -> CommandHelper {
+        let mut helper = CommandHelper::new();
+        helper.init();
+
+        helper.write_file("old.txt", "1\n2\n3\n4\n").unwrap();
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("create");
+
+        helper.write_file("old.txt", "1\n2\n3\n4\n5\n").unwrap();
+        helper.write_file("other.txt", "unrelated").unwrap();
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("extend");
+
+        helper.jit_cmd(&["rm", "old.txt"]);
+        helper.write_file("new.txt", "1\n2\n3\n4\n5\n6\n").unwrap();
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("rename");
+
+        helper
+            .write_file("new.txt", "1\n2\n3\n4\n5\n6\n7\n")
+            .unwrap();
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("edit");
+
+        helper
+    }
+
+    #[rstest]
+    fn stop_at_the_rename_without_follow(mut helper: CommandHelper) {
+        helper
+            .jit_cmd(&["log", "--format=oneline", "--abbrev-commit", "new.txt"])
+            .assert()
+            .code(0)
+            .stdout(format!(
+                "{} edit\n{} rename\n",
+                helper.resolve_revision("@").unwrap()[0..7].to_owned(),
+                helper.resolve_revision("@^").unwrap()[0..7].to_owned(),
+            ));
+    }
+
+    #[rstest]
+    fn follow_the_file_across_the_rename(mut helper: CommandHelper) -> Result<()> {
+        let oids: Vec<_> = ["@", "@^", "@^^", "@^^^"]
+            .iter()
+            .map(|rev| Database::short_oid(&helper.resolve_revision(rev).unwrap()))
+            .collect();
+
+        helper
+            .jit_cmd(&["log", "--oneline", "--follow", "new.txt"])
+            .assert()
+            .code(0)
+            .stdout(format!(
+                "\
+{} edit
+{} rename
+{} extend
+{} create
+",
+                oids[0], oids[1], oids[2], oids[3],
+            ));
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn show_the_rename_in_patches_when_following(mut helper: CommandHelper) -> Result<()> {
+        let oids: Vec<_> = ["@", "@^", "@^^", "@^^^"]
+            .iter()
+            .map(|rev| Database::short_oid(&helper.resolve_revision(rev).unwrap()))
+            .collect();
+
+        helper
+            .jit_cmd(&["log", "--oneline", "--patch", "--follow", "new.txt"])
+            .assert()
+            .code(0)
+            .stdout(format!(
+                "\
+{} edit
+diff --git a/new.txt b/new.txt
+index b414108..06e567b 100644
+--- a/new.txt
++++ b/new.txt
+@@ -4,3 +4,4 @@
+ 4
+ 5
+ 6
++7
+{} rename
+diff --git a/old.txt b/new.txt
+similarity index 83%
+rename from old.txt
+rename to new.txt
+index 8a1218a..b414108 100644
+--- a/old.txt
++++ b/new.txt
+@@ -3,3 +3,4 @@
+ 3
+ 4
+ 5
++6
+{} extend
+diff --git a/old.txt b/old.txt
+index 94ebaf9..8a1218a 100644
+--- a/old.txt
++++ b/old.txt
+@@ -2,3 +2,4 @@
+ 2
+ 3
+ 4
++5
+{} create
+diff --git a/old.txt b/old.txt
+new file mode 100644
+index 0000000..94ebaf9
+--- /dev/null
++++ b/old.txt
+@@ -0,0 +1,4 @@
++1
++2
++3
++4
+",
+                oids[0], oids[1], oids[2], oids[3],
+            ));
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn require_exactly_one_path_to_follow(mut helper: CommandHelper) {
+        helper
+            .jit_cmd(&["log", "--follow", "new.txt", "other.txt"])
+            .assert()
+            .code(128)
+            .stderr("fatal: --follow requires exactly one pathspec\n");
+    }
+}
+
+mod with_stats {
+    use super::*;
+
+    #[fixture]
+    fn helper() -> CommandHelper {
+        let mut helper = CommandHelper::new();
+        helper.init();
+
+        helper.write_file("f.txt", "a\nb\n").unwrap();
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("one");
+
+        helper.write_file("f.txt", "a\nc\nd\n").unwrap();
+        helper
+            .write_file("long/name.txt", &"x\n".repeat(100))
+            .unwrap();
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("two");
+
+        helper
+    }
+
+    #[rstest]
+    fn print_a_scaled_stat_for_each_commit(mut helper: CommandHelper) -> Result<()> {
+        helper
+            .jit_cmd(&["log", "--oneline", "--stat"])
+            .assert()
+            .code(0)
+            .stdout(format!(
+                "\
+{} two
+ f.txt         |   3 +-
+ long/name.txt | 100 {}
+ 2 files changed, 102 insertions(+), 1 deletion(-)
+{} one
+ f.txt | 2 ++
+ 1 file changed, 2 insertions(+)
+",
+                Database::short_oid(&helper.resolve_revision("@")?),
+                "+".repeat(58),
+                Database::short_oid(&helper.resolve_revision("@^")?),
+            ));
+
+        Ok(())
+    }
+}
+
+///   o---o---o---o
+///   A   B   C   D
+mod with_a_pickaxe {
+    use std::path::PathBuf;
+
+    use jit::database::entry::Entry;
+    use jit::database::tree::{TreeEntry, GITLINK_MODE};
+
+    use super::*;
+
+    #[fixture]
+    fn helper() -> CommandHelper {
+        let mut helper = CommandHelper::new();
+        helper.init();
+
+        let tree = HashMap::from([("a.txt", "one\n"), ("b.txt", "two\n")]);
+        commit_tree(&mut helper, "A", tree).unwrap();
+
+        let tree = HashMap::from([("a.txt", "one\nfoo\n")]);
+        commit_tree(&mut helper, "B", tree).unwrap();
+
+        let tree = HashMap::from([("b.txt", "two\nthree\n")]);
+        commit_tree(&mut helper, "C", tree).unwrap();
+
+        let tree = HashMap::from([("a.txt", "one\nfood\n"), ("b.txt", "foo\n")]);
+        commit_tree(&mut helper, "D", tree).unwrap();
+
+        helper
+    }
+
+    #[rstest]
+    fn log_commits_that_change_the_number_of_occurrences(mut helper: CommandHelper) {
+        helper
+            .jit_cmd(&["log", "--oneline", "-Sfoo"])
+            .assert()
+            .code(0)
+            .stdout(format!(
+                "\
+{} D
+{} B
+",
+                Database::short_oid(&helper.resolve_revision("@").unwrap()),
+                Database::short_oid(&helper.resolve_revision("@^^").unwrap()),
+            ));
+    }
+
+    #[rstest]
+    fn respect_path_filters(mut helper: CommandHelper) {
+        helper
+            .jit_cmd(&["log", "--oneline", "-Sfoo", "a.txt"])
+            .assert()
+            .code(0)
+            .stdout(format!(
+                "{} B\n",
+                Database::short_oid(&helper.resolve_revision("@^^").unwrap()),
+            ));
+    }
+
+    #[rstest]
+    fn log_commits_with_changed_lines_matching_a_regex(mut helper: CommandHelper) {
+        helper
+            .jit_cmd(&["log", "--oneline", "-G", "^(three|food)$"])
+            .assert()
+            .code(0)
+            .stdout(format!(
+                "\
+{} D
+{} C
+",
+                Database::short_oid(&helper.resolve_revision("@").unwrap()),
+                Database::short_oid(&helper.resolve_revision("@^").unwrap()),
+            ));
+    }
+
+    #[rstest]
+    fn skip_over_submodules(mut helper: CommandHelper) -> Result<()> {
+        let mut tree = helper
+            .repo
+            .database
+            .load_tree(&helper.load_commit("@")?.tree)?;
+        tree.entries.insert(
+            PathBuf::from("sub"),
+            TreeEntry::Entry(Entry::new(
+                String::from("8ab686eafeb1f44702738c8b0f24f2567c36da6d"),
+                GITLINK_MODE,
+            )),
+        );
+        helper.repo.database.store(&tree)?;
+        helper.jit_cmd(&["read-tree", &tree.oid()]);
+        helper.commit("E");
+
+        helper
+            .jit_cmd(&["log", "--oneline", "-Sfoo"])
+            .assert()
+            .code(0)
+            .stdout(format!(
+                "\
+{} D
+{} B
+",
+                Database::short_oid(&helper.resolve_revision("@^").unwrap()),
+                Database::short_oid(&helper.resolve_revision("@~3").unwrap()),
+            ));
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn fail_for_an_invalid_regex(mut helper: CommandHelper) {
+        helper
+            .jit_cmd(&["log", "-G", "("])
+            .assert()
+            .code(128)
+            .stdout("");
+    }
+}
@@ -264,6 +264,23 @@ mod unconflicted_merge_with_two_files {
 
         Ok(())
     }
+
+    #[rstest]
+    fn let_you_undo_the_merge_using_orig_head(mut helper: CommandHelper) -> Result<()> {
+        let old_head = helper.resolve_revision("@^")?;
+        assert_eq!(helper.resolve_revision("ORIG_HEAD")?, old_head);
+
+        helper
+            .jit_cmd(&["reset", "--hard", "ORIG_HEAD"])
+            .assert()
+            .code(0);
+
+        assert_eq!(helper.resolve_revision("@")?, old_head);
+        let workspace = HashMap::from([("f.txt", "2"), ("g.txt", "1")]);
+        helper.assert_workspace(&workspace)?;
+
+        Ok(())
+    }
 }
 
 mod unconflicted_merge_with_a_deleted_file {
@@ -409,6 +426,104 @@ mod unconflicted_merge_in_file_merge_possible {
     }
 }
 
+mod quiet_merge {
+    use super::*;
+
+    #[fixture]
+    fn helper() -> CommandHelper {
+        let mut helper = CommandHelper::new();
+        helper.init();
+
+        let tree = BTreeMap::from([("f.txt", Change::content("1\n2\n3\n"))]);
+        commit_tree(&mut helper, "A", tree).unwrap();
+
+        let tree = BTreeMap::from([("f.txt", Change::content("4\n2\n3\n"))]);
+        commit_tree(&mut helper, "B", tree).unwrap();
+
+        helper.jit_cmd(&["branch", "topic", "main^"]);
+        helper.jit_cmd(&["checkout", "topic"]);
+        let tree = BTreeMap::from([("f.txt", Change::content("1\n2\n5\n"))]);
+        commit_tree(&mut helper, "C", tree).unwrap();
+
+        helper.jit_cmd(&["checkout", "main"]);
+
+        helper
+    }
+
+    #[rstest]
+    fn print_nothing_for_a_clean_merge(mut helper: CommandHelper) -> Result<()> {
+        helper
+            .jit_cmd(&["merge", "-q", "topic", "-m", "M"])
+            .assert()
+            .code(0)
+            .stdout("");
+
+        assert_clean_merge(&mut helper)?;
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn print_nothing_for_a_fast_forward(mut helper: CommandHelper) {
+        helper.jit_cmd(&["checkout", "topic^"]);
+
+        helper
+            .jit_cmd(&["merge", "--quiet", "topic"])
+            .assert()
+            .code(0)
+            .stdout("");
+    }
+}
+
+mod ours_strategy {
+    use super::*;
+
+    #[fixture]
+    fn helper() -> CommandHelper {
+        let mut helper = CommandHelper::new();
+        helper.init();
+
+        let base = BTreeMap::from([
+            ("f.txt", Change::content("1")),
+            ("g.txt", Change::content("1")),
+        ]);
+
+        let left = BTreeMap::from([("f.txt", Change::content("2"))]);
+
+        let right = BTreeMap::from([
+            ("f.txt", Change::content("3")),
+            ("g.txt", Change::content("3")),
+        ]);
+
+        commit_tree(&mut helper, "A", base).unwrap();
+        commit_tree(&mut helper, "B", left).unwrap();
+
+        helper.jit_cmd(&["branch", "topic", "main^"]);
+        helper.jit_cmd(&["checkout", "topic"]);
+        commit_tree(&mut helper, "C", right).unwrap();
+
+        helper.jit_cmd(&["checkout", "main"]);
+
+        helper
+    }
+
+    #[rstest]
+    fn keep_the_current_tree(mut helper: CommandHelper) -> Result<()> {
+        let head_tree = helper.load_commit("@")?.tree;
+
+        helper
+            .jit_cmd(&["merge", "-s", "ours", "topic", "-m", "M"])
+            .assert()
+            .code(0);
+
+        assert_clean_merge(&mut helper)?;
+        assert_eq!(helper.load_commit("@")?.tree, head_tree);
+        helper.assert_workspace(&HashMap::from([("f.txt", "2"), ("g.txt", "1")]))?;
+
+        Ok(())
+    }
+}
+
 mod unconflicted_merge_edit_and_mode_change {
     use super::*;
 
@@ -1523,7 +1638,7 @@ fatal: Exiting because of an unresolved conflict.
         helper.jit_cmd(&["commit"]).assert().code(0);
 
         let commit = helper.load_commit("@")?;
-        assert_eq!(commit.message.trim_end(), "M");
+        assert_eq!(commit.message.trim_end(), "M\n\nConflicts:\n\tf.txt");
 
         let parents: Vec<_> = commit
             .parents
@@ -1542,13 +1657,107 @@ fatal: Exiting because of an unresolved conflict.
         Ok(())
     }
 
+    #[rstest]
+    fn show_the_merge_in_progress_in_the_status(mut helper: CommandHelper) -> Result<()> {
+        helper.jit_cmd(&["status"]).assert().code(0).stdout(
+            "\
+On branch main
+You have unmerged paths.
+  (fix conflicts and run 'jit commit')
+  (use 'jit merge --abort' to abort the merge)
+
+Unmerged paths:
+
+\tboth modified:   f.txt
+
+no changes added to commit
+",
+        );
+
+        helper.jit_cmd(&["add", "f.txt"]);
+
+        helper.jit_cmd(&["status"]).assert().code(0).stdout(
+            "\
+On branch main
+All conflicts fixed but you are still merging.
+  (use 'jit commit' to conclude merge)
+
+Changes to be committed:
+
+\tmodified:   f.txt
+
+",
+        );
+
+        helper
+            .jit_cmd(&["status", "--porcelain", "--branch"])
+            .assert()
+            .code(0)
+            .stdout(format!(
+                "\
+# branch.oid {}
+# branch.head main
+# merge.oid {}
+M  f.txt
+",
+                helper.resolve_revision("main")?,
+                helper.resolve_revision("topic")?
+            ));
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn save_the_merge_head_and_message_until_the_merge_is_committed(
+        mut helper: CommandHelper,
+    ) -> Result<()> {
+        let git_path = helper.repo_path.join(".git");
+        assert_eq!(
+            std::fs::read_to_string(git_path.join("MERGE_HEAD"))?.trim_end(),
+            helper.resolve_revision("topic")?
+        );
+        assert_eq!(
+            std::fs::read_to_string(git_path.join("MERGE_MSG"))?,
+            "M\n\nConflicts:\n\tf.txt\n"
+        );
+
+        helper.jit_cmd(&["add", "f.txt"]);
+        helper.jit_cmd(&["commit"]).assert().code(0);
+
+        helper.assert_noent(".git/MERGE_HEAD");
+        helper.assert_noent(".git/MERGE_MSG");
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn commit_a_merge_with_a_new_message(mut helper: CommandHelper) -> Result<()> {
+        helper.jit_cmd(&["add", "f.txt"]);
+        helper
+            .jit_cmd(&["commit", "-m", "Resolved"])
+            .assert()
+            .code(0);
+
+        let commit = helper.load_commit("@")?;
+        assert_eq!(commit.message.trim_end(), "Resolved");
+        assert_eq!(
+            commit.parents,
+            vec![
+                helper.resolve_revision("@^")?,
+                helper.resolve_revision("topic")?
+            ]
+        );
+
+        Ok(())
+    }
+
     #[rstest]
     fn allow_merge_continue_after_resolving_conflicts(mut helper: CommandHelper) -> Result<()> {
         helper.jit_cmd(&["add", "f.txt"]);
         helper.jit_cmd(&["merge", "--continue"]).assert().code(0);
 
         let commit = helper.load_commit("@")?;
-        assert_eq!(commit.message.trim_end(), "M");
+        assert_eq!(commit.message.trim_end(), "M\n\nConflicts:\n\tf.txt");
 
         let parents: Vec<_> = commit
             .parents
@@ -1609,3 +1818,304 @@ fatal: Exiting because of an unresolved conflict.
         );
     }
 }
+
+mod partial_conflict_resolution {
+    use super::*;
+
+    #[fixture]
+    fn helper() -> CommandHelper {
+        let mut helper = CommandHelper::new();
+        helper.init();
+
+        let base = BTreeMap::from([
+            ("f.txt", Change::content("1")),
+            ("g.txt", Change::content("1")),
+        ]);
+
+        let left = BTreeMap::from([
+            ("f.txt", Change::content("2")),
+            ("g.txt", Change::content("2")),
+        ]);
+
+        let right = BTreeMap::from([
+            ("f.txt", Change::content("3")),
+            ("g.txt", Change::content("3")),
+        ]);
+
+        merge3(&mut helper, base, left, right).unwrap();
+
+        helper
+    }
+
+    #[rstest]
+    fn keep_blocking_the_commit_until_every_conflict_is_resolved(
+        mut helper: CommandHelper,
+    ) -> Result<()> {
+        helper
+            .jit_cmd(&["add", "f.txt"])
+            .assert()
+            .code(0)
+            .stderr("Resolved conflict in 'f.txt'\nStill unmerged:\n\tg.txt\n");
+        helper.assert_status("M  f.txt\nUU g.txt\n");
+
+        helper.jit_cmd(&["commit"]).assert().code(128);
+        assert_eq!(helper.load_commit("@")?.message.trim_end(), "B");
+        assert!(helper.repo.git_path.join("MERGE_HEAD").exists());
+
+        helper.jit_cmd(&["add", "g.txt"]).assert().code(0).stderr(
+            "Resolved conflict in 'g.txt'\nAll conflicts fixed; run 'jit commit' to conclude\n",
+        );
+        helper.jit_cmd(&["commit"]).assert().code(0);
+        assert_eq!(helper.load_commit("@")?.parents.len(), 2);
+
+        Ok(())
+    }
+}
+
+mod merge_log {
+    use super::*;
+
+    #[fixture]
+    fn helper() -> CommandHelper {
+        let mut helper = CommandHelper::new();
+        helper.init();
+
+        commit_tree(
+            &mut helper,
+            "A",
+            BTreeMap::from([("f.txt", Change::content("1"))]),
+        )
+        .unwrap();
+        helper.jit_cmd(&["branch", "topic"]);
+        commit_tree(
+            &mut helper,
+            "B",
+            BTreeMap::from([("g.txt", Change::content("1"))]),
+        )
+        .unwrap();
+
+        helper.jit_cmd(&["checkout", "topic"]);
+        for (message, content) in [("C", "2"), ("D", "3"), ("E", "4")] {
+            commit_tree(
+                &mut helper,
+                message,
+                BTreeMap::from([("f.txt", Change::content(content))]),
+            )
+            .unwrap();
+        }
+        helper.jit_cmd(&["checkout", "main"]);
+
+        helper
+    }
+
+    #[rstest]
+    fn append_the_merged_commits_to_the_message(mut helper: CommandHelper) -> Result<()> {
+        helper
+            .jit_cmd(&["merge", "topic", "-m", "M", "--log"])
+            .assert()
+            .code(0);
+
+        assert_eq!(
+            helper.load_commit("@")?.message.trim_end(),
+            "M\n\n* topic:\n  E\n  D\n  C"
+        );
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn limit_the_number_of_merged_commits(mut helper: CommandHelper) -> Result<()> {
+        helper
+            .jit_cmd(&["merge", "topic", "-m", "M", "--log=2"])
+            .assert()
+            .code(0);
+
+        assert_eq!(
+            helper.load_commit("@")?.message.trim_end(),
+            "M\n\n* topic:\n  E\n  D\n  ..."
+        );
+
+        Ok(())
+    }
+}
+
+mod blob_cache {
+    use std::path::Path;
+
+    use jit::merge::inputs::Inputs;
+    use jit::merge::resolve::Resolve;
+    use jit::repository::Repository;
+
+    use super::*;
+
+    #[fixture]
+    fn helper() -> CommandHelper {
+        let mut helper = CommandHelper::new();
+        helper.init();
+
+        let base = BTreeMap::from([("f.txt", Change::content("1\n2\n3\n"))]);
+        commit_tree(&mut helper, "A", base).unwrap();
+
+        let left = BTreeMap::from([("f.txt", Change::content("L\n2\n3\n"))]);
+        commit_tree(&mut helper, "B", left).unwrap();
+
+        helper.jit_cmd(&["branch", "topic", "main^"]);
+        helper.jit_cmd(&["checkout", "topic"]);
+        let right = BTreeMap::from([("f.txt", Change::content("1\n2\nR\n"))]);
+        commit_tree(&mut helper, "C", right).unwrap();
+
+        helper.jit_cmd(&["checkout", "main"]);
+
+        helper
+    }
+
+    fn blob_oid(repo: &Repository, revision: &str) -> Result<String> {
+        let commit_oid = repo.database.load_commit(revision)?.oid();
+        let entry = repo
+            .database
+            .load_tree_entry(&commit_oid, Some(Path::new("f.txt")))?;
+
+        Ok(entry.unwrap().oid())
+    }
+
+    fn merge(helper: &CommandHelper) -> Result<Repository> {
+        let mut repo = Repository::new(helper.repo_path.join(".git"));
+        let inputs = Inputs::new(&repo, String::from("main"), String::from("topic"))?;
+
+        repo.index.load_for_update()?;
+        Resolve::new(&mut repo, &inputs).execute()?;
+        repo.index.write_updates()?;
+
+        Ok(repo)
+    }
+
+    #[rstest]
+    fn cache_the_blobs_loaded_by_a_merge(helper: CommandHelper) -> Result<()> {
+        let repo = merge(&helper)?;
+
+        for revision in ["main^", "main", "topic"] {
+            let oid = blob_oid(&repo, &helper.resolve_revision(revision)?)?;
+            assert!(repo.database.blob_cache().contains(&oid));
+        }
+
+        let hits = repo.database.blob_cache().hits();
+        repo.database
+            .load_blob(&blob_oid(&repo, &helper.resolve_revision("main^")?)?)?;
+        assert_eq!(repo.database.blob_cache().hits(), hits + 1);
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn evict_blobs_beyond_the_configured_limit(mut helper: CommandHelper) -> Result<()> {
+        helper.jit_cmd(&["config", "core.blobCacheLimit", "12"]);
+
+        let repo = merge(&helper)?;
+        let cache = repo.database.blob_cache();
+
+        assert!(cache.size() <= 12);
+        let base_oid = blob_oid(&repo, &helper.resolve_revision("main^")?)?;
+        assert!(!cache.contains(&base_oid));
+
+        Ok(())
+    }
+}
+
+mod conflicted_merge_with_a_non_branch_ref {
+    use super::*;
+
+    ///   A   B
+    ///   o---o [main]
+    ///    \
+    ///     `---o [v1.0]
+    ///         C
+    ///
+    #[fixture]
+    fn helper() -> CommandHelper {
+        let mut helper = CommandHelper::new();
+        helper.init();
+
+        commit_tree(
+            &mut helper,
+            "A",
+            BTreeMap::from([("f.txt", Change::content("1\n"))]),
+        )
+        .unwrap();
+        commit_tree(
+            &mut helper,
+            "B",
+            BTreeMap::from([("f.txt", Change::content("2\n"))]),
+        )
+        .unwrap();
+
+        helper.jit_cmd(&["branch", "topic", "main^"]);
+        helper.jit_cmd(&["checkout", "topic"]);
+        commit_tree(
+            &mut helper,
+            "C",
+            BTreeMap::from([("f.txt", Change::content("3\n"))]),
+        )
+        .unwrap();
+
+        let oid = helper.resolve_revision("@").unwrap();
+        helper.repo.refs.update_ref("refs/tags/v1.0", &oid).unwrap();
+        helper.jit_cmd(&["checkout", "main"]);
+        helper.jit_cmd(&["branch", "-D", "topic"]);
+
+        helper
+    }
+
+    #[rstest]
+    fn label_the_conflict_with_the_merged_name(mut helper: CommandHelper) -> Result<()> {
+        helper
+            .jit_cmd(&["merge", "v1.0", "-m", "M"])
+            .assert()
+            .code(1);
+
+        helper.assert_workspace(&HashMap::from([(
+            "f.txt",
+            "\
+<<<<<<< HEAD
+2
+=======
+3
+>>>>>>> v1.0
+",
+        )]))?;
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn label_the_current_branch_with_the_given_name(mut helper: CommandHelper) -> Result<()> {
+        helper
+            .jit_cmd(&["merge", "--into-name", "release", "v1.0", "-m", "M"])
+            .assert()
+            .code(1);
+
+        helper.assert_workspace(&HashMap::from([(
+            "f.txt",
+            "\
+<<<<<<< release
+2
+=======
+3
+>>>>>>> v1.0
+",
+        )]))?;
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn name_the_current_branch_in_the_default_message(mut helper: CommandHelper) -> Result<()> {
+        helper.jit_cmd(&["merge", "--into-name", "release", "-s", "ours", "v1.0"]);
+
+        assert_eq!(
+            helper.load_commit("@")?.message,
+            "Merge commit 'v1.0' into release\n"
+        );
+
+        Ok(())
+    }
+}
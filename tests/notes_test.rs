@@ -0,0 +1,108 @@
+mod common;
+
+use assert_cmd::prelude::OutputAssertExt;
+pub use common::CommandHelper;
+use jit::errors::Result;
+use rstest::{fixture, rstest};
+
+#[fixture]
+fn helper() -> CommandHelper {
+    let mut helper = CommandHelper::new();
+    helper.init();
+
+    for message in ["first", "second"] {
+        helper.write_file("file.txt", message).unwrap();
+        helper.jit_cmd(&["add", "."]);
+        helper.commit(message);
+    }
+
+    helper
+}
+
+#[rstest]
+fn show_a_note_added_to_a_commit(mut helper: CommandHelper) {
+    helper
+        .jit_cmd(&["notes", "add", "-m", "reviewed", "@^"])
+        .assert()
+        .code(0);
+
+    helper
+        .jit_cmd(&["notes", "show", "@^"])
+        .assert()
+        .code(0)
+        .stdout("reviewed\n");
+}
+
+#[rstest]
+fn fail_to_show_a_missing_note(mut helper: CommandHelper) -> Result<()> {
+    let head = helper.resolve_revision("@")?;
+
+    helper
+        .jit_cmd(&["notes", "show"])
+        .assert()
+        .code(1)
+        .stderr(format!("error: no note found for object {}.\n", head));
+
+    Ok(())
+}
+
+#[rstest]
+fn refuse_to_overwrite_a_note_without_force(mut helper: CommandHelper) {
+    helper.jit_cmd(&["notes", "add", "-m", "one"]);
+
+    helper
+        .jit_cmd(&["notes", "add", "-m", "two"])
+        .assert()
+        .code(1);
+    helper.jit_cmd(&["notes", "show"]).assert().stdout("one\n");
+
+    helper
+        .jit_cmd(&["notes", "add", "-f", "-m", "two"])
+        .assert()
+        .code(0);
+    helper.jit_cmd(&["notes", "show"]).assert().stdout("two\n");
+}
+
+#[rstest]
+fn list_notes_by_annotated_object(mut helper: CommandHelper) -> Result<()> {
+    helper.jit_cmd(&["notes", "add", "-m", "note", "@"]);
+
+    let head = helper.resolve_revision("@")?;
+    let note_oid = helper.repo.notes().list()?[&head].clone();
+
+    helper
+        .jit_cmd(&["notes", "list"])
+        .assert()
+        .code(0)
+        .stdout(format!("{} {}\n", note_oid, head));
+
+    Ok(())
+}
+
+#[rstest]
+fn print_notes_in_the_log(mut helper: CommandHelper) -> Result<()> {
+    helper.jit_cmd(&["notes", "add", "-m", "looks good", "@^"]);
+
+    let first = helper.load_commit("@^")?;
+
+    helper
+        .jit_cmd(&["log", "@^"])
+        .assert()
+        .code(0)
+        .stdout(format!(
+            "\
+commit {}
+Author: A. U. Thor <author@example.com>
+Date:   {}
+
+    first
+
+Notes:
+    looks good
+",
+            helper.resolve_revision("@^")?,
+            first.author.readable_time(),
+        ));
+
+    Ok(())
+}
@@ -0,0 +1,88 @@
+mod common;
+
+use std::fs;
+
+use assert_cmd::prelude::OutputAssertExt;
+pub use common::CommandHelper;
+use jit::errors::Result;
+use jit::repository::Repository;
+use rstest::{fixture, rstest};
+use tempfile::TempDir;
+
+mod with_a_gitfile {
+    use super::*;
+
+    /// Move the repository's `.git` directory elsewhere and replace it with a `.git` file
+    /// pointing at its new location.
+    #[fixture]
+    fn helper() -> (CommandHelper, TempDir) {
+        let mut helper = CommandHelper::new();
+        helper.init();
+
+        helper.write_file("file.txt", "1").unwrap();
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("first");
+
+        let git_dir = TempDir::new().unwrap();
+        let real_git_path = git_dir.path().join("repo.git");
+        fs::rename(helper.repo_path.join(".git"), &real_git_path).unwrap();
+        helper
+            .write_file(".git", &format!("gitdir: {}\n", real_git_path.display()))
+            .unwrap();
+        helper.repo = Repository::new(helper.repo_path.join(".git"));
+
+        (helper, git_dir)
+    }
+
+    #[rstest]
+    fn resolve_the_git_directory_through_the_gitfile(helper: (CommandHelper, TempDir)) {
+        let (helper, git_dir) = helper;
+
+        assert_eq!(helper.repo.git_path, git_dir.path().join("repo.git"));
+        assert_eq!(helper.repo.root_path, helper.repo_path);
+    }
+
+    #[rstest]
+    fn read_refs_and_objects_through_the_gitfile(helper: (CommandHelper, TempDir)) -> Result<()> {
+        let (mut helper, _git_dir) = helper;
+
+        helper
+            .jit_cmd(&["status", "--porcelain"])
+            .assert()
+            .code(0)
+            .stdout("");
+
+        helper.write_file("file.txt", "2")?;
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("second");
+
+        let head = helper.load_commit("@")?;
+        assert_eq!(head.message, "second\n");
+        assert_eq!(helper.load_commit("@^")?.message, "first\n");
+        assert!(!helper.repo_path.join(".git").is_dir());
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn follow_a_relative_gitdir(helper: (CommandHelper, TempDir)) -> Result<()> {
+        let (mut helper, git_dir) = helper;
+
+        fs::rename(
+            git_dir.path().join("repo.git"),
+            helper.repo_path.join("repo.git"),
+        )?;
+        helper.write_file(".git", "gitdir: repo.git\n")?;
+        helper.repo = Repository::new(helper.repo_path.join(".git"));
+        assert_eq!(helper.repo.git_path, helper.repo_path.join("repo.git"));
+        let head = helper.resolve_revision("@")?;
+
+        helper
+            .jit_cmd(&["log", "--oneline"])
+            .assert()
+            .code(0)
+            .stdout(format!("{} first\n", &head[0..7]));
+
+        Ok(())
+    }
+}
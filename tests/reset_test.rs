@@ -187,6 +187,61 @@ mod with_a_head_commit {
         Ok(())
     }
 
+    #[rstest]
+    fn unstage_a_modification(mut helper: CommandHelper) -> Result<()> {
+        helper
+            .jit_cmd(&["reset", "HEAD", "outer/inner/c.txt"])
+            .assert()
+            .code(0)
+            .stdout("Unstaged changes after reset:\nM\touter/inner/c.txt\n");
+
+        let index = HashMap::from([
+            ("outer/b.txt", "4"),
+            ("outer/d.txt", "5"),
+            ("outer/inner/c.txt", "3"),
+        ]);
+        helper.assert_index(&index)?;
+
+        assert_unchanged_head(&helper)?;
+        assert_unchanged_workspace(&helper)?;
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn unstage_an_addition(mut helper: CommandHelper) -> Result<()> {
+        helper
+            .jit_cmd(&["reset", "HEAD", "outer/d.txt"])
+            .assert()
+            .code(0)
+            .stdout("");
+
+        let index = HashMap::from([("outer/b.txt", "4"), ("outer/inner/c.txt", "6")]);
+        helper.assert_index(&index)?;
+        helper.assert_status("D  a.txt\nM  outer/inner/c.txt\n?? outer/d.txt\n?? outer/e.txt\n");
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn unstage_a_deletion(mut helper: CommandHelper) -> Result<()> {
+        helper
+            .jit_cmd(&["reset", "HEAD", "a.txt"])
+            .assert()
+            .code(0)
+            .stdout("Unstaged changes after reset:\nD\ta.txt\n");
+
+        let index = HashMap::from([
+            ("a.txt", "1"),
+            ("outer/b.txt", "4"),
+            ("outer/d.txt", "5"),
+            ("outer/inner/c.txt", "6"),
+        ]);
+        helper.assert_index(&index)?;
+
+        Ok(())
+    }
+
     #[rstest]
     fn reset_the_whole_index(mut helper: CommandHelper) -> Result<()> {
         helper.jit_cmd(&["reset"]).assert().code(0);
@@ -221,7 +276,7 @@ mod with_a_head_commit {
                 .repo
                 .database
                 .load_commit(helper.head_oid.as_ref().unwrap())?
-                .parent()
+                .first_parent()
         );
 
         assert_unchanged_workspace(&helper)?;
@@ -246,7 +301,7 @@ mod with_a_head_commit {
                 .repo
                 .database
                 .load_commit(helper.head_oid.as_ref().unwrap())?
-                .parent()
+                .first_parent()
         );
 
         assert_unchanged_workspace(&helper)?;
@@ -278,6 +333,62 @@ mod with_a_head_commit {
         Ok(())
     }
 
+    #[rstest]
+    fn keep_unstaged_changes_to_files_the_reset_does_not_touch(
+        mut helper: CommandHelper,
+    ) -> Result<()> {
+        helper.jit_cmd(&["reset", "--hard"]).assert().code(0);
+        helper.write_file("a.txt", "changed")?;
+
+        helper.jit_cmd(&["reset", "--merge", "@^"]).assert().code(0);
+
+        let index = HashMap::from([
+            ("a.txt", "1"),
+            ("outer/b.txt", "2"),
+            ("outer/inner/c.txt", "3"),
+        ]);
+        helper.assert_index(&index)?;
+
+        let workspace = HashMap::from([
+            ("a.txt", "changed"),
+            ("outer/b.txt", "2"),
+            ("outer/e.txt", "7"),
+            ("outer/inner/c.txt", "3"),
+        ]);
+        helper.assert_workspace(&workspace)?;
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn refuse_to_overwrite_local_changes_with_keep(mut helper: CommandHelper) -> Result<()> {
+        helper.write_file("outer/b.txt", "10")?;
+
+        helper
+            .jit_cmd(&["reset", "--keep", "@^"])
+            .assert()
+            .code(128)
+            .stderr(
+                "\
+error: Your local changes to the following files would be overwritten by checkout:
+\touter/b.txt
+Please commit your changes or stash them before you switch branches.
+fatal: Could not reset index file to revision '@^'.
+",
+            );
+        assert_unchanged_head(&helper)?;
+
+        let workspace = HashMap::from([
+            ("outer/b.txt", "10"),
+            ("outer/d.txt", "5"),
+            ("outer/e.txt", "7"),
+            ("outer/inner/c.txt", "6"),
+        ]);
+        helper.assert_workspace(&workspace)?;
+
+        Ok(())
+    }
+
     #[rstest]
     fn let_you_return_to_the_previous_state_using_orig_head(
         mut helper: CommandHelper,
@@ -306,3 +417,58 @@ mod with_a_head_commit {
         Ok(())
     }
 }
+
+mod with_a_conflicted_merge {
+    use super::*;
+
+    #[fixture]
+    fn helper() -> CommandHelper {
+        let mut helper = CommandHelper::new();
+        helper.init();
+
+        helper.write_file("d.txt", "1").unwrap();
+        helper.write_file("f.txt", "1").unwrap();
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("A");
+
+        helper.jit_cmd(&["branch", "topic"]);
+
+        helper.jit_cmd(&["rm", "d.txt"]);
+        helper.write_file("f.txt", "2").unwrap();
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("B");
+
+        helper.jit_cmd(&["checkout", "topic"]);
+        helper.write_file("d.txt", "3").unwrap();
+        helper.write_file("f.txt", "3").unwrap();
+        helper.write_file("g.txt", "3").unwrap();
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("C");
+
+        helper.jit_cmd(&["checkout", "main"]);
+        helper
+            .jit_cmd(&["merge", "topic", "-m", "M"])
+            .assert()
+            .code(1);
+
+        helper
+    }
+
+    #[rstest]
+    fn reset_the_conflicted_files_with_merge(mut helper: CommandHelper) -> Result<()> {
+        helper.jit_cmd(&["reset", "--merge"]).assert().code(0);
+
+        let workspace = HashMap::from([("f.txt", "2")]);
+        helper.assert_workspace(&workspace)?;
+        helper.assert_index(&workspace)?;
+
+        helper
+            .jit_cmd(&["status", "--porcelain"])
+            .assert()
+            .stdout("");
+        assert!(!helper.repo_path.join(".git/MERGE_HEAD").exists());
+        assert!(!helper.repo_path.join(".git/MERGE_MSG").exists());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,126 @@
+mod common;
+
+use assert_cmd::prelude::OutputAssertExt;
+pub use common::CommandHelper;
+use jit::errors::Result;
+use rstest::{fixture, rstest};
+
+fn commit_file(helper: &mut CommandHelper, message: &str) {
+    helper.write_file("file.txt", message).unwrap();
+    helper.jit_cmd(&["add", "."]);
+    helper.commit(message);
+}
+
+fn sorted_lines(output: &[u8]) -> Vec<String> {
+    let mut lines: Vec<_> = String::from_utf8_lossy(output)
+        .lines()
+        .map(String::from)
+        .collect();
+    lines.sort();
+
+    lines
+}
+
+//   o---o  topic
+//  /
+// o---o---o---o  main
+#[fixture]
+fn helper() -> CommandHelper {
+    let mut helper = CommandHelper::new();
+    helper.init();
+
+    commit_file(&mut helper, "base");
+    helper.jit_cmd(&["branch", "topic"]);
+
+    for message in ["main-1", "main-2", "main-3"] {
+        commit_file(&mut helper, message);
+    }
+
+    helper.jit_cmd(&["checkout", "topic"]);
+    for message in ["topic-1", "topic-2"] {
+        commit_file(&mut helper, message);
+    }
+
+    helper
+}
+
+#[rstest]
+fn list_the_commits_in_a_range(mut helper: CommandHelper) -> Result<()> {
+    let output = helper.jit_cmd(&["rev-list", "topic..main"]);
+
+    let mut expected = vec![
+        helper.resolve_revision("main")?,
+        helper.resolve_revision("main^")?,
+        helper.resolve_revision("main^^")?,
+    ];
+    expected.sort();
+
+    assert_eq!(sorted_lines(&output.stdout), expected);
+
+    Ok(())
+}
+
+#[rstest]
+fn count_the_commits_in_a_range(mut helper: CommandHelper) {
+    helper
+        .jit_cmd(&["rev-list", "--count", "topic..main"])
+        .assert()
+        .code(0)
+        .stdout("3\n");
+
+    helper
+        .jit_cmd(&["rev-list", "--count", "main..topic"])
+        .assert()
+        .code(0)
+        .stdout("2\n");
+
+    helper
+        .jit_cmd(&["rev-list", "--count", "main"])
+        .assert()
+        .code(0)
+        .stdout("4\n");
+}
+
+#[rstest]
+fn count_each_side_of_a_symmetric_range(mut helper: CommandHelper) {
+    helper
+        .jit_cmd(&["rev-list", "--count", "topic...main"])
+        .assert()
+        .code(0)
+        .stdout("5\n");
+
+    helper
+        .jit_cmd(&["rev-list", "--left-right", "--count", "topic...main"])
+        .assert()
+        .code(0)
+        .stdout("2\t3\n");
+}
+
+#[rstest]
+fn label_the_side_of_each_commit(mut helper: CommandHelper) -> Result<()> {
+    let output = helper.jit_cmd(&["rev-list", "--left-right", "topic...main"]);
+
+    let mut expected = vec![
+        format!("<{}", helper.resolve_revision("topic")?),
+        format!("<{}", helper.resolve_revision("topic^")?),
+        format!(">{}", helper.resolve_revision("main")?),
+        format!(">{}", helper.resolve_revision("main^")?),
+        format!(">{}", helper.resolve_revision("main^^")?),
+    ];
+    expected.sort();
+
+    assert_eq!(sorted_lines(&output.stdout), expected);
+
+    Ok(())
+}
+
+#[rstest]
+fn fail_for_an_unknown_revision(mut helper: CommandHelper) {
+    helper
+        .jit_cmd(&["rev-list", "nope"])
+        .assert()
+        .code(128)
+        .stderr(
+            "fatal: ambiguous argument 'nope': unknown revision or path not in the working tree.\n",
+        );
+}
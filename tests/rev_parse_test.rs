@@ -0,0 +1,61 @@
+mod common;
+
+use assert_cmd::prelude::OutputAssertExt;
+pub use common::{helper, CommandHelper};
+use jit::errors::Result;
+use jit::util::path_to_string;
+use rstest::rstest;
+
+#[rstest]
+fn print_the_git_dir_relative_to_the_root(mut helper: CommandHelper) {
+    helper
+        .jit_cmd(&["rev-parse", "--git-dir"])
+        .assert()
+        .code(0)
+        .stdout(".git\n");
+}
+
+#[rstest]
+fn locate_the_repository_from_a_subdirectory(mut helper: CommandHelper) -> Result<()> {
+    helper.mkdir("a/b")?;
+    let root = path_to_string(&helper.repo_path);
+
+    helper
+        .jit_cmd_in(
+            "a/b",
+            &[
+                "rev-parse",
+                "--git-dir",
+                "--absolute-git-dir",
+                "--show-toplevel",
+                "--is-inside-work-tree",
+            ],
+        )
+        .assert()
+        .code(0)
+        .stdout(format!("{0}/.git\n{0}/.git\n{0}\ntrue\n", root));
+
+    Ok(())
+}
+
+#[rstest]
+fn report_being_outside_the_work_tree_inside_git_dir(mut helper: CommandHelper) {
+    helper
+        .jit_cmd_in(".git/refs", &["rev-parse", "--is-inside-work-tree"])
+        .assert()
+        .code(0)
+        .stdout("false\n");
+}
+
+#[rstest]
+fn fail_outside_a_repository(mut helper: CommandHelper) -> Result<()> {
+    helper.delete(".git")?;
+
+    helper
+        .jit_cmd(&["rev-parse", "--show-toplevel"])
+        .assert()
+        .code(128)
+        .stderr("fatal: not a git repository (or any of the parent directories): .git\n");
+
+    Ok(())
+}
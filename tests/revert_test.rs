@@ -61,10 +61,13 @@ mod with_a_chain_of_commits {
     fn revert_a_commit_on_top_of_the_current_head(mut helper: CommandHelper) -> Result<()> {
         helper.jit_cmd(&["revert", "@~2"]).assert().code(0);
 
-        let revs = RevList::new(&helper.repo, &[String::from("@~3..")], Default::default())?;
+        let revs: Vec<_> =
+            RevList::new(&helper.repo, &[String::from("@~3..")], Default::default())?
+                .collect::<Result<_>>()?;
 
         assert_eq!(
-            revs.map(|commit| commit.title_line().trim().to_owned())
+            revs.iter()
+                .map(|commit| commit.title_line().trim().to_owned())
                 .collect::<Vec<_>>(),
             vec![
                 String::from("Revert \"six\""),
@@ -82,6 +85,91 @@ mod with_a_chain_of_commits {
         Ok(())
     }
 
+    #[rstest]
+    fn revert_commits_without_committing(mut helper: CommandHelper) -> Result<()> {
+        let head = helper.resolve_revision("@")?;
+
+        helper
+            .jit_cmd(&["revert", "--no-commit", "@", "@~2"])
+            .assert()
+            .code(0);
+
+        assert_eq!(helper.resolve_revision("@")?, head);
+        helper.assert_status("M  f.txt\nM  g.txt\n");
+
+        let tree = HashMap::from([("f.txt", "four"), ("g.txt", "seven")]);
+        helper.assert_index(&tree)?;
+        helper.assert_workspace(&tree)?;
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn revert_commits_to_the_same_file_without_committing(mut helper: CommandHelper) -> Result<()> {
+        helper
+            .jit_cmd(&["revert", "--no-commit", "@", "@^"])
+            .assert()
+            .code(0);
+
+        helper.assert_status("M  g.txt\n");
+
+        let tree = HashMap::from([("f.txt", "six"), ("g.txt", "five")]);
+        helper.assert_index(&tree)?;
+        helper.assert_workspace(&tree)?;
+
+        assert!(!helper.repo_path.join(".git/sequencer").exists());
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn clean_up_when_local_changes_block_the_revert(mut helper: CommandHelper) -> Result<()> {
+        helper.write_file("g.txt", "changed")?;
+
+        helper.jit_cmd(&["revert", "@"]).assert().failure();
+
+        assert!(!helper.repo_path.join(".git/index.lock").exists());
+        assert!(!helper.repo_path.join(".git/sequencer").exists());
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn continue_a_conflicted_revert_without_committing(mut helper: CommandHelper) -> Result<()> {
+        let head = helper.resolve_revision("@")?;
+
+        helper.jit_cmd(&["revert", "-n", "@~3"]).assert().code(1);
+        helper.jit_cmd(&["add", "g.txt"]);
+
+        helper.jit_cmd(&["revert", "--continue"]).assert().code(0);
+
+        assert_eq!(helper.resolve_revision("@")?, head);
+        helper.assert_status("");
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn edit_the_revert_message(mut helper: CommandHelper) -> Result<()> {
+        let reverted = helper.resolve_revision("@~2")?;
+        helper.env.insert(
+            String::from("GIT_EDITOR"),
+            String::from("sed -i -e 1s/Revert/Undo/"),
+        );
+
+        helper
+            .jit_cmd(&["revert", "--edit", "@~2"])
+            .assert()
+            .code(0);
+
+        assert_eq!(
+            helper.load_commit("@")?.message,
+            format!("Undo \"six\"\n\nThis reverts commit {}.\n", reverted)
+        );
+
+        Ok(())
+    }
+
     #[rstest]
     fn fail_to_revert_a_content_conflict(mut helper: CommandHelper) -> Result<()> {
         helper.jit_cmd(&["revert", "@~4"]).assert().code(1);
@@ -136,7 +224,8 @@ three>>>>>>> parent of {}... four
             .stderr("");
 
         let commits: Vec<_> =
-            RevList::new(&helper.repo, &[String::from("@~3..")], Default::default())?.collect();
+            RevList::new(&helper.repo, &[String::from("@~3..")], Default::default())?
+                .collect::<Result<_>>()?;
         assert_eq!(vec![commits[1].oid()], commits[0].parents);
 
         assert_eq!(
@@ -168,7 +257,8 @@ three>>>>>>> parent of {}... four
         helper.jit_cmd(&["commit"]).assert().code(0);
 
         let commits: Vec<_> =
-            RevList::new(&helper.repo, &[String::from("@~3..")], Default::default())?.collect();
+            RevList::new(&helper.repo, &[String::from("@~3..")], Default::default())?
+                .collect::<Result<_>>()?;
         assert_eq!(vec![commits[1].oid()], commits[0].parents);
 
         assert_eq!(
@@ -193,10 +283,13 @@ three>>>>>>> parent of {}... four
             .assert()
             .code(0);
 
-        let revs = RevList::new(&helper.repo, &[String::from("@~4..")], Default::default())?;
+        let revs: Vec<_> =
+            RevList::new(&helper.repo, &[String::from("@~4..")], Default::default())?
+                .collect::<Result<_>>()?;
 
         assert_eq!(
-            revs.map(|commit| commit.title_line().trim().to_owned())
+            revs.iter()
+                .map(|commit| commit.title_line().trim().to_owned())
                 .collect::<Vec<_>>(),
             vec![
                 String::from("Revert \"six\""),
@@ -276,10 +369,13 @@ fatal: Exiting because of an unresolved conflict.
 
         helper.jit_cmd(&["revert", "--continue"]).assert().code(0);
 
-        let revs = RevList::new(&helper.repo, &[String::from("@~4..")], Default::default())?;
+        let revs: Vec<_> =
+            RevList::new(&helper.repo, &[String::from("@~4..")], Default::default())?
+                .collect::<Result<_>>()?;
 
         assert_eq!(
-            revs.map(|commit| commit.title_line().trim().to_owned())
+            revs.iter()
+                .map(|commit| commit.title_line().trim().to_owned())
                 .collect::<Vec<_>>(),
             vec![
                 String::from("Revert \"five\""),
@@ -307,10 +403,13 @@ fatal: Exiting because of an unresolved conflict.
 
         helper.jit_cmd(&["revert", "--continue"]).assert().code(0);
 
-        let revs = RevList::new(&helper.repo, &[String::from("@~4..")], Default::default())?;
+        let revs: Vec<_> =
+            RevList::new(&helper.repo, &[String::from("@~4..")], Default::default())?
+                .collect::<Result<_>>()?;
 
         assert_eq!(
-            revs.map(|commit| commit.title_line().trim().to_owned())
+            revs.iter()
+                .map(|commit| commit.title_line().trim().to_owned())
                 .collect::<Vec<_>>(),
             vec![
                 String::from("Revert \"five\""),
@@ -505,10 +604,13 @@ mod with_merges {
         helper.jit_cmd(&["rm", "-f", "h.txt"]);
         helper.jit_cmd(&["revert", "--continue"]).assert().code(0);
 
-        let revs = RevList::new(&helper.repo, &[String::from("@~3..")], Default::default())?;
+        let revs: Vec<_> =
+            RevList::new(&helper.repo, &[String::from("@~3..")], Default::default())?
+                .collect::<Result<_>>()?;
 
         assert_eq!(
-            revs.map(|commit| commit.title_line().trim().to_owned())
+            revs.iter()
+                .map(|commit| commit.title_line().trim().to_owned())
                 .collect::<Vec<_>>(),
             vec![
                 String::from("Revert \"merge topic^\""),
@@ -0,0 +1,219 @@
+mod common;
+
+use assert_cmd::prelude::OutputAssertExt;
+pub use common::CommandHelper;
+use jit::database::blob::Blob;
+use jit::database::object::Object;
+use jit::errors::{Error, Result};
+use jit::revision::{Revision, COMMIT};
+use rstest::{fixture, rstest};
+
+#[fixture]
+fn helper() -> CommandHelper {
+    let mut helper = CommandHelper::new();
+    helper.init();
+
+    helper.write_file("file.txt", "1").unwrap();
+    helper.jit_cmd(&["add", "."]);
+    helper.commit("first");
+
+    helper
+}
+
+/// Store blobs until two of their IDs share their first four characters, returning that prefix.
+fn store_ambiguous_blobs(helper: &CommandHelper) -> Result<String> {
+    let mut prefixes = vec![];
+
+    for n in 0.. {
+        let blob = Blob::new(n.to_string().into_bytes());
+        helper.repo.database.store(&blob)?;
+
+        let prefix = blob.oid()[..4].to_string();
+        if prefixes.contains(&prefix) {
+            return Ok(prefix);
+        }
+        prefixes.push(prefix);
+    }
+
+    unreachable!()
+}
+
+#[rstest]
+fn fail_for_an_unknown_revision(mut helper: CommandHelper) {
+    let error = Revision::new(&helper.repo, "nope").resolve(Some(COMMIT));
+    assert!(matches!(error, Err(Error::UnknownRevision(name)) if name == "nope"));
+
+    helper.jit_cmd(&["log", "nope"]).assert().code(128).stderr(
+        "fatal: ambiguous argument 'nope': unknown revision or path not in the working tree.\n",
+    );
+}
+
+#[rstest]
+fn fail_for_an_ambiguous_short_oid(mut helper: CommandHelper) -> Result<()> {
+    let prefix = store_ambiguous_blobs(&helper)?;
+
+    let mut revision = Revision::new(&helper.repo, &prefix);
+    match revision.resolve(None) {
+        Err(Error::AmbiguousRevision(name, candidates)) => {
+            assert_eq!(name, prefix);
+            assert!(candidates.len() >= 2);
+            assert!(candidates.iter().all(|oid| oid.starts_with(&prefix)));
+        }
+        result => panic!("expected an ambiguous revision, got {:?}", result),
+    }
+    assert_eq!(
+        revision.errors[0].message,
+        format!("short SHA1 {} is ambiguous", prefix)
+    );
+
+    helper
+        .jit_cmd(&["log", &prefix])
+        .assert()
+        .code(128)
+        .stderr(format!(
+            "fatal: ambiguous argument '{}': unknown revision or path not in the working tree.\n",
+            prefix
+        ));
+
+    Ok(())
+}
+
+#[rstest]
+fn fail_for_a_revision_that_is_not_a_commit(helper: CommandHelper) -> Result<()> {
+    let tree = helper.load_commit("@")?.tree;
+
+    let error = Revision::new(&helper.repo, &tree).resolve(Some(COMMIT));
+    assert!(matches!(error, Err(Error::NotACommit)));
+    assert_eq!(error.unwrap_err().to_string(), "Needed a single revision");
+
+    Ok(())
+}
+
+#[rstest]
+fn peel_a_commit_to_its_tree(helper: CommandHelper) -> Result<()> {
+    let commit = helper.load_commit("@")?;
+
+    assert_eq!(helper.resolve_revision("HEAD^{tree}")?, commit.tree);
+    assert_eq!(helper.resolve_revision("HEAD^{commit}")?, commit.oid());
+
+    let mut revision = Revision::new(&helper.repo, &format!("{}^{{commit}}", commit.tree));
+    assert!(revision.resolve(None).is_err());
+    assert_eq!(
+        revision.errors[0].message,
+        format!("object {} is a tree, not a commit", commit.tree)
+    );
+
+    Ok(())
+}
+
+#[rstest]
+fn peel_a_tag_to_its_commit(helper: CommandHelper) -> Result<()> {
+    let oid = helper.resolve_revision("@")?;
+    helper.repo.refs.update_ref("refs/tags/v1.0", &oid)?;
+
+    assert_eq!(helper.resolve_revision("v1.0^{commit}")?, oid);
+    assert_eq!(helper.resolve_revision("v1.0^{}")?, oid);
+
+    Ok(())
+}
+
+mod with_a_chain_of_commits {
+    use super::*;
+
+    #[fixture]
+    fn helper() -> CommandHelper {
+        let mut helper = super::helper();
+
+        for message in ["apple", "banana split", "cherry", "banana bread", "date"] {
+            helper.write_file("file.txt", message).unwrap();
+            helper.jit_cmd(&["add", "."]);
+            helper.commit(message);
+        }
+
+        helper
+    }
+
+    #[rstest]
+    fn resolve_the_latest_commit_with_a_matching_message(helper: CommandHelper) -> Result<()> {
+        assert_eq!(
+            helper.resolve_revision(":/banana")?,
+            helper.resolve_revision("@^")?
+        );
+        assert_eq!(
+            helper.resolve_revision(":/banana split")?,
+            helper.resolve_revision("@~3")?
+        );
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn search_the_history_of_a_revision(helper: CommandHelper) -> Result<()> {
+        assert_eq!(
+            helper.resolve_revision("@~2^{/banana}")?,
+            helper.resolve_revision("@~3")?
+        );
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn fail_when_no_message_matches(helper: CommandHelper) {
+        let error = Revision::new(&helper.repo, ":/kiwi").resolve(Some(COMMIT));
+        assert!(matches!(error, Err(Error::UnknownRevision(name)) if name == ":/kiwi"));
+    }
+}
+
+mod with_an_upstream {
+    use super::*;
+
+    #[fixture]
+    fn helper() -> CommandHelper {
+        let mut helper = super::helper();
+        helper.jit_cmd(&["remote", "add", "origin", "ssh://example.com/repo"]);
+        helper.jit_cmd(&["config", "branch.main.remote", "origin"]);
+        helper.jit_cmd(&["config", "branch.main.merge", "refs/heads/main"]);
+
+        let oid = helper.resolve_revision("@").unwrap();
+        helper
+            .repo
+            .refs
+            .update_ref("refs/remotes/origin/main", &oid)
+            .unwrap();
+
+        helper.write_file("file.txt", "2").unwrap();
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("second");
+
+        helper
+    }
+
+    #[rstest]
+    fn resolve_the_remote_tracking_ref(helper: CommandHelper) -> Result<()> {
+        let tracking_oid = helper.repo.refs.read_ref("refs/remotes/origin/main")?;
+
+        for name in ["@{u}", "@{upstream}", "main@{u}", "@{push}"] {
+            assert_eq!(Some(helper.resolve_revision(name)?), tracking_oid);
+        }
+        assert_eq!(
+            helper.resolve_revision("@{u}")?,
+            helper.resolve_revision("@^")?
+        );
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn fail_when_no_upstream_is_configured(mut helper: CommandHelper) {
+        helper.jit_cmd(&["branch", "topic"]);
+
+        let error = Revision::new(&helper.repo, "topic@{u}").resolve(None);
+        assert!(matches!(error, Err(Error::InvalidUpstream(..))));
+
+        helper
+            .jit_cmd(&["log", "topic@{u}"])
+            .assert()
+            .code(128)
+            .stderr("fatal: no upstream configured for branch 'topic'\n");
+    }
+}
@@ -0,0 +1,179 @@
+mod common;
+
+use std::path::PathBuf;
+
+use assert_cmd::prelude::OutputAssertExt;
+pub use common::CommandHelper;
+use jit::database::commit::Commit;
+use jit::database::entry::Entry;
+use jit::database::object::Object;
+use jit::database::tree::{Tree, TreeEntry, GITLINK_MODE};
+use jit::errors::Result;
+use rstest::{fixture, rstest};
+
+#[fixture]
+fn helper() -> CommandHelper {
+    let mut helper = CommandHelper::new();
+    helper.init();
+
+    helper.write_file("f.txt", "a\nb\n").unwrap();
+    helper.write_file("g.txt", "x\n").unwrap();
+    helper.jit_cmd(&["add", "."]);
+    helper.commit("one");
+
+    helper.write_file("f.txt", "a\nc\nd\n").unwrap();
+    helper.jit_cmd(&["add", "."]);
+    helper.commit("two");
+
+    helper
+}
+
+#[rstest]
+fn show_the_stat_of_a_commit(mut helper: CommandHelper) -> Result<()> {
+    let commit = helper.load_commit("@^")?;
+
+    helper
+        .jit_cmd(&["show", "--stat", "@^"])
+        .assert()
+        .code(0)
+        .stdout(format!(
+            "\
+commit {}
+Author: A. U. Thor <author@example.com>
+Date:   {}
+
+    one
+
+ f.txt | 2 ++
+ g.txt | 1 +
+ 2 files changed, 3 insertions(+)
+",
+            helper.resolve_revision("@^")?,
+            commit.author.readable_time(),
+        ));
+
+    Ok(())
+}
+
+#[rstest]
+fn show_the_stat_followed_by_the_patch(mut helper: CommandHelper) -> Result<()> {
+    let commit = helper.load_commit("@")?;
+
+    helper
+        .jit_cmd(&["show", "--stat", "--patch"])
+        .assert()
+        .code(0)
+        .stdout(format!(
+            "\
+commit {}
+Author: A. U. Thor <author@example.com>
+Date:   {}
+
+    two
+---
+ f.txt | 3 ++-
+ 1 file changed, 2 insertions(+), 1 deletion(-)
+
+diff --git a/f.txt b/f.txt
+index 422c2b7..6372083 100644
+--- a/f.txt
++++ b/f.txt
+@@ -1,2 +1,3 @@
+ a
+-b
++c
++d
+",
+            helper.resolve_revision("@")?,
+            commit.author.readable_time(),
+        ));
+
+    Ok(())
+}
+
+#[rstest]
+fn show_that_a_commit_is_signed(mut helper: CommandHelper) -> Result<()> {
+    let commit = helper.load_commit("@")?;
+    let mut signed = Commit::new(
+        commit.parents.clone(),
+        commit.tree.clone(),
+        commit.author.clone(),
+        commit.committer.clone(),
+        commit.message.clone(),
+    );
+    signed.extra_headers.push((
+        String::from("gpgsig"),
+        String::from("-----BEGIN PGP SIGNATURE-----\n\nabcd\n-----END PGP SIGNATURE-----"),
+    ));
+    helper.repo.database.store(&signed)?;
+    helper.repo.refs.update_head(&signed.oid())?;
+
+    helper
+        .jit_cmd(&["show", "--show-signature", "--stat"])
+        .assert()
+        .code(0)
+        .stdout(format!(
+            "\
+commit {}
+Signature: present (not verified)
+Author: A. U. Thor <author@example.com>
+Date:   {}
+
+    two
+
+ f.txt | 3 ++-
+ 1 file changed, 2 insertions(+), 1 deletion(-)
+",
+            signed.oid(),
+            commit.author.readable_time(),
+        ));
+    assert_eq!(helper.load_commit("@")?.gpgsig(), signed.gpgsig());
+
+    Ok(())
+}
+
+#[rstest]
+fn show_a_submodule_as_the_commit_it_points_to(mut helper: CommandHelper) -> Result<()> {
+    let sub_oid = "0123456789abcdef0123456789abcdef01234567";
+    let mut tree = helper
+        .repo
+        .database
+        .load_tree(&helper.load_commit("@")?.tree)?;
+    tree.entries.insert(
+        PathBuf::from("sub"),
+        TreeEntry::Entry(Entry::new(sub_oid.to_owned(), GITLINK_MODE)),
+    );
+    let tree = Tree::new(Some(tree.entries));
+    helper.repo.database.store(&tree)?;
+
+    let output = helper.jit_cmd(&["commit-tree", &tree.oid(), "-p", "@", "-m", "sub"]);
+    let commit_oid = String::from_utf8(output.stdout).unwrap().trim().to_owned();
+    let commit = helper.load_commit(&commit_oid)?;
+
+    helper
+        .jit_cmd(&["show", &commit_oid])
+        .assert()
+        .code(0)
+        .stdout(format!(
+            "\
+commit {}
+Author: A. U. Thor <author@example.com>
+Date:   {}
+
+    sub
+
+diff --git a/sub b/sub
+new file mode 160000
+index 0000000..0123456
+--- /dev/null
++++ b/sub
+@@ -0,0 +1,1 @@
++Subproject commit {}
+",
+            commit_oid,
+            commit.author.readable_time(),
+            sub_oid,
+        ));
+
+    Ok(())
+}
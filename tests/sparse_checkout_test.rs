@@ -0,0 +1,88 @@
+mod common;
+
+use std::collections::HashMap;
+
+use assert_cmd::prelude::OutputAssertExt;
+pub use common::CommandHelper;
+use jit::errors::Result;
+use rstest::{fixture, rstest};
+
+#[fixture]
+fn helper() -> CommandHelper {
+    let mut helper = CommandHelper::new();
+    helper.init();
+
+    helper.write_file("README.md", "readme").unwrap();
+    helper.write_file("src/lib.rs", "lib 1").unwrap();
+    helper.write_file("docs/guide.md", "guide 1").unwrap();
+    helper.jit_cmd(&["add", "."]);
+    helper.commit("first");
+
+    helper.write_file("src/lib.rs", "lib 2").unwrap();
+    helper.write_file("docs/guide.md", "guide 2").unwrap();
+    helper.jit_cmd(&["add", "."]);
+    helper.commit("second");
+
+    helper
+}
+
+#[rstest]
+fn remove_excluded_files_from_the_workspace(mut helper: CommandHelper) -> Result<()> {
+    helper
+        .jit_cmd(&["sparse-checkout", "set", "/src/"])
+        .assert()
+        .code(0);
+
+    helper.assert_workspace(&HashMap::from([("src/lib.rs", "lib 2")]))?;
+    helper.assert_status("");
+    helper
+        .jit_cmd(&["sparse-checkout", "list"])
+        .assert()
+        .code(0)
+        .stdout("/src/\n");
+
+    Ok(())
+}
+
+#[rstest]
+fn restrict_checkout_to_one_directory(mut helper: CommandHelper) -> Result<()> {
+    helper.jit_cmd(&["sparse-checkout", "set", "/src/"]);
+
+    helper.jit_cmd(&["checkout", "@^"]).assert().code(0);
+
+    helper.assert_workspace(&HashMap::from([("src/lib.rs", "lib 1")]))?;
+    helper.assert_noent("docs");
+    helper.assert_index(&HashMap::from([
+        ("README.md", "readme"),
+        ("src/lib.rs", "lib 1"),
+        ("docs/guide.md", "guide 1"),
+    ]))?;
+    helper.assert_status("");
+
+    Ok(())
+}
+
+#[rstest]
+fn restore_every_file_when_disabled(mut helper: CommandHelper) -> Result<()> {
+    helper.jit_cmd(&["sparse-checkout", "set", "/src/"]);
+    helper.jit_cmd(&["checkout", "@^"]);
+
+    helper
+        .jit_cmd(&["sparse-checkout", "disable"])
+        .assert()
+        .code(0);
+
+    helper.assert_workspace(&HashMap::from([
+        ("README.md", "readme"),
+        ("src/lib.rs", "lib 1"),
+        ("docs/guide.md", "guide 1"),
+    ]))?;
+    helper.assert_status("");
+    helper
+        .jit_cmd(&["sparse-checkout", "list"])
+        .assert()
+        .code(128)
+        .stderr("fatal: this worktree is not sparse\n");
+
+    Ok(())
+}
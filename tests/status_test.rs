@@ -1,5 +1,6 @@
 mod common;
 
+use assert_cmd::prelude::OutputAssertExt;
 pub use common::{helper, CommandHelper};
 use jit::errors::Result;
 use jit::repository::Repository;
@@ -67,6 +68,25 @@ fn list_untracked_files_inside_tracked_directories(mut helper: CommandHelper) ->
     Ok(())
 }
 
+#[rstest]
+fn dont_list_ignored_files(mut helper: CommandHelper) -> Result<()> {
+    helper.write_file(".gitignore", "build/\n")?;
+    helper.write_file("build/out.o", "")?;
+    helper.write_file("logs/.gitignore", "*.log\n!keep.log\n")?;
+    helper.write_file("logs/debug.log", "")?;
+    helper.write_file("only-ignored/.gitignore", "*\n")?;
+
+    helper.assert_status("?? .gitignore\n?? logs/\n");
+
+    helper
+        .jit_cmd(&["status", "--porcelain", "-u", "all"])
+        .assert()
+        .code(0)
+        .stdout("?? .gitignore\n?? logs/.gitignore\n");
+
+    Ok(())
+}
+
 #[rstest]
 fn dont_list_empty_untracked_directories(mut helper: CommandHelper) -> Result<()> {
     helper.mkdir("outer")?;
@@ -87,6 +107,171 @@ fn list_untracked_directories_that_indirectly_contain_files(
     Ok(())
 }
 
+#[rstest]
+fn list_every_file_in_untracked_directories_with_untracked_files_all(
+    mut helper: CommandHelper,
+) -> Result<()> {
+    helper.write_file("file.txt", "")?;
+    helper.write_file("dir/another.txt", "")?;
+    helper.write_file("dir/nested/inner.txt", "")?;
+    helper.mkdir("dir/empty")?;
+
+    helper
+        .jit_cmd(&["status", "--porcelain", "-u", "all"])
+        .assert()
+        .code(0)
+        .stdout(
+            "\
+?? dir/another.txt
+?? dir/nested/inner.txt
+?? file.txt
+",
+        );
+    helper
+        .jit_cmd(&["status", "--porcelain", "-u"])
+        .assert()
+        .code(0)
+        .stdout(
+            "\
+?? dir/another.txt
+?? dir/nested/inner.txt
+?? file.txt
+",
+        );
+    helper
+        .jit_cmd(&["status", "--porcelain", "--untracked-files=normal"])
+        .assert()
+        .code(0)
+        .stdout(
+            "\
+?? dir/
+?? file.txt
+",
+        );
+
+    Ok(())
+}
+
+#[rstest]
+fn terminate_porcelain_records_with_nul(mut helper: CommandHelper) -> Result<()> {
+    helper.write_file("committed.txt", "")?;
+    helper.jit_cmd(&["add", "."]);
+    helper.commit("commit message");
+
+    helper.write_file("committed.txt", "changed")?;
+    helper.write_file("with space.txt", "")?;
+
+    let output = helper
+        .jit_cmd(&["status", "--porcelain", "-z"])
+        .assert()
+        .code(0);
+    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+
+    assert_eq!(
+        stdout.split_terminator('\0').collect::<Vec<_>>(),
+        vec![" M committed.txt", "?? with space.txt"]
+    );
+    assert!(!stdout.contains(['\n', '"']));
+
+    helper
+        .jit_cmd(&["status", "-z"])
+        .assert()
+        .code(0)
+        .stdout(stdout);
+
+    Ok(())
+}
+
+#[rstest]
+fn quote_paths_with_unusual_characters(mut helper: CommandHelper) -> Result<()> {
+    helper.write_file("tab\there.txt", "")?;
+    helper.write_file("café.txt", "")?;
+
+    helper.assert_status("?? \"caf\\303\\251.txt\"\n?? \"tab\\there.txt\"\n");
+
+    helper
+        .jit_cmd(&["status", "-z"])
+        .assert()
+        .code(0)
+        .stdout("?? café.txt\0?? tab\there.txt\0");
+
+    // Like Git, `core.quotePath` only stops quoting bytes above 0x7f
+    helper.jit_cmd(&["config", "core.quotePath", "false"]);
+    helper.assert_status("?? café.txt\n?? \"tab\\there.txt\"\n");
+
+    Ok(())
+}
+
+#[rstest]
+fn hide_untracked_files_with_untracked_files_no(mut helper: CommandHelper) -> Result<()> {
+    helper.write_file("committed.txt", "")?;
+    helper.jit_cmd(&["add", "."]);
+    helper.commit("commit message");
+
+    helper.write_file("committed.txt", "changed")?;
+    helper.write_file("dir/file.txt", "")?;
+
+    helper
+        .jit_cmd(&["status", "--porcelain", "--untracked-files=no"])
+        .assert()
+        .code(0)
+        .stdout(" M committed.txt\n");
+
+    helper.jit_cmd(&["config", "status.showUntrackedFiles", "no"]);
+    helper.assert_status(" M committed.txt\n");
+
+    helper.jit_cmd(&["config", "status.showUntrackedFiles", "all"]);
+    helper.assert_status(" M committed.txt\n?? dir/file.txt\n");
+
+    Ok(())
+}
+
+#[rstest]
+fn never_list_git_directories(mut helper: CommandHelper) -> Result<()> {
+    helper.write_file("a/file.txt", "")?;
+    helper.jit_cmd(&["add", "."]);
+    helper.commit("commit message");
+
+    helper.write_file(".git/stray.txt", "")?;
+    helper.write_file("a/.git/config", "")?;
+    helper.write_file("b/.git", "gitdir: ../.git/modules/b")?;
+
+    helper.assert_status("");
+
+    Ok(())
+}
+
+#[rstest]
+fn match_files_in_any_case_with_ignore_case(mut helper: CommandHelper) -> Result<()> {
+    helper.write_file("file.txt", "one")?;
+    helper.jit_cmd(&["add", "."]);
+    helper.commit("commit message");
+
+    std::fs::rename(
+        helper.repo_path.join("file.txt"),
+        helper.repo_path.join("FILE.txt"),
+    )?;
+    helper.assert_status(" D file.txt\n?? FILE.txt\n");
+
+    helper.jit_cmd(&["config", "core.ignoreCase", "true"]);
+    helper.jit_cmd(&["add", "FILE.txt"]);
+    helper.assert_status("");
+
+    helper.repo.index.load()?;
+    assert_eq!(
+        helper
+            .repo
+            .index
+            .entries
+            .keys()
+            .cloned()
+            .collect::<Vec<_>>(),
+        vec![(String::from("file.txt"), 0)],
+    );
+
+    Ok(())
+}
+
 mod index_workspace_changes {
     use super::*;
 
@@ -187,6 +372,28 @@ mod index_workspace_changes {
 
         Ok(())
     }
+
+    #[rstest]
+    fn print_nothing_for_deleted_skip_worktree_files(mut helper: CommandHelper) -> Result<()> {
+        let mut index = Repository::new(helper.repo_path.join(".git")).index;
+        index.load_for_update()?;
+        index.set_skip_worktree("a/2.txt", true);
+        index.write_updates()?;
+
+        helper.delete("a/2.txt")?;
+
+        helper.assert_status("");
+        helper.assert_diff("");
+
+        helper.jit_cmd(&["add", "."]);
+        helper.assert_status("");
+
+        let mut index = Repository::new(helper.repo_path.join(".git")).index;
+        index.load()?;
+        assert!(index.entries[&(String::from("a/2.txt"), 0)].skip_worktree());
+
+        Ok(())
+    }
 }
 
 mod head_index_changes {
@@ -273,3 +480,153 @@ D  a/b/3.txt
         Ok(())
     }
 }
+
+mod submodules {
+    use std::path::PathBuf;
+
+    use jit::database::entry::Entry;
+    use jit::database::object::Object;
+    use jit::database::tree::{TreeEntry, GITLINK_MODE};
+
+    use super::*;
+
+    const RECORDED_OID: &str = "8ab686eafeb1f44702738c8b0f24f2567c36da6d";
+
+    #[fixture]
+    fn helper() -> CommandHelper {
+        let mut helper = super::helper();
+        helper.write_file("file.txt", "1").unwrap();
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("first");
+
+        helper.jit_cmd(&["init", "sub"]);
+        helper.write_file("sub/inner.txt", "inner").unwrap();
+        set_submodule_head(&helper, RECORDED_OID);
+
+        let mut tree = helper
+            .repo
+            .database
+            .load_tree(&helper.load_commit("@").unwrap().tree)
+            .unwrap();
+        tree.entries.insert(
+            PathBuf::from("sub"),
+            TreeEntry::Entry(Entry::new(String::from(RECORDED_OID), GITLINK_MODE)),
+        );
+        helper.repo.database.store(&tree).unwrap();
+        helper.jit_cmd(&["read-tree", &tree.oid()]);
+        helper.commit("add submodule");
+
+        helper
+    }
+
+    fn set_submodule_head(helper: &CommandHelper, oid: &str) {
+        Repository::new(helper.repo_path.join("sub/.git"))
+            .refs
+            .update_head(oid)
+            .unwrap();
+    }
+
+    #[rstest]
+    fn print_nothing_when_the_submodule_is_at_the_recorded_commit(mut helper: CommandHelper) {
+        helper.assert_status("");
+    }
+
+    #[rstest]
+    fn report_a_submodule_whose_head_has_moved(mut helper: CommandHelper) {
+        set_submodule_head(&helper, "e2f1a7b3c4d5e6f708192a3b4c5d6e7f80919a0b");
+
+        helper.assert_status(" M sub\n");
+    }
+}
+
+mod porcelain_v2 {
+    use assert_cmd::prelude::OutputAssertExt;
+    use jit::database::blob::Blob;
+    use jit::database::object::Object;
+
+    use super::*;
+
+    #[fixture]
+    fn helper() -> CommandHelper {
+        let mut helper = CommandHelper::new();
+        helper.init();
+
+        helper.write_file("a.txt", "one\n").unwrap();
+        helper.write_file("old.txt", "moving\n").unwrap();
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("first commit");
+
+        helper
+    }
+
+    fn oid(contents: &str) -> String {
+        Blob::new(contents.as_bytes().to_vec()).oid()
+    }
+
+    #[rstest]
+    fn report_a_modified_file(mut helper: CommandHelper) -> Result<()> {
+        helper.write_file("a.txt", "two\n")?;
+        helper.jit_cmd(&["add", "."]);
+        helper.write_file("a.txt", "three\n")?;
+
+        helper
+            .jit_cmd(&["status", "--porcelain=v2"])
+            .assert()
+            .code(0)
+            .stdout(format!(
+                "1 MM N... 100644 100644 100644 {} {} a.txt\n",
+                oid("one\n"),
+                oid("two\n"),
+            ));
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn report_a_renamed_file(mut helper: CommandHelper) -> Result<()> {
+        helper.jit_cmd(&["rm", "old.txt"]);
+        helper.write_file("new.txt", "moving\n")?;
+        helper.jit_cmd(&["add", "new.txt"]);
+
+        helper
+            .jit_cmd(&["status", "--porcelain=v2"])
+            .assert()
+            .code(0)
+            .stdout(format!(
+                "2 R. N... 100644 100644 100644 {0} {0} R100 new.txt\told.txt\n",
+                oid("moving\n"),
+            ));
+
+        Ok(())
+    }
+
+    #[rstest]
+    fn report_an_unmerged_file(mut helper: CommandHelper) -> Result<()> {
+        helper.jit_cmd(&["branch", "topic"]);
+        helper.write_file("a.txt", "ours\n")?;
+        helper.jit_cmd(&["add", "."]);
+        helper.commit("ours");
+
+        helper.jit_cmd(&["checkout", "topic"]);
+        helper.write_file("a.txt", "theirs\n")?;
+        helper.write_file("untracked.txt", "")?;
+        helper.jit_cmd(&["add", "a.txt"]);
+        helper.commit("theirs");
+
+        helper.jit_cmd(&["checkout", "main"]);
+        helper.jit_cmd(&["merge", "topic", "-m", "merge"]);
+
+        helper
+            .jit_cmd(&["status", "--porcelain=v2"])
+            .assert()
+            .code(0)
+            .stdout(format!(
+                "u UU N... 100644 100644 100644 100644 {} {} {} a.txt\n? untracked.txt\n",
+                oid("one\n"),
+                oid("ours\n"),
+                oid("theirs\n"),
+            ));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,131 @@
+mod common;
+
+use std::path::PathBuf;
+
+use assert_cmd::prelude::OutputAssertExt;
+pub use common::CommandHelper;
+use jit::database::entry::Entry;
+use jit::database::object::Object;
+use jit::database::tree::{Tree, TreeEntry, GITLINK_MODE, TREE_MODE};
+use jit::errors::Result;
+use jit::repository::Repository;
+use rstest::{fixture, rstest};
+
+const RECORDED_OID: &str = "8ab686eafeb1f44702738c8b0f24f2567c36da6d";
+
+#[fixture]
+fn helper() -> CommandHelper {
+    let mut helper = CommandHelper::new();
+    helper.init();
+
+    helper.write_file("file.txt", "1").unwrap();
+    helper.jit_cmd(&["add", "."]);
+    helper.commit("first");
+
+    helper.jit_cmd(&["init", "lib/sub"]);
+    set_submodule_head(&helper, RECORDED_OID);
+
+    let mut tree = helper
+        .repo
+        .database
+        .load_tree(&helper.load_commit("@").unwrap().tree)
+        .unwrap();
+    tree.entries.insert(
+        PathBuf::from("lib"),
+        TreeEntry::Entry(Entry::new(store_gitlink_tree(&helper, "sub"), TREE_MODE)),
+    );
+    helper.repo.database.store(&tree).unwrap();
+    helper.jit_cmd(&["read-tree", &tree.oid()]);
+    helper.commit("add submodule");
+
+    helper
+}
+
+fn store_gitlink_tree(helper: &CommandHelper, name: &str) -> String {
+    let mut tree = Tree::new(None);
+    tree.entries.insert(
+        PathBuf::from(name),
+        TreeEntry::Entry(Entry::new(String::from(RECORDED_OID), GITLINK_MODE)),
+    );
+    helper.repo.database.store(&tree).unwrap();
+    tree.oid()
+}
+
+fn set_submodule_head(helper: &CommandHelper, oid: &str) {
+    Repository::new(helper.repo_path.join("lib/sub/.git"))
+        .refs
+        .update_head(oid)
+        .unwrap();
+}
+
+fn write_gitmodules(helper: &CommandHelper) -> Result<()> {
+    helper.write_file(
+        ".gitmodules",
+        "\
+[submodule \"vendor\"]
+\tpath = lib/sub
+\turl = https://example.com/vendor.git
+",
+    )
+}
+
+#[rstest]
+fn list_a_submodule_at_the_recorded_commit(mut helper: CommandHelper) -> Result<()> {
+    write_gitmodules(&helper)?;
+
+    helper
+        .jit_cmd(&["submodule", "status"])
+        .assert()
+        .code(0)
+        .stdout(format!(" {} lib/sub\n", RECORDED_OID));
+
+    Ok(())
+}
+
+#[rstest]
+fn mark_a_submodule_whose_head_has_moved(mut helper: CommandHelper) -> Result<()> {
+    write_gitmodules(&helper)?;
+    let moved = "e2f1a7b3c4d5e6f708192a3b4c5d6e7f80919a0b";
+    set_submodule_head(&helper, moved);
+
+    helper
+        .jit_cmd(&["submodule"])
+        .assert()
+        .code(0)
+        .stdout(format!("+{} lib/sub\n", moved));
+
+    Ok(())
+}
+
+#[rstest]
+fn mark_a_submodule_that_has_not_been_cloned(mut helper: CommandHelper) -> Result<()> {
+    write_gitmodules(&helper)?;
+    helper.delete("lib/sub")?;
+    helper.mkdir("lib/sub")?;
+
+    helper
+        .jit_cmd(&["submodule", "status"])
+        .assert()
+        .code(0)
+        .stdout(format!("-{} lib/sub\n", RECORDED_OID));
+
+    Ok(())
+}
+
+#[rstest]
+fn fail_for_a_submodule_missing_from_gitmodules(mut helper: CommandHelper) {
+    helper
+        .jit_cmd(&["submodule", "status"])
+        .assert()
+        .code(128)
+        .stderr("fatal: no submodule mapping found in .gitmodules for path 'lib/sub'\n");
+}
+
+#[rstest]
+fn fail_for_an_unknown_subcommand(mut helper: CommandHelper) {
+    helper
+        .jit_cmd(&["submodule", "sync"])
+        .assert()
+        .code(129)
+        .stderr("error: unknown subcommand: sync\n");
+}
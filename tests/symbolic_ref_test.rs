@@ -0,0 +1,73 @@
+mod common;
+
+use assert_cmd::prelude::OutputAssertExt;
+pub use common::CommandHelper;
+use jit::errors::Result;
+use rstest::{fixture, rstest};
+
+#[fixture]
+fn helper() -> CommandHelper {
+    let mut helper = CommandHelper::new();
+    helper.init();
+
+    helper.write_file("file.txt", "1").unwrap();
+    helper.jit_cmd(&["add", "."]);
+    helper.commit("first");
+    helper.jit_cmd(&["branch", "topic"]);
+
+    helper
+}
+
+#[rstest]
+fn read_the_target_of_head(mut helper: CommandHelper) {
+    helper
+        .jit_cmd(&["symbolic-ref", "HEAD"])
+        .assert()
+        .code(0)
+        .stdout("refs/heads/main\n");
+
+    helper
+        .jit_cmd(&["symbolic-ref", "--short", "HEAD"])
+        .assert()
+        .code(0)
+        .stdout("main\n");
+}
+
+#[rstest]
+fn point_head_at_another_branch(mut helper: CommandHelper) -> Result<()> {
+    helper
+        .jit_cmd(&["symbolic-ref", "HEAD", "refs/heads/topic"])
+        .assert()
+        .code(0);
+
+    helper
+        .jit_cmd(&["symbolic-ref", "HEAD"])
+        .assert()
+        .stdout("refs/heads/topic\n");
+    helper
+        .jit_cmd(&["branch"])
+        .assert()
+        .stdout("  main\n* topic\n");
+
+    Ok(())
+}
+
+#[rstest]
+fn refuse_to_point_outside_refs(mut helper: CommandHelper) {
+    helper
+        .jit_cmd(&["symbolic-ref", "HEAD", "topic"])
+        .assert()
+        .code(128)
+        .stderr("fatal: Refusing to point HEAD outside of refs/\n");
+}
+
+#[rstest]
+fn fail_to_read_a_detached_head(mut helper: CommandHelper) {
+    helper.jit_cmd(&["checkout", "@"]);
+
+    helper
+        .jit_cmd(&["symbolic-ref", "HEAD"])
+        .assert()
+        .code(128)
+        .stderr("fatal: ref HEAD is not a symbolic ref\n");
+}
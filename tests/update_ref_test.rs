@@ -0,0 +1,127 @@
+mod common;
+
+use assert_cmd::prelude::OutputAssertExt;
+pub use common::CommandHelper;
+use jit::errors::Result;
+use rstest::{fixture, rstest};
+
+#[fixture]
+fn helper() -> CommandHelper {
+    let mut helper = CommandHelper::new();
+    helper.init();
+
+    for message in ["first", "second"] {
+        helper.write_file("file.txt", message).unwrap();
+        helper.jit_cmd(&["add", "."]);
+        helper.commit(message);
+    }
+
+    helper
+}
+
+#[rstest]
+fn create_a_new_ref(mut helper: CommandHelper) -> Result<()> {
+    helper
+        .jit_cmd(&["update-ref", "refs/heads/topic", "@^"])
+        .assert()
+        .code(0);
+
+    assert_eq!(
+        helper.repo.refs.read_ref("topic")?,
+        Some(helper.resolve_revision("@^")?)
+    );
+
+    Ok(())
+}
+
+#[rstest]
+fn update_a_ref_whose_old_value_matches(mut helper: CommandHelper) -> Result<()> {
+    let first = helper.resolve_revision("@^")?;
+    let second = helper.resolve_revision("@")?;
+
+    helper
+        .jit_cmd(&["update-ref", "refs/heads/main", &first, &second])
+        .assert()
+        .code(0);
+
+    assert_eq!(helper.repo.refs.read_head()?, Some(first));
+
+    Ok(())
+}
+
+#[rstest]
+fn update_the_branch_behind_head(mut helper: CommandHelper) -> Result<()> {
+    let first = helper.resolve_revision("@^")?;
+
+    helper
+        .jit_cmd(&["update-ref", "HEAD", &first])
+        .assert()
+        .code(0);
+
+    assert_eq!(helper.repo.refs.read_ref("main")?, Some(first));
+    helper
+        .jit_cmd(&["symbolic-ref", "HEAD"])
+        .assert()
+        .stdout("refs/heads/main\n");
+
+    Ok(())
+}
+
+#[rstest]
+fn reject_an_update_whose_old_value_does_not_match(mut helper: CommandHelper) -> Result<()> {
+    let first = helper.resolve_revision("@^")?;
+    let second = helper.resolve_revision("@")?;
+
+    helper
+        .jit_cmd(&["update-ref", "refs/heads/main", &first, &first])
+        .assert()
+        .code(128)
+        .stderr(format!(
+            "fatal: cannot lock ref 'refs/heads/main': is at {} but expected {}\n",
+            second, first
+        ));
+
+    assert_eq!(helper.repo.refs.read_head()?, Some(second));
+
+    Ok(())
+}
+
+#[rstest]
+fn reject_creating_a_ref_that_already_exists(mut helper: CommandHelper) {
+    helper
+        .jit_cmd(&["update-ref", "refs/heads/main", "@^", ""])
+        .assert()
+        .code(128)
+        .stderr("fatal: cannot lock ref 'refs/heads/main': reference already exists\n");
+}
+
+#[rstest]
+fn delete_a_ref(mut helper: CommandHelper) -> Result<()> {
+    helper.jit_cmd(&["branch", "topic/one"]);
+
+    helper
+        .jit_cmd(&["update-ref", "-d", "refs/heads/topic/one"])
+        .assert()
+        .code(0);
+
+    assert_eq!(helper.repo.refs.read_ref("topic/one")?, None);
+    assert!(!helper.repo_path.join(".git/refs/heads/topic").exists());
+    assert!(helper.repo_path.join(".git/refs/heads").exists());
+
+    Ok(())
+}
+
+#[rstest]
+fn reject_a_delete_whose_old_value_does_not_match(mut helper: CommandHelper) -> Result<()> {
+    let first = helper.resolve_revision("@^")?;
+    helper.jit_cmd(&["branch", "topic"]);
+
+    helper
+        .jit_cmd(&["update-ref", "-d", "refs/heads/topic", &first])
+        .assert()
+        .code(128);
+
+    assert!(helper.repo.refs.read_ref("topic")?.is_some());
+
+    Ok(())
+}
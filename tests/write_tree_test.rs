@@ -0,0 +1,96 @@
+mod common;
+
+use std::collections::HashMap;
+
+use assert_cmd::prelude::OutputAssertExt;
+pub use common::CommandHelper;
+use jit::errors::Result;
+use rstest::{fixture, rstest};
+
+const TREE: &str = "8f572dc8f2aea9b74b4a954b3d06c5d93df2cabc";
+
+#[fixture]
+fn helper() -> CommandHelper {
+    let mut helper = CommandHelper::new();
+    helper.init();
+
+    helper.write_file("a.txt", "1\n").unwrap();
+    helper.write_file("b/c/d.txt", "2\n").unwrap();
+    helper.jit_cmd(&["add", "."]);
+
+    helper
+}
+
+#[rstest]
+fn write_a_tree_from_the_index(mut helper: CommandHelper) -> Result<()> {
+    helper
+        .jit_cmd(&["write-tree"])
+        .assert()
+        .code(0)
+        .stdout(format!("{}\n", TREE));
+
+    helper.repo.database.load_tree(TREE)?;
+
+    Ok(())
+}
+
+#[rstest]
+fn round_trip_the_index_through_a_tree(mut helper: CommandHelper) -> Result<()> {
+    helper.jit_cmd(&["write-tree"]);
+
+    helper.jit_cmd(&["rm", "--cached", "a.txt", "b/c/d.txt"]);
+    helper.write_file("e.txt", "3\n").unwrap();
+    helper.jit_cmd(&["add", "e.txt"]);
+    helper.assert_index(&HashMap::from([("e.txt", "3\n")]))?;
+
+    helper.jit_cmd(&["read-tree", TREE]).assert().code(0);
+
+    helper.assert_index(&HashMap::from([("a.txt", "1\n"), ("b/c/d.txt", "2\n")]))?;
+    helper.assert_workspace(&HashMap::from([
+        ("a.txt", "1\n"),
+        ("b/c/d.txt", "2\n"),
+        ("e.txt", "3\n"),
+    ]))?;
+
+    Ok(())
+}
+
+#[rstest]
+fn read_the_tree_of_a_commit(mut helper: CommandHelper) -> Result<()> {
+    helper.commit("first");
+    helper.write_file("a.txt", "changed\n").unwrap();
+    helper.jit_cmd(&["add", "."]);
+
+    helper.jit_cmd(&["read-tree", "@"]).assert().code(0);
+
+    helper.assert_index(&HashMap::from([("a.txt", "1\n"), ("b/c/d.txt", "2\n")]))?;
+
+    Ok(())
+}
+
+#[rstest]
+fn fail_to_read_a_missing_tree(mut helper: CommandHelper) {
+    helper
+        .jit_cmd(&["read-tree", "nope"])
+        .assert()
+        .code(128)
+        .stderr("fatal: Not a valid object name nope\n");
+}
+
+#[rstest]
+fn sort_directories_as_if_they_had_a_trailing_slash() {
+    let mut helper = CommandHelper::new();
+    helper.init();
+
+    helper.write_file("foo/bar.txt", "1\n").unwrap();
+    helper.write_file("foo.txt", "2\n").unwrap();
+    helper.write_file("foo-bar", "3\n").unwrap();
+    helper.jit_cmd(&["add", "."]);
+
+    // Git writes `foo-bar`, `foo.txt`, then `foo/`, giving this ID
+    helper
+        .jit_cmd(&["write-tree"])
+        .assert()
+        .code(0)
+        .stdout("12ef8591c5a16264c560c4df52d8f470a63e1fad\n");
+}